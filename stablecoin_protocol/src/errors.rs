@@ -66,4 +66,244 @@ pub enum ErrorCode {
     RateLimitExceeded,
     #[msg("The voting period has already ended")]
     VotingPeriodEnded,
+    #[msg("The strategy is not whitelisted or is inactive")]
+    StrategyNotWhitelisted,
+    #[msg("Deployment would exceed the strategy cap")]
+    StrategyCapExceeded,
+    #[msg("Not enough liquid reserves to cover redemptions")]
+    InsufficientLiquidReserves,
+    #[msg("The address is blacklisted")]
+    AddressBlacklisted,
+    #[msg("The bond market does not have enough capacity left")]
+    BondCapacityExceeded,
+    #[msg("Price moved beyond the accepted slippage")]
+    SlippageExceeded,
+    #[msg("Nothing has vested yet")]
+    NothingVested,
+    #[msg("The merkle proof is invalid")]
+    InvalidMerkleProof,
+    #[msg("This airdrop allocation has already been claimed")]
+    AirdropAlreadyClaimed,
+    #[msg("The oracle account is invalid")]
+    InvalidOracleAccount,
+    #[msg("The oracle price is stale")]
+    StalePrice,
+    #[msg("The oracle confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Stake amount is below the pool minimum")]
+    StakeBelowMinimum,
+    #[msg("The governance token max supply has been reached")]
+    GovTokenSupplyExhausted,
+    #[msg("Minting would exceed the collateral concentration limit")]
+    ConcentrationLimitExceeded,
+    #[msg("The same account was passed for two distinct roles")]
+    DuplicateAccounts,
+    #[msg("Another protocol operation is already in progress")]
+    ReentrancyDetected,
+    #[msg("Mint does not match the protocol's canonical mint")]
+    InvalidMint,
+    #[msg("Treasury account does not match the protocol's canonical treasury")]
+    InvalidTreasuryAccount,
+    #[msg("Proposal is still within its voting period or expiry grace")]
+    ProposalNotExpired,
+    #[msg("Protocol configuration is frozen for an upgrade")]
+    ConfigFrozen,
+    #[msg("Protocol configuration is not frozen")]
+    ConfigNotFrozen,
+    #[msg("The unfreeze timelock has not elapsed")]
+    UnfreezeTimelockActive,
+    #[msg("Upgrade checklist is not complete")]
+    UpgradeChecklistIncomplete,
+    #[msg("Position exceeds the guarded launch per-wallet cap")]
+    LaunchCapExceeded,
+    #[msg("Guarded launch mode is not active")]
+    LaunchModeInactive,
+    #[msg("Reward claim cooldown has not elapsed")]
+    ClaimCooldownActive,
+    #[msg("Claimable rewards are below the minimum claim amount")]
+    ClaimBelowMinimum,
+    #[msg("Batch exceeds the maximum number of entries")]
+    BatchTooLarge,
+    #[msg("Position is not insolvent")]
+    PositionNotInsolvent,
+    #[msg("Position was liquidated too recently")]
+    LiquidationCooldownActive,
+    #[msg("Guardian set size or threshold is invalid")]
+    InvalidGuardianSet,
+    #[msg("Signer is not a guardian")]
+    NotAGuardian,
+    #[msg("Guardian has already approved this action")]
+    AlreadyApproved,
+    #[msg("Emergency action has expired")]
+    EmergencyActionExpired,
+    #[msg("Emergency action has already executed")]
+    EmergencyActionExecuted,
+    #[msg("Emergency action is still pending")]
+    EmergencyActionPending,
+    #[msg("A snapshot was already recorded this epoch")]
+    SnapshotAlreadyRecorded,
+    #[msg("Permit signature does not match the requested action")]
+    InvalidPermit,
+    #[msg("Permit has expired")]
+    PermitExpired,
+    #[msg("Permit nonce does not match the expected nonce")]
+    InvalidNonce,
+    #[msg("Campaign must end after it starts and after the current time")]
+    InvalidCampaignWindow,
+    #[msg("Campaign has been terminated")]
+    CampaignTerminated,
+    #[msg("Account does not match the campaign target")]
+    InvalidCampaignTarget,
+    #[msg("Fee exceeds the maximum allowed")]
+    FeeTooHigh,
+    #[msg("Protocol accounting invariant violated")]
+    InvariantViolated,
+    #[msg("Minting is halted while the stablecoin trades below the peg band")]
+    MintingBelowPeg,
+    #[msg("ve lock is boosting another staker; kick it first")]
+    BoostStillApplied,
+    #[msg("Margin account does not match the position")]
+    InvalidMarginAccount,
+    #[msg("Position is pooled in cross margin")]
+    PositionInCrossMargin,
+    #[msg("Margin account cannot pool more positions")]
+    TooManyCrossPositions,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Auction price is above the buyer's limit")]
+    AuctionPriceAboveLimit,
+    #[msg("Buy-now is no longer available for this auction")]
+    BuyNowUnavailable,
+    #[msg("Swap schedule is cancelled or fully executed")]
+    SwapScheduleClosed,
+    #[msg("Next swap slice is not due yet")]
+    SwapSliceNotDue,
+    #[msg("Swap returned less than the limit price allows")]
+    SwapOutputTooLow,
+    #[msg("Bad debt queue is empty")]
+    BadDebtQueueEmpty,
+    #[msg("Bad debt stage is not available yet")]
+    BadDebtStageUnavailable,
+    #[msg("Debt note is already settled")]
+    DebtNoteSettled,
+    #[msg("Debt note has not matured")]
+    DebtNoteNotMatured,
+    #[msg("Loyalty pass could not be verified")]
+    InvalidLoyaltyPass,
+    #[msg("Payment stream has been cancelled")]
+    StreamCancelled,
+    #[msg("Reporting epoch has not ended")]
+    EpochNotOver,
+    #[msg("Invalid stake pool account")]
+    InvalidStakePool,
+    #[msg("Collateral is priced through its stake pool")]
+    StakePoolPricedCollateral,
+    #[msg("Instruction is only available in testing builds")]
+    TestingOnly,
+    #[msg("Auction lot is reserved for its sealed-bid round")]
+    SealedBidAuction,
+    #[msg("Auction does not take sealed bids")]
+    NotSealedBidAuction,
+    #[msg("Bid phase is not open")]
+    BidPhaseClosed,
+    #[msg("Revealed bid does not match its commitment")]
+    BidCommitmentMismatch,
+    #[msg("Caller does not hold the winning sealed bid")]
+    NoWinningBid,
+    #[msg("Minting against this collateral type is paused")]
+    CollateralMintingPaused,
+    #[msg("Unstaking is delayed while an incident is active")]
+    IncidentUnstakeDelay,
+    #[msg("AMM pool liquidity is below the oracle minimum")]
+    PoolLiquidityTooLow,
+    #[msg("No governance token price is available")]
+    GovTokenPriceUnavailable,
+    #[msg("Proposal has not been approved")]
+    ProposalNotApproved,
+    #[msg("A targeted parameter changed since the proposal was created")]
+    StaleProposalBase,
+    #[msg("Proposal changes do not match its action")]
+    ProposalActionMismatch,
+    #[msg("Voting period has not ended yet")]
+    VotingPeriodNotEnded,
+    #[msg("Proposal timelock has not passed yet")]
+    ProposalTimelockActive,
+    #[msg("Crank ran too recently; wait for its next eligible slot")]
+    CrankTooSoon,
+    #[msg("Reward pool does not pay rewards from this source")]
+    RewardSourceMismatch,
+    #[msg("Reward pool still holds stake")]
+    RewardPoolNotEmpty,
+    #[msg("Transaction landed after its deadline")]
+    DeadlineExceeded,
+    #[msg("Collateral exceeds the lot cap; an auction series account is required")]
+    AuctionSeriesRequired,
+    #[msg("Market maker facility is suspended")]
+    MmFacilityInactive,
+    #[msg("Market maker facility collateral attestation is missing or too old")]
+    MmAttestationStale,
+    #[msg("Mint would exceed the market maker's credit line")]
+    MmCreditLineExceeded,
+    #[msg("Transfer does not match its travel-rule attestation, or the attestation was already used")]
+    TravelRuleAttestationMismatch,
+    #[msg("Tokens of this mint back protocol balances and cannot be rescued")]
+    ProtectedMint,
+    #[msg("Minting is paused")]
+    MintingPaused,
+    #[msg("Redemptions are paused")]
+    RedemptionsPaused,
+    #[msg("Liquidations are paused")]
+    LiquidationPaused,
+    #[msg("Governance is paused")]
+    GovernancePaused,
+    #[msg("Cached portfolio health is missing, stale or taken over a different position list")]
+    PortfolioHealthStale,
+    #[msg("Redemption request is not at the head of the queue")]
+    RedemptionNotAtHead,
+    #[msg("Invalid lending market reserve or bank account")]
+    InvalidReceiptAccount,
+    #[msg("Invalid valuation override bounds or duration")]
+    InvalidPriceOverride,
+    #[msg("Invalid auto-deleverage trigger, target, slippage or tip")]
+    InvalidDeleverageParams,
+    #[msg("Position health is above its deleverage trigger")]
+    DeleverageNotTriggered,
+    #[msg("Deleverage must improve health without exceeding the owner's target")]
+    DeleverageOutOfRange,
+    #[msg("Reward pool is being sunset")]
+    PoolSunsetting,
+    #[msg("Reward pool's sunset withdrawal window has not ended")]
+    SunsetWindowOpen,
+    #[msg("Rewards can no longer be claimed from this sunset pool")]
+    SunsetClaimsClosed,
+    #[msg("Stability fee is outside the rate controller's bounds")]
+    StabilityFeeOutOfBounds,
+    #[msg("Stability fee moved more than the per-epoch cap allows")]
+    StabilityFeeChangeTooLarge,
+    #[msg("Negative stability fees require a fresh peg TWAP above the premium threshold")]
+    NegativeStabilityFeeNotAllowed,
+    #[msg("Oracle program whitelist must hold one to four distinct programs")]
+    InvalidOraclePrograms,
+    #[msg("The staker has not opted in to staking its rewards")]
+    AutoCompoundDisabled,
+    #[msg("Pending rewards exceed the pool's claim-to-stake threshold")]
+    ClaimAboveStakeThreshold,
+    #[msg("Auction settlement asset must share the stablecoin's decimals")]
+    InvalidSettlementMint,
+    #[msg("Coverage floor must be positive and no higher than the alert threshold")]
+    InvalidCoverageThresholds,
+    #[msg("Liquidity coverage is not below the recall floor")]
+    LiquidityCoverageAboveFloor,
+    #[msg("Partner revenue share exceeds the cap or its wallet is unset")]
+    InvalidPartnerTerms,
+    #[msg("Partner account does not match the partner id")]
+    InvalidPartner,
+    #[msg("A follow-up proposal must be opened exactly when a circuit breaker trips")]
+    IncidentProposalMismatch,
+    #[msg("Repay the position's debt before migrating it")]
+    MigrationWithOutstandingDebt,
+    #[msg("Collateral is priced through its lending market receipt")]
+    ReceiptPricedCollateral,
+    #[msg("Reward rate exceeds the maximum a proposal may set")]
+    RewardRateTooHigh,
 }
@@ -0,0 +1,94 @@
+// gov_oracle.rs
+
+//! Price of the protocol's own governance token.
+//!
+//! Combines the token's Pyth feed, when it is listed, with a TWAP of its AMM
+//! pool against the stablecoin. Both sources must be fresh and agree within
+//! the oracle's divergence band; otherwise the governance fallback price is
+//! used, and without one the read fails.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::ErrorCode;
+use crate::math::{self, BPS_DENOMINATOR};
+use crate::oracle::{self, PRICE_PRECISION};
+use crate::state::{GovPriceSource, GovTokenOracle};
+
+/// Governance token price in PRICE_PRECISION, with the source it came from.
+#[derive(Clone, Copy, Debug)]
+pub struct GovTokenPrice {
+    pub price: u64,
+    pub source: GovPriceSource,
+}
+
+/// Spot price of the AMM pool: stablecoin reserve per governance token reserve.
+///
+/// Both mints are assumed to use the same number of decimals. Pools with
+/// less than `min_quote_liquidity` stablecoin are too cheap to move to trust.
+pub fn amm_spot_price(gov_vault: &TokenAccount, quote_vault: &TokenAccount, min_quote_liquidity: u64) -> Result<u64> {
+    require!(
+        gov_vault.amount > 0 && quote_vault.amount >= min_quote_liquidity,
+        ErrorCode::PoolLiquidityTooLow
+    );
+    math::mul_div_down(quote_vault.amount as u128, PRICE_PRECISION as u128, gov_vault.amount as u128)
+}
+
+/// Fold an AMM spot price into the TWAP and return the spot price actually used.
+///
+/// The spot price is clamped to within `max_spot_move_bps` of the current
+/// TWAP, and the TWAP moves towards it in proportion to the time elapsed over
+/// the window, so a pool manipulated for a single block barely moves it.
+pub fn fold_twap(gov_oracle: &mut GovTokenOracle, spot_price: u64, now: i64) -> Result<u64> {
+    let twap = gov_oracle.twap_price as i128;
+    let max_move = twap * gov_oracle.max_spot_move_bps as i128 / BPS_DENOMINATOR as i128;
+    let clamped = (spot_price as i128).clamp(twap - max_move, twap + max_move);
+
+    let elapsed = now.saturating_sub(gov_oracle.last_update).max(0) as u64;
+    let weight = elapsed.min(gov_oracle.twap_window_secs) as i128;
+    let window = gov_oracle.twap_window_secs.max(1) as i128;
+    gov_oracle.twap_price = u64::try_from(twap + (clamped - twap) * weight / window).map_err(|_| ErrorCode::Overflow)?;
+    gov_oracle.last_update = now;
+
+    Ok(clamped as u64)
+}
+
+/// Read the governance token price.
+///
+/// `pyth_feed` must be supplied whenever the token has a listed feed, so a
+/// caller cannot fall back to the AMM TWAP alone by omitting it.
+pub fn gov_token_price(gov_oracle: &GovTokenOracle, pyth_feed: Option<&AccountInfo>, oracle_programs: &[Pubkey], now: i64) -> Result<GovTokenPrice> {
+    let twap_age = now.saturating_sub(gov_oracle.last_update);
+    let twap = (twap_age >= 0 && twap_age as u64 <= gov_oracle.max_twap_age_secs && gov_oracle.twap_price > 0)
+        .then_some(gov_oracle.twap_price);
+
+    let combined = if gov_oracle.pyth_feed == Pubkey::default() {
+        twap.map(|price| GovTokenPrice { price, source: GovPriceSource::AmmTwap })
+    } else {
+        let pyth_feed = pyth_feed.ok_or(ErrorCode::InvalidOracleAccount)?;
+        require_keys_eq!(pyth_feed.key(), gov_oracle.pyth_feed, ErrorCode::InvalidOracleAccount);
+        let pyth = oracle::read_feed_price(pyth_feed, gov_oracle.max_price_age_secs, oracle_programs).ok().map(|oracle_price| oracle_price.price);
+        match (twap, pyth) {
+            (Some(twap), Some(pyth)) if within_divergence(twap, pyth, gov_oracle.max_divergence_bps) => Some(GovTokenPrice {
+                price: ((twap as u128 + pyth as u128) / 2) as u64,
+                source: GovPriceSource::Combined,
+            }),
+            _ => None,
+        }
+    };
+
+    match combined {
+        Some(price) => Ok(price),
+        None if gov_oracle.fallback_price > 0 => Ok(GovTokenPrice {
+            price: gov_oracle.fallback_price,
+            source: GovPriceSource::Fallback,
+        }),
+        None => err!(ErrorCode::GovTokenPriceUnavailable),
+    }
+}
+
+/// Whether two prices are within `max_divergence_bps` of their lower value.
+fn within_divergence(a: u64, b: u64, max_divergence_bps: u64) -> bool {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    (high - low) as u128 * BPS_DENOMINATOR as u128 <= low as u128 * max_divergence_bps as u128
+}
@@ -1,8 +1,22 @@
 // instructions.rs
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, MintTo, Transfer, TokenAccount, Mint, Token};
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Burn, CloseAccount, MintTo, Transfer, TokenAccount, Mint, Token};
 
+use crate::permit::{self, PermitAction, PermitMessage};
+use crate::gov_oracle;
+use crate::loyalty;
+use crate::math::{self, FeeCharge, REWARD_PRECISION};
+use crate::stake_pool::{self, DepositSolAccounts};
+use crate::receipt;
+use crate::oracle::{self, DEFAULT_MAX_CONFIDENCE_BPS, DEFAULT_MAX_PRICE_AGE_SECS, PRICE_PRECISION};
 use crate::state::*;
 use crate::errors::*;
 use crate::errors::ErrorCode;
@@ -17,6 +31,7 @@ pub fn initialize(ctx: Context<Initialize>, collateral_ratio: u64) -> Result<()>
 
     let governance = &mut ctx.accounts.governance;
     governance.collateral_ratio = collateral_ratio;
+    governance.bump = ctx.bumps.governance;
 
     // Emit an event for the protocol initialization
     emit!(ProtocolInitialized {
@@ -26,28 +41,111 @@ pub fn initialize(ctx: Context<Initialize>, collateral_ratio: u64) -> Result<()>
     Ok(())
 }
 
+/// Open a position at the protocol collateral ratio, with an optional tag for reconciliation.
+pub fn open_position(ctx: Context<OpenPosition>, tag: [u8; 32]) -> Result<()> {
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.collateral_balance = 0;
+    user_account.stablecoin_balance = 0;
+    user_account.collateral_ratio = ctx.accounts.governance.collateral_ratio;
+    user_account.health_band = HealthBand::Healthy;
+    user_account.collateral_type = Pubkey::default();
+    user_account.tag = tag;
+    user_account.last_liquidation_time = 0;
+    user_account.last_liquidation_ratio = u64::MAX;
+    user_account.entry_exchange_rate = 0;
+    user_account.owner = ctx.accounts.payer.key();
+    user_account.subaccount_index = 0;
+    user_account.margin_account = Pubkey::default();
+    user_account.opened_at = Clock::get()?.unix_timestamp;
+
+    emit!(PositionOpenedEvent {
+        user: user_account.key(),
+        collateral_ratio: user_account.collateral_ratio,
+        tag,
+    });
+
+    Ok(())
+}
+
+/// Open an isolated sub-account of the owner, bound to one collateral type.
+///
+/// Each index is its own PDA, so a wallet can hold several independent risk
+/// buckets per collateral type without managing extra keypairs.
+pub fn create_subaccount(ctx: Context<CreateSubaccount>, index: u16, tag: [u8; 32]) -> Result<()> {
+    let collateral_type = &ctx.accounts.collateral_type;
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.collateral_balance = 0;
+    user_account.stablecoin_balance = 0;
+    user_account.collateral_ratio = collateral_type.collateral_ratio;
+    user_account.health_band = HealthBand::Healthy;
+    user_account.collateral_type = collateral_type.key();
+    user_account.tag = tag;
+    user_account.last_liquidation_time = 0;
+    user_account.last_liquidation_ratio = u64::MAX;
+    user_account.entry_exchange_rate = collateral_type.exchange_rate;
+    user_account.owner = ctx.accounts.owner.key();
+    user_account.subaccount_index = index;
+    user_account.margin_account = Pubkey::default();
+    user_account.opened_at = Clock::get()?.unix_timestamp;
+
+    emit!(SubaccountCreatedEvent {
+        user: user_account.key(),
+        owner: user_account.owner,
+        collateral_type: user_account.collateral_type,
+        index,
+        tag,
+    });
+
+    Ok(())
+}
+
 // -------------------------------------
 // Minting and Burning Instructions
 // -------------------------------------
 
 /// Mint stablecoin with a dynamic fee based on the current price.
-pub fn mint_stablecoin(ctx: Context<MintStablecoin>, amount: u64, current_price: u64) -> Result<()> {
+pub fn mint_stablecoin(
+    ctx: Context<MintStablecoin>,
+    amount: u64,
+    current_price: u64,
+    deadline_ts: Option<i64>,
+    max_fee: Option<u64>,
+    partner_id: Option<u64>,
+) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
     require!(current_price > 0, ErrorCode::InvalidPrice);
+    check_deadline(deadline_ts)?;
+
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Mint)?;
+    if ctx.accounts.user_account.collateral_type != Pubkey::default() {
+        let collateral_type = ctx.accounts.collateral_type.as_ref().ok_or(ErrorCode::InvalidCollateralType)?;
+        check_collateral_minting(collateral_type)?;
+    }
+    acquire_operation_lock(&mut ctx.accounts.system_state)?;
+    enforce_peg_band(&ctx.accounts.peg_state, Clock::get()?.unix_timestamp)?;
 
     let user_account = &mut ctx.accounts.user_account;
     let mint = &ctx.accounts.stablecoin_mint;
 
     // Calculate minting fee based on the price of the stablecoin
-    let mut fee = amount / 100; // Default 1% fee
+    let mut fee_bps = 100; // Default 1% fee
     if current_price > 100 {
-        fee /= 2; // Reduce fee if the stablecoin price is above $1.00
+        fee_bps /= 2; // Reduce fee if the stablecoin price is above $1.00
     }
+    let FeeCharge { fee, rounding_remainder } = math::fee_charge(amount, fee_bps)?;
+    require!(fee <= max_fee.unwrap_or(u64::MAX), ErrorCode::SlippageExceeded);
+    let (fee_due, fee_credits_used) = spend_fee_credits(user_account, fee);
 
     // Ensure the user has enough collateral to mint the stablecoin
     let total_amount = amount + fee;
+    let collateral_ratio = onboarding_collateral_ratio(
+        &ctx.accounts.system_state,
+        user_account,
+        user_account.collateral_ratio,
+        Clock::get()?.unix_timestamp,
+    );
     let required_collateral = total_amount
-        .checked_mul(user_account.collateral_ratio)
+        .checked_mul(collateral_ratio)
         .ok_or(ErrorCode::Overflow)?;
     require!(
         user_account.collateral_balance >= required_collateral,
@@ -69,6 +167,10 @@ pub fn mint_stablecoin(ctx: Context<MintStablecoin>, amount: u64, current_price:
         .stablecoin_balance
         .checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
+    enforce_launch_caps(user_account, &ctx.accounts.system_state)?;
+
+    // Notify watchers if the new debt moved the position across a health band
+    update_health_band(user_account, &ctx.accounts.system_state, ctx.accounts.collateral_type.as_deref(), None)?;
 
     // Mint the fee to a treasury or governance account
     let cpi_accounts_fee = MintTo {
@@ -77,13 +179,27 @@ pub fn mint_stablecoin(ctx: Context<MintStablecoin>, amount: u64, current_price:
         authority: ctx.accounts.payer.to_account_info(),
     };
     let cpi_ctx_fee = CpiContext::new(cpi_program, cpi_accounts_fee);
-    token::mint_to(cpi_ctx_fee, fee)?;
+    token::mint_to(cpi_ctx_fee, fee_due)?;
+    accrue_partner_revenue(ctx.accounts.partner.as_mut(), partner_id, amount, fee_due)?;
+
+    // Untyped debt has no collateral type to record it, so it is tracked on its own
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.legacy_issuance = protocol_stats.legacy_issuance.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    protocol_stats.fee_issuance = protocol_stats.fee_issuance.checked_add(fee_due).ok_or(ErrorCode::Overflow)?;
+
+    release_operation_lock(&mut ctx.accounts.system_state);
+
+    let price = ledger_price(&ctx.accounts.user_account, None);
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Mint, 0, amount, price)?;
 
     // Emit an event for the minting action
     emit!(MintStablecoinEvent {
         user: ctx.accounts.user_account.key(),
         amount,
         fee,
+        rounding_remainder,
+        fee_credits_used,
+        tag: ctx.accounts.user_account.tag,
     });
 
     Ok(())
@@ -93,21 +209,106 @@ pub fn mint_stablecoin(ctx: Context<MintStablecoin>, amount: u64, current_price:
 // Liquidation Instructions
 // -------------------------------------
 
+/// Flat bonus for positions not linked to a collateral type (10%).
+pub const LEGACY_LIQUIDATION_BONUS_BPS: u64 = 1_000;
+/// Default slope of the liquidation bonus curve for new collateral.
+pub const DEFAULT_LIQUIDATION_BONUS_SLOPE_BPS: u64 = 5_000;
+/// Default upper bound of the liquidation bonus for new collateral.
+pub const DEFAULT_MAX_LIQUIDATION_BONUS_BPS: u64 = 2_000;
+
+/// Liquidation bonus for a position at `collateral_ratio` (percent).
+///
+/// At the liquidation threshold the bonus equals the collateral's base
+/// penalty; it then grows linearly with the relative depth below the
+/// threshold, capped at the collateral's maximum bonus. Shared by every
+/// liquidation path.
+pub fn liquidation_bonus_bps(collateral_type: &CollateralType, collateral_ratio: u64) -> u64 {
+    let threshold = collateral_type.liquidation_threshold;
+    let depth_bps = if threshold > collateral_ratio {
+        (threshold - collateral_ratio) as u128 * 10_000 / threshold as u128
+    } else {
+        0
+    };
+    let extra_bps = depth_bps * collateral_type.liquidation_bonus_slope_bps as u128 / 10_000;
+
+    (collateral_type.liquidation_penalty_bps as u128 + extra_bps)
+        .min(collateral_type.max_liquidation_bonus_bps as u128) as u64
+}
+
+/// Default gap (percentage points) between the collateral ratio and the liquidation threshold.
+pub const DEFAULT_LIQUIDATION_BUFFER: u64 = 20;
+
+/// Collateral ratio (percent) below which a position can be liquidated.
+///
+/// Positions backed by a collateral type use its liquidation threshold, which
+/// sits below the collateral ratio new debt must respect. Legacy positions
+/// only carry a single ratio.
+pub fn liquidation_ratio_floor(user_account: &UserAccount, collateral_type: Option<&CollateralType>) -> u64 {
+    match collateral_type {
+        Some(collateral_type) => collateral_type.liquidation_threshold,
+        None => user_account.collateral_ratio,
+    }
+}
+
+/// Value of a position's collateral in stablecoin units.
+///
+/// Positions backed by a collateral type are valued with its cached oracle
+/// price; legacy positions count collateral one-to-one.
+pub fn position_collateral_value(user_account: &UserAccount, collateral_type: Option<&CollateralType>, price_cache: Option<&PriceCache>) -> Result<u128> {
+    match (collateral_type, price_cache) {
+        (Some(collateral_type), Some(price_cache)) => {
+            let oracle_price = oracle::read_cached_price(price_cache, collateral_type)?;
+            Ok((user_account.collateral_balance as u128)
+                .checked_mul(oracle_price.price as u128)
+                .ok_or(ErrorCode::Overflow)?
+                / PRICE_PRECISION as u128)
+        }
+        (Some(_), None) => err!(ErrorCode::InvalidOracleAccount),
+        (None, _) => {
+            require!(user_account.collateral_type == Pubkey::default(), ErrorCode::InvalidCollateralType);
+            Ok(user_account.collateral_balance as u128)
+        }
+    }
+}
+
 /// Partially liquidate a user's under-collateralized position.
 pub fn partial_liquidate(ctx: Context<Liquidate>, liquidation_amount: u64) -> Result<()> {
     require!(liquidation_amount > 0, ErrorCode::InvalidAmount);
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Liquidate)?;
 
     let user_account = &mut ctx.accounts.user_account;
 
-    // Check if the user is under-collateralized
-    let current_ratio = (user_account.collateral_balance * 100) / user_account.stablecoin_balance;
+    let collateral_value = position_collateral_value(
+        user_account,
+        ctx.accounts.collateral_type.as_deref(),
+        ctx.accounts.price_cache.as_deref(),
+    )?;
+    let current_ratio = u64::try_from(collateral_value * 100 / user_account.stablecoin_balance as u128)
+        .map_err(|_| ErrorCode::Overflow)?;
+
+    // Check if the user is below the liquidation threshold, not merely below the mint target
+    require_liquidatable(
+        user_account,
+        ctx.accounts.collateral_type.as_deref(),
+        ctx.accounts.margin_account.as_deref(),
+        ctx.remaining_accounts,
+        current_ratio,
+    )?;
+
+    // Space out successive bites unless the position kept deteriorating since the last one
+    let now = Clock::get()?.unix_timestamp as u64;
+    let cooldown_end = user_account.last_liquidation_time.saturating_add(ctx.accounts.system_state.liquidation_cooldown_secs);
     require!(
-        current_ratio < user_account.collateral_ratio,
-        ErrorCode::NotEligibleForLiquidation
+        now >= cooldown_end || current_ratio < user_account.last_liquidation_ratio,
+        ErrorCode::LiquidationCooldownActive
     );
 
-    // Calculate the liquidation penalty (e.g., 10%)
-    let penalty = liquidation_amount / 10;
+    // Scale the liquidation bonus with how far the position is underwater
+    let bonus_bps = match &ctx.accounts.collateral_type {
+        Some(collateral_type) => liquidation_bonus_bps(collateral_type, current_ratio),
+        None => LEGACY_LIQUIDATION_BONUS_BPS,
+    };
+    let penalty = ((liquidation_amount as u128 * bonus_bps as u128) / 10_000) as u64;
     let remaining_collateral = liquidation_amount.checked_sub(penalty).ok_or(ErrorCode::Overflow)?;
 
     // Deduct the stablecoin and collateral from the user's account
@@ -119,34 +320,432 @@ pub fn partial_liquidate(ctx: Context<Liquidate>, liquidation_amount: u64) -> Re
         .checked_sub(remaining_collateral)
         .ok_or(ErrorCode::Overflow)?;
 
-    // Transfer the penalty to the liquidator's account
-    ctx.accounts.liquidator_collateral_account.amount += penalty;
+    // Skim the protocol's share of the yield the position earned since entry, then restart
+    // accrual at the current rate so the same yield is never skimmed twice
+    if let Some(collateral_type) = &ctx.accounts.collateral_type {
+        let position_collateral = user_account.collateral_balance.checked_add(remaining_collateral).ok_or(ErrorCode::Overflow)?;
+        let skim = collateral_yield_skim(user_account, collateral_type, position_collateral)?.min(user_account.collateral_balance);
+        if skim > 0 {
+            let entry_exchange_rate = user_account.entry_exchange_rate;
+            user_account.collateral_balance -= skim;
+            user_account.entry_exchange_rate = collateral_type.exchange_rate;
+            let revenue = &mut ctx.accounts.liquidation_revenue;
+            revenue.surplus_buffer_balance = revenue.surplus_buffer_balance.checked_add(skim).ok_or(ErrorCode::Overflow)?;
+
+            emit!(CollateralYieldSkimmedEvent {
+                user: user_account.key(),
+                collateral_type: collateral_type.key(),
+                amount: skim,
+                entry_exchange_rate,
+                exchange_rate: collateral_type.exchange_rate,
+            });
+        }
+    }
+
+    // Record where this liquidation left the position for the cooldown check
+    user_account.last_liquidation_time = now;
+    user_account.last_liquidation_ratio = if user_account.stablecoin_balance == 0 {
+        u64::MAX
+    } else {
+        let collateral_value = position_collateral_value(
+            user_account,
+            ctx.accounts.collateral_type.as_deref(),
+            ctx.accounts.price_cache.as_deref(),
+        )?;
+        u64::try_from(collateral_value * 100 / user_account.stablecoin_balance as u128).unwrap_or(u64::MAX)
+    };
+
+    update_health_band(
+        user_account,
+        &ctx.accounts.system_state,
+        ctx.accounts.collateral_type.as_deref(),
+        ctx.accounts.price_cache.as_deref(),
+    )?;
+
+    // Split the penalty between the liquidator and the protocol funds
+    let split = distribute_liquidation_proceeds(&mut ctx.accounts.liquidation_revenue, penalty)?;
+
+    // Credit the liquidator's share to the liquidator's position
+    credit_liquidator_position(
+        &mut ctx.accounts.liquidator_account,
+        ctx.accounts.user_account.collateral_type,
+        ctx.accounts.collateral_type.as_deref(),
+        split.liquidator,
+    )?;
+
+    // Reimburse the liquidator's transaction costs out of the protocol's surplus share
+    if let (Some(liquidator_stablecoin_account), Some(treasury_account), Some(revenue_authority), Some(revenue_authority_bump)) = (
+        ctx.accounts.liquidator_stablecoin_account.as_ref(),
+        ctx.accounts.treasury_account.as_ref(),
+        ctx.accounts.revenue_authority.as_ref(),
+        ctx.bumps.revenue_authority,
+    ) {
+        let collateral_price = match (&ctx.accounts.collateral_type, &ctx.accounts.price_cache) {
+            (Some(collateral_type), Some(price_cache)) => oracle::read_cached_price(price_cache, collateral_type)?.price,
+            _ => PRICE_PRECISION,
+        };
+        let revenue = &mut ctx.accounts.liquidation_revenue;
+        let (reimbursement, collateral_debit) = liquidator_reimbursement(revenue, split.surplus, collateral_price)?;
+        let reimbursement = reimbursement.min(treasury_allowance(treasury_account, revenue_authority));
+        if reimbursement > 0 {
+            let bump = [revenue_authority_bump];
+            let seeds: &[&[u8]] = &[b"revenue_authority", &bump];
+            let signer_seeds = &[seeds];
+            let cpi_accounts = Transfer {
+                from: treasury_account.to_account_info(),
+                to: liquidator_stablecoin_account.to_account_info(),
+                authority: revenue_authority.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+            token::transfer(cpi_ctx, reimbursement)?;
+
+            revenue.surplus_buffer_balance = revenue.surplus_buffer_balance.saturating_sub(collateral_debit);
+            revenue.total_reimbursed = revenue.total_reimbursed.checked_add(reimbursement).ok_or(ErrorCode::Overflow)?;
+
+            emit!(LiquidatorReimbursedEvent {
+                user: ctx.accounts.user_account.key(),
+                liquidator: ctx.accounts.payer.key(),
+                amount: reimbursement,
+                collateral_debit,
+            });
+        }
+    }
+
+    let price = ledger_price(&ctx.accounts.user_account, ctx.accounts.price_cache.as_deref());
+    record_ledger_entry(
+        ctx.accounts.position_ledger.as_deref_mut(),
+        LedgerEntryKind::Liquidation,
+        remaining_collateral,
+        liquidation_amount,
+        price,
+    )?;
 
     // Emit an event for the liquidation
     emit!(LiquidationEvent {
         user: ctx.accounts.user_account.key(),
         amount: liquidation_amount,
         penalty,
+        tag: ctx.accounts.user_account.tag,
+    });
+
+    Ok(())
+}
+
+/// Credit liquidated collateral to the liquidator's own position.
+///
+/// The collateral can only land in a position backed by the same collateral
+/// type; an empty legacy position adopts the liquidated position's type.
+fn credit_liquidator_position(
+    liquidator_account: &mut UserAccount,
+    collateral_type_key: Pubkey,
+    collateral_type: Option<&CollateralType>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        liquidator_account.collateral_type == collateral_type_key || liquidator_account.collateral_type == Pubkey::default(),
+        ErrorCode::InvalidCollateralType
+    );
+    if liquidator_account.collateral_type == Pubkey::default() && collateral_type_key != Pubkey::default() {
+        let collateral_type = collateral_type.ok_or(ErrorCode::InvalidCollateralType)?;
+        liquidator_account.collateral_type = collateral_type_key;
+        liquidator_account.entry_exchange_rate = collateral_type.exchange_rate;
+    }
+    liquidator_account.collateral_balance = liquidator_account.collateral_balance
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    Ok(())
+}
+
+/// Liquidate an insolvent position in full, leaving it empty.
+///
+/// The liquidator burns stablecoin worth the collateral less the liquidation
+/// bonus and takes all the collateral into its own position, so the bonus is
+/// the penalty it earns. The remaining debt is covered by the insurance pool
+/// where possible and recorded as bad debt otherwise. The liquidated position
+/// is zeroed rather than closed so its record survives.
+pub fn full_liquidate(ctx: Context<FullLiquidate>) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Liquidate)?;
+
+    let user_account = &ctx.accounts.user_account;
+    let debt = user_account.stablecoin_balance;
+    require!(debt > 0, ErrorCode::InvalidAmount);
+
+    let collateral_value = position_collateral_value(
+        user_account,
+        ctx.accounts.collateral_type.as_deref(),
+        ctx.accounts.price_cache.as_deref(),
+    )?;
+    let current_ratio = u64::try_from(collateral_value * 100 / debt as u128).map_err(|_| ErrorCode::Overflow)?;
+    require!(current_ratio < 100, ErrorCode::PositionNotInsolvent);
+
+    // A cross-margin position underwater on its own is still backed by the rest of the portfolio
+    if let Some(margin_account) = ctx.accounts.margin_account.as_deref() {
+        if margin_account.mode == MarginMode::Cross {
+            let portfolio = portfolio_health(margin_account, ctx.remaining_accounts)?;
+            require!(portfolio.liquidatable, ErrorCode::PositionNotInsolvent);
+        }
+    } else {
+        require!(user_account.margin_account == Pubkey::default(), ErrorCode::InvalidMarginAccount);
+    }
+
+    // The liquidator pays for the collateral at a discount equal to the bonus
+    let bonus_bps = match &ctx.accounts.collateral_type {
+        Some(collateral_type) => liquidation_bonus_bps(collateral_type, current_ratio),
+        None => LEGACY_LIQUIDATION_BONUS_BPS,
+    };
+    let repay_amount = (collateral_value * (10_000 - bonus_bps) as u128 / 10_000) as u64;
+    let collateral_seized = user_account.collateral_balance;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.liquidator_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, repay_amount)?;
+
+    // Move the collateral, bonus included, into the liquidator's position
+    let collateral_type_key = user_account.collateral_type;
+    credit_liquidator_position(
+        &mut ctx.accounts.liquidator_account,
+        collateral_type_key,
+        ctx.accounts.collateral_type.as_deref(),
+        collateral_seized,
+    )?;
+
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.collateral_balance = 0;
+    user_account.stablecoin_balance = 0;
+    user_account.last_liquidation_time = Clock::get()?.unix_timestamp as u64;
+    user_account.last_liquidation_ratio = u64::MAX;
+    user_account.health_band = HealthBand::Healthy;
+
+    // Cover the shortfall from the insurance pool, recording the rest as bad debt
+    let shortfall = debt - repay_amount;
+    let (insurance_covered, bad_debt) = absorb_shortfall(
+        &mut ctx.accounts.liquidation_revenue,
+        &mut ctx.accounts.bad_debt_queue,
+        user_account.key(),
+        collateral_type_key,
+        debt,
+        shortfall,
+        ShortfallSource::FullLiquidation,
+    )?;
+
+    match &mut ctx.accounts.collateral_type {
+        Some(collateral_type) => release_collateral_debt(collateral_type, &mut ctx.accounts.protocol_stats, debt),
+        None => {
+            let protocol_stats = &mut ctx.accounts.protocol_stats;
+            protocol_stats.legacy_issuance = protocol_stats.legacy_issuance.saturating_sub(debt);
+        }
+    }
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.written_off_debt = protocol_stats.written_off_debt.checked_add(shortfall).ok_or(ErrorCode::Overflow)?;
+    emit_solvency_ratio(&ctx.accounts.protocol_stats, &ctx.accounts.liquidation_revenue, &ctx.accounts.bad_debt_queue);
+
+    let price = ledger_price(&ctx.accounts.user_account, ctx.accounts.price_cache.as_deref());
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Liquidation, collateral_seized, debt, price)?;
+    record_ledger_entry(ctx.accounts.liquidator_ledger.as_deref_mut(), LedgerEntryKind::Deposit, collateral_seized, 0, price)?;
+
+    emit!(FullLiquidationEvent {
+        user: ctx.accounts.user_account.key(),
+        collateral_seized,
+        debt,
+        debt_repaid: repay_amount,
+        insurance_covered,
+        bad_debt,
+        tag: ctx.accounts.user_account.tag,
+    });
+
+    Ok(())
+}
+
+/// Set the minimum time between partial liquidations of the same position.
+pub fn set_liquidation_cooldown(ctx: Context<SetLiquidationCooldown>, cooldown_secs: u64) -> Result<()> {
+    ctx.accounts.system_state.liquidation_cooldown_secs = cooldown_secs;
+
+    emit!(LiquidationCooldownUpdatedEvent {
+        cooldown_secs,
+    });
+
+    Ok(())
+}
+
+/// Set the extra collateral ratio new positions must hold and how long it takes to decay.
+pub fn set_onboarding_ratio(ctx: Context<SetOnboardingRatio>, ratio_bonus: u64, period_secs: u64) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.onboarding_ratio_bonus = ratio_bonus;
+    system_state.onboarding_period_secs = period_secs;
+
+    emit!(OnboardingRatioUpdatedEvent {
+        ratio_bonus,
+        period_secs,
+    });
+
+    Ok(())
+}
+
+/// Collateral ratio a position must keep when taking on debt at `now`.
+///
+/// Newly opened positions owe `base_ratio` plus the onboarding bonus, decaying
+/// linearly to `base_ratio` over the onboarding period, so a flash-funded
+/// position cannot mint at the normal ratio right after opening.
+pub fn onboarding_collateral_ratio(system_state: &SystemState, user_account: &UserAccount, base_ratio: u64, now: i64) -> u64 {
+    let age = now.saturating_sub(user_account.opened_at).max(0) as u64;
+    if system_state.onboarding_period_secs == 0 || age >= system_state.onboarding_period_secs {
+        return base_ratio;
+    }
+
+    let remaining = system_state.onboarding_period_secs - age;
+    let bonus = (system_state.onboarding_ratio_bonus as u128 * remaining as u128 / system_state.onboarding_period_secs as u128) as u64;
+    base_ratio.saturating_add(bonus)
+}
+
+/// Shares of a liquidation penalty.
+#[derive(Clone, Copy, Debug)]
+pub struct LiquidationSplit {
+    pub liquidator: u64,
+    pub insurance: u64,
+    pub surplus: u64,
+}
+
+/// Split a liquidation penalty between the liquidator, the insurance pool and the surplus buffer.
+///
+/// Every liquidation path routes its penalty through here. Rounding dust
+/// goes to the surplus buffer so the three shares always sum to the penalty.
+/// The protocol's shares are accounting entries in `LiquidationRevenue`; no
+/// tokens move here, the penalty collateral stays in the collateral vaults.
+pub fn distribute_liquidation_proceeds(revenue: &mut LiquidationRevenue, penalty: u64) -> Result<LiquidationSplit> {
+    let liquidator = ((penalty as u128 * revenue.liquidator_share_bps as u128) / 10_000) as u64;
+    let insurance = ((penalty as u128 * revenue.insurance_share_bps as u128) / 10_000) as u64;
+    let surplus = penalty - liquidator - insurance;
+
+    revenue.insurance_pool_balance = revenue.insurance_pool_balance.checked_add(insurance).ok_or(ErrorCode::Overflow)?;
+    revenue.surplus_buffer_balance = revenue.surplus_buffer_balance.checked_add(surplus).ok_or(ErrorCode::Overflow)?;
+    revenue.total_proceeds = revenue.total_proceeds.checked_add(insurance + surplus).ok_or(ErrorCode::Overflow)?;
+
+    emit!(LiquidationProceedsDistributedEvent {
+        penalty,
+        liquidator_share: liquidator,
+        insurance_share: insurance,
+        surplus_share: surplus,
+    });
+
+    Ok(LiquidationSplit { liquidator, insurance, surplus })
+}
+
+/// Initialize the liquidation revenue split.
+pub fn initialize_liquidation_revenue(ctx: Context<InitializeLiquidationRevenue>, liquidator_share_bps: u64, insurance_share_bps: u64) -> Result<()> {
+    let revenue = &mut ctx.accounts.liquidation_revenue;
+    revenue.liquidator_share_bps = liquidator_share_bps;
+    revenue.insurance_share_bps = insurance_share_bps;
+    revenue.surplus_share_bps = 10_000 - liquidator_share_bps - insurance_share_bps;
+    revenue.insurance_pool_balance = 0;
+    revenue.surplus_buffer_balance = 0;
+    revenue.bad_debt = 0;
+    revenue.total_proceeds = 0;
+    revenue.liquidator_reimbursement = 0;
+    revenue.total_reimbursed = 0;
+
+    Ok(())
+}
+
+/// Update the liquidation revenue split.
+pub fn set_liquidation_revenue_shares(ctx: Context<SetLiquidationRevenueShares>, liquidator_share_bps: u64, insurance_share_bps: u64) -> Result<()> {
+    let revenue = &mut ctx.accounts.liquidation_revenue;
+    revenue.liquidator_share_bps = liquidator_share_bps;
+    revenue.insurance_share_bps = insurance_share_bps;
+    revenue.surplus_share_bps = 10_000 - liquidator_share_bps - insurance_share_bps;
+
+    emit!(LiquidationRevenueSharesUpdatedEvent {
+        liquidator_share_bps,
+        insurance_share_bps,
+        surplus_share_bps: revenue.surplus_share_bps,
+    });
+
+    Ok(())
+}
+
+/// Set the stablecoin reimbursed to liquidators per partial liquidation.
+pub fn set_liquidator_reimbursement(ctx: Context<SetLiquidatorReimbursement>, amount: u64) -> Result<()> {
+    require!(amount <= MAX_LIQUIDATOR_REIMBURSEMENT, ErrorCode::InvalidAmount);
+    ctx.accounts.liquidation_revenue.liquidator_reimbursement = amount;
+
+    emit!(LiquidatorReimbursementUpdatedEvent {
+        amount,
     });
 
     Ok(())
 }
 
+/// Stablecoin reimbursed to a liquidator and the surplus collateral it is drawn from.
+///
+/// The reimbursement never exceeds the value of the liquidation's surplus
+/// share, so it cannot cost the protocol more than the liquidation earned it.
+/// The collateral debit rounds up so the buffer always covers the payout.
+pub fn liquidator_reimbursement(revenue: &LiquidationRevenue, surplus_share: u64, collateral_price: u64) -> Result<(u64, u64)> {
+    let surplus_value = math::mul_div_down(surplus_share as u128, collateral_price as u128, PRICE_PRECISION as u128)?;
+    let reimbursement = revenue.liquidator_reimbursement.min(surplus_value);
+    if reimbursement == 0 {
+        return Ok((0, 0));
+    }
+
+    let collateral_debit = math::mul_div_up(reimbursement as u128, PRICE_PRECISION as u128, collateral_price as u128)?.min(surplus_share);
+    Ok((reimbursement, collateral_debit))
+}
+
+/// Stablecoin the revenue authority may move out of the treasury: its remaining allowance, capped by the balance.
+pub fn treasury_allowance(treasury_account: &TokenAccount, revenue_authority: &UncheckedAccount) -> u64 {
+    if treasury_account.delegate == COption::Some(revenue_authority.key()) {
+        treasury_account.delegated_amount.min(treasury_account.amount)
+    } else {
+        0
+    }
+}
+
 // -------------------------------------
 // Staking Instructions
 // -------------------------------------
 
+/// Open an empty stake account in a reward pool, owned by the signer.
+pub fn open_staker_account(ctx: Context<OpenStakerAccount>) -> Result<()> {
+    let staker_account = &mut ctx.accounts.staker_account;
+    staker_account.owner = ctx.accounts.owner.key();
+    staker_account.reward_pool = ctx.accounts.reward_pool.key();
+    staker_account.reward_per_share_paid = ctx.accounts.reward_pool.accumulated_reward_per_share;
+    staker_account.last_reward_claim = Clock::get()?.unix_timestamp as u64;
+
+    Ok(())
+}
+
 /// Stake tokens to earn rewards with lock-up periods.
 pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64, lockup_period: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
     require!(lockup_period > 0, ErrorCode::InvalidLockupPeriod);
+    require!(amount >= ctx.accounts.reward_pool.min_stake_amount, ErrorCode::StakeBelowMinimum);
 
-    let staker_account = &mut ctx.accounts.staker_account;
-    staker_account.staked_balance = staker_account.staked_balance
-        .checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    staker_account.lockup_period = lockup_period;
-    staker_account.early_withdrawal_penalty = if lockup_period > 30 * 24 * 60 * 60 { 5 } else { 2 };
+    // The first staker owns the stake account and where its rewards go
+    if ctx.accounts.staker_account.owner == Pubkey::default() {
+        ctx.accounts.staker_account.owner = ctx.accounts.payer.key();
+    }
+    ctx.accounts.staker_account.reward_pool = ctx.accounts.reward_pool.key();
+
+    let now = Clock::get()?.unix_timestamp;
+    record_stake(
+        &mut ctx.accounts.reward_pool,
+        &mut ctx.accounts.staker_account,
+        amount,
+        lockup_period,
+        now as u64,
+    )?;
+    refresh_boost(
+        &mut ctx.accounts.reward_pool,
+        &mut ctx.accounts.staker_account,
+        ctx.accounts.ve_config.as_deref_mut(),
+        ctx.accounts.ve_lock.as_deref_mut(),
+        now,
+    )?;
 
     // Transfer the tokens to the staking pool
     let cpi_accounts = Transfer {
@@ -167,32 +766,135 @@ pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64, lockup_period: u64)
     Ok(())
 }
 
+/// Add a stake to the pool and the staker's balance.
+pub fn record_stake(reward_pool: &mut RewardPool, staker_account: &mut StakerAccount, amount: u64, lockup_period: u64, now: u64) -> Result<()> {
+    require!(reward_pool.sunset_deadline == 0, ErrorCode::PoolSunsetting);
+
+    // Settle rewards at the old balance before it changes
+    update_reward_pool(reward_pool, now)?;
+    settle_staker_rewards(staker_account, reward_pool)?;
+
+    // Track stake internally so tokens sent straight to the vault do not count
+    reward_pool.total_staked = reward_pool.total_staked
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    staker_account.staked_balance = staker_account.staked_balance
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    staker_account.lockup_period = lockup_period;
+    staker_account.early_withdrawal_penalty = if lockup_period > 30 * 24 * 60 * 60 { 5 } else { 2 };
+    let boost_bps = staker_boost_bps(staker_account);
+    set_working_balance(reward_pool, staker_account, boost_bps)?;
+
+    Ok(())
+}
+
 /// Withdraw staked tokens with optional early withdrawal penalty.
+///
+/// While an incident is active the first call only records an unstake
+/// request; the withdrawal goes through on a call made
+/// `INCIDENT_UNSTAKE_DELAY_SECS` later.
 pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
+    let reward_pool = &mut ctx.accounts.reward_pool;
     let staker_account = &mut ctx.accounts.staker_account;
     let current_time = ctx.accounts.clock.unix_timestamp as u64;
-    let penalty = if current_time < staker_account.lockup_period {
+
+    // During an incident every exit first waits out the incident delay, so
+    // stakers cannot leave the safety module just ahead of a slash
+    let system_state = &ctx.accounts.system_state;
+    if system_state.incident_active {
+        let now = ctx.accounts.clock.unix_timestamp;
+        if staker_account.unstake_requested_at < system_state.incident_declared_at {
+            staker_account.unstake_requested_at = now;
+
+            emit!(UnstakeDelayedEvent {
+                staker: staker_account.key(),
+                amount,
+                available_at: now + INCIDENT_UNSTAKE_DELAY_SECS,
+            });
+            return Ok(());
+        }
+        require!(
+            now >= staker_account.unstake_requested_at + INCIDENT_UNSTAKE_DELAY_SECS,
+            ErrorCode::IncidentUnstakeDelay
+        );
+    }
+    staker_account.unstake_requested_at = 0;
+
+    // Do not leave dust positions below the minimum stake behind
+    let remaining = staker_account.staked_balance.checked_sub(amount).ok_or(ErrorCode::InsufficientStakingBalance)?;
+    require!(remaining == 0 || remaining >= reward_pool.min_stake_amount, ErrorCode::StakeBelowMinimum);
+
+    update_reward_pool(reward_pool, current_time)?;
+    settle_staker_rewards(staker_account, reward_pool)?;
+
+    // A pool being sunset lets everyone leave without the early withdrawal penalty
+    let penalty = if current_time < staker_account.lockup_period && reward_pool.sunset_deadline == 0 {
         amount * staker_account.early_withdrawal_penalty / 100
     } else {
         0
     };
 
-    let final_amount = amount.checked_sub(penalty).ok_or(ErrorCode::Overflow)?;
+    let mut final_amount = amount.checked_sub(penalty).ok_or(ErrorCode::Overflow)?;
 
-    // Transfer the staked tokens back to the user
+    // Once bad debt has slashed the vault below total stake, every withdrawal
+    // shares the loss pro rata. Staked rewards not yet minted still back their stake.
+    let vault_balance = ctx.accounts.staking_pool.amount
+        .checked_add(reward_pool.unsettled_stake)
+        .ok_or(ErrorCode::Overflow)?;
+    if vault_balance < reward_pool.total_staked {
+        final_amount = (final_amount as u128 * vault_balance as u128 / reward_pool.total_staked as u128) as u64;
+    }
+
+    // Mint the rewards `claim_to_stake` credited to this stake into the vault before paying out
+    let reward_pool_key = reward_pool.key();
+    let unsettled_stake = staker_account.unsettled_stake;
+    if unsettled_stake > 0 {
+        let emission_schedule = ctx.accounts.emission_schedule.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let reward_token_mint = ctx.accounts.reward_token_mint.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let reward_mint_authority = ctx.accounts.reward_mint_authority.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+
+        let bump = [emission_schedule.authority_bump];
+        let seeds: &[&[u8]] = &[b"reward_mint_authority", reward_pool_key.as_ref(), &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = MintTo {
+            mint: reward_token_mint.to_account_info(),
+            to: ctx.accounts.staking_pool.to_account_info(),
+            authority: reward_mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::mint_to(cpi_ctx, unsettled_stake)?;
+
+        staker_account.unsettled_stake = 0;
+        reward_pool.unsettled_stake = reward_pool.unsettled_stake.checked_sub(unsettled_stake).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Transfer the staked tokens back to the user, signed by the vault authority
+    let bump = [reward_pool.staking_authority_bump];
+    let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
     let cpi_accounts = Transfer {
         from: ctx.accounts.staking_pool.to_account_info(),
         to: ctx.accounts.user_token_account.to_account_info(),
-        authority: ctx.accounts.payer.to_account_info(),
+        authority: ctx.accounts.staking_authority.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
     token::transfer(cpi_ctx, final_amount)?;
 
     // Update the staked balance
-    staker_account.staked_balance = staker_account.staked_balance.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+    staker_account.staked_balance = remaining;
+    reward_pool.total_staked = reward_pool.total_staked.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+    refresh_boost(
+        reward_pool,
+        staker_account,
+        ctx.accounts.ve_config.as_deref_mut(),
+        ctx.accounts.ve_lock.as_deref_mut(),
+        current_time as i64,
+    )?;
 
     // Emit an event for the withdrawal
     emit!(WithdrawStakeEvent {
@@ -204,33 +906,329 @@ pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
     Ok(())
 }
 
+/// Initialize the staking reward pool.
+pub fn initialize_reward_pool(ctx: Context<InitializeRewardPool>, reward_rate: u64, min_stake_amount: u64) -> Result<()> {
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.total_staked = 0;
+    reward_pool.reward_rate = reward_rate;
+    reward_pool.last_update_time = Clock::get()?.unix_timestamp as u64;
+    reward_pool.accumulated_reward_per_share = 0;
+    reward_pool.min_stake_amount = min_stake_amount;
+    reward_pool.reward_mint = ctx.accounts.reward_mint.key();
+    reward_pool.system_state = ctx.accounts.system_state.key();
+    reward_pool.min_claim_interval = 0;
+    reward_pool.min_claim_amount = 0;
+    reward_pool.total_working = 0;
+    reward_pool.reward_residue = 0;
+    reward_pool.last_run_slot = 0;
+    reward_pool.reward_source = RewardSource::Emissions;
+    reward_pool.reward_vault = Pubkey::default();
+    reward_pool.revenue_share_bps = 0;
+    reward_pool.undistributed_revenue = 0;
+    reward_pool.sunset_deadline = 0;
+    reward_pool.claim_to_stake_threshold = 0;
+    reward_pool.unsettled_stake = 0;
+    reward_pool.slashed_stake = 0;
+
+    Ok(())
+}
+
+/// Create the staking vault, owned by a PDA so withdrawals can be signed by the program.
+pub fn initialize_staking_pool(ctx: Context<InitializeStakingPool>) -> Result<()> {
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.staking_vault = ctx.accounts.staking_pool.key();
+    reward_pool.staking_authority_bump = ctx.bumps.staking_authority;
+
+    emit!(StakingPoolInitializedEvent {
+        reward_pool: reward_pool.key(),
+        staking_mint: ctx.accounts.staking_mint.key(),
+        staking_vault: reward_pool.staking_vault,
+    });
+
+    Ok(())
+}
+
+/// Set the claim cooldown and minimum claim size of a reward pool.
+pub fn set_claim_limits(ctx: Context<SetClaimLimits>, min_claim_interval: u64, min_claim_amount: u64) -> Result<()> {
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.min_claim_interval = min_claim_interval;
+    reward_pool.min_claim_amount = min_claim_amount;
+
+    emit!(ClaimLimitsUpdatedEvent {
+        reward_pool: reward_pool.key(),
+        min_claim_interval,
+        min_claim_amount,
+    });
+
+    Ok(())
+}
+
+/// Advance the reward accumulator to `now`.
+///
+/// While nothing is staked no rewards accrue, so the first staker cannot
+/// collect emissions for the period the pool was empty. The part of the
+/// emission the integer division drops is kept in `reward_residue` and
+/// distributed with the next update, so no rewards are lost to rounding.
+pub fn update_reward_pool(reward_pool: &mut RewardPool, now: u64) -> Result<()> {
+    if now <= reward_pool.last_update_time {
+        return Ok(());
+    }
+    if reward_pool.total_working > 0 {
+        let elapsed = (now - reward_pool.last_update_time) as u128;
+        let emitted = (reward_pool.reward_rate as u128)
+            .checked_mul(elapsed)
+            .and_then(|value| value.checked_mul(REWARD_PRECISION))
+            .and_then(|value| value.checked_add(reward_pool.reward_residue))
+            .ok_or(ErrorCode::Overflow)?;
+        let total_working = reward_pool.total_working as u128;
+        reward_pool.accumulated_reward_per_share = reward_pool.accumulated_reward_per_share
+            .checked_add(emitted / total_working)
+            .ok_or(ErrorCode::Overflow)?;
+        reward_pool.reward_residue = emitted % total_working;
+    }
+    reward_pool.last_update_time = now;
+
+    Ok(())
+}
+
+/// Move the staker's rewards earned since the last settlement into `reward_debt`.
+///
+/// Rounds down, so a settlement loses less than one base unit of rewards.
+pub fn settle_staker_rewards(staker_account: &mut StakerAccount, reward_pool: &RewardPool) -> Result<()> {
+    let delta = reward_pool.accumulated_reward_per_share
+        .checked_sub(staker_account.reward_per_share_paid)
+        .ok_or(ErrorCode::Overflow)?;
+    let earned = (staker_account.working_balance as u128)
+        .checked_mul(delta)
+        .ok_or(ErrorCode::Overflow)?
+        / REWARD_PRECISION;
+    staker_account.reward_debt = staker_account.reward_debt
+        .checked_add(u64::try_from(earned).map_err(|_| ErrorCode::Overflow)?)
+        .ok_or(ErrorCode::Overflow)?;
+    staker_account.reward_per_share_paid = reward_pool.accumulated_reward_per_share;
+
+    Ok(())
+}
+
 // -------------------------------------
 // Governance Instructions
 // -------------------------------------
 
 /// Create a new governance proposal.
-pub fn create_proposal(ctx: Context<CreateProposal>, description: String, new_collateral_ratio: Option<u64>, new_reward_rate: Option<u64>) -> Result<()> {
-    require!(description.len() <= 200, ErrorCode::DescriptionTooLong);
+///
+/// The voting period and timelock come from the governance config entry of
+/// `action`. Only parameter changes carry new values; the other kinds are
+/// signaling proposals. A kind with no voting period is approved at once.
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    description: String,
+    action: ProposalAction,
+    new_collateral_ratio: Option<u64>,
+    new_reward_rate: Option<u64>,
+) -> Result<()> {
+    require!(action != ProposalAction::IncidentFollowUp, ErrorCode::ProposalActionMismatch);
+
+    // Make sure parameter changes propose at least one change and other kinds none
+    let has_changes = new_collateral_ratio.is_some() || new_reward_rate.is_some();
+    require!(new_reward_rate.map_or(true, |rate| rate <= MAX_REWARD_RATE), ErrorCode::RewardRateTooHigh);
+    if action == ProposalAction::ParameterChange {
+        require!(has_changes, ErrorCode::ProposalNoChangesSpecified);
+    } else {
+        require!(!has_changes, ErrorCode::ProposalActionMismatch);
+    }
+
+    open_proposal(
+        ctx,
+        description,
+        action,
+        ProposalChanges { new_collateral_ratio, new_reward_rate, ..Default::default() },
+    )
+}
+
+/// New values a proposal carries.
+#[derive(Clone, Copy, Default)]
+struct ProposalChanges {
+    new_collateral_ratio: Option<u64>,
+    new_reward_rate: Option<u64>,
+    new_minting_fee_rate: Option<u64>,
+    new_redemption_fee_rate: Option<u64>,
+}
+
+/// Propose a new collateral ratio from the parameter change template.
+pub fn propose_collateral_ratio_change(ctx: Context<CreateProposal>, new_collateral_ratio: u64) -> Result<()> {
+    require!(new_collateral_ratio > 100, ErrorCode::InvalidCollateralRatio);
+    require!(new_collateral_ratio != ctx.accounts.governance.collateral_ratio, ErrorCode::ProposalNoChangesSpecified);
+
+    let description = format!("Set the collateral ratio to {}%", new_collateral_ratio);
+    open_proposal(
+        ctx,
+        description,
+        ProposalAction::ParameterChange,
+        ProposalChanges { new_collateral_ratio: Some(new_collateral_ratio), ..Default::default() },
+    )
+}
 
-    // Make sure at least one change is proposed
+/// Largest reward rate a proposal may set (reward units per second).
+pub const MAX_REWARD_RATE: u64 = 1_000_000_000;
+
+/// Propose a new reward rate from the parameter change template.
+pub fn propose_reward_rate_change(ctx: Context<CreateProposal>, new_reward_rate: u64) -> Result<()> {
+    require!(new_reward_rate <= MAX_REWARD_RATE, ErrorCode::RewardRateTooHigh);
+    require!(new_reward_rate != ctx.accounts.governance.reward_adjustment_rate, ErrorCode::ProposalNoChangesSpecified);
+
+    let description = format!("Set the reward rate to {}", new_reward_rate);
+    open_proposal(
+        ctx,
+        description,
+        ProposalAction::ParameterChange,
+        ProposalChanges { new_reward_rate: Some(new_reward_rate), ..Default::default() },
+    )
+}
+
+/// Propose new minting and/or redemption fee rates from the fee change template.
+///
+/// Rates are checked against `MAX_FEE_BPS` up front, so an approved fee
+/// change can always be executed.
+pub fn propose_fee_change(
+    ctx: Context<CreateProposal>,
+    new_minting_fee_rate: Option<u64>,
+    new_redemption_fee_rate: Option<u64>,
+) -> Result<()> {
+    require!(new_minting_fee_rate.is_some() || new_redemption_fee_rate.is_some(), ErrorCode::ProposalNoChangesSpecified);
     require!(
-        new_collateral_ratio.is_some() || new_reward_rate.is_some(),
-        ErrorCode::ProposalNoChangesSpecified
+        new_minting_fee_rate.unwrap_or(0) <= MAX_FEE_BPS && new_redemption_fee_rate.unwrap_or(0) <= MAX_FEE_BPS,
+        ErrorCode::FeeTooHigh
     );
+    require!(ctx.accounts.system_state.is_some(), ErrorCode::InvalidAccountData);
+
+    let describe = |rate: Option<u64>| rate.map_or("unchanged".to_string(), |rate| format!("{} bps", rate));
+    let description = format!(
+        "Set the minting fee to {} and the redemption fee to {}",
+        describe(new_minting_fee_rate),
+        describe(new_redemption_fee_rate)
+    );
+    open_proposal(
+        ctx,
+        description,
+        ProposalAction::FeeChange,
+        ProposalChanges { new_minting_fee_rate, new_redemption_fee_rate, ..Default::default() },
+    )
+}
+
+/// Create a proposal carrying `changes`, already validated against `action` by the caller.
+fn open_proposal(ctx: Context<CreateProposal>, description: String, action: ProposalAction, changes: ProposalChanges) -> Result<()> {
+    require!(description.len() <= 200, ErrorCode::DescriptionTooLong);
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Governance)?;
+
+    let timing = ctx.accounts.governance_config.timings[action as usize];
+    let now = Clock::get()?.unix_timestamp as u64;
 
     let proposal = &mut ctx.accounts.proposal;
     proposal.description = description;
-    proposal.new_collateral_ratio = new_collateral_ratio;
-    proposal.new_reward_rate = new_reward_rate;
+    proposal.new_collateral_ratio = changes.new_collateral_ratio;
+    proposal.new_reward_rate = changes.new_reward_rate;
+    proposal.new_minting_fee_rate = changes.new_minting_fee_rate;
+    proposal.new_redemption_fee_rate = changes.new_redemption_fee_rate;
     proposal.approval_votes = 0;
     proposal.reject_votes = 0;
-    proposal.status = ProposalStatus::Pending;
     proposal.proposer = *ctx.accounts.proposer.key;
+    proposal.voting_period_end = now.checked_add(timing.voting_period_secs).ok_or(ErrorCode::Overflow)?;
+    proposal.bond = PROPOSAL_BOND_LAMPORTS;
+    proposal.governance = ctx.accounts.governance.key();
+    proposal.base_checksum = proposal_base_checksum(proposal, &ctx.accounts.governance, ctx.accounts.system_state.as_deref());
+    proposal.executed = false;
+    proposal.action = action;
+    proposal.execution_delay_secs = timing.execution_delay_secs;
+    proposal.incident_nonce = None;
+    proposal.incident_collateral_type = Pubkey::default();
+    if timing.voting_period_secs == 0 {
+        proposal.status = ProposalStatus::Approved;
+        proposal.executable_at = now.checked_add(timing.execution_delay_secs).ok_or(ErrorCode::Overflow)?;
+    } else {
+        proposal.status = ProposalStatus::Pending;
+        proposal.executable_at = 0;
+    }
+
+    // Escrow the proposal bond in the proposal account
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.proposer.to_account_info(),
+        to: ctx.accounts.proposal.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, PROPOSAL_BOND_LAMPORTS)?;
 
     // Emit an event for the proposal creation
     emit!(ProposalCreatedEvent {
         proposer: *ctx.accounts.proposer.key,
         proposal_id: *ctx.accounts.proposal.to_account_info().key,
+        action,
+        voting_period_end: ctx.accounts.proposal.voting_period_end,
+    });
+
+    Ok(())
+}
+
+/// Open the follow-up proposal of a circuit breaker trip under the next incident nonce.
+///
+/// The proposal lives at a PDA derived from the nonce and records it, so the
+/// governance action taken afterwards links back to the trip that prompted
+/// it. It is opened in the same call as the trip, without a bond and
+/// regardless of any governance pause; the trip must not depend on either.
+/// Executing it re-enables `collateral_type`, or ends the declared incident
+/// when that is the default key.
+#[allow(clippy::too_many_arguments)]
+fn open_incident_proposal(
+    proposal: &mut Account<Proposal>,
+    governance: &Account<Governance>,
+    governance_config: &GovernanceConfig,
+    system_state: &mut SystemState,
+    proposer: Pubkey,
+    trigger: Pubkey,
+    collateral_type: Pubkey,
+    description: String,
+) -> Result<()> {
+    let incident_nonce = system_state.next_incident_nonce;
+    system_state.next_incident_nonce = incident_nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    let action = ProposalAction::IncidentFollowUp;
+    let timing = governance_config.timings[action as usize];
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    proposal.description = description;
+    proposal.new_collateral_ratio = None;
+    proposal.new_reward_rate = None;
+    proposal.new_minting_fee_rate = None;
+    proposal.new_redemption_fee_rate = None;
+    proposal.approval_votes = 0;
+    proposal.reject_votes = 0;
+    proposal.proposer = proposer;
+    proposal.voting_period_end = now.checked_add(timing.voting_period_secs).ok_or(ErrorCode::Overflow)?;
+    proposal.bond = 0;
+    proposal.governance = governance.key();
+    proposal.base_checksum = proposal_base_checksum(proposal, governance, None);
+    proposal.executed = false;
+    proposal.action = action;
+    proposal.execution_delay_secs = timing.execution_delay_secs;
+    proposal.incident_nonce = Some(incident_nonce);
+    proposal.incident_collateral_type = collateral_type;
+    if timing.voting_period_secs == 0 {
+        proposal.status = ProposalStatus::Approved;
+        proposal.executable_at = now.checked_add(timing.execution_delay_secs).ok_or(ErrorCode::Overflow)?;
+    } else {
+        proposal.status = ProposalStatus::Pending;
+        proposal.executable_at = 0;
+    }
+
+    emit!(ProposalCreatedEvent {
+        proposer,
+        proposal_id: proposal.key(),
+        action,
+        voting_period_end: proposal.voting_period_end,
+    });
+    emit!(IncidentProposalOpenedEvent {
+        incident_nonce,
+        proposal_id: proposal.key(),
+        trigger,
     });
 
     Ok(())
@@ -238,8 +1236,11 @@ pub fn create_proposal(ctx: Context<CreateProposal>, description: String, new_co
 
 /// Vote on an existing proposal.
 pub fn vote_on_proposal(ctx: Context<VoteOnProposal>, approve: bool) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Governance)?;
+
     let proposal = &mut ctx.accounts.proposal;
     require!(proposal.status == ProposalStatus::Pending, ErrorCode::ProposalAlreadyConcluded);
+    require!(Clock::get()?.unix_timestamp as u64 <= proposal.voting_period_end, ErrorCode::VotingPeriodEnded);
 
     if approve {
         proposal.approval_votes += 1;
@@ -247,28 +1248,235 @@ pub fn vote_on_proposal(ctx: Context<VoteOnProposal>, approve: bool) -> Result<(
         proposal.reject_votes += 1;
     }
 
-    // Update proposal status if the vote threshold is reached
+    // Emit an event for the voting action
+    emit!(ProposalVotedEvent {
+        voter: *ctx.accounts.voter.key,
+        proposal_id: *ctx.accounts.proposal.to_account_info().key,
+        approved: approve,
+    });
+
+    Ok(())
+}
+
+/// Conclude a proposal once its voting period has ended. Callable by anyone.
+///
+/// An approved proposal becomes executable after its timelock.
+pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Pending, ErrorCode::ProposalAlreadyConcluded);
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(now > proposal.voting_period_end, ErrorCode::VotingPeriodNotEnded);
+
     if proposal.approval_votes > proposal.reject_votes {
         proposal.status = ProposalStatus::Approved;
+        proposal.executable_at = now.checked_add(proposal.execution_delay_secs).ok_or(ErrorCode::Overflow)?;
     } else {
         proposal.status = ProposalStatus::Rejected;
     }
 
-    // Apply the changes if the proposal is approved
-    if proposal.status == ProposalStatus::Approved {
-        if let Some(new_collateral_ratio) = proposal.new_collateral_ratio {
-            ctx.accounts.governance.collateral_ratio = new_collateral_ratio;
+    emit!(ProposalFinalizedEvent {
+        proposal_id: proposal.key(),
+        approved: proposal.status == ProposalStatus::Approved,
+        approval_votes: proposal.approval_votes,
+        reject_votes: proposal.reject_votes,
+        executable_at: proposal.executable_at,
+    });
+
+    Ok(())
+}
+
+/// Cancel a pending or approved proposal before it is executed.
+pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        matches!(proposal.status, ProposalStatus::Pending | ProposalStatus::Approved) && !proposal.executed,
+        ErrorCode::ProposalAlreadyConcluded
+    );
+    proposal.status = ProposalStatus::Vetoed;
+
+    emit!(ProposalVetoedEvent {
+        proposal_id: proposal.key(),
+        action: proposal.action,
+        vetoed_by: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Apply an approved proposal's changes once its timelock has passed. Callable by anyone.
+///
+/// The targeted parameters must still hold the values they had when the
+/// proposal was created; if another proposal changed them in the meantime
+/// this fails with `StaleProposalBase` instead of overwriting that change.
+/// An incident follow-up resumes minting against its paused collateral, or
+/// ends the incident it was opened for.
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Governance)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Approved, ErrorCode::ProposalNotApproved);
+    require!(!proposal.executed, ErrorCode::ProposalAlreadyConcluded);
+    require!(Clock::get()?.unix_timestamp as u64 >= proposal.executable_at, ErrorCode::ProposalTimelockActive);
+    require!(
+        proposal_base_checksum(proposal, &ctx.accounts.governance, ctx.accounts.system_state.as_deref()) == proposal.base_checksum,
+        ErrorCode::StaleProposalBase
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    if let Some(new_collateral_ratio) = proposal.new_collateral_ratio {
+        governance.collateral_ratio = new_collateral_ratio;
+    }
+    if let Some(new_reward_rate) = proposal.new_reward_rate {
+        governance.reward_adjustment_rate = new_reward_rate;
+    }
+    if proposal.new_minting_fee_rate.is_some() || proposal.new_redemption_fee_rate.is_some() {
+        let system_state = ctx.accounts.system_state.as_deref_mut().ok_or(ErrorCode::InvalidAccountData)?;
+        require!(!system_state.config_frozen, ErrorCode::ConfigFrozen);
+        if let Some(new_minting_fee_rate) = proposal.new_minting_fee_rate {
+            system_state.minting_fee_rate = new_minting_fee_rate;
+        }
+        if let Some(new_redemption_fee_rate) = proposal.new_redemption_fee_rate {
+            system_state.redemption_fee_rate = new_redemption_fee_rate;
         }
-        if let Some(new_reward_rate) = proposal.new_reward_rate {
-            ctx.accounts.governance.reward_adjustment_rate = new_reward_rate;
+    }
+    if proposal.action == ProposalAction::IncidentFollowUp {
+        if proposal.incident_collateral_type == Pubkey::default() {
+            let system_state = ctx.accounts.system_state.as_deref_mut().ok_or(ErrorCode::InvalidAccountData)?;
+            system_state.incident_active = false;
+        } else {
+            let collateral_type = ctx.accounts.collateral_type.as_deref_mut().ok_or(ErrorCode::InvalidAccountData)?;
+            collateral_type.minting_paused = false;
         }
     }
+    proposal.executed = true;
 
-    // Emit an event for the voting action
-    emit!(ProposalVotedEvent {
-        voter: *ctx.accounts.voter.key,
-        proposal_id: *ctx.accounts.proposal.to_account_info().key,
-        approved: approve,
+    emit!(ProposalExecutedEvent {
+        proposal_id: proposal.key(),
+        action: proposal.action,
+        new_collateral_ratio: proposal.new_collateral_ratio,
+        new_reward_rate: proposal.new_reward_rate,
+        new_minting_fee_rate: proposal.new_minting_fee_rate,
+        new_redemption_fee_rate: proposal.new_redemption_fee_rate,
+    });
+
+    Ok(())
+}
+
+/// Create the governance config with the default timing of every proposal kind.
+pub fn initialize_governance_config(ctx: Context<InitializeGovernanceConfig>) -> Result<()> {
+    let governance_config = &mut ctx.accounts.governance_config;
+    governance_config.governance = ctx.accounts.governance.key();
+    governance_config.timings[ProposalAction::ParameterChange as usize] = ProposalTiming {
+        voting_period_secs: PROPOSAL_VOTING_PERIOD_SECS,
+        execution_delay_secs: DEFAULT_PARAMETER_CHANGE_DELAY_SECS,
+    };
+    governance_config.timings[ProposalAction::FeeChange as usize] = ProposalTiming {
+        voting_period_secs: DEFAULT_FEE_CHANGE_VOTING_SECS,
+        execution_delay_secs: DEFAULT_FEE_CHANGE_DELAY_SECS,
+    };
+    governance_config.timings[ProposalAction::CollateralOnboarding as usize] = ProposalTiming {
+        voting_period_secs: DEFAULT_ONBOARDING_VOTING_SECS,
+        execution_delay_secs: DEFAULT_ONBOARDING_DELAY_SECS,
+    };
+    governance_config.timings[ProposalAction::EmergencyShutdown as usize] = ProposalTiming {
+        voting_period_secs: 0,
+        execution_delay_secs: DEFAULT_EMERGENCY_VETO_WINDOW_SECS,
+    };
+    governance_config.timings[ProposalAction::IncidentFollowUp as usize] = ProposalTiming {
+        voting_period_secs: DEFAULT_INCIDENT_VOTING_SECS,
+        execution_delay_secs: DEFAULT_INCIDENT_DELAY_SECS,
+    };
+    governance_config.bump = ctx.bumps.governance_config;
+
+    Ok(())
+}
+
+/// Set the voting period and timelock of one proposal kind.
+///
+/// Applies to proposals created afterwards; existing proposals keep the
+/// timing they were created with.
+pub fn set_proposal_timing(ctx: Context<SetProposalTiming>, action: ProposalAction, voting_period_secs: u64, execution_delay_secs: u64) -> Result<()> {
+    ctx.accounts.governance_config.timings[action as usize] = ProposalTiming {
+        voting_period_secs,
+        execution_delay_secs,
+    };
+
+    emit!(ProposalTimingUpdatedEvent {
+        action,
+        voting_period_secs,
+        execution_delay_secs,
+    });
+
+    Ok(())
+}
+
+/// Checksum of the current values of the parameters a proposal changes.
+fn proposal_base_checksum(proposal: &Proposal, governance: &Governance, system_state: Option<&SystemState>) -> [u8; 32] {
+    let mut data = Vec::with_capacity(36);
+    if proposal.new_collateral_ratio.is_some() {
+        data.push(0);
+        data.extend_from_slice(&governance.collateral_ratio.to_le_bytes());
+    }
+    if proposal.new_reward_rate.is_some() {
+        data.push(1);
+        data.extend_from_slice(&governance.reward_adjustment_rate.to_le_bytes());
+    }
+    if let Some(system_state) = system_state {
+        if proposal.new_minting_fee_rate.is_some() {
+            data.push(2);
+            data.extend_from_slice(&system_state.minting_fee_rate.to_le_bytes());
+        }
+        if proposal.new_redemption_fee_rate.is_some() {
+            data.push(3);
+            data.extend_from_slice(&system_state.redemption_fee_rate.to_le_bytes());
+        }
+    }
+    hash(&data).to_bytes()
+}
+
+/// Expire a proposal left pending past its voting period and grace, refunding its bond.
+///
+/// Further proposals can be expired in the same call by passing
+/// `[proposal, proposer]` pairs as remaining accounts.
+pub fn expire_proposal<'info>(ctx: Context<'_, '_, 'info, 'info, ExpireProposal<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+
+    let proposal_info = ctx.accounts.proposal.to_account_info();
+    let proposer_info = ctx.accounts.proposer.to_account_info();
+    expire_pending_proposal(&mut ctx.accounts.proposal, &proposal_info, &proposer_info, now)?;
+
+    require!(ctx.remaining_accounts.len() % 2 == 0, ErrorCode::InvalidAccountData);
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let mut proposal = Account::<Proposal>::try_from(&pair[0])?;
+        require_keys_eq!(pair[1].key(), proposal.proposer, ErrorCode::Unauthorized);
+        require!(pair[1].is_writable, ErrorCode::InvalidAccountData);
+
+        expire_pending_proposal(&mut proposal, &pair[0], &pair[1], now)?;
+        proposal.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+/// Mark a single pending proposal as expired and return its bond to the proposer.
+fn expire_pending_proposal(proposal: &mut Proposal, proposal_info: &AccountInfo, proposer_info: &AccountInfo, now: u64) -> Result<()> {
+    require!(proposal.status == ProposalStatus::Pending, ErrorCode::ProposalAlreadyConcluded);
+    require!(
+        now > proposal.voting_period_end.saturating_add(PROPOSAL_EXPIRY_GRACE_SECS),
+        ErrorCode::ProposalNotExpired
+    );
+
+    let bond = proposal.bond;
+    proposal.status = ProposalStatus::Expired;
+    proposal.bond = 0;
+
+    **proposal_info.try_borrow_mut_lamports()? -= bond;
+    **proposer_info.try_borrow_mut_lamports()? += bond;
+
+    emit!(ProposalExpiredEvent {
+        proposal_id: proposal_info.key(),
+        proposer: proposal.proposer,
+        bond_refunded: bond,
     });
 
     Ok(())
@@ -280,12 +1488,29 @@ pub fn vote_on_proposal(ctx: Context<VoteOnProposal>, approve: bool) -> Result<(
 
 /// Add a new collateral type to the protocol.
 pub fn add_collateral_type(ctx: Context<AddCollateralType>, collateral_ratio: u64) -> Result<()> {
-    require!(collateral_ratio > 100, ErrorCode::InvalidCollateralRatio);
+    // Leave room for a liquidation threshold strictly between 100% and the collateral ratio
+    require!(collateral_ratio > 101, ErrorCode::InvalidCollateralRatio);
 
     let collateral_type = &mut ctx.accounts.collateral_type;
     collateral_type.collateral_mint = *ctx.accounts.collateral_type.to_account_info().key;
     collateral_type.collateral_ratio = collateral_ratio;
     collateral_type.price_feed = *ctx.accounts.collateral_type.to_account_info().key;
+    collateral_type.max_price_age_secs = DEFAULT_MAX_PRICE_AGE_SECS;
+    collateral_type.max_confidence_bps = DEFAULT_MAX_CONFIDENCE_BPS;
+    collateral_type.liquidation_threshold = collateral_ratio.saturating_sub(DEFAULT_LIQUIDATION_BUFFER).max(101);
+    collateral_type.liquidation_penalty_bps = LEGACY_LIQUIDATION_BONUS_BPS;
+    collateral_type.liquidation_bonus_slope_bps = DEFAULT_LIQUIDATION_BONUS_SLOPE_BPS;
+    collateral_type.max_liquidation_bonus_bps = DEFAULT_MAX_LIQUIDATION_BONUS_BPS;
+    collateral_type.mint_fee_bps = None;
+    collateral_type.redemption_fee_bps = None;
+    collateral_type.exchange_rate = 0;
+    collateral_type.yield_skim_bps = 0;
+    collateral_type.confidence_premium_threshold_bps = 0;
+    collateral_type.confidence_premium_slope_bps = 0;
+    collateral_type.max_confidence_premium_bps = 0;
+    collateral_type.stake_pool_config = Pubkey::default();
+    collateral_type.minting_paused = false;
+    collateral_type.receipt_config = Pubkey::default();
 
     // Emit an event for adding a new collateral type
     emit!(CollateralTypeAddedEvent {
@@ -297,20 +1522,89 @@ pub fn add_collateral_type(ctx: Context<AddCollateralType>, collateral_ratio: u6
 }
 
 /// Mint stablecoin using a specified collateral type.
-pub fn mint_stablecoin_with_collateral(ctx: Context<MintStablecoinWithCollateral>, amount: u64, collateral_type: Pubkey) -> Result<()> {
+pub fn mint_stablecoin_with_collateral(
+    ctx: Context<MintStablecoinWithCollateral>,
+    amount: u64,
+    collateral_type: Pubkey,
+    deadline_ts: Option<i64>,
+    max_fee: Option<u64>,
+    partner_id: Option<u64>,
+) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
+    check_deadline(deadline_ts)?;
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Mint)?;
+    enforce_peg_band(&ctx.accounts.peg_state, Clock::get()?.unix_timestamp)?;
 
     let user_account = &mut ctx.accounts.user_account;
     let collateral_type_account = &ctx.accounts.collateral_type;
+    check_collateral_minting(collateral_type_account)?;
 
     // Ensure the specified collateral type matches
     require!(collateral_type_account.collateral_mint == collateral_type, ErrorCode::InvalidCollateralType);
 
-    // Check if the user has enough collateral based on the collateral type's ratio
-    let required_collateral = amount.checked_mul(collateral_type_account.collateral_ratio).ok_or(ErrorCode::Overflow)?;
-    require!(user_account.collateral_balance >= required_collateral, ErrorCode::InsufficientCollateral);
+    // Bind the position to the collateral type backing it
+    if user_account.collateral_type == Pubkey::default() {
+        user_account.collateral_type = collateral_type_account.key();
+        user_account.entry_exchange_rate = collateral_type_account.exchange_rate;
+    }
+    require_keys_eq!(user_account.collateral_type, collateral_type_account.key(), ErrorCode::InvalidCollateralType);
+
+    // Value the collateral with a fresh, tight cached oracle price
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, collateral_type_account)?;
+    let collateral_value = (user_account.collateral_balance as u128)
+        .checked_mul(oracle_price.price as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / PRICE_PRECISION as u128;
+
+    // New debt must leave the whole position at or above the target collateral ratio,
+    // a buffer above the liquidation threshold, raised while the position is new
+    let collateral_ratio = onboarding_collateral_ratio(
+        &ctx.accounts.system_state,
+        user_account,
+        collateral_type_account.collateral_ratio,
+        Clock::get()?.unix_timestamp,
+    );
+    let debt_after = user_account.stablecoin_balance.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    if user_account.margin_account == Pubkey::default() {
+        let required_value = (debt_after as u128)
+            .checked_mul(collateral_ratio as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / 100;
+        require!(collateral_value >= required_value, ErrorCode::InsufficientCollateral);
+    } else {
+        // Cross-margin positions are backed by the whole portfolio, checked against its cached valuation
+        let margin_account = ctx.accounts.margin_account.as_deref().ok_or(ErrorCode::InvalidMarginAccount)?;
+        let portfolio_health = ctx.accounts.portfolio_health.as_deref_mut().ok_or(ErrorCode::PortfolioHealthStale)?;
+        charge_cached_portfolio(portfolio_health, margin_account, amount, ctx.accounts.system_state.health_warning_bps)?;
+    }
+    let premium_bps = confidence_premium_bps(collateral_type_account, &oracle_price);
+
+    // Price the size surcharge against the headroom left before this mint takes its share
+    let size_surcharge_bps = mint_size_surcharge_bps(&ctx.accounts.system_state, &ctx.accounts.collateral_type, amount)?;
+
+    // Keep any single collateral from backing too much of the system's debt
+    record_collateral_debt(&mut ctx.accounts.collateral_type, &mut ctx.accounts.protocol_stats, amount)?;
 
-    // Mint stablecoins
+    // Mint stablecoins net of the collateral's mint fee and any oracle confidence premium,
+    // both minted to the treasury with the premium credited to the insurance pool
+    let mut fee_bps = effective_mint_fee_bps(&ctx.accounts.system_state, Some(&ctx.accounts.collateral_type));
+    if let (Some(token_account), Some(metadata), Some(loyalty_collection)) = (
+        &ctx.accounts.loyalty_token_account,
+        &ctx.accounts.loyalty_metadata,
+        &ctx.accounts.loyalty_collection,
+    ) {
+        fee_bps = loyalty_fee_bps(fee_bps, token_account, metadata, loyalty_collection, &ctx.accounts.payer.key())?;
+    }
+    // The size surcharge is not discounted, so loyalty passes cannot shield an outsized mint
+    fee_bps = fee_bps.checked_add(size_surcharge_bps).ok_or(ErrorCode::Overflow)?;
+    let fee_charge = math::fee_charge(amount, fee_bps)?;
+    let premium_charge = math::fee_charge(amount, premium_bps)?;
+    let (fee, premium) = (fee_charge.fee, premium_charge.fee);
+    // The caller's limit covers everything withheld from the minted amount
+    require!(fee.checked_add(premium).ok_or(ErrorCode::Overflow)? <= max_fee.unwrap_or(u64::MAX), ErrorCode::SlippageExceeded);
+    let (fee_due, fee_credits_used) = spend_fee_credits(user_account, fee);
+    let treasury_amount = fee_due.checked_add(premium).ok_or(ErrorCode::Overflow)?;
+    require!(treasury_amount <= amount, ErrorCode::FeeTooHigh);
     let cpi_accounts = MintTo {
         mint: ctx.accounts.stablecoin_mint.to_account_info(),
         to: ctx.accounts.user_stablecoin_account.to_account_info(),
@@ -318,16 +1612,53 @@ pub fn mint_stablecoin_with_collateral(ctx: Context<MintStablecoinWithCollateral
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::mint_to(cpi_ctx, amount)?;
+    token::mint_to(cpi_ctx, amount - treasury_amount)?;
+
+    if treasury_amount > 0 {
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::mint_to(cpi_ctx, treasury_amount)?;
+
+        let protocol_stats = &mut ctx.accounts.protocol_stats;
+        protocol_stats.fee_issuance = protocol_stats.fee_issuance.checked_add(treasury_amount).ok_or(ErrorCode::Overflow)?;
+    }
+    // Partners share in the mint fee; the confidence premium belongs to the insurance pool
+    accrue_partner_revenue(ctx.accounts.partner.as_deref_mut(), partner_id, amount, fee_due)?;
+
+    if premium > 0 {
+        let revenue = &mut ctx.accounts.liquidation_revenue;
+        revenue.insurance_pool_balance = revenue.insurance_pool_balance.checked_add(premium).ok_or(ErrorCode::Overflow)?;
+
+        emit!(ConfidencePremiumChargedEvent {
+            user: user_account.key(),
+            collateral_type,
+            confidence: oracle_price.confidence,
+            premium_bps,
+            premium,
+        });
+    }
 
     // Update the user's stablecoin balance
     user_account.stablecoin_balance = user_account.stablecoin_balance.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    enforce_launch_caps(user_account, &ctx.accounts.system_state)?;
+    update_health_band(user_account, &ctx.accounts.system_state, Some(&ctx.accounts.collateral_type), Some(&ctx.accounts.price_cache))?;
+
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Mint, 0, amount, oracle_price.price)?;
 
     // Emit an event for minting stablecoin with collateral
     emit!(MintStablecoinWithCollateralEvent {
         user: ctx.accounts.user_account.key(),
         amount,
         collateral_type,
+        fee,
+        premium,
+        rounding_remainder: fee_charge.rounding_remainder + premium_charge.rounding_remainder,
+        fee_credits_used,
+        tag: ctx.accounts.user_account.tag,
     });
 
     Ok(())
@@ -339,87 +1670,9006 @@ pub fn mint_stablecoin_with_collateral(ctx: Context<MintStablecoinWithCollateral
 
 /// Claim staking rewards.
 pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-    let staker_account = &mut ctx.accounts.staker_account;
-    let current_time = Clock::get()?.unix_timestamp as u64;
-
-    // Calculate rewards
-    let time_since_last_claim = current_time.checked_sub(staker_account.last_reward_claim).ok_or(ErrorCode::Overflow)?;
-    let reward_amount = (staker_account.staked_balance * time_since_last_claim) / 1_000_000; // Example calculation
+    let now = Clock::get()?.unix_timestamp;
+    let reward_amount = take_claimable_rewards(
+        &mut ctx.accounts.reward_pool,
+        &mut ctx.accounts.staker_account,
+        now as u64,
+    )?;
+    refresh_boost(
+        &mut ctx.accounts.reward_pool,
+        &mut ctx.accounts.staker_account,
+        ctx.accounts.ve_config.as_deref_mut(),
+        ctx.accounts.ve_lock.as_deref_mut(),
+        now,
+    )?;
+    consume_emission_budget(&mut ctx.accounts.emission_schedule, reward_amount, Clock::get()?.unix_timestamp as u64)?;
 
-    // Update last reward claim time
-    staker_account.last_reward_claim = current_time;
-
-    // Mint the rewards
+    // Mint the rewards with the program-owned mint authority
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let bump = [ctx.accounts.emission_schedule.authority_bump];
+    let seeds: &[&[u8]] = &[b"reward_mint_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
     let cpi_accounts = MintTo {
         mint: ctx.accounts.reward_token_mint.to_account_info(),
-        to: ctx.accounts.user_reward_account.to_account_info(),
+        to: ctx.accounts.destination_reward_account.to_account_info(),
         authority: ctx.accounts.reward_mint_authority.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
     token::mint_to(cpi_ctx, reward_amount)?;
 
     Ok(())
 }
 
+/// Settle a staker's rewards and take everything claimable, resetting the claim timer.
+pub fn take_claimable_rewards(reward_pool: &mut RewardPool, staker_account: &mut StakerAccount, now: u64) -> Result<u64> {
+    // Unclaimed rewards of a sunset pool are forfeited once its withdrawal window ends
+    require!(
+        reward_pool.sunset_deadline == 0 || now < reward_pool.sunset_deadline as u64,
+        ErrorCode::SunsetClaimsClosed
+    );
+
+    // Calculate rewards from the pool accumulator
+    update_reward_pool(reward_pool, now)?;
+    settle_staker_rewards(staker_account, reward_pool)?;
+    let reward_amount = staker_account.reward_debt;
+    require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+    require!(reward_amount >= reward_pool.min_claim_amount, ErrorCode::ClaimBelowMinimum);
+    staker_account.reward_debt = 0;
+
+    // Update last reward claim time
+    staker_account.last_reward_claim = now;
+
+    Ok(reward_amount)
+}
+
+/// Stake a staker's dust rewards instead of paying them out. Callable by anyone once the staker opted in.
+///
+/// Only the stake is credited, so no token account or CPI is needed; the
+/// tokens are minted into the staking vault when the staker next withdraws.
+pub fn claim_to_stake(ctx: Context<ClaimToStake>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let staker_account = &mut ctx.accounts.staker_account;
+    require!(reward_pool.sunset_deadline == 0, ErrorCode::PoolSunsetting);
+
+    update_reward_pool(reward_pool, now)?;
+    settle_staker_rewards(staker_account, reward_pool)?;
+    let amount = staker_account.reward_debt;
+    require!(amount > 0, ErrorCode::NoRewardsAvailable);
+    require!(amount <= reward_pool.claim_to_stake_threshold, ErrorCode::ClaimAboveStakeThreshold);
+    consume_emission_budget(&mut ctx.accounts.emission_schedule, amount, now)?;
+    staker_account.reward_debt = 0;
+    staker_account.last_reward_claim = now;
+
+    reward_pool.total_staked = reward_pool.total_staked.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    reward_pool.unsettled_stake = reward_pool.unsettled_stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    staker_account.staked_balance = staker_account.staked_balance.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    staker_account.unsettled_stake = staker_account.unsettled_stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    refresh_boost(
+        reward_pool,
+        staker_account,
+        ctx.accounts.ve_config.as_deref_mut(),
+        ctx.accounts.ve_lock.as_deref_mut(),
+        now as i64,
+    )?;
+
+    emit!(RewardsStakedEvent {
+        staker_account: staker_account.key(),
+        amount,
+        staked_balance: staker_account.staked_balance,
+    });
+
+    Ok(())
+}
+
+/// Opt a stake in or out of having its dust rewards staked by `claim_to_stake`.
+pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+    ctx.accounts.staker_account.auto_compound = enabled;
+
+    emit!(AutoCompoundSetEvent {
+        staker_account: ctx.accounts.staker_account.key(),
+        enabled,
+    });
+
+    Ok(())
+}
+
+/// Set the largest pending reward a reward pool lets `claim_to_stake` stake.
+pub fn set_claim_to_stake_threshold(ctx: Context<SetClaimLimits>, threshold: u64) -> Result<()> {
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.claim_to_stake_threshold = threshold;
+
+    emit!(ClaimToStakeThresholdUpdatedEvent {
+        reward_pool: reward_pool.key(),
+        threshold,
+    });
+
+    Ok(())
+}
+
+/// Let `reward_delegate` receive the staker's rewards, e.g. a custodian or cold wallet.
+pub fn set_reward_delegate(ctx: Context<SetRewardDelegate>, reward_delegate: Pubkey) -> Result<()> {
+    ctx.accounts.staker_account.reward_delegate = reward_delegate;
+
+    emit!(RewardDelegateSetEvent {
+        staker_account: ctx.accounts.staker_account.key(),
+        owner: ctx.accounts.owner.key(),
+        reward_delegate,
+    });
+
+    Ok(())
+}
+
 // -------------------------------------
-// Event Definitions
+// Emission Schedule Instructions
 // -------------------------------------
 
-#[event]
-pub struct ProtocolInitialized {
-    pub collateral_ratio: u64,
+/// Bound a reward pool's claims to `epoch_budget` per epoch.
+///
+/// The reward mint's authority must be handed to the reward mint authority
+/// PDA, so emissions no longer depend on an off-chain signer.
+pub fn initialize_emission_schedule(ctx: Context<InitializeEmissionSchedule>, epoch_duration: u64, epoch_budget: u64) -> Result<()> {
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    emission_schedule.reward_pool = ctx.accounts.reward_pool.key();
+    emission_schedule.epoch_duration = epoch_duration;
+    emission_schedule.epoch_budget = epoch_budget;
+    emission_schedule.epoch_start = Clock::get()?.unix_timestamp as u64;
+    emission_schedule.minted_this_epoch = 0;
+    emission_schedule.authority_bump = ctx.bumps.reward_mint_authority;
+    emission_schedule.bump = ctx.bumps.emission_schedule;
+
+    emit!(EmissionBudgetUpdatedEvent {
+        reward_pool: emission_schedule.reward_pool,
+        epoch_duration,
+        epoch_budget,
+    });
+
+    Ok(())
 }
 
-#[event]
-pub struct MintStablecoinEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub fee: u64,
+/// Change the per-epoch reward budget, effective for the current epoch.
+pub fn set_emission_budget(ctx: Context<SetEmissionBudget>, epoch_budget: u64) -> Result<()> {
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+    emission_schedule.epoch_budget = epoch_budget;
+
+    emit!(EmissionBudgetUpdatedEvent {
+        reward_pool: emission_schedule.reward_pool,
+        epoch_duration: emission_schedule.epoch_duration,
+        epoch_budget,
+    });
+
+    Ok(())
 }
 
-#[event]
-pub struct LiquidationEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub penalty: u64,
+/// Count `amount` against the current epoch's budget, rolling over to a new epoch when due.
+pub fn consume_emission_budget(emission_schedule: &mut EmissionSchedule, amount: u64, now: u64) -> Result<()> {
+    let elapsed_epochs = now.saturating_sub(emission_schedule.epoch_start) / emission_schedule.epoch_duration;
+    if elapsed_epochs > 0 {
+        emission_schedule.epoch_start += elapsed_epochs * emission_schedule.epoch_duration;
+        emission_schedule.minted_this_epoch = 0;
+    }
+
+    let minted = emission_schedule.minted_this_epoch.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    require!(minted <= emission_schedule.epoch_budget, ErrorCode::NoRewardsAvailable);
+    emission_schedule.minted_this_epoch = minted;
+
+    Ok(())
 }
 
-#[event]
-pub struct StakeEvent {
-    pub user: Pubkey,
-    pub amount: u64,
+// -------------------------------------
+// Batch Staking Instructions
+// -------------------------------------
+
+/// Maximum number of (staker_account, token_account) pairs processed per batch.
+pub const MAX_STAKE_BATCH_SIZE: usize = 10;
+
+/// Stake for many beneficiaries in one transaction.
+///
+/// Remaining accounts are `[staker_account, token_account]` pairs, where each
+/// token account is owned by the custodian and funds the matching amount.
+pub fn batch_stake<'info>(ctx: Context<'_, '_, 'info, 'info, BatchStake<'info>>, amounts: Vec<u64>, lockup_period: u64) -> Result<()> {
+    require!(ctx.remaining_accounts.len() == amounts.len() * 2, ErrorCode::InvalidAccountData);
+    require!(amounts.len() <= MAX_STAKE_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let staking_mint = ctx.accounts.staking_pool.mint;
+
+    for (pair, &amount) in ctx.remaining_accounts.chunks(2).zip(amounts.iter()) {
+        require!(amount >= ctx.accounts.reward_pool.min_stake_amount, ErrorCode::StakeBelowMinimum);
+
+        let mut staker_account = Account::<StakerAccount>::try_from(&pair[0])?;
+        let token_account = Account::<TokenAccount>::try_from(&pair[1])?;
+        require_keys_eq!(token_account.mint, staking_mint, ErrorCode::InvalidMint);
+        require_keys_eq!(token_account.owner, ctx.accounts.custodian.key(), ErrorCode::InvalidAccountOwner);
+        require_keys_neq!(token_account.key(), ctx.accounts.staking_pool.key(), ErrorCode::DuplicateAccounts);
+
+        if staker_account.owner == Pubkey::default() {
+            staker_account.owner = ctx.accounts.custodian.key();
+        }
+        if staker_account.reward_pool == Pubkey::default() {
+            staker_account.reward_pool = ctx.accounts.reward_pool.key();
+        }
+        require_keys_eq!(staker_account.reward_pool, ctx.accounts.reward_pool.key(), ErrorCode::InvalidAccountData);
+        record_stake(&mut ctx.accounts.reward_pool, &mut staker_account, amount, lockup_period, now)?;
+        staker_account.exit(&crate::ID)?;
+
+        let cpi_accounts = Transfer {
+            from: pair[1].clone(),
+            to: ctx.accounts.staking_pool.to_account_info(),
+            authority: ctx.accounts.custodian.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(StakeEvent {
+            user: pair[1].key(),
+            amount,
+        });
+    }
+
+    Ok(())
 }
 
-#[event]
-pub struct WithdrawStakeEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub penalty: u64,
+/// Claim rewards for many beneficiaries in one transaction.
+///
+/// Remaining accounts are `[staker_account, token_account]` pairs, where each
+/// token account receives the matching staker's rewards. Stakers still in
+/// their claim cooldown are rejected like a single claim would be.
+pub fn batch_claim<'info>(ctx: Context<'_, '_, 'info, 'info, BatchClaim<'info>>) -> Result<()> {
+    require!(ctx.remaining_accounts.len() % 2 == 0, ErrorCode::InvalidAccountData);
+    require!(ctx.remaining_accounts.len() / 2 <= MAX_STAKE_BATCH_SIZE, ErrorCode::BatchTooLarge);
+
+    let now = Clock::get()?.unix_timestamp as u64;
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let bump = [ctx.accounts.emission_schedule.authority_bump];
+    let seeds: &[&[u8]] = &[b"reward_mint_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let mut staker_account = Account::<StakerAccount>::try_from(&pair[0])?;
+        let token_account = Account::<TokenAccount>::try_from(&pair[1])?;
+        require_keys_eq!(staker_account.reward_pool, reward_pool_key, ErrorCode::InvalidAccountData);
+        require_keys_eq!(token_account.mint, ctx.accounts.reward_token_mint.key(), ErrorCode::InvalidMint);
+        require!(is_reward_destination(&staker_account, &token_account), ErrorCode::InvalidAccountOwner);
+
+        let next_claim_time = staker_account.last_reward_claim.saturating_add(ctx.accounts.reward_pool.min_claim_interval);
+        require!(now >= next_claim_time, ErrorCode::ClaimCooldownActive);
+
+        let reward_amount = take_claimable_rewards(&mut ctx.accounts.reward_pool, &mut staker_account, now)?;
+        consume_emission_budget(&mut ctx.accounts.emission_schedule, reward_amount, now)?;
+        staker_account.exit(&crate::ID)?;
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.reward_token_mint.to_account_info(),
+            to: pair[1].clone(),
+            authority: ctx.accounts.reward_mint_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::mint_to(cpi_ctx, reward_amount)?;
+    }
+
+    Ok(())
 }
 
-#[event]
-pub struct ProposalCreatedEvent {
-    pub proposer: Pubkey,
-    pub proposal_id: Pubkey,
+// -------------------------------------
+// Treasury Strategy Instructions
+// -------------------------------------
+
+/// Instruction every strategy adapter exposes to receive reserves.
+pub const STRATEGY_DEPOSIT_IX: &str = "global:deposit";
+/// Instruction every strategy adapter exposes to return reserves.
+pub const STRATEGY_WITHDRAW_IX: &str = "global:withdraw";
+
+/// Initialize the system state with the governance authority.
+pub fn initialize_system_state(ctx: Context<InitializeSystemState>, governance_authority: Pubkey) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.staking_paused = false;
+    system_state.governance_authority = governance_authority;
+    system_state.global_stability_fee = 0;
+    system_state.minting_fee_rate = 0;
+    system_state.redemption_fee_rate = 0;
+    system_state.health_warning_bps = 13_000;
+    system_state.health_critical_bps = 11_500;
+    system_state.operation_locked = false;
+    system_state.onboarding_ratio_bonus = 0;
+    system_state.onboarding_period_secs = 0;
+    system_state.incident_active = false;
+    system_state.incident_declared_at = 0;
+    system_state.bump = ctx.bumps.system_state;
+    system_state.size_fee_threshold_bps = 10_000;
+    system_state.size_fee_slope_bps = 0;
+    system_state.max_size_fee_bps = 0;
+    system_state.oracle_programs = [Pubkey::default(); MAX_ORACLE_PROGRAMS];
+    system_state.next_incident_nonce = 0;
+
+    Ok(())
 }
 
-#[event]
-pub struct ProposalVotedEvent {
-    pub voter: Pubkey,
-    pub proposal_id: Pubkey,
-    pub approved: bool,
+/// Pin the stablecoin mint and treasury account every minting context is checked against.
+pub fn set_canonical_accounts(ctx: Context<SetCanonicalAccounts>) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.stablecoin_mint = ctx.accounts.stablecoin_mint.key();
+    system_state.treasury_account = ctx.accounts.treasury_account.key();
+
+    emit!(CanonicalAccountsUpdatedEvent {
+        stablecoin_mint: system_state.stablecoin_mint,
+        treasury_account: system_state.treasury_account,
+    });
+
+    Ok(())
 }
 
-#[event]
-pub struct CollateralTypeAddedEvent {
-    pub collateral_mint: Pubkey,
-    pub collateral_ratio: u64,
+/// Initialize the treasury reserves tracked for strategy deployments.
+pub fn initialize_treasury_reserves(ctx: Context<InitializeTreasuryReserves>, min_liquidity_bps: u64) -> Result<()> {
+    require!(min_liquidity_bps <= 10_000, ErrorCode::InvalidAmount);
+
+    let treasury_reserves = &mut ctx.accounts.treasury_reserves;
+    treasury_reserves.reserve_mint = ctx.accounts.reserve_vault.mint;
+    treasury_reserves.reserve_vault = ctx.accounts.reserve_vault.key();
+    treasury_reserves.liquid_reserves = ctx.accounts.reserve_vault.amount;
+    treasury_reserves.deployed_reserves = 0;
+    treasury_reserves.min_liquidity_bps = min_liquidity_bps;
+    treasury_reserves.authority_bump = ctx.bumps.treasury_authority;
+    treasury_reserves.bump = ctx.bumps.treasury_reserves;
+
+    Ok(())
 }
 
-#[event]
-pub struct MintStablecoinWithCollateralEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub collateral_type: Pubkey,
+/// Whitelist a yield strategy with a deployment cap.
+pub fn whitelist_strategy(ctx: Context<WhitelistStrategy>, cap: u64) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.adapter_program = ctx.accounts.adapter_program.key();
+    strategy.strategy_account = ctx.accounts.strategy_account.key();
+    strategy.cap = cap;
+    strategy.deployed = 0;
+    strategy.active = true;
+    strategy.treasury_reserves = ctx.accounts.treasury_reserves.key();
+
+    // Emit an event for the whitelisted strategy
+    emit!(StrategyWhitelistedEvent {
+        strategy: ctx.accounts.strategy.key(),
+        adapter_program: ctx.accounts.adapter_program.key(),
+        cap,
+    });
+
+    Ok(())
+}
+
+/// Update the cap of a strategy or stop new deployments to it.
+pub fn set_strategy_cap(ctx: Context<SetStrategyCap>, cap: u64, active: bool) -> Result<()> {
+    let strategy = &mut ctx.accounts.strategy;
+    strategy.cap = cap;
+    strategy.active = active;
+
+    emit!(StrategyCapUpdatedEvent {
+        strategy: ctx.accounts.strategy.key(),
+        cap,
+        active,
+    });
+
+    Ok(())
+}
+
+/// Deploy liquid treasury reserves into a whitelisted strategy.
+pub fn deploy_to_strategy<'info>(ctx: Context<'_, '_, '_, 'info, ManageStrategyFunds<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let strategy = &ctx.accounts.strategy;
+    require!(strategy.active, ErrorCode::StrategyNotWhitelisted);
+    let strategy_deployed = strategy.deployed.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    require!(strategy_deployed <= strategy.cap, ErrorCode::StrategyCapExceeded);
+
+    // Keep enough liquid reserves to honour redemptions after the deployment
+    let treasury_reserves = &ctx.accounts.treasury_reserves;
+    let liquid_after = treasury_reserves.liquid_reserves
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientLiquidReserves)?;
+    let total_reserves = treasury_reserves.liquid_reserves
+        .checked_add(treasury_reserves.deployed_reserves)
+        .ok_or(ErrorCode::Overflow)?;
+    let min_liquid = (total_reserves as u128)
+        .checked_mul(treasury_reserves.min_liquidity_bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / 10_000;
+    require!(liquid_after as u128 >= min_liquid, ErrorCode::InsufficientLiquidReserves);
+
+    let balance_before = ctx.accounts.reserve_vault.amount;
+    acquire_operation_lock(&mut ctx.accounts.system_state)?;
+    invoke_strategy_adapter(&ctx.accounts, ctx.remaining_accounts, STRATEGY_DEPOSIT_IX, amount)?;
+    release_operation_lock(&mut ctx.accounts.system_state);
+
+    // Make sure the adapter pulled exactly the requested amount
+    ctx.accounts.reserve_vault.reload()?;
+    let moved = balance_before.checked_sub(ctx.accounts.reserve_vault.amount).ok_or(ErrorCode::Overflow)?;
+    require!(moved == amount, ErrorCode::InvalidAmount);
+
+    let treasury_reserves = &mut ctx.accounts.treasury_reserves;
+    treasury_reserves.liquid_reserves = liquid_after;
+    treasury_reserves.deployed_reserves = treasury_reserves.deployed_reserves
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    ctx.accounts.strategy.deployed = strategy_deployed;
+
+    emit!(StrategyDeployedEvent {
+        strategy: ctx.accounts.strategy.key(),
+        amount,
+        liquid_reserves: ctx.accounts.treasury_reserves.liquid_reserves,
+        deployed_reserves: ctx.accounts.treasury_reserves.deployed_reserves,
+    });
+
+    Ok(())
+}
+
+/// Recall reserves from a strategy back into the liquid treasury vault.
+pub fn recall_from_strategy<'info>(ctx: Context<'_, '_, '_, 'info, ManageStrategyFunds<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let balance_before = ctx.accounts.reserve_vault.amount;
+    acquire_operation_lock(&mut ctx.accounts.system_state)?;
+    invoke_strategy_adapter(&ctx.accounts, ctx.remaining_accounts, STRATEGY_WITHDRAW_IX, amount)?;
+    release_operation_lock(&mut ctx.accounts.system_state);
+    ctx.accounts.reserve_vault.reload()?;
+    let received = ctx.accounts.reserve_vault.amount.checked_sub(balance_before).ok_or(ErrorCode::Overflow)?;
+    let yield_earned = record_strategy_recall(&mut ctx.accounts.strategy, &mut ctx.accounts.treasury_reserves, received)?;
+
+    emit!(StrategyRecalledEvent {
+        strategy: ctx.accounts.strategy.key(),
+        amount: received,
+        yield_earned,
+        liquid_reserves: ctx.accounts.treasury_reserves.liquid_reserves,
+        deployed_reserves: ctx.accounts.treasury_reserves.deployed_reserves,
+    });
+
+    Ok(())
+}
+
+/// Book `received` reserves returned by a strategy, returning the part that is yield.
+///
+/// Anything returned above the deployed principal is yield.
+fn record_strategy_recall(strategy: &mut YieldStrategy, treasury_reserves: &mut TreasuryReserves, received: u64) -> Result<u64> {
+    let principal = received.min(strategy.deployed);
+    strategy.deployed -= principal;
+
+    treasury_reserves.deployed_reserves = treasury_reserves.deployed_reserves.saturating_sub(principal);
+    treasury_reserves.liquid_reserves = treasury_reserves.liquid_reserves
+        .checked_add(received)
+        .ok_or(ErrorCode::Overflow)?;
+
+    Ok(received - principal)
+}
+
+/// Accounts passed to a strategy adapter, whichever instruction drives it.
+struct StrategyAdapterAccounts<'a, 'info> {
+    adapter_program: &'a AccountInfo<'info>,
+    reserve_vault: &'a AccountInfo<'info>,
+    strategy_account: &'a AccountInfo<'info>,
+    treasury_authority: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+    authority_bump: u8,
+}
+
+/// Call the strategy adapter interface, signing as the treasury authority PDA.
+fn invoke_strategy_adapter<'info>(
+    accounts: &ManageStrategyFunds<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    ix_name: &str,
+    amount: u64,
+) -> Result<()> {
+    let accounts = StrategyAdapterAccounts {
+        adapter_program: accounts.adapter_program.as_ref(),
+        reserve_vault: accounts.reserve_vault.as_ref(),
+        strategy_account: accounts.strategy_account.as_ref(),
+        treasury_authority: accounts.treasury_authority.as_ref(),
+        token_program: accounts.token_program.as_ref(),
+        authority_bump: accounts.treasury_reserves.authority_bump,
+    };
+    call_strategy_adapter(&accounts, remaining_accounts, ix_name, amount)
+}
+
+/// Adapters receive `[reserve_vault, strategy_account, treasury_authority, token_program]`
+/// followed by any remaining accounts, with instruction data `discriminator || amount`.
+fn call_strategy_adapter<'info>(
+    accounts: &StrategyAdapterAccounts<'_, 'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    ix_name: &str,
+    amount: u64,
+) -> Result<()> {
+    let mut data = hash(ix_name.as_bytes()).to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new(accounts.reserve_vault.key(), false),
+        AccountMeta::new(accounts.strategy_account.key(), false),
+        AccountMeta::new_readonly(accounts.treasury_authority.key(), true),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    let mut infos = vec![
+        accounts.reserve_vault.clone(),
+        accounts.strategy_account.clone(),
+        accounts.treasury_authority.clone(),
+        accounts.token_program.clone(),
+    ];
+    for account in remaining_accounts {
+        metas.push(if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        });
+        infos.push(account.clone());
+    }
+
+    let ix = Instruction {
+        program_id: accounts.adapter_program.key(),
+        accounts: metas,
+        data,
+    };
+    let bump = [accounts.authority_bump];
+    let seeds: &[&[u8]] = &[b"treasury_authority", &bump];
+    invoke_signed(&ix, &infos, &[seeds])?;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Reserve Attestation Instructions
+// -------------------------------------
+
+/// Initialize reserve attestation with the attestor role.
+pub fn initialize_reserve_attestation(ctx: Context<InitializeReserveAttestation>, attestor: Pubkey) -> Result<()> {
+    let reserve_attestation = &mut ctx.accounts.reserve_attestation;
+    reserve_attestation.attestor = attestor;
+    reserve_attestation.treasury_reserves = ctx.accounts.treasury_reserves.key();
+    reserve_attestation.last_report_epoch = 0;
+    reserve_attestation.report_count = 0;
+
+    Ok(())
+}
+
+/// Rotate the attestor allowed to publish reserve reports.
+pub fn set_reserve_attestor(ctx: Context<SetReserveAttestor>, attestor: Pubkey) -> Result<()> {
+    ctx.accounts.reserve_attestation.attestor = attestor;
+
+    Ok(())
+}
+
+/// Publish the reserve report for the current epoch.
+pub fn publish_reserve_report(ctx: Context<PublishReserveReport>, collateral_value: u64, report_hash: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let treasury_reserves = &ctx.accounts.treasury_reserves;
+
+    // Treasury components are read on-chain, only the collateral valuation is attested
+    let total_backing = collateral_value
+        .checked_add(treasury_reserves.liquid_reserves)
+        .and_then(|total| total.checked_add(treasury_reserves.deployed_reserves))
+        .ok_or(ErrorCode::Overflow)?;
+
+    let reserve_report = &mut ctx.accounts.reserve_report;
+    reserve_report.epoch = clock.epoch;
+    reserve_report.collateral_value = collateral_value;
+    reserve_report.psm_reserves = treasury_reserves.liquid_reserves;
+    reserve_report.deployed_reserves = treasury_reserves.deployed_reserves;
+    reserve_report.total_backing = total_backing;
+    reserve_report.report_hash = report_hash;
+    reserve_report.attestor = ctx.accounts.attestor.key();
+    reserve_report.published_at = clock.unix_timestamp;
+
+    let reserve_attestation = &mut ctx.accounts.reserve_attestation;
+    reserve_attestation.last_report_epoch = clock.epoch;
+    reserve_attestation.report_count = reserve_attestation.report_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    // Emit an event so the backing history can be verified from logs
+    emit!(ReserveReportEvent {
+        report: ctx.accounts.reserve_report.key(),
+        epoch: clock.epoch,
+        collateral_value,
+        psm_reserves: ctx.accounts.reserve_report.psm_reserves,
+        deployed_reserves: ctx.accounts.reserve_report.deployed_reserves,
+        total_backing,
+        report_hash,
+        attestor: ctx.accounts.attestor.key(),
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Compliance Instructions
+// -------------------------------------
+
+/// Blacklist a wallet from transferring the permissioned stablecoin.
+pub fn add_to_blacklist(ctx: Context<AddToBlacklist>, wallet: Pubkey) -> Result<()> {
+    let blacklist_entry = &mut ctx.accounts.blacklist_entry;
+    blacklist_entry.wallet = wallet;
+    blacklist_entry.added_at = Clock::get()?.unix_timestamp;
+
+    emit!(BlacklistUpdatedEvent {
+        wallet,
+        blacklisted: true,
+    });
+
+    Ok(())
+}
+
+/// Remove a wallet from the blacklist by closing its entry.
+pub fn remove_from_blacklist(ctx: Context<RemoveFromBlacklist>) -> Result<()> {
+    emit!(BlacklistUpdatedEvent {
+        wallet: ctx.accounts.blacklist_entry.wallet,
+        blacklisted: false,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Wrapped Yield Token Instructions
+// -------------------------------------
+
+/// Fixed-point precision of the wrapper exchange rate.
+pub const EXCHANGE_RATE_PRECISION: u128 = 1_000_000_000_000;
+/// Seconds in a year, used to pro-rate annual rates.
+pub const SECONDS_PER_YEAR: u128 = 31_536_000;
+
+/// Initialize the wrapper vault with a starting savings rate.
+pub fn initialize_wrapper_vault(ctx: Context<InitializeWrapperVault>, savings_rate_bps: u64) -> Result<()> {
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    wrapper_vault.stablecoin_mint = ctx.accounts.stablecoin_mint.key();
+    wrapper_vault.wrapped_mint = ctx.accounts.wrapped_mint.key();
+    wrapper_vault.vault = ctx.accounts.vault.key();
+    wrapper_vault.savings_rate_bps = savings_rate_bps;
+    wrapper_vault.exchange_rate = EXCHANGE_RATE_PRECISION;
+    wrapper_vault.last_accrual_time = Clock::get()?.unix_timestamp;
+    wrapper_vault.authority_bump = ctx.bumps.wrapper_authority;
+
+    Ok(())
+}
+
+/// Update the savings rate, accruing at the old rate up to now.
+pub fn set_savings_rate(ctx: Context<SetSavingsRate>, savings_rate_bps: u64) -> Result<()> {
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    accrue_exchange_rate(wrapper_vault, Clock::get()?.unix_timestamp)?;
+    wrapper_vault.savings_rate_bps = savings_rate_bps;
+
+    emit!(SavingsRateUpdatedEvent {
+        wrapper_vault: wrapper_vault.key(),
+        savings_rate_bps,
+        exchange_rate: wrapper_vault.exchange_rate,
+    });
+
+    Ok(())
+}
+
+/// Deposit stablecoin and receive wrapped tokens at the current exchange rate.
+pub fn wrap(ctx: Context<WrapStablecoin>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    accrue_exchange_rate(wrapper_vault, Clock::get()?.unix_timestamp)?;
+    let wrapped_amount = stablecoin_to_wrapped(wrapper_vault, amount)?;
+
+    // Move the stablecoin into the vault
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_stablecoin_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    // Mint the wrapped token to the user
+    let wrapper_key = ctx.accounts.wrapper_vault.key();
+    let bump = [ctx.accounts.wrapper_vault.authority_bump];
+    let seeds: &[&[u8]] = &[b"wrapper_authority", wrapper_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.wrapped_mint.to_account_info(),
+        to: ctx.accounts.user_wrapped_account.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, wrapped_amount)?;
+
+    emit!(WrapEvent {
+        user: ctx.accounts.user.key(),
+        stablecoin_amount: amount,
+        wrapped_amount,
+        exchange_rate: ctx.accounts.wrapper_vault.exchange_rate,
+    });
+
+    Ok(())
+}
+
+/// Burn wrapped tokens and withdraw the underlying stablecoin plus accrued yield.
+pub fn unwrap(ctx: Context<WrapStablecoin>, wrapped_amount: u64, deadline_ts: Option<i64>, min_out: Option<u64>) -> Result<()> {
+    require!(wrapped_amount > 0, ErrorCode::InvalidAmount);
+    check_deadline(deadline_ts)?;
+
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    accrue_exchange_rate(wrapper_vault, Clock::get()?.unix_timestamp)?;
+    let amount = wrapped_to_stablecoin(wrapper_vault, wrapped_amount)?;
+    require!(amount >= min_out.unwrap_or(0), ErrorCode::SlippageExceeded);
+    require!(ctx.accounts.vault.amount >= amount, ErrorCode::InsufficientFunds);
+
+    // Burn the wrapped token from the user
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.wrapped_mint.to_account_info(),
+        from: ctx.accounts.user_wrapped_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, wrapped_amount)?;
+
+    // Release the stablecoin from the vault
+    let wrapper_key = ctx.accounts.wrapper_vault.key();
+    let bump = [ctx.accounts.wrapper_vault.authority_bump];
+    let seeds: &[&[u8]] = &[b"wrapper_authority", wrapper_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.user_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(UnwrapEvent {
+        user: ctx.accounts.user.key(),
+        stablecoin_amount: amount,
+        wrapped_amount,
+        exchange_rate: ctx.accounts.wrapper_vault.exchange_rate,
+    });
+
+    Ok(())
+}
+
+/// Top up the wrapper vault with stablecoin that backs accrued yield.
+pub fn fund_wrapper_yield(ctx: Context<FundWrapperYield>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_stablecoin_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+/// Grow the exchange rate by the savings rate for the time elapsed since the last accrual.
+fn accrue_exchange_rate(wrapper_vault: &mut WrapperVault, now: i64) -> Result<()> {
+    let elapsed = now.saturating_sub(wrapper_vault.last_accrual_time).max(0) as u128;
+    if elapsed > 0 && wrapper_vault.savings_rate_bps > 0 {
+        let growth = wrapper_vault.exchange_rate
+            .checked_mul(wrapper_vault.savings_rate_bps as u128)
+            .and_then(|value| value.checked_mul(elapsed))
+            .ok_or(ErrorCode::Overflow)?
+            / (10_000 * SECONDS_PER_YEAR);
+        wrapper_vault.exchange_rate = wrapper_vault.exchange_rate.checked_add(growth).ok_or(ErrorCode::Overflow)?;
+    }
+    wrapper_vault.last_accrual_time = now;
+
+    Ok(())
+}
+
+/// Wrapped tokens issued for a stablecoin deposit, rounded down so the wrapper never issues more than it holds.
+fn stablecoin_to_wrapped(wrapper_vault: &WrapperVault, amount: u64) -> Result<u64> {
+    let wrapped_amount = math::mul_div_down(amount as u128, EXCHANGE_RATE_PRECISION, wrapper_vault.exchange_rate)?;
+    require!(wrapped_amount > 0, ErrorCode::InvalidAmount);
+
+    Ok(wrapped_amount)
+}
+
+/// Stablecoin released for burned wrapped tokens, rounded down in the vault's favour.
+fn wrapped_to_stablecoin(wrapper_vault: &WrapperVault, wrapped_amount: u64) -> Result<u64> {
+    math::mul_div_down(wrapped_amount as u128, wrapper_vault.exchange_rate, EXCHANGE_RATE_PRECISION)
+}
+
+// -------------------------------------
+// Bond Market Instructions
+// -------------------------------------
+
+/// Fixed-point precision of bond prices.
+pub const BOND_PRICE_PRECISION: u128 = 1_000_000;
+
+/// Bond market pricing and vesting parameters.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BondMarketParams {
+    pub initial_price: u64,
+    pub min_price: u64,
+    pub decay_bps_per_day: u64,
+    pub price_bump_bps: u64,
+    pub vesting_period: i64,
+    pub capacity: u64,
+}
+
+/// Create a bond market selling protocol tokens for a quote asset.
+pub fn create_bond_market(ctx: Context<CreateBondMarket>, params: BondMarketParams) -> Result<()> {
+    require!(params.initial_price >= params.min_price && params.min_price > 0, ErrorCode::InvalidPrice);
+    require!(params.decay_bps_per_day <= 10_000, ErrorCode::InvalidAmount);
+    require!(params.vesting_period > 0, ErrorCode::InvalidLockupPeriod);
+
+    let bond_market = &mut ctx.accounts.bond_market;
+    bond_market.quote_mint = ctx.accounts.quote_mint.key();
+    bond_market.payout_mint = ctx.accounts.payout_mint.key();
+    bond_market.payout_vault = ctx.accounts.payout_vault.key();
+    bond_market.pol_account = ctx.accounts.pol_account.key();
+    bond_market.last_price = params.initial_price;
+    bond_market.min_price = params.min_price;
+    bond_market.decay_bps_per_day = params.decay_bps_per_day;
+    bond_market.price_bump_bps = params.price_bump_bps;
+    bond_market.last_price_update = Clock::get()?.unix_timestamp;
+    bond_market.vesting_period = params.vesting_period;
+    bond_market.capacity = params.capacity;
+    bond_market.total_sold = 0;
+    bond_market.total_redeemed = 0;
+    bond_market.authority_bump = ctx.bumps.bond_authority;
+
+    emit!(BondMarketCreatedEvent {
+        bond_market: ctx.accounts.bond_market.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        payout_mint: ctx.accounts.payout_mint.key(),
+        initial_price: params.initial_price,
+        capacity: params.capacity,
+    });
+
+    Ok(())
+}
+
+/// Purchase a bond, paying the quote asset into protocol-owned liquidity.
+pub fn purchase_bond(ctx: Context<PurchaseBond>, quote_amount: u64, max_price: u64) -> Result<()> {
+    require!(quote_amount > 0, ErrorCode::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let bond_market = &mut ctx.accounts.bond_market;
+    let price = current_bond_price(bond_market, now)?;
+    require!(price <= max_price, ErrorCode::SlippageExceeded);
+
+    let payout = (quote_amount as u128)
+        .checked_mul(BOND_PRICE_PRECISION)
+        .ok_or(ErrorCode::Overflow)?
+        / price as u128;
+    let payout = u64::try_from(payout).map_err(|_| ErrorCode::Overflow)?;
+    require!(payout > 0, ErrorCode::InvalidAmount);
+
+    // Never let unredeemed bonds exceed the market capacity or what the vault can pay out
+    let total_sold = bond_market.total_sold.checked_add(payout).ok_or(ErrorCode::Overflow)?;
+    let outstanding = total_sold.saturating_sub(bond_market.total_redeemed);
+    require!(outstanding <= bond_market.capacity, ErrorCode::BondCapacityExceeded);
+    require!(outstanding <= ctx.accounts.payout_vault.amount, ErrorCode::BondCapacityExceeded);
+
+    // Each purchase pushes the price up, it then decays until the next purchase
+    let bumped_price = (price as u128)
+        .checked_mul(10_000 + bond_market.price_bump_bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / 10_000;
+    bond_market.last_price = u64::try_from(bumped_price).map_err(|_| ErrorCode::Overflow)?;
+    bond_market.last_price_update = now;
+    bond_market.total_sold = total_sold;
+
+    let bond_position = &mut ctx.accounts.bond_position;
+    bond_position.owner = ctx.accounts.buyer.key();
+    bond_position.bond_market = ctx.accounts.bond_market.key();
+    bond_position.payout_total = payout;
+    bond_position.payout_claimed = 0;
+    bond_position.vesting_start = now;
+    bond_position.vesting_end = now.checked_add(ctx.accounts.bond_market.vesting_period).ok_or(ErrorCode::Overflow)?;
+
+    // Proceeds go straight to protocol-owned liquidity
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.buyer_quote_account.to_account_info(),
+        to: ctx.accounts.pol_account.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, quote_amount)?;
+
+    emit!(BondPurchasedEvent {
+        bond_market: ctx.accounts.bond_market.key(),
+        bond_position: ctx.accounts.bond_position.key(),
+        buyer: ctx.accounts.buyer.key(),
+        quote_amount,
+        payout,
+        price,
+    });
+
+    Ok(())
+}
+
+/// Redeem the vested portion of a bond.
+pub fn redeem_bond(ctx: Context<RedeemBond>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let bond_position = &mut ctx.accounts.bond_position;
+
+    // Linear vesting between start and end
+    let vested = if now >= bond_position.vesting_end {
+        bond_position.payout_total
+    } else {
+        let elapsed = now.saturating_sub(bond_position.vesting_start).max(0) as u128;
+        let duration = bond_position.vesting_end.saturating_sub(bond_position.vesting_start).max(1) as u128;
+        ((bond_position.payout_total as u128 * elapsed) / duration) as u64
+    };
+    let amount = vested.checked_sub(bond_position.payout_claimed).ok_or(ErrorCode::Overflow)?;
+    require!(amount > 0, ErrorCode::NothingVested);
+    bond_position.payout_claimed = vested;
+    let bond_market = &mut ctx.accounts.bond_market;
+    bond_market.total_redeemed = bond_market.total_redeemed.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    let bond_market_key = ctx.accounts.bond_market.key();
+    let bump = [ctx.accounts.bond_market.authority_bump];
+    let seeds: &[&[u8]] = &[b"bond_authority", bond_market_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.payout_vault.to_account_info(),
+        to: ctx.accounts.owner_payout_account.to_account_info(),
+        authority: ctx.accounts.bond_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(BondRedeemedEvent {
+        bond_position: ctx.accounts.bond_position.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Price after decaying from the last purchase, floored at the market minimum.
+fn current_bond_price(bond_market: &BondMarket, now: i64) -> Result<u64> {
+    let elapsed = now.saturating_sub(bond_market.last_price_update).max(0) as u128;
+    let decay = (bond_market.last_price as u128)
+        .checked_mul(bond_market.decay_bps_per_day as u128)
+        .and_then(|value| value.checked_mul(elapsed))
+        .ok_or(ErrorCode::Overflow)?
+        / (10_000 * 86_400);
+    let decayed = (bond_market.last_price as u128).saturating_sub(decay) as u64;
+
+    Ok(decayed.max(bond_market.min_price))
+}
+
+// -------------------------------------
+// Airdrop Instructions
+// -------------------------------------
+
+/// Create an airdrop distributor for a merkle tree of claims.
+pub fn create_airdrop_distributor(ctx: Context<CreateAirdropDistributor>, merkle_root: [u8; 32], max_num_nodes: u64) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.merkle_root = merkle_root;
+    distributor.mint = ctx.accounts.mint.key();
+    distributor.vault = ctx.accounts.vault.key();
+    distributor.max_num_nodes = max_num_nodes;
+    distributor.num_claimed = 0;
+    distributor.total_claimed = 0;
+    distributor.authority_bump = ctx.bumps.airdrop_authority;
+    distributor.claimed_bitmap = vec![0u8; (max_num_nodes as usize + 7) / 8];
+
+    emit!(AirdropDistributorCreatedEvent {
+        distributor: ctx.accounts.distributor.key(),
+        mint: ctx.accounts.mint.key(),
+        merkle_root,
+        max_num_nodes,
+    });
+
+    Ok(())
+}
+
+/// Claim an airdrop allocation by proving its leaf is in the merkle tree.
+pub fn claim_airdrop(ctx: Context<ClaimAirdrop>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+    let distributor = &mut ctx.accounts.distributor;
+    require!(index < distributor.max_num_nodes, ErrorCode::InvalidMerkleProof);
+
+    // Each leaf can only be claimed once
+    let byte = (index / 8) as usize;
+    let bit = 1u8 << (index % 8);
+    require!(distributor.claimed_bitmap[byte] & bit == 0, ErrorCode::AirdropAlreadyClaimed);
+
+    let claimant = ctx.accounts.claimant.key();
+    let leaf = keccak::hashv(&[&index.to_le_bytes(), claimant.as_ref(), &amount.to_le_bytes()]).0;
+    require!(verify_merkle_proof(&proof, distributor.merkle_root, leaf), ErrorCode::InvalidMerkleProof);
+
+    distributor.claimed_bitmap[byte] |= bit;
+    distributor.num_claimed = distributor.num_claimed.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    distributor.total_claimed = distributor.total_claimed.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    let distributor_key = ctx.accounts.distributor.key();
+    let bump = [ctx.accounts.distributor.authority_bump];
+    let seeds: &[&[u8]] = &[b"airdrop_authority", distributor_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.claimant_token_account.to_account_info(),
+        authority: ctx.accounts.airdrop_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(AirdropClaimedEvent {
+        distributor: distributor_key,
+        claimant,
+        index,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Verify a merkle proof built with sorted-pair keccak hashing.
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).0
+        } else {
+            keccak::hashv(&[node, &computed]).0
+        };
+    }
+    computed == root
+}
+
+// -------------------------------------
+// Vesting Stream Instructions
+// -------------------------------------
+
+/// Create a vesting stream funded from the treasury.
+pub fn create_vesting_stream(
+    ctx: Context<CreateVestingStream>,
+    recipient: Pubkey,
+    rate_per_second: u64,
+    start_time: i64,
+    cliff_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    require!(rate_per_second > 0, ErrorCode::InvalidAmount);
+    require!(start_time <= cliff_time && cliff_time <= end_time && start_time < end_time, ErrorCode::InvalidLockupPeriod);
+
+    // Escrow the full stream up front so it can never be underfunded
+    let total = rate_per_second
+        .checked_mul((end_time - start_time) as u64)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.recipient = recipient;
+    stream.mint = ctx.accounts.mint.key();
+    stream.escrow = ctx.accounts.escrow.key();
+    stream.rate_per_second = rate_per_second;
+    stream.start_time = start_time;
+    stream.cliff_time = cliff_time;
+    stream.end_time = end_time;
+    stream.withdrawn = 0;
+    stream.authority_bump = ctx.bumps.stream_authority;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury_account.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.governance_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, total)?;
+
+    emit!(VestingStreamCreatedEvent {
+        stream: ctx.accounts.stream.key(),
+        recipient,
+        mint: ctx.accounts.mint.key(),
+        rate_per_second,
+        cliff_time,
+        end_time,
+        total,
+    });
+
+    Ok(())
+}
+
+/// Withdraw everything streamed to the recipient so far.
+pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let stream = &mut ctx.accounts.stream;
+    require!(now >= stream.cliff_time, ErrorCode::LockupPeriodNotOver);
+
+    let elapsed = now.min(stream.end_time).saturating_sub(stream.start_time).max(0) as u64;
+    let streamed = stream.rate_per_second.checked_mul(elapsed).ok_or(ErrorCode::Overflow)?;
+    let amount = streamed.checked_sub(stream.withdrawn).ok_or(ErrorCode::Overflow)?;
+    require!(amount > 0, ErrorCode::NothingVested);
+    stream.withdrawn = streamed;
+
+    let stream_key = ctx.accounts.stream.key();
+    let bump = [ctx.accounts.stream.authority_bump];
+    let seeds: &[&[u8]] = &[b"stream_authority", stream_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.stream_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(VestingStreamWithdrawnEvent {
+        stream: stream_key,
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Position Health Instructions
+// -------------------------------------
+
+/// Update the health bands used for position notifications.
+pub fn set_health_bands(ctx: Context<SetHealthBands>, warning_bps: u64, critical_bps: u64) -> Result<()> {
+    require!(warning_bps > critical_bps && critical_bps > 10_000, ErrorCode::InvalidCollateralRatio);
+
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.health_warning_bps = warning_bps;
+    system_state.health_critical_bps = critical_bps;
+
+    Ok(())
+}
+
+/// Crank that re-evaluates a position's health band and emits crossing events.
+pub fn refresh_position_health(ctx: Context<RefreshPositionHealth>) -> Result<()> {
+    update_health_band(
+        &mut ctx.accounts.user_account,
+        &ctx.accounts.system_state,
+        ctx.accounts.collateral_type.as_deref(),
+        ctx.accounts.price_cache.as_deref(),
+    )
+}
+
+/// Health factor of a position in bps, where 10_000 is its liquidation threshold.
+///
+/// Collateral-backed positions are measured against their collateral's
+/// liquidation threshold rather than the ratio required to mint, so the
+/// bands line up with `require_liquidatable`. Collateral is valued at the
+/// cached price when one is passed and one-to-one otherwise.
+pub fn position_health_bps(user_account: &UserAccount, collateral_type: Option<&CollateralType>, price_cache: Option<&PriceCache>) -> Result<u64> {
+    let floor = liquidation_ratio_floor(user_account, collateral_type);
+    if user_account.stablecoin_balance == 0 || floor == 0 {
+        return Ok(u64::MAX);
+    }
+    let collateral_value = match price_cache {
+        Some(_) => position_collateral_value(user_account, collateral_type, price_cache)?,
+        None => user_account.collateral_balance as u128,
+    };
+    let health = (collateral_value * 100 * 10_000) / (user_account.stablecoin_balance as u128 * floor as u128);
+    Ok(health.min(u64::MAX as u128) as u64)
+}
+
+/// Record the position's current band and emit an event when it changed.
+pub fn update_health_band(
+    user_account: &mut Account<UserAccount>,
+    system_state: &SystemState,
+    collateral_type: Option<&CollateralType>,
+    price_cache: Option<&PriceCache>,
+) -> Result<()> {
+    let health_bps = position_health_bps(user_account, collateral_type, price_cache)?;
+    let new_band = if health_bps < 10_000 {
+        HealthBand::Liquidatable
+    } else if health_bps < system_state.health_critical_bps {
+        HealthBand::Critical
+    } else if health_bps < system_state.health_warning_bps {
+        HealthBand::Warning
+    } else {
+        HealthBand::Healthy
+    };
+
+    let previous_band = user_account.health_band;
+    if new_band > previous_band {
+        emit!(HealthDeteriorated {
+            user: user_account.key(),
+            previous_band,
+            new_band,
+            health_bps,
+            tag: user_account.tag,
+        });
+    } else if new_band < previous_band {
+        emit!(HealthRestored {
+            user: user_account.key(),
+            previous_band,
+            new_band,
+            health_bps,
+            tag: user_account.tag,
+        });
+    }
+    user_account.health_band = new_band;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Oracle Configuration Instructions
+// -------------------------------------
+
+/// Set the price feed and its staleness and confidence limits for a collateral type.
+pub fn set_oracle_config(ctx: Context<SetOracleConfig>, max_price_age_secs: u64, max_confidence_bps: u64) -> Result<()> {
+    oracle::check_feed_owner(&ctx.accounts.price_feed, &ctx.accounts.system_state.oracle_programs)?;
+
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.price_feed = ctx.accounts.price_feed.key();
+    collateral_type.max_price_age_secs = max_price_age_secs;
+    collateral_type.max_confidence_bps = max_confidence_bps;
+
+    emit!(OracleConfigUpdatedEvent {
+        collateral_type: collateral_type.key(),
+        price_feed: collateral_type.price_feed,
+        max_price_age_secs,
+        max_confidence_bps,
+    });
+
+    Ok(())
+}
+
+/// Replace the oracle programs price feeds may be owned by.
+///
+/// Every price read checks the feed's owner against this list, so a spoofed
+/// account laid out like a price update is rejected.
+pub fn set_oracle_programs(ctx: Context<SetOraclePrograms>, programs: Vec<Pubkey>) -> Result<()> {
+    require!(!programs.is_empty() && programs.len() <= MAX_ORACLE_PROGRAMS, ErrorCode::InvalidOraclePrograms);
+    for (i, program) in programs.iter().enumerate() {
+        require!(*program != Pubkey::default() && !programs[..i].contains(program), ErrorCode::InvalidOraclePrograms);
+    }
+
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.oracle_programs = [Pubkey::default(); MAX_ORACLE_PROGRAMS];
+    system_state.oracle_programs[..programs.len()].copy_from_slice(&programs);
+
+    emit!(OracleProgramsUpdatedEvent { programs });
+
+    Ok(())
+}
+
+/// Set the liquidation bonus curve of a collateral type.
+pub fn set_liquidation_bonus_curve(ctx: Context<SetLiquidationBonusCurve>, base_bonus_bps: u64, slope_bps: u64, max_bonus_bps: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.liquidation_penalty_bps = base_bonus_bps;
+    collateral_type.liquidation_bonus_slope_bps = slope_bps;
+    collateral_type.max_liquidation_bonus_bps = max_bonus_bps;
+
+    emit!(LiquidationBonusCurveUpdatedEvent {
+        collateral_type: collateral_type.key(),
+        base_bonus_bps,
+        slope_bps,
+        max_bonus_bps,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Governance Token Instructions
+// -------------------------------------
+
+/// Create the governance token mint under a program PDA.
+pub fn initialize_gov_token(ctx: Context<InitializeGovToken>) -> Result<()> {
+    let gov_token = &mut ctx.accounts.gov_token;
+    gov_token.mint = ctx.accounts.gov_token_mint.key();
+    gov_token.remaining_mintable = GOV_TOKEN_MAX_SUPPLY;
+    gov_token.total_emitted = 0;
+    gov_token.total_auctioned = 0;
+    gov_token.authority_bump = ctx.bumps.gov_token_authority;
+
+    Ok(())
+}
+
+/// Mint governance tokens for emissions, bounded by the remaining supply.
+pub fn mint_gov_emissions(ctx: Context<MintGovEmissions>, amount: u64) -> Result<()> {
+    mint_gov_tokens(
+        &mut ctx.accounts.gov_token,
+        ctx.accounts.gov_token_authority.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        GovMintPath::Emissions,
+    )
+}
+
+/// The only path through which governance tokens are ever minted.
+///
+/// Every caller decrements the remaining mintable supply, so the sum of all
+/// mint paths can never exceed `GOV_TOKEN_MAX_SUPPLY`.
+pub fn mint_gov_tokens<'info>(
+    gov_token: &mut Account<'info, GovToken>,
+    gov_token_authority: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    path: GovMintPath,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    gov_token.remaining_mintable = gov_token.remaining_mintable
+        .checked_sub(amount)
+        .ok_or(ErrorCode::GovTokenSupplyExhausted)?;
+    match path {
+        GovMintPath::Emissions => {
+            gov_token.total_emitted = gov_token.total_emitted.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        }
+        GovMintPath::DebtAuction => {
+            gov_token.total_auctioned = gov_token.total_auctioned.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        }
+    }
+
+    let bump = [gov_token.authority_bump];
+    let seeds: &[&[u8]] = &[b"gov_token_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = MintTo {
+        mint,
+        to: destination.clone(),
+        authority: gov_token_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, amount)?;
+
+    emit!(GovTokensMintedEvent {
+        destination: destination.key(),
+        amount,
+        path,
+        remaining_mintable: gov_token.remaining_mintable,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Risk Tier Instructions
+// -------------------------------------
+
+/// Default parameter bundles for each risk tier, indexed by `RiskTier`.
+pub const DEFAULT_RISK_TIERS: [RiskParams; 4] = [
+    // Stable: fiat-backed stablecoins
+    RiskParams { collateral_ratio: 110, liquidation_threshold: 105, liquidation_penalty_bps: 300, debt_ceiling: 50_000_000_000_000, stability_fee: 50 },
+    // Major: SOL, BTC, ETH
+    RiskParams { collateral_ratio: 150, liquidation_threshold: 130, liquidation_penalty_bps: 1_000, debt_ceiling: 20_000_000_000_000, stability_fee: 200 },
+    // Volatile: liquid staking tokens and large caps
+    RiskParams { collateral_ratio: 200, liquidation_threshold: 170, liquidation_penalty_bps: 1_300, debt_ceiling: 5_000_000_000_000, stability_fee: 400 },
+    // Exotic: long-tail assets
+    RiskParams { collateral_ratio: 300, liquidation_threshold: 250, liquidation_penalty_bps: 1_500, debt_ceiling: 1_000_000_000_000, stability_fee: 800 },
+];
+
+/// Initialize the risk tier config with the default parameter bundles.
+pub fn initialize_risk_tiers(ctx: Context<InitializeRiskTiers>) -> Result<()> {
+    ctx.accounts.risk_tier_config.tiers = DEFAULT_RISK_TIERS;
+
+    Ok(())
+}
+
+/// Replace the parameter bundle of a risk tier.
+pub fn set_risk_tier_params(ctx: Context<SetRiskTierParams>, tier: RiskTier, params: RiskParams) -> Result<()> {
+    validate_risk_params(&params)?;
+    ctx.accounts.risk_tier_config.tiers[tier as usize] = params;
+
+    emit!(RiskTierUpdatedEvent {
+        tier,
+        params,
+    });
+
+    Ok(())
+}
+
+/// Onboard a new collateral type from a risk tier, overriding only the given parameters.
+pub fn onboard_collateral(ctx: Context<OnboardCollateral>, tier: RiskTier, overrides: RiskParamOverrides) -> Result<()> {
+    let defaults = ctx.accounts.risk_tier_config.tiers[tier as usize];
+    let params = RiskParams {
+        collateral_ratio: overrides.collateral_ratio.unwrap_or(defaults.collateral_ratio),
+        liquidation_threshold: overrides.liquidation_threshold.unwrap_or(defaults.liquidation_threshold),
+        liquidation_penalty_bps: overrides.liquidation_penalty_bps.unwrap_or(defaults.liquidation_penalty_bps),
+        debt_ceiling: overrides.debt_ceiling.unwrap_or(defaults.debt_ceiling),
+        stability_fee: overrides.stability_fee.unwrap_or(defaults.stability_fee),
+    };
+    validate_risk_params(&params)?;
+    oracle::check_feed_owner(&ctx.accounts.price_feed, &ctx.accounts.system_state.oracle_programs)?;
+
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.collateral_mint = ctx.accounts.collateral_mint.key();
+    collateral_type.collateral_ratio = params.collateral_ratio;
+    collateral_type.price_feed = ctx.accounts.price_feed.key();
+    collateral_type.liquidation_threshold = params.liquidation_threshold;
+    collateral_type.stability_fee = params.stability_fee;
+    collateral_type.max_price_age_secs = DEFAULT_MAX_PRICE_AGE_SECS;
+    collateral_type.max_confidence_bps = DEFAULT_MAX_CONFIDENCE_BPS;
+    collateral_type.liquidation_penalty_bps = params.liquidation_penalty_bps;
+    collateral_type.debt_ceiling = params.debt_ceiling;
+    collateral_type.risk_tier = tier;
+    collateral_type.liquidation_bonus_slope_bps = DEFAULT_LIQUIDATION_BONUS_SLOPE_BPS;
+    collateral_type.max_liquidation_bonus_bps = DEFAULT_MAX_LIQUIDATION_BONUS_BPS.max(params.liquidation_penalty_bps);
+    collateral_type.mint_fee_bps = None;
+    collateral_type.redemption_fee_bps = None;
+    collateral_type.exchange_rate = 0;
+    collateral_type.yield_skim_bps = 0;
+    collateral_type.confidence_premium_threshold_bps = 0;
+    collateral_type.confidence_premium_slope_bps = 0;
+    collateral_type.max_confidence_premium_bps = 0;
+    collateral_type.stake_pool_config = Pubkey::default();
+    collateral_type.minting_paused = false;
+    collateral_type.receipt_config = Pubkey::default();
+
+    emit!(CollateralOnboardedEvent {
+        collateral_type: collateral_type.key(),
+        collateral_mint: collateral_type.collateral_mint,
+        tier,
+        params,
+    });
+
+    Ok(())
+}
+
+/// Check that a parameter bundle is internally consistent.
+fn validate_risk_params(params: &RiskParams) -> Result<()> {
+    require!(risk_ratios_valid(params), ErrorCode::InvalidCollateralRatio);
+    require!(params.liquidation_penalty_bps < 10_000, ErrorCode::InvalidAmount);
+    require!(debt_ceiling_valid(params), ErrorCode::InvalidAmount);
+
+    Ok(())
+}
+
+fn risk_ratios_valid(params: &RiskParams) -> bool {
+    params.collateral_ratio > params.liquidation_threshold && params.liquidation_threshold > 100
+}
+
+fn debt_ceiling_valid(params: &RiskParams) -> bool {
+    params.debt_ceiling > 0 && params.debt_ceiling <= MAX_DEBT_CEILING
+}
+
+/// Largest debt ceiling a single collateral type may be configured with.
+pub const MAX_DEBT_CEILING: u64 = 1_000_000_000 * 1_000_000;
+/// Largest collateral mint decimals the valuation math supports.
+pub const MAX_COLLATERAL_DECIMALS: u8 = 12;
+
+/// Per-check outcome of a collateral configuration dry run.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct CollateralParamsReport {
+    pub ratios_valid: bool,             // collateral_ratio > liquidation_threshold > 100
+    pub penalty_valid: bool,            // Liquidation penalty below 100%
+    pub debt_ceiling_valid: bool,       // Ceiling is non-zero and below MAX_DEBT_CEILING
+    pub oracle_valid: bool,             // Price feed parses as a fully verified price update
+    pub decimals_supported: bool,       // Mint decimals are within MAX_COLLATERAL_DECIMALS
+    pub valid: bool,                    // Every check passed
+}
+
+/// Dry-run a proposed collateral configuration, returning the result of every check.
+///
+/// Nothing is written; the report is returned through return data so
+/// governance UIs can pre-validate a proposal by simulating this instruction.
+pub fn validate_collateral_params(ctx: Context<ValidateCollateralParams>, params: RiskParams) -> Result<CollateralParamsReport> {
+    let ratios_valid = risk_ratios_valid(&params);
+    let penalty_valid = params.liquidation_penalty_bps < 10_000;
+    let debt_ceiling_valid = debt_ceiling_valid(&params);
+    let oracle_valid = oracle::probe_price_feed(&ctx.accounts.price_feed, &ctx.accounts.system_state.oracle_programs);
+    let decimals_supported = ctx.accounts.collateral_mint.decimals <= MAX_COLLATERAL_DECIMALS;
+
+    Ok(CollateralParamsReport {
+        ratios_valid,
+        penalty_valid,
+        debt_ceiling_valid,
+        oracle_valid,
+        decimals_supported,
+        valid: ratios_valid && penalty_valid && debt_ceiling_valid && oracle_valid && decimals_supported,
+    })
+}
+
+// -------------------------------------
+// Collateral Concentration Instructions
+// -------------------------------------
+
+/// Initialize protocol-wide stats with the collateral concentration limit.
+pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>, cap_bps: u64, warning_bps: u64, floor: u64) -> Result<()> {
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.total_collateral_debt = 0;
+    protocol_stats.concentration_cap_bps = cap_bps;
+    protocol_stats.concentration_warning_bps = warning_bps;
+    protocol_stats.concentration_floor = floor;
+    protocol_stats.fee_issuance = 0;
+    protocol_stats.written_off_debt = 0;
+    protocol_stats.legacy_issuance = 0;
+    protocol_stats.fee_credit_burns = 0;
+    protocol_stats.mm_facility_debt = 0;
+    protocol_stats.reserve_backed_debt = 0;
+    protocol_stats.auction_surplus_burns = 0;
+
+    Ok(())
+}
+
+/// Update the collateral concentration limit.
+pub fn set_concentration_limit(ctx: Context<SetConcentrationLimit>, cap_bps: u64, warning_bps: u64, floor: u64) -> Result<()> {
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.concentration_cap_bps = cap_bps;
+    protocol_stats.concentration_warning_bps = warning_bps;
+    protocol_stats.concentration_floor = floor;
+
+    Ok(())
+}
+
+/// Add newly minted debt to a collateral type, enforcing the concentration cap.
+///
+/// A collateral's share of system backing is measured as its share of the
+/// debt minted against all collateral types.
+pub fn record_collateral_debt(collateral_type: &mut Account<CollateralType>, protocol_stats: &mut ProtocolStats, amount: u64) -> Result<()> {
+    let collateral_debt = collateral_type.total_debt.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let total_debt = protocol_stats.total_collateral_debt.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    // Let the first collateral types bootstrap the system before the cap applies
+    if total_debt >= protocol_stats.concentration_floor {
+        let share_bps = ((collateral_debt as u128 * 10_000) / total_debt as u128) as u64;
+        require!(share_bps <= protocol_stats.concentration_cap_bps, ErrorCode::ConcentrationLimitExceeded);
+
+        if share_bps >= protocol_stats.concentration_warning_bps {
+            emit!(ConcentrationWarningEvent {
+                collateral_type: collateral_type.key(),
+                share_bps,
+                cap_bps: protocol_stats.concentration_cap_bps,
+            });
+        }
+    }
+
+    collateral_type.total_debt = collateral_debt;
+    protocol_stats.total_collateral_debt = total_debt;
+
+    Ok(())
+}
+
+/// Remove repaid or written-off debt from a collateral type and the protocol totals.
+pub fn release_collateral_debt(collateral_type: &mut CollateralType, protocol_stats: &mut ProtocolStats, amount: u64) {
+    collateral_type.total_debt = collateral_type.total_debt.saturating_sub(amount);
+    protocol_stats.total_collateral_debt = protocol_stats.total_collateral_debt.saturating_sub(amount);
+}
+
+// -------------------------------------
+// Operation Lock Helpers
+// -------------------------------------
+
+/// Take the protocol-wide mutex before a multi-CPI flow.
+///
+/// The flag is persisted immediately so a re-entrant call deserializing the
+/// system state observes it while the outer instruction is still running.
+pub fn acquire_operation_lock(system_state: &mut Account<SystemState>) -> Result<()> {
+    require!(!system_state.operation_locked, ErrorCode::ReentrancyDetected);
+    system_state.operation_locked = true;
+    system_state.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+/// Release the protocol-wide mutex at the end of a multi-CPI flow.
+pub fn release_operation_lock(system_state: &mut Account<SystemState>) {
+    system_state.operation_locked = false;
+}
+
+// -------------------------------------
+// Upgrade Freeze Instructions
+// -------------------------------------
+
+/// Freeze parameter changes and new debt ahead of a program upgrade.
+pub fn freeze_config_for_upgrade(ctx: Context<ManageConfigFreeze>) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    require!(!system_state.config_frozen, ErrorCode::ConfigFrozen);
+
+    system_state.config_frozen = true;
+    system_state.frozen_at = Clock::get()?.unix_timestamp;
+    system_state.upgrade_checklist = 0;
+
+    emit!(ConfigFrozenEvent {
+        frozen_at: system_state.frozen_at,
+    });
+
+    Ok(())
+}
+
+/// Record a completed item of the upgrade checklist.
+pub fn complete_upgrade_checklist_item(ctx: Context<ManageConfigFreeze>, item: UpgradeChecklistItem) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    require!(system_state.config_frozen, ErrorCode::ConfigNotFrozen);
+
+    system_state.upgrade_checklist |= 1 << item as u8;
+
+    emit!(UpgradeChecklistItemCompletedEvent {
+        item,
+        checklist: system_state.upgrade_checklist,
+    });
+
+    Ok(())
+}
+
+/// Lift the upgrade freeze once the timelock has passed and the checklist is complete.
+pub fn unfreeze_config(ctx: Context<ManageConfigFreeze>) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    require!(system_state.config_frozen, ErrorCode::ConfigNotFrozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= system_state.frozen_at.saturating_add(CONFIG_UNFREEZE_TIMELOCK_SECS),
+        ErrorCode::UnfreezeTimelockActive
+    );
+    require!(
+        system_state.upgrade_checklist == UPGRADE_CHECKLIST_COMPLETE,
+        ErrorCode::UpgradeChecklistIncomplete
+    );
+
+    system_state.config_frozen = false;
+
+    emit!(ConfigUnfrozenEvent {
+        frozen_at: system_state.frozen_at,
+        unfrozen_at: now,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Guarded Launch Instructions
+// -------------------------------------
+
+/// Reject positions above the per-wallet caps while the guarded launch is active.
+pub fn enforce_launch_caps(user_account: &UserAccount, system_state: &SystemState) -> Result<()> {
+    if !system_state.launch_mode {
+        return Ok(());
+    }
+    require!(
+        user_account.collateral_balance <= system_state.launch_collateral_cap,
+        ErrorCode::LaunchCapExceeded
+    );
+    require!(
+        user_account.stablecoin_balance <= system_state.launch_debt_cap,
+        ErrorCode::LaunchCapExceeded
+    );
+
+    Ok(())
+}
+
+/// Enable the guarded launch, or update its caps while it is active.
+pub fn set_launch_mode(ctx: Context<ManageLaunchMode>, collateral_cap: u64, debt_cap: u64) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.launch_mode = true;
+    system_state.launch_collateral_cap = collateral_cap;
+    system_state.launch_debt_cap = debt_cap;
+
+    emit!(LaunchModeUpdatedEvent {
+        launch_mode: true,
+        collateral_cap,
+        debt_cap,
+    });
+
+    Ok(())
+}
+
+/// End the guarded launch, lifting the per-wallet caps.
+pub fn graduate_launch_mode(ctx: Context<ManageLaunchMode>) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    require!(system_state.launch_mode, ErrorCode::LaunchModeInactive);
+    system_state.launch_mode = false;
+
+    emit!(LaunchModeUpdatedEvent {
+        launch_mode: false,
+        collateral_cap: system_state.launch_collateral_cap,
+        debt_cap: system_state.launch_debt_cap,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Price Cache Instructions
+// -------------------------------------
+
+/// Create the price cache of a collateral type.
+pub fn initialize_price_cache(ctx: Context<InitializePriceCache>) -> Result<()> {
+    let price_cache = &mut ctx.accounts.price_cache;
+    price_cache.collateral_type = ctx.accounts.collateral_type.key();
+    price_cache.price = 0;
+    price_cache.confidence = 0;
+    price_cache.publish_time = 0;
+    price_cache.bump = ctx.bumps.price_cache;
+    price_cache.reference_price = 0;
+    price_cache.price_floor = 0;
+    price_cache.price_ceiling = 0;
+    price_cache.reference_cap_bps = 0;
+    price_cache.override_expires_at = 0;
+
+    Ok(())
+}
+
+/// Refresh a collateral's cached price from its oracle feed. Callable by anyone.
+pub fn refresh_price(ctx: Context<RefreshPrice>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.collateral_type.stake_pool_config, Pubkey::default(), ErrorCode::StakePoolPricedCollateral);
+    require_keys_eq!(ctx.accounts.collateral_type.receipt_config, Pubkey::default(), ErrorCode::ReceiptPricedCollateral);
+    let oracle_price = oracle::read_price(&ctx.accounts.price_feed, &ctx.accounts.collateral_type, &ctx.accounts.system_state.oracle_programs)?;
+
+    let price_cache = &mut ctx.accounts.price_cache;
+    // Never overwrite the cache with an older update
+    if oracle_price.publish_time <= price_cache.publish_time {
+        return Ok(());
+    }
+    price_cache.price = oracle_price.price;
+    price_cache.confidence = oracle_price.confidence;
+    price_cache.publish_time = oracle_price.publish_time;
+    price_cache.reference_price = oracle_price.price;
+
+    emit!(PriceRefreshedEvent {
+        collateral_type: price_cache.collateral_type,
+        price: oracle_price.price,
+        confidence: oracle_price.confidence,
+        publish_time: oracle_price.publish_time,
+    });
+
+    Ok(())
+}
+
+/// Temporarily clamp a collateral's valuation during a depeg scare. Restricted to governance.
+///
+/// Every cached price read is capped at `reference_cap_bps` of the reference
+/// feed price and at `price_ceiling`, and floored at `price_floor`, until the
+/// override lapses after `duration_secs`. Zero disables a bound.
+pub fn set_price_override(
+    ctx: Context<SetPriceOverride>,
+    price_floor: u64,
+    price_ceiling: u64,
+    reference_cap_bps: u64,
+    duration_secs: u64,
+) -> Result<()> {
+    require!(duration_secs > 0 && duration_secs <= MAX_PRICE_OVERRIDE_SECS, ErrorCode::InvalidPriceOverride);
+    require!(reference_cap_bps <= 10_000, ErrorCode::InvalidPriceOverride);
+    require!(price_ceiling == 0 || price_floor <= price_ceiling, ErrorCode::InvalidPriceOverride);
+    require!(price_floor > 0 || price_ceiling > 0 || reference_cap_bps > 0, ErrorCode::InvalidPriceOverride);
+
+    let price_cache = &mut ctx.accounts.price_cache;
+    price_cache.price_floor = price_floor;
+    price_cache.price_ceiling = price_ceiling;
+    price_cache.reference_cap_bps = reference_cap_bps;
+    price_cache.override_expires_at = Clock::get()?.unix_timestamp + duration_secs as i64;
+
+    emit!(PriceOverrideSetEvent {
+        collateral_type: price_cache.collateral_type,
+        price_floor,
+        price_ceiling,
+        reference_cap_bps,
+        expires_at: price_cache.override_expires_at,
+        set_by: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Lift a collateral's valuation override before it lapses. Restricted to governance.
+pub fn clear_price_override(ctx: Context<SetPriceOverride>) -> Result<()> {
+    let price_cache = &mut ctx.accounts.price_cache;
+    price_cache.price_floor = 0;
+    price_cache.price_ceiling = 0;
+    price_cache.reference_cap_bps = 0;
+    price_cache.override_expires_at = 0;
+
+    emit!(PriceOverrideSetEvent {
+        collateral_type: price_cache.collateral_type,
+        price_floor: 0,
+        price_ceiling: 0,
+        reference_cap_bps: 0,
+        expires_at: 0,
+        set_by: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Config View Instructions
+// -------------------------------------
+
+/// Effective configuration of a single collateral type.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CollateralConfigView {
+    pub collateral_mint: Pubkey,
+    pub collateral_ratio: u64,
+    pub liquidation_threshold: u64,
+    pub liquidation_penalty_bps: u64,
+    pub liquidation_bonus_slope_bps: u64,
+    pub max_liquidation_bonus_bps: u64,
+    pub stability_fee: u64,
+    pub debt_ceiling: u64,
+    pub total_debt: u64,
+    pub risk_tier: RiskTier,
+    pub price_feed: Pubkey,
+    pub max_price_age_secs: u64,
+    pub max_confidence_bps: u64,
+    pub mint_fee_bps: u64,
+    pub redemption_fee_bps: u64,
+    pub exchange_rate: u128,
+    pub yield_skim_bps: u64,
+    pub confidence_premium_threshold_bps: u64,
+    pub confidence_premium_slope_bps: u64,
+    pub max_confidence_premium_bps: u64,
+}
+
+/// Snapshot of the protocol's effective configuration.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProtocolConfigView {
+    // Governance
+    pub collateral_ratio: u64,
+    pub minimum_approval_threshold: u32,
+    pub proposal_voting_period_secs: u64,
+    pub proposal_bond_lamports: u64,
+    // Fees and flags
+    pub global_stability_fee: i64,
+    pub minting_fee_rate: u64,
+    pub redemption_fee_rate: u64,
+    pub staking_paused: bool,
+    pub incident_active: bool,
+    pub config_frozen: bool,
+    pub launch_mode: bool,
+    pub launch_collateral_cap: u64,
+    pub launch_debt_cap: u64,
+    // Health and liquidation
+    pub health_warning_bps: u64,
+    pub health_critical_bps: u64,
+    pub liquidation_cooldown_secs: u64,
+    pub onboarding_ratio_bonus: u64,
+    pub onboarding_period_secs: u64,
+    pub liquidator_share_bps: u64,
+    pub insurance_share_bps: u64,
+    pub surplus_share_bps: u64,
+    // Debt and concentration
+    pub total_collateral_debt: u64,
+    pub concentration_cap_bps: u64,
+    pub concentration_warning_bps: u64,
+    pub concentration_floor: u64,
+    // Canonical accounts and precision
+    pub stablecoin_mint: Pubkey,
+    pub treasury_account: Pubkey,
+    pub price_precision: u64,
+    pub collateral: Option<CollateralConfigView>,
+}
+
+/// Return the effective protocol configuration as one consistent snapshot.
+pub fn get_protocol_config(ctx: Context<GetProtocolConfig>) -> Result<ProtocolConfigView> {
+    let governance = &ctx.accounts.governance;
+    let system_state = &ctx.accounts.system_state;
+    let protocol_stats = &ctx.accounts.protocol_stats;
+    let revenue = &ctx.accounts.liquidation_revenue;
+
+    let collateral = ctx.accounts.collateral_type.as_ref().map(|collateral_type| CollateralConfigView {
+        collateral_mint: collateral_type.collateral_mint,
+        collateral_ratio: collateral_type.collateral_ratio,
+        liquidation_threshold: collateral_type.liquidation_threshold,
+        liquidation_penalty_bps: collateral_type.liquidation_penalty_bps,
+        liquidation_bonus_slope_bps: collateral_type.liquidation_bonus_slope_bps,
+        max_liquidation_bonus_bps: collateral_type.max_liquidation_bonus_bps,
+        stability_fee: collateral_type.stability_fee,
+        debt_ceiling: collateral_type.debt_ceiling,
+        total_debt: collateral_type.total_debt,
+        risk_tier: collateral_type.risk_tier,
+        price_feed: collateral_type.price_feed,
+        max_price_age_secs: collateral_type.max_price_age_secs,
+        max_confidence_bps: collateral_type.max_confidence_bps,
+        mint_fee_bps: effective_mint_fee_bps(system_state, Some(collateral_type)),
+        redemption_fee_bps: effective_redemption_fee_bps(system_state, Some(collateral_type)),
+        exchange_rate: collateral_type.exchange_rate,
+        yield_skim_bps: collateral_type.yield_skim_bps,
+        confidence_premium_threshold_bps: collateral_type.confidence_premium_threshold_bps,
+        confidence_premium_slope_bps: collateral_type.confidence_premium_slope_bps,
+        max_confidence_premium_bps: collateral_type.max_confidence_premium_bps,
+    });
+
+    Ok(ProtocolConfigView {
+        collateral_ratio: governance.collateral_ratio,
+        minimum_approval_threshold: governance.minimum_approval_threshold,
+        proposal_voting_period_secs: PROPOSAL_VOTING_PERIOD_SECS,
+        proposal_bond_lamports: PROPOSAL_BOND_LAMPORTS,
+        global_stability_fee: system_state.global_stability_fee,
+        minting_fee_rate: system_state.minting_fee_rate,
+        redemption_fee_rate: system_state.redemption_fee_rate,
+        staking_paused: system_state.staking_paused,
+        incident_active: system_state.incident_active,
+        config_frozen: system_state.config_frozen,
+        launch_mode: system_state.launch_mode,
+        launch_collateral_cap: system_state.launch_collateral_cap,
+        launch_debt_cap: system_state.launch_debt_cap,
+        health_warning_bps: system_state.health_warning_bps,
+        health_critical_bps: system_state.health_critical_bps,
+        liquidation_cooldown_secs: system_state.liquidation_cooldown_secs,
+        onboarding_ratio_bonus: system_state.onboarding_ratio_bonus,
+        onboarding_period_secs: system_state.onboarding_period_secs,
+        liquidator_share_bps: revenue.liquidator_share_bps,
+        insurance_share_bps: revenue.insurance_share_bps,
+        surplus_share_bps: revenue.surplus_share_bps,
+        total_collateral_debt: protocol_stats.total_collateral_debt,
+        concentration_cap_bps: protocol_stats.concentration_cap_bps,
+        concentration_warning_bps: protocol_stats.concentration_warning_bps,
+        concentration_floor: protocol_stats.concentration_floor,
+        stablecoin_mint: system_state.stablecoin_mint,
+        treasury_account: system_state.treasury_account,
+        price_precision: PRICE_PRECISION,
+        collateral,
+    })
+}
+
+// -------------------------------------
+// Guardian Instructions
+// -------------------------------------
+
+/// Initialize the M-of-N guardian set allowed to take emergency actions.
+pub fn initialize_guardian_set(ctx: Context<InitializeGuardianSet>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    validate_guardian_set(&guardians, threshold)?;
+
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    guardian_set.guardians = guardians;
+    guardian_set.threshold = threshold;
+    guardian_set.epoch = 0;
+    guardian_set.next_action_id = 0;
+    guardian_set.bump = ctx.bumps.guardian_set;
+
+    Ok(())
+}
+
+/// Replace the guardian set, invalidating approvals on open actions.
+pub fn set_guardian_set(ctx: Context<SetGuardianSet>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    validate_guardian_set(&guardians, threshold)?;
+
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    guardian_set.guardians = guardians;
+    guardian_set.threshold = threshold;
+    guardian_set.epoch = guardian_set.epoch.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit!(GuardianSetUpdatedEvent {
+        guardians: guardian_set.guardians.clone(),
+        threshold,
+        epoch: guardian_set.epoch,
+    });
+
+    Ok(())
+}
+
+fn validate_guardian_set(guardians: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(!guardians.is_empty() && guardians.len() <= MAX_GUARDIANS, ErrorCode::InvalidGuardianSet);
+    require!(threshold > 0 && threshold as usize <= guardians.len(), ErrorCode::InvalidGuardianSet);
+    for (i, guardian) in guardians.iter().enumerate() {
+        require!(!guardians[..i].contains(guardian), ErrorCode::InvalidGuardianSet);
+    }
+
+    Ok(())
+}
+
+/// Open an emergency action, counting the proposing guardian's approval.
+pub fn propose_emergency_action(ctx: Context<ProposeEmergencyAction>, kind: EmergencyActionKind) -> Result<()> {
+    let guardian_set = &mut ctx.accounts.guardian_set;
+    let guardian_index = guardian_index(guardian_set, &ctx.accounts.guardian.key())?;
+
+    let emergency_action = &mut ctx.accounts.emergency_action;
+    emergency_action.action_id = guardian_set.next_action_id;
+    emergency_action.kind = kind;
+    emergency_action.proposer = ctx.accounts.guardian.key();
+    emergency_action.guardian_epoch = guardian_set.epoch;
+    emergency_action.approvals = 0;
+    emergency_action.expires_at = Clock::get()?.unix_timestamp + EMERGENCY_ACTION_TTL_SECS;
+    emergency_action.executed = false;
+    guardian_set.next_action_id = guardian_set.next_action_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit!(EmergencyActionProposedEvent {
+        action_id: emergency_action.action_id,
+        kind,
+        proposer: emergency_action.proposer,
+        expires_at: emergency_action.expires_at,
+    });
+
+    record_guardian_approval(guardian_set, emergency_action, guardian_index, &mut ctx.accounts.system_state)?;
+    follow_up_emergency_action(
+        emergency_action,
+        ctx.accounts.follow_up_proposal.as_mut(),
+        &ctx.accounts.governance,
+        &ctx.accounts.governance_config,
+        &mut ctx.accounts.system_state,
+        ctx.accounts.guardian.key(),
+    )
+}
+
+/// Approve an open emergency action, executing it once the threshold is reached.
+pub fn approve_emergency_action(ctx: Context<ApproveEmergencyAction>) -> Result<()> {
+    let guardian_set = &ctx.accounts.guardian_set;
+    let guardian_index = guardian_index(guardian_set, &ctx.accounts.guardian.key())?;
+
+    let emergency_action = &mut ctx.accounts.emergency_action;
+    require!(!emergency_action.executed, ErrorCode::EmergencyActionExecuted);
+    require!(Clock::get()?.unix_timestamp <= emergency_action.expires_at, ErrorCode::EmergencyActionExpired);
+    require!(emergency_action.guardian_epoch == guardian_set.epoch, ErrorCode::EmergencyActionExpired);
+
+    record_guardian_approval(guardian_set, emergency_action, guardian_index, &mut ctx.accounts.system_state)?;
+    follow_up_emergency_action(
+        emergency_action,
+        ctx.accounts.follow_up_proposal.as_mut(),
+        &ctx.accounts.governance,
+        &ctx.accounts.governance_config,
+        &mut ctx.accounts.system_state,
+        ctx.accounts.guardian.key(),
+    )
+}
+
+/// Open the follow-up proposal of an emergency action that put the protocol into recovery mode.
+///
+/// The proposal must be passed with exactly the approval that declares an
+/// incident, so every incident gets one and nothing else does.
+fn follow_up_emergency_action(
+    emergency_action: &Account<EmergencyAction>,
+    follow_up_proposal: Option<&mut Account<Proposal>>,
+    governance: &Account<Governance>,
+    governance_config: &GovernanceConfig,
+    system_state: &mut SystemState,
+    guardian: Pubkey,
+) -> Result<()> {
+    let declares_incident = emergency_action.executed && emergency_action.kind == EmergencyActionKind::DeclareIncident;
+    match follow_up_proposal {
+        Some(proposal) => {
+            require!(declares_incident, ErrorCode::IncidentProposalMismatch);
+            let description = format!("Clear the incident declared by emergency action {}", emergency_action.action_id);
+            open_incident_proposal(
+                proposal,
+                governance,
+                governance_config,
+                system_state,
+                guardian,
+                emergency_action.key(),
+                Pubkey::default(),
+                description,
+            )
+        }
+        None => {
+            require!(!declares_incident, ErrorCode::IncidentProposalMismatch);
+            Ok(())
+        }
+    }
+}
+
+/// Close an executed or expired emergency action, refunding its rent. Callable by anyone.
+pub fn close_emergency_action(ctx: Context<CloseEmergencyAction>) -> Result<()> {
+    let emergency_action = &ctx.accounts.emergency_action;
+    require!(
+        emergency_action.executed || Clock::get()?.unix_timestamp > emergency_action.expires_at,
+        ErrorCode::EmergencyActionPending
+    );
+
+    Ok(())
+}
+
+fn guardian_index(guardian_set: &GuardianSet, guardian: &Pubkey) -> Result<usize> {
+    guardian_set.guardians
+        .iter()
+        .position(|key| key == guardian)
+        .ok_or_else(|| ErrorCode::NotAGuardian.into())
+}
+
+/// Record a distinct guardian approval and execute the action at the threshold.
+fn record_guardian_approval(
+    guardian_set: &GuardianSet,
+    emergency_action: &mut EmergencyAction,
+    guardian_index: usize,
+    system_state: &mut SystemState,
+) -> Result<()> {
+    let bit = 1u16 << guardian_index;
+    require!(emergency_action.approvals & bit == 0, ErrorCode::AlreadyApproved);
+    emergency_action.approvals |= bit;
+
+    if emergency_action.approvals.count_ones() < guardian_set.threshold as u32 {
+        return Ok(());
+    }
+
+    match emergency_action.kind {
+        EmergencyActionKind::PauseStaking => system_state.staking_paused = true,
+        EmergencyActionKind::ResumeStaking => system_state.staking_paused = false,
+        EmergencyActionKind::ResetOperationLock => system_state.operation_locked = false,
+        EmergencyActionKind::DeclareIncident => {
+            system_state.incident_active = true;
+            system_state.incident_declared_at = Clock::get()?.unix_timestamp;
+        }
+        EmergencyActionKind::ClearIncident => system_state.incident_active = false,
+    }
+    emergency_action.executed = true;
+
+    emit!(EmergencyActionExecutedEvent {
+        action_id: emergency_action.action_id,
+        kind: emergency_action.kind,
+        approvals: emergency_action.approvals.count_ones() as u8,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Rate Snapshot Instructions
+// -------------------------------------
+
+/// Create the rate snapshot ring buffer with the stability fee index at one.
+pub fn initialize_rate_snapshots(ctx: Context<InitializeRateSnapshots>) -> Result<()> {
+    let rate_snapshots = &mut ctx.accounts.rate_snapshots;
+    rate_snapshots.head = 0;
+    rate_snapshots.count = 0;
+    rate_snapshots.stability_fee_index = EXCHANGE_RATE_PRECISION;
+    rate_snapshots.last_index_update = Clock::get()?.unix_timestamp;
+    rate_snapshots.last_run_slot = 0;
+
+    Ok(())
+}
+
+/// Append this epoch's rate and index snapshot, overwriting the oldest when full. Callable by anyone.
+pub fn record_rate_snapshot(ctx: Context<RecordRateSnapshot>) -> Result<CrankStatus> {
+    let clock = Clock::get()?;
+    let rate_snapshots = &mut ctx.accounts.rate_snapshots;
+    let status = run_crank(&ctx.accounts.crank_schedule, CrankKind::InterestIndex, &mut rate_snapshots.last_run_slot, clock.slot)?;
+
+    // One snapshot per epoch
+    if rate_snapshots.count > 0 {
+        let last = (rate_snapshots.head as usize + RATE_SNAPSHOT_CAPACITY - 1) % RATE_SNAPSHOT_CAPACITY;
+        require!(clock.epoch > rate_snapshots.snapshots[last].epoch, ErrorCode::SnapshotAlreadyRecorded);
+    }
+
+    accrue_stability_fee_index(rate_snapshots, ctx.accounts.system_state.global_stability_fee, clock.unix_timestamp)?;
+    accrue_exchange_rate(&mut ctx.accounts.wrapper_vault, clock.unix_timestamp)?;
+
+    let snapshot = RateSnapshot {
+        epoch: clock.epoch,
+        timestamp: clock.unix_timestamp,
+        stability_fee_index: rate_snapshots.stability_fee_index,
+        savings_exchange_rate: ctx.accounts.wrapper_vault.exchange_rate,
+        total_debt: ctx.accounts.protocol_stats.total_collateral_debt,
+    };
+    let head = rate_snapshots.head as usize;
+    rate_snapshots.snapshots[head] = snapshot;
+    rate_snapshots.head = ((head + 1) % RATE_SNAPSHOT_CAPACITY) as u16;
+    rate_snapshots.count = (rate_snapshots.count + 1).min(RATE_SNAPSHOT_CAPACITY as u16);
+
+    emit!(RateSnapshotRecordedEvent {
+        epoch: snapshot.epoch,
+        stability_fee_index: snapshot.stability_fee_index,
+        savings_exchange_rate: snapshot.savings_exchange_rate,
+        total_debt: snapshot.total_debt,
+    });
+
+    Ok(status)
+}
+
+/// Accrue the stability fee index to `now` at `rate_bps` per year.
+///
+/// A negative fee shrinks the index, so borrowers owe less over time.
+pub fn accrue_stability_fee_index(rate_snapshots: &mut RateSnapshots, rate_bps: i64, now: i64) -> Result<()> {
+    let elapsed = now.saturating_sub(rate_snapshots.last_index_update).max(0) as u128;
+    let change = rate_snapshots.stability_fee_index
+        .checked_mul(rate_bps.unsigned_abs() as u128)
+        .and_then(|value| value.checked_mul(elapsed))
+        .ok_or(ErrorCode::Overflow)?
+        / (10_000 * SECONDS_PER_YEAR);
+    rate_snapshots.stability_fee_index = if rate_bps >= 0 {
+        rate_snapshots.stability_fee_index.checked_add(change)
+    } else {
+        rate_snapshots.stability_fee_index.checked_sub(change)
+    }
+    .ok_or(ErrorCode::Overflow)?;
+    rate_snapshots.last_index_update = now;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Rate Controller Instructions
+// -------------------------------------
+
+/// Create the rate controller bounding the global stability fee.
+pub fn initialize_rate_controller(ctx: Context<InitializeRateController>, params: RateControllerParams) -> Result<()> {
+    validate_rate_controller_params(&params)?;
+
+    let rate_controller = &mut ctx.accounts.rate_controller;
+    rate_controller.params = params;
+    rate_controller.epoch = Clock::get()?.epoch;
+    rate_controller.epoch_start_rate_bps = ctx.accounts.system_state.global_stability_fee;
+    rate_controller.bump = ctx.bumps.rate_controller;
+
+    emit_rate_controller_params(&params);
+
+    Ok(())
+}
+
+/// Change the bounds on the global stability fee. Restricted to governance.
+///
+/// Tightened bounds apply to the next fee change; the current fee is left as is.
+pub fn set_rate_controller_params(ctx: Context<SetRateControllerParams>, params: RateControllerParams) -> Result<()> {
+    validate_rate_controller_params(&params)?;
+    ctx.accounts.rate_controller.params = params;
+
+    emit_rate_controller_params(&params);
+
+    Ok(())
+}
+
+/// Set the global stability fee within the controller's bounds. Restricted to governance.
+///
+/// The index is accrued at the old fee first. Within one epoch the fee may
+/// move at most `max_change_per_epoch_bps` from where it started the epoch,
+/// and it may only go negative while the peg TWAP is fresh and trades above
+/// $1 by the controller's premium.
+pub fn set_stability_fee(ctx: Context<SetStabilityFee>, rate_bps: i64) -> Result<()> {
+    let clock = Clock::get()?;
+    let params = ctx.accounts.rate_controller.params;
+    require!(
+        rate_bps >= params.min_rate_bps && rate_bps <= params.max_rate_bps,
+        ErrorCode::StabilityFeeOutOfBounds
+    );
+    if rate_bps < 0 {
+        require!(
+            negative_rate_allowed(&ctx.accounts.peg_state, &params, clock.unix_timestamp),
+            ErrorCode::NegativeStabilityFeeNotAllowed
+        );
+    }
+
+    let system_state = &mut ctx.accounts.system_state;
+    let rate_controller = &mut ctx.accounts.rate_controller;
+    if clock.epoch > rate_controller.epoch {
+        rate_controller.epoch = clock.epoch;
+        rate_controller.epoch_start_rate_bps = system_state.global_stability_fee;
+    }
+    require!(
+        rate_bps.abs_diff(rate_controller.epoch_start_rate_bps) <= params.max_change_per_epoch_bps,
+        ErrorCode::StabilityFeeChangeTooLarge
+    );
+
+    accrue_stability_fee_index(&mut ctx.accounts.rate_snapshots, system_state.global_stability_fee, clock.unix_timestamp)?;
+    let previous_rate_bps = system_state.global_stability_fee;
+    system_state.global_stability_fee = rate_bps;
+
+    emit!(StabilityFeeChangedEvent {
+        previous_rate_bps,
+        rate_bps,
+        stability_fee_index: ctx.accounts.rate_snapshots.stability_fee_index,
+        peg_twap_price: ctx.accounts.peg_state.twap_price,
+    });
+
+    Ok(())
+}
+
+/// Reset a negative global stability fee to zero once the peg no longer trades above the premium.
+/// Callable by anyone.
+///
+/// Moving back to zero is exempt from the per-epoch change cap, so the
+/// protocol never keeps paying borrowers after the above-peg period ends.
+pub fn normalize_negative_stability_fee(ctx: Context<NormalizeNegativeStabilityFee>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let system_state = &mut ctx.accounts.system_state;
+    let previous_rate_bps = system_state.global_stability_fee;
+    require!(
+        previous_rate_bps < 0 && !negative_rate_allowed(&ctx.accounts.peg_state, &ctx.accounts.rate_controller.params, now),
+        ErrorCode::NegativeStabilityFeeNotAllowed
+    );
+
+    accrue_stability_fee_index(&mut ctx.accounts.rate_snapshots, previous_rate_bps, now)?;
+    system_state.global_stability_fee = 0;
+
+    emit!(StabilityFeeChangedEvent {
+        previous_rate_bps,
+        rate_bps: 0,
+        stability_fee_index: ctx.accounts.rate_snapshots.stability_fee_index,
+        peg_twap_price: ctx.accounts.peg_state.twap_price,
+    });
+
+    Ok(())
+}
+
+/// Whether the peg TWAP is fresh and far enough above $1 to justify paying borrowers.
+fn negative_rate_allowed(peg_state: &PegState, params: &RateControllerParams, now: i64) -> bool {
+    let fresh = now.saturating_sub(peg_state.last_update) as u64 <= peg_state.max_price_age_secs;
+    let threshold = PRICE_PRECISION as u128 * (10_000 + params.negative_rate_premium_bps as u128) / 10_000;
+    fresh && peg_state.twap_price as u128 >= threshold
+}
+
+fn validate_rate_controller_params(params: &RateControllerParams) -> Result<()> {
+    require!(
+        params.min_rate_bps >= -MAX_NEGATIVE_STABILITY_FEE_BPS
+            && params.min_rate_bps <= params.max_rate_bps
+            && params.max_rate_bps <= MAX_STABILITY_FEE_BPS,
+        ErrorCode::StabilityFeeOutOfBounds
+    );
+    require!(params.max_change_per_epoch_bps > 0, ErrorCode::InvalidAmount);
+
+    Ok(())
+}
+
+fn emit_rate_controller_params(params: &RateControllerParams) {
+    emit!(RateControllerUpdatedEvent {
+        min_rate_bps: params.min_rate_bps,
+        max_rate_bps: params.max_rate_bps,
+        max_change_per_epoch_bps: params.max_change_per_epoch_bps,
+        negative_rate_premium_bps: params.negative_rate_premium_bps,
+    });
+}
+
+// -------------------------------------
+// Permit Instructions
+// -------------------------------------
+
+/// Create the permit nonce account of a wallet, payable by anyone (typically the relayer).
+pub fn initialize_permit_nonce(ctx: Context<InitializePermitNonce>) -> Result<()> {
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = ctx.accounts.user.key();
+    permit_nonce.nonce = 0;
+    permit_nonce.bump = ctx.bumps.permit_nonce;
+
+    Ok(())
+}
+
+/// Verify a permit against the user's nonce and consume it.
+fn consume_permit(
+    permit_nonce: &mut PermitNonce,
+    instructions_sysvar: &AccountInfo,
+    user: Pubkey,
+    relayer: Pubkey,
+    action: PermitAction,
+    nonce: u64,
+    expiry: i64,
+) -> Result<()> {
+    require!(nonce == permit_nonce.nonce, ErrorCode::InvalidNonce);
+
+    let message = PermitMessage { program_id: crate::ID, action, nonce, expiry };
+    permit::verify_permit(instructions_sysvar, &user, &message)?;
+
+    permit_nonce.nonce = permit_nonce.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit!(PermitConsumedEvent {
+        user,
+        relayer,
+        action,
+        nonce,
+    });
+
+    Ok(())
+}
+
+/// Wrap stablecoin for a user who signed an off-chain permit, submitted by a relayer.
+pub fn permit_wrap(ctx: Context<PermitWrapStablecoin>, amount: u64, nonce: u64, expiry: i64) -> Result<()> {
+    let action = PermitAction::Wrap { wrapper_vault: ctx.accounts.wrapper_vault.key(), amount };
+    consume_permit(
+        &mut ctx.accounts.permit_nonce,
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.user.key(),
+        ctx.accounts.relayer.key(),
+        action,
+        nonce,
+        expiry,
+    )?;
+
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    accrue_exchange_rate(wrapper_vault, Clock::get()?.unix_timestamp)?;
+    let wrapped_amount = stablecoin_to_wrapped(wrapper_vault, amount)?;
+
+    // Pull the stablecoin as the user's approved delegate, then mint the wrapped token
+    let wrapper_key = ctx.accounts.wrapper_vault.key();
+    let bump = [ctx.accounts.wrapper_vault.authority_bump];
+    let seeds: &[&[u8]] = &[b"wrapper_authority", wrapper_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_stablecoin_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.wrapped_mint.to_account_info(),
+        to: ctx.accounts.user_wrapped_account.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, wrapped_amount)?;
+
+    emit!(WrapEvent {
+        user: ctx.accounts.user.key(),
+        stablecoin_amount: amount,
+        wrapped_amount,
+        exchange_rate: ctx.accounts.wrapper_vault.exchange_rate,
+    });
+
+    Ok(())
+}
+
+/// Unwrap for a user who signed an off-chain permit, submitted by a relayer.
+pub fn permit_unwrap(ctx: Context<PermitWrapStablecoin>, wrapped_amount: u64, nonce: u64, expiry: i64) -> Result<()> {
+    let action = PermitAction::Unwrap { wrapper_vault: ctx.accounts.wrapper_vault.key(), wrapped_amount };
+    consume_permit(
+        &mut ctx.accounts.permit_nonce,
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.user.key(),
+        ctx.accounts.relayer.key(),
+        action,
+        nonce,
+        expiry,
+    )?;
+
+    let wrapper_vault = &mut ctx.accounts.wrapper_vault;
+    accrue_exchange_rate(wrapper_vault, Clock::get()?.unix_timestamp)?;
+    let amount = wrapped_to_stablecoin(wrapper_vault, wrapped_amount)?;
+    require!(ctx.accounts.vault.amount >= amount, ErrorCode::InsufficientFunds);
+
+    // Burn the wrapped token as the user's approved delegate, then release the stablecoin
+    let wrapper_key = ctx.accounts.wrapper_vault.key();
+    let bump = [ctx.accounts.wrapper_vault.authority_bump];
+    let seeds: &[&[u8]] = &[b"wrapper_authority", wrapper_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.wrapped_mint.to_account_info(),
+        from: ctx.accounts.user_wrapped_account.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::burn(cpi_ctx, wrapped_amount)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.user_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.wrapper_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(UnwrapEvent {
+        user: ctx.accounts.user.key(),
+        stablecoin_amount: amount,
+        wrapped_amount,
+        exchange_rate: ctx.accounts.wrapper_vault.exchange_rate,
+    });
+
+    Ok(())
+}
+
+/// Mint against an isolated position for a user who signed an off-chain permit.
+///
+/// Follows `mint_stablecoin_with_collateral` without the loyalty discount,
+/// partner attribution or cross-margin support. The relayer signs as the
+/// mint authority in place of the payer.
+pub fn permit_mint(ctx: Context<PermitMintStablecoin>, amount: u64, max_fee: u64, nonce: u64, expiry: i64) -> Result<()> {
+    let action = PermitAction::Mint { user_account: ctx.accounts.user_account.key(), amount, max_fee };
+    consume_permit(
+        &mut ctx.accounts.permit_nonce,
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.user.key(),
+        ctx.accounts.relayer.key(),
+        action,
+        nonce,
+        expiry,
+    )?;
+
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Mint)?;
+    let now = Clock::get()?.unix_timestamp;
+    enforce_peg_band(&ctx.accounts.peg_state, now)?;
+
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_type = &ctx.accounts.collateral_type;
+    check_collateral_minting(collateral_type)?;
+    require!(user_account.margin_account == Pubkey::default(), ErrorCode::InvalidMarginAccount);
+
+    // Bind the position to the collateral type backing it
+    if user_account.collateral_type == Pubkey::default() {
+        user_account.collateral_type = collateral_type.key();
+        user_account.entry_exchange_rate = collateral_type.exchange_rate;
+    }
+    require_keys_eq!(user_account.collateral_type, collateral_type.key(), ErrorCode::InvalidCollateralType);
+
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, collateral_type)?;
+    let collateral_value = (user_account.collateral_balance as u128)
+        .checked_mul(oracle_price.price as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / PRICE_PRECISION as u128;
+    let collateral_ratio = onboarding_collateral_ratio(&ctx.accounts.system_state, user_account, collateral_type.collateral_ratio, now);
+    let debt_after = user_account.stablecoin_balance.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let required_value = (debt_after as u128)
+        .checked_mul(collateral_ratio as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / 100;
+    require!(collateral_value >= required_value, ErrorCode::InsufficientCollateral);
+
+    let premium_bps = confidence_premium_bps(collateral_type, &oracle_price);
+    let size_surcharge_bps = mint_size_surcharge_bps(&ctx.accounts.system_state, collateral_type, amount)?;
+    record_collateral_debt(&mut ctx.accounts.collateral_type, &mut ctx.accounts.protocol_stats, amount)?;
+
+    let fee_bps = effective_mint_fee_bps(&ctx.accounts.system_state, Some(&ctx.accounts.collateral_type))
+        .checked_add(size_surcharge_bps)
+        .ok_or(ErrorCode::Overflow)?;
+    let fee_charge = math::fee_charge(amount, fee_bps)?;
+    let premium_charge = math::fee_charge(amount, premium_bps)?;
+    let (fee, premium) = (fee_charge.fee, premium_charge.fee);
+    require!(fee.checked_add(premium).ok_or(ErrorCode::Overflow)? <= max_fee, ErrorCode::SlippageExceeded);
+    let (fee_due, fee_credits_used) = spend_fee_credits(user_account, fee);
+    let treasury_amount = fee_due.checked_add(premium).ok_or(ErrorCode::Overflow)?;
+    require!(treasury_amount <= amount, ErrorCode::FeeTooHigh);
+
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        to: ctx.accounts.user_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.relayer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::mint_to(cpi_ctx, amount - treasury_amount)?;
+
+    if treasury_amount > 0 {
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.relayer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::mint_to(cpi_ctx, treasury_amount)?;
+
+        let protocol_stats = &mut ctx.accounts.protocol_stats;
+        protocol_stats.fee_issuance = protocol_stats.fee_issuance.checked_add(treasury_amount).ok_or(ErrorCode::Overflow)?;
+    }
+    if premium > 0 {
+        let revenue = &mut ctx.accounts.liquidation_revenue;
+        revenue.insurance_pool_balance = revenue.insurance_pool_balance.checked_add(premium).ok_or(ErrorCode::Overflow)?;
+    }
+
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.stablecoin_balance = debt_after;
+    enforce_launch_caps(user_account, &ctx.accounts.system_state)?;
+    update_health_band(user_account, &ctx.accounts.system_state, Some(&ctx.accounts.collateral_type), Some(&ctx.accounts.price_cache))?;
+
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Mint, 0, amount, oracle_price.price)?;
+
+    emit!(MintStablecoinWithCollateralEvent {
+        user: ctx.accounts.user_account.key(),
+        amount,
+        collateral_type: ctx.accounts.collateral_type.collateral_mint,
+        fee,
+        premium,
+        rounding_remainder: fee_charge.rounding_remainder + premium_charge.rounding_remainder,
+        fee_credits_used,
+        tag: ctx.accounts.user_account.tag,
+    });
+
+    Ok(())
+}
+
+/// Queue a redemption for a user who signed an off-chain permit.
+///
+/// Follows `request_redemption`, fees included, without partner attribution.
+/// The stablecoin is pulled with the treasury authority as the user's
+/// approved delegate, and the relayer pays the request's rent.
+pub fn permit_request_redemption(
+    ctx: Context<PermitRequestRedemption>,
+    amount: u64,
+    lane: RedemptionLane,
+    request_id: u64,
+    nonce: u64,
+    expiry: i64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    let action = PermitAction::Redeem { redemption_queue: ctx.accounts.redemption_queue.key(), amount, lane };
+    consume_permit(
+        &mut ctx.accounts.permit_nonce,
+        &ctx.accounts.instructions_sysvar,
+        ctx.accounts.user.key(),
+        ctx.accounts.relayer.key(),
+        action,
+        nonce,
+        expiry,
+    )?;
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Redeem)?;
+
+    // Reserve-asset redemptions have no collateral type, so the global rate applies
+    let fee = fee_amount(amount, effective_redemption_fee_bps(&ctx.accounts.system_state, None))?;
+    let (redemption_fee, fee_credits_used) = match ctx.accounts.user_account.as_deref_mut() {
+        Some(user_account) => spend_fee_credits(user_account, fee),
+        None => (fee, 0),
+    };
+    let net_amount = amount.checked_sub(redemption_fee).filter(|net| *net > 0).ok_or(ErrorCode::InvalidAmount)?;
+
+    let redemption_queue = &mut ctx.accounts.redemption_queue;
+    require!(request_id == redemption_queue.tails[lane as usize], ErrorCode::InvalidAccountData);
+    redemption_queue.tails[lane as usize] = request_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    redemption_queue.queued_amount = redemption_queue.queued_amount.checked_add(net_amount).ok_or(ErrorCode::Overflow)?;
+
+    let priority_fee = match lane {
+        RedemptionLane::Standard => 0,
+        RedemptionLane::Priority => math::fee_charge(amount, redemption_queue.priority_fee_bps)?.fee,
+    };
+    let treasury_fee = redemption_fee.checked_add(priority_fee).ok_or(ErrorCode::Overflow)?;
+
+    let bump = [ctx.accounts.treasury_reserves.authority_bump];
+    let seeds: &[&[u8]] = &[b"treasury_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.user_stablecoin_account.to_account_info(),
+        to: ctx.accounts.escrow_vault.to_account_info(),
+        authority: ctx.accounts.treasury_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, net_amount)?;
+
+    if treasury_fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_stablecoin_account.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, treasury_fee)?;
+    }
+
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    redemption_request.queue = redemption_queue.key();
+    redemption_request.owner = ctx.accounts.user.key();
+    redemption_request.lane = lane;
+    redemption_request.id = request_id;
+    redemption_request.destination = ctx.accounts.user_reserve_account.key();
+    redemption_request.amount = net_amount;
+    redemption_request.remaining = net_amount;
+    redemption_request.cancelled = false;
+    redemption_request.created_at = Clock::get()?.unix_timestamp;
+    redemption_request.bump = ctx.bumps.redemption_request;
+
+    emit!(RedemptionQueuedEvent {
+        owner: redemption_request.owner,
+        lane,
+        request_id,
+        amount: net_amount,
+        priority_fee,
+        queued_amount: redemption_queue.queued_amount,
+        redemption_fee,
+        fee_credits_used,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Campaign Instructions
+// -------------------------------------
+
+/// Create a time-boxed incentive campaign over a staking pool or collateral type.
+pub fn create_campaign(ctx: Context<CreateCampaign>, target: CampaignTarget, reward_rate: u64, start_time: i64, end_time: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(start_time < end_time && end_time > now, ErrorCode::InvalidCampaignWindow);
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.target = target;
+    campaign.reward_mint = ctx.accounts.reward_mint.key();
+    campaign.reward_vault = ctx.accounts.reward_vault.key();
+    campaign.reward_rate = reward_rate;
+    campaign.start_time = start_time;
+    campaign.end_time = end_time;
+    campaign.last_update_time = start_time;
+    campaign.accumulated_reward_per_share = 0;
+    campaign.funded_amount = 0;
+    campaign.emitted_amount = 0;
+    campaign.distributed_amount = 0;
+    campaign.terminated = false;
+    campaign.authority_bump = ctx.bumps.campaign_authority;
+    campaign.joined_weight = 0;
+
+    emit!(CampaignCreatedEvent {
+        campaign: campaign.key(),
+        target,
+        reward_mint: campaign.reward_mint,
+        reward_rate,
+        start_time,
+        end_time,
+    });
+
+    Ok(())
+}
+
+/// Deposit rewards into a campaign. Callable by anyone, typically the partner sponsoring it.
+pub fn fund_campaign(ctx: Context<FundCampaign>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.reward_vault.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.funded_amount = campaign.funded_amount.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    emit!(CampaignFundedEvent {
+        campaign: campaign.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        funded_amount: campaign.funded_amount,
+    });
+
+    Ok(())
+}
+
+/// Enroll a staker or user account in a campaign. Only weight held after joining earns rewards.
+///
+/// Only the wallet owning the position can enroll it, so rewards cannot be
+/// redirected by joining someone else's position first.
+pub fn join_campaign(ctx: Context<JoinCampaign>) -> Result<()> {
+    let (position_owner, weight) = campaign_position_weight(&ctx.accounts.campaign.target, &ctx.accounts.position_account)?;
+    require_keys_eq!(position_owner, ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+
+    let campaign = &mut ctx.accounts.campaign;
+    accrue_campaign(campaign, Clock::get()?.unix_timestamp)?;
+    campaign.joined_weight = campaign.joined_weight.checked_add(weight).ok_or(ErrorCode::Overflow)?;
+
+    let campaign_position = &mut ctx.accounts.campaign_position;
+    campaign_position.campaign = campaign.key();
+    campaign_position.position = ctx.accounts.position_account.key();
+    campaign_position.owner = ctx.accounts.owner.key();
+    campaign_position.weight = weight;
+    campaign_position.reward_per_share_paid = campaign.accumulated_reward_per_share;
+    campaign_position.pending_rewards = 0;
+    campaign_position.bump = ctx.bumps.campaign_position;
+
+    emit!(CampaignJoinedEvent {
+        campaign: campaign.key(),
+        position: campaign_position.position,
+        owner: campaign_position.owner,
+        weight,
+    });
+
+    Ok(())
+}
+
+/// Settle a campaign position and pick up its current weight. Callable by anyone.
+pub fn sync_campaign_position(ctx: Context<SyncCampaignPosition>) -> Result<()> {
+    let (_, weight) = campaign_position_weight(&ctx.accounts.campaign.target, &ctx.accounts.position_account)?;
+
+    let campaign = &mut ctx.accounts.campaign;
+    accrue_campaign(campaign, Clock::get()?.unix_timestamp)?;
+    settle_campaign_position(campaign, &mut ctx.accounts.campaign_position, weight)?;
+
+    Ok(())
+}
+
+/// Claim settled campaign rewards. Remains available after the campaign ends.
+pub fn claim_campaign_rewards(ctx: Context<ClaimCampaignRewards>) -> Result<()> {
+    let (_, weight) = campaign_position_weight(&ctx.accounts.campaign.target, &ctx.accounts.position_account)?;
+
+    let campaign = &mut ctx.accounts.campaign;
+    accrue_campaign(campaign, Clock::get()?.unix_timestamp)?;
+    let campaign_position = &mut ctx.accounts.campaign_position;
+    settle_campaign_position(campaign, campaign_position, weight)?;
+
+    let amount = campaign_position.pending_rewards;
+    require!(amount > 0, ErrorCode::NoRewardsAvailable);
+    campaign_position.pending_rewards = 0;
+    campaign.distributed_amount = campaign.distributed_amount.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    let campaign_key = campaign.key();
+    let bump = [campaign.authority_bump];
+    let seeds: &[&[u8]] = &[b"campaign_authority", campaign_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.owner_reward_account.to_account_info(),
+        authority: ctx.accounts.campaign_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(CampaignRewardsClaimedEvent {
+        campaign: campaign_key,
+        position: ctx.accounts.campaign_position.position,
+        owner: ctx.accounts.owner.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// End a campaign now and refund the rewards it has not yet emitted.
+pub fn terminate_campaign(ctx: Context<TerminateCampaign>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let campaign = &mut ctx.accounts.campaign;
+    accrue_campaign(campaign, now)?;
+    campaign.end_time = campaign.end_time.min(now);
+    campaign.terminated = true;
+
+    // Emitted rewards stay behind for participants to claim
+    let refund = campaign.funded_amount.saturating_sub(campaign.emitted_amount);
+    campaign.funded_amount = campaign.emitted_amount;
+
+    if refund > 0 {
+        let campaign_key = campaign.key();
+        let bump = [campaign.authority_bump];
+        let seeds: &[&[u8]] = &[b"campaign_authority", campaign_key.as_ref(), &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.refund_account.to_account_info(),
+            authority: ctx.accounts.campaign_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, refund)?;
+    }
+
+    emit!(CampaignTerminatedEvent {
+        campaign: ctx.accounts.campaign.key(),
+        emitted_amount: ctx.accounts.campaign.emitted_amount,
+        refunded_amount: refund,
+    });
+
+    Ok(())
+}
+
+/// Owner of a position in a campaign's target and the weight it holds there.
+pub fn campaign_position_weight(target: &CampaignTarget, position_account: &AccountInfo) -> Result<(Pubkey, u64)> {
+    match target {
+        CampaignTarget::StakingPool(reward_pool) => {
+            let staker_account = load_program_account::<StakerAccount>(position_account)?;
+            require_keys_eq!(staker_account.reward_pool, *reward_pool, ErrorCode::InvalidCampaignTarget);
+            Ok((staker_account.owner, staker_account.staked_balance))
+        }
+        CampaignTarget::Collateral(collateral_type) => {
+            let user_account = load_program_account::<UserAccount>(position_account)?;
+            require_keys_eq!(user_account.collateral_type, *collateral_type, ErrorCode::InvalidCampaignTarget);
+            Ok((user_account.owner, user_account.stablecoin_balance))
+        }
+    }
+}
+
+/// Deserialize a read-only account owned by this program, checking its discriminator.
+fn load_program_account<T: AccountDeserialize>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidAccountData);
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// Advance a campaign's accumulator to `now`, never emitting more than it was funded with.
+///
+/// Emissions are shared over the weight of joined positions only, so every
+/// emitted reward has a claimant. Time with no joined weight emits nothing,
+/// so those rewards stay refundable.
+pub fn accrue_campaign(campaign: &mut Campaign, now: i64) -> Result<()> {
+    let total_weight = campaign.joined_weight;
+    let from = campaign.last_update_time.max(campaign.start_time);
+    let until = now.min(campaign.end_time);
+    if until > from && total_weight > 0 {
+        let unemitted = campaign.funded_amount.saturating_sub(campaign.emitted_amount);
+        let emission = (campaign.reward_rate as u128)
+            .checked_mul((until - from) as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .min(unemitted as u128);
+        let increment = emission.checked_mul(REWARD_PRECISION).ok_or(ErrorCode::Overflow)? / total_weight as u128;
+        campaign.accumulated_reward_per_share = campaign.accumulated_reward_per_share
+            .checked_add(increment)
+            .ok_or(ErrorCode::Overflow)?;
+        campaign.emitted_amount = campaign.emitted_amount
+            .checked_add(emission as u64)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    campaign.last_update_time = campaign.last_update_time.max(until);
+
+    Ok(())
+}
+
+/// Settle a position's campaign rewards and record its new weight.
+///
+/// Rewards accrue on the lower of the recorded and current weight, so weight
+/// added since the last settlement cannot claim rewards emitted before it.
+pub fn settle_campaign_position(campaign: &mut Campaign, campaign_position: &mut CampaignPosition, weight: u64) -> Result<()> {
+    let delta = campaign.accumulated_reward_per_share
+        .checked_sub(campaign_position.reward_per_share_paid)
+        .ok_or(ErrorCode::Overflow)?;
+    let earned = (campaign_position.weight.min(weight) as u128)
+        .checked_mul(delta)
+        .ok_or(ErrorCode::Overflow)?
+        / REWARD_PRECISION;
+    campaign_position.pending_rewards = campaign_position.pending_rewards
+        .checked_add(u64::try_from(earned).map_err(|_| ErrorCode::Overflow)?)
+        .ok_or(ErrorCode::Overflow)?;
+    campaign_position.reward_per_share_paid = campaign.accumulated_reward_per_share;
+    campaign.joined_weight = campaign.joined_weight
+        .saturating_sub(campaign_position.weight)
+        .checked_add(weight)
+        .ok_or(ErrorCode::Overflow)?;
+    campaign_position.weight = weight;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Fee Instructions
+// -------------------------------------
+
+/// Whitelist an NFT collection as a loyalty pass with a mint fee discount.
+pub fn add_loyalty_collection(ctx: Context<AddLoyaltyCollection>, fee_discount_bps: u64) -> Result<()> {
+    let loyalty_collection = &mut ctx.accounts.loyalty_collection;
+    loyalty_collection.collection_mint = ctx.accounts.collection_mint.key();
+    loyalty_collection.fee_discount_bps = fee_discount_bps;
+    loyalty_collection.bump = ctx.bumps.loyalty_collection;
+
+    emit!(LoyaltyCollectionUpdatedEvent {
+        collection_mint: loyalty_collection.collection_mint,
+        fee_discount_bps,
+        whitelisted: true,
+    });
+
+    Ok(())
+}
+
+/// Change the mint fee discount of a whitelisted loyalty collection.
+pub fn set_loyalty_discount(ctx: Context<SetLoyaltyDiscount>, fee_discount_bps: u64) -> Result<()> {
+    let loyalty_collection = &mut ctx.accounts.loyalty_collection;
+    loyalty_collection.fee_discount_bps = fee_discount_bps;
+
+    emit!(LoyaltyCollectionUpdatedEvent {
+        collection_mint: loyalty_collection.collection_mint,
+        fee_discount_bps,
+        whitelisted: true,
+    });
+
+    Ok(())
+}
+
+/// Remove a collection from the loyalty pass whitelist.
+pub fn remove_loyalty_collection(ctx: Context<RemoveLoyaltyCollection>) -> Result<()> {
+    emit!(LoyaltyCollectionUpdatedEvent {
+        collection_mint: ctx.accounts.loyalty_collection.collection_mint,
+        fee_discount_bps: 0,
+        whitelisted: false,
+    });
+
+    Ok(())
+}
+
+/// Set a collateral type's oracle confidence insurance premium curve.
+pub fn set_confidence_premium(ctx: Context<SetConfidencePremium>, threshold_bps: u64, slope_bps: u64, max_premium_bps: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.confidence_premium_threshold_bps = threshold_bps;
+    collateral_type.confidence_premium_slope_bps = slope_bps;
+    collateral_type.max_confidence_premium_bps = max_premium_bps;
+
+    emit!(ConfidencePremiumUpdatedEvent {
+        collateral_type: collateral_type.key(),
+        threshold_bps,
+        slope_bps,
+        max_premium_bps,
+    });
+
+    Ok(())
+}
+
+/// Set the global mint and redemption fee rates (bps).
+pub fn set_fee_rates(ctx: Context<SetFeeRates>, minting_fee_rate: u64, redemption_fee_rate: u64) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.minting_fee_rate = minting_fee_rate;
+    system_state.redemption_fee_rate = redemption_fee_rate;
+
+    emit!(FeeRatesUpdatedEvent {
+        minting_fee_rate,
+        redemption_fee_rate,
+    });
+
+    Ok(())
+}
+
+/// Set the progressive surcharge on single mints that take a large share of a collateral's debt ceiling headroom.
+pub fn set_mint_size_fee(ctx: Context<SetFeeRates>, threshold_bps: u64, slope_bps: u64, max_surcharge_bps: u64) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.size_fee_threshold_bps = threshold_bps;
+    system_state.size_fee_slope_bps = slope_bps;
+    system_state.max_size_fee_bps = max_surcharge_bps;
+
+    emit!(MintSizeFeeUpdatedEvent {
+        threshold_bps,
+        slope_bps,
+        max_surcharge_bps,
+    });
+
+    Ok(())
+}
+
+/// Surcharge (bps) on a single mint of `amount` against a collateral.
+///
+/// Zero while the mint takes at most the threshold share of the headroom
+/// left under the collateral's debt ceiling; above it the surcharge grows
+/// linearly with the share taken, up to the cap. A mint into a ceiling with
+/// no headroom left pays the cap.
+pub fn mint_size_surcharge_bps(system_state: &SystemState, collateral_type: &CollateralType, amount: u64) -> Result<u64> {
+    let headroom = collateral_type.debt_ceiling.saturating_sub(collateral_type.total_debt);
+    if headroom == 0 {
+        return Ok(system_state.max_size_fee_bps);
+    }
+
+    let share_bps = (amount as u128)
+        .checked_mul(math::BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / headroom as u128;
+    let excess_bps = share_bps.saturating_sub(system_state.size_fee_threshold_bps as u128);
+    let surcharge = excess_bps
+        .checked_mul(system_state.size_fee_slope_bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / math::BPS_DENOMINATOR as u128;
+
+    Ok(surcharge.min(system_state.max_size_fee_bps as u128) as u64)
+}
+
+/// Override the mint and redemption fees of one collateral type. `None` falls back to the global rate.
+pub fn set_collateral_fee_overrides(ctx: Context<SetCollateralFeeOverrides>, mint_fee_bps: Option<u64>, redemption_fee_bps: Option<u64>) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.mint_fee_bps = mint_fee_bps;
+    collateral_type.redemption_fee_bps = redemption_fee_bps;
+
+    emit!(CollateralFeeOverridesUpdatedEvent {
+        collateral_type: collateral_type.key(),
+        mint_fee_bps,
+        redemption_fee_bps,
+    });
+
+    Ok(())
+}
+
+/// Mint fee (bps) for a collateral: its override if set, otherwise the global rate.
+pub fn effective_mint_fee_bps(system_state: &SystemState, collateral_type: Option<&CollateralType>) -> u64 {
+    collateral_type
+        .and_then(|collateral_type| collateral_type.mint_fee_bps)
+        .unwrap_or(system_state.minting_fee_rate)
+}
+
+/// Redemption fee (bps) for a collateral: its override if set, otherwise the global rate.
+pub fn effective_redemption_fee_bps(system_state: &SystemState, collateral_type: Option<&CollateralType>) -> u64 {
+    collateral_type
+        .and_then(|collateral_type| collateral_type.redemption_fee_bps)
+        .unwrap_or(system_state.redemption_fee_rate)
+}
+
+/// Mint fee (bps) after the discount of a loyalty pass the minter proves they hold.
+pub fn loyalty_fee_bps(
+    fee_bps: u64,
+    token_account: &TokenAccount,
+    metadata: &AccountInfo,
+    loyalty_collection: &LoyaltyCollection,
+    minter: &Pubkey,
+) -> Result<u64> {
+    let collection = loyalty::verify_loyalty_pass(token_account, metadata, minter)?;
+    require_keys_eq!(collection, loyalty_collection.collection_mint, ErrorCode::InvalidLoyaltyPass);
+
+    Ok(fee_bps * (10_000 - loyalty_collection.fee_discount_bps) / 10_000)
+}
+
+/// Insurance premium (bps) owed on a mint priced with `oracle_price`.
+///
+/// Grows linearly with the oracle's confidence interval above the
+/// collateral's threshold, up to its cap; zero while the feed is tight.
+pub fn confidence_premium_bps(collateral_type: &CollateralType, oracle_price: &oracle::OraclePrice) -> u64 {
+    let confidence_bps = (oracle_price.confidence as u128 * 10_000 / oracle_price.price as u128) as u64;
+    let excess_bps = confidence_bps.saturating_sub(collateral_type.confidence_premium_threshold_bps);
+    let premium_bps = excess_bps as u128 * collateral_type.confidence_premium_slope_bps as u128 / 10_000;
+    (premium_bps as u64).min(collateral_type.max_confidence_premium_bps)
+}
+
+/// Fee charged on `amount` at `fee_bps`, rounded up in the protocol's favor.
+pub fn fee_amount(amount: u64, fee_bps: u64) -> Result<u64> {
+    Ok(math::fee_charge(amount, fee_bps)?.fee)
+}
+
+/// Fee quote returned by the preview instructions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct FeePreview {
+    pub fee_bps: u64,                   // Fee rate that would apply
+    pub fee: u64,                       // Fee taken from the amount
+    pub net_amount: u64,                // Amount received after the fee
+    pub collateral_override: bool,      // Whether the rate comes from a collateral override
+    pub size_surcharge_bps: u64,        // Part of the rate charged for the mint's share of the ceiling headroom
+}
+
+/// Preview the mint fee on `amount`, including any collateral override.
+pub fn preview_mint_fee(ctx: Context<PreviewFee>, amount: u64) -> Result<FeePreview> {
+    let collateral_type = ctx.accounts.collateral_type.as_deref();
+    let size_surcharge_bps = match collateral_type {
+        Some(collateral_type) => mint_size_surcharge_bps(&ctx.accounts.system_state, collateral_type, amount)?,
+        None => 0,
+    };
+    let fee_bps = effective_mint_fee_bps(&ctx.accounts.system_state, collateral_type) + size_surcharge_bps;
+    let fee = fee_amount(amount, fee_bps)?;
+
+    Ok(FeePreview {
+        fee_bps,
+        fee,
+        net_amount: amount - fee,
+        collateral_override: collateral_type.map_or(false, |collateral_type| collateral_type.mint_fee_bps.is_some()),
+        size_surcharge_bps,
+    })
+}
+
+/// Preview the redemption fee on `amount`, including any collateral override.
+pub fn preview_redemption_fee(ctx: Context<PreviewFee>, amount: u64) -> Result<FeePreview> {
+    let collateral_type = ctx.accounts.collateral_type.as_deref();
+    let fee_bps = effective_redemption_fee_bps(&ctx.accounts.system_state, collateral_type);
+    let fee = fee_amount(amount, fee_bps)?;
+
+    Ok(FeePreview {
+        fee_bps,
+        fee,
+        net_amount: amount - fee,
+        collateral_override: collateral_type.map_or(false, |collateral_type| collateral_type.redemption_fee_bps.is_some()),
+        size_surcharge_bps: 0,
+    })
+}
+
+/// Bonus on fee credits bought by burning stablecoin (bps), i.e. the prepayment discount.
+pub const FEE_CREDIT_BONUS_BPS: u64 = 200;
+
+/// Burn stablecoin for fee credits on the position, with the prepayment bonus.
+///
+/// Credits are spent before any mint or redemption fee is charged on the
+/// position, letting market makers budget their fees up front.
+pub fn purchase_fee_credits(ctx: Context<PurchaseFeeCredits>, amount: u64) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.owner_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, amount)?;
+
+    let credits = math::mul_div_down(
+        amount as u128,
+        (math::BPS_DENOMINATOR + FEE_CREDIT_BONUS_BPS) as u128,
+        math::BPS_DENOMINATOR as u128,
+    )?;
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.fee_credits = user_account.fee_credits.checked_add(credits).ok_or(ErrorCode::Overflow)?;
+
+    // The burn retires supply without repaying any debt, so it is booked against the accounted supply
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.fee_credit_burns = protocol_stats.fee_credit_burns.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    emit!(FeeCreditsPurchasedEvent {
+        user: user_account.key(),
+        burned: amount,
+        credits,
+        fee_credits: user_account.fee_credits,
+    });
+
+    Ok(())
+}
+
+/// Pay as much of `fee` as possible from the position's fee credits.
+///
+/// Returns the fee still due and the credits spent. Credits were paid for in
+/// burned stablecoin, so the part they cover is not minted to the treasury.
+pub fn spend_fee_credits(user_account: &mut UserAccount, fee: u64) -> (u64, u64) {
+    let credits_used = fee.min(user_account.fee_credits);
+    user_account.fee_credits -= credits_used;
+    (fee - credits_used, credits_used)
+}
+
+// -------------------------------------
+// Position Migration Instructions
+// -------------------------------------
+
+/// Move a v1 user account into a v2 position PDA and close the v1 account.
+///
+/// The v1 account is decoded with the layout it was written in, which is
+/// shorter than today's `UserAccount`. Collateral and timestamps carry over
+/// unchanged; the fields v1 never had start at their defaults, and the fee
+/// index at the current stability fee index. Positions with outstanding debt
+/// are refused, since nothing can repay debt held in a v2 position yet.
+pub fn migrate_position(ctx: Context<MigratePosition>) -> Result<()> {
+    let user_info = ctx.accounts.user_account.to_account_info();
+    let legacy = {
+        let data = user_info.try_borrow_data()?;
+        require!(
+            data.len() == 8 + 8 * 5 && data[..8] == UserAccount::DISCRIMINATOR,
+            ErrorCode::InvalidAccountData
+        );
+        LegacyUserAccount::deserialize(&mut &data[8..])?
+    };
+    require!(legacy.stablecoin_balance == 0, ErrorCode::MigrationWithOutstandingDebt);
+
+    let position = &mut ctx.accounts.position;
+    position.version = POSITION_VERSION;
+    position.owner = ctx.accounts.owner.key();
+    position.migrated_from = user_info.key();
+    position.collateral_balance = legacy.collateral_balance;
+    position.stablecoin_balance = legacy.stablecoin_balance;
+    position.collateral_ratio = legacy.collateral_ratio;
+    position.collateral_type = Pubkey::default();
+    position.health_band = HealthBand::Healthy;
+    position.tag = [0; 32];
+    position.last_liquidation_time = legacy.last_liquidation_time;
+    position.last_mint_time = legacy.last_mint_time;
+    position.last_liquidation_ratio = u64::MAX;
+    position.stability_fee_index = ctx.accounts.rate_snapshots.stability_fee_index;
+    position.entry_exchange_rate = 0;
+    position.migrated_at = Clock::get()?.unix_timestamp;
+    position.bump = ctx.bumps.position;
+
+    // Close the v1 account by hand, as it cannot be loaded as a `UserAccount`
+    let owner_info = ctx.accounts.owner.to_account_info();
+    **owner_info.try_borrow_mut_lamports()? += user_info.lamports();
+    **user_info.try_borrow_mut_lamports()? = 0;
+    user_info.assign(&system_program::ID);
+    user_info.realloc(0, false)?;
+
+    emit!(PositionMigratedEvent {
+        old_position: position.migrated_from,
+        new_position: position.key(),
+        owner: position.owner,
+        version: POSITION_VERSION,
+        collateral_balance: position.collateral_balance,
+        stablecoin_balance: position.stablecoin_balance,
+        stability_fee_index: position.stability_fee_index,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Invariant Instructions
+// -------------------------------------
+
+/// Result of the global accounting invariant checks.
+///
+/// Vault checks are `None` when the corresponding accounts were not passed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InvariantReport {
+    pub stablecoin_supply: u64,         // Current supply of the canonical stablecoin mint
+    pub accounted_supply: u64,          // Collateral debt + legacy debt + fee issuance + written-off debt
+    pub supply_consistent: bool,        // Whether the supply equals the accounted supply
+    pub staking_vault_balance: Option<u64>,
+    pub total_staked: Option<u64>,
+    pub wrapper_vault_balance: Option<u64>,
+    pub wrapper_obligations: Option<u64>,
+    pub holds: bool,                    // Whether every checked invariant holds
+    pub collateral_vault_balance: Option<u64>,
+    pub recorded_collateral: Option<u64>,
+}
+
+/// Check the protocol's core accounting invariants. Callable by anyone.
+///
+/// - Stablecoin supply equals collateral-typed debt plus legacy untyped debt
+///   plus fee issuance plus debt written off by full liquidations plus market
+///   maker facility debt plus auction debt settled in the reserve asset, less
+///   stablecoin burned for fee credits and auction proceeds burned above the
+///   debt they settled.
+/// - The staking vault holds at least the recorded total stake, less rewards
+///   `claim_to_stake` credited that are only minted on withdrawal and stake
+///   slashed to cover bad debt.
+/// - The savings wrapper vault holds at least what all wrapped tokens redeem for.
+/// - A stake pool collateral's LST vault holds at least the LST credited to positions.
+///
+/// Emits the report when everything holds, and fails with the report logged otherwise.
+pub fn assert_invariants(ctx: Context<AssertInvariants>) -> Result<InvariantReport> {
+    let protocol_stats = &ctx.accounts.protocol_stats;
+    let stablecoin_supply = ctx.accounts.stablecoin_mint.supply;
+    let accounted_supply = protocol_stats.total_collateral_debt
+        .checked_add(protocol_stats.legacy_issuance)
+        .and_then(|value| value.checked_add(protocol_stats.fee_issuance))
+        .and_then(|value| value.checked_add(protocol_stats.written_off_debt))
+        .and_then(|value| value.checked_add(protocol_stats.mm_facility_debt))
+        .and_then(|value| value.checked_add(protocol_stats.reserve_backed_debt))
+        .and_then(|value| value.checked_sub(protocol_stats.fee_credit_burns))
+        .and_then(|value| value.checked_sub(protocol_stats.auction_surplus_burns))
+        .ok_or(ErrorCode::Overflow)?;
+    let supply_consistent = stablecoin_supply == accounted_supply;
+    let mut holds = supply_consistent;
+
+    // Staking vault balance >= recorded stake already minted into it and not slashed
+    let (mut staking_vault_balance, mut total_staked) = (None, None);
+    if let (Some(reward_pool), Some(staking_vault)) = (&ctx.accounts.reward_pool, &ctx.accounts.staking_vault) {
+        require_keys_eq!(staking_vault.key(), reward_pool.staking_vault, ErrorCode::InvalidAccountData);
+        let minted_stake = reward_pool.total_staked.checked_sub(reward_pool.unsettled_stake).ok_or(ErrorCode::Overflow)?;
+        holds &= staking_vault.amount >= minted_stake.saturating_sub(reward_pool.slashed_stake);
+        staking_vault_balance = Some(staking_vault.amount);
+        total_staked = Some(reward_pool.total_staked);
+    }
+
+    // Wrapper vault balance >= stablecoin owed to all wrapped token holders
+    let (mut wrapper_vault_balance, mut wrapper_obligations) = (None, None);
+    if let (Some(wrapper_vault), Some(vault), Some(wrapped_mint)) =
+        (&ctx.accounts.wrapper_vault, &ctx.accounts.wrapper_token_vault, &ctx.accounts.wrapped_mint)
+    {
+        require_keys_eq!(vault.key(), wrapper_vault.vault, ErrorCode::InvalidAccountData);
+        require_keys_eq!(wrapped_mint.key(), wrapper_vault.wrapped_mint, ErrorCode::InvalidMint);
+        let obligations = wrapped_to_stablecoin(wrapper_vault, wrapped_mint.supply)?;
+        holds &= vault.amount >= obligations;
+        wrapper_vault_balance = Some(vault.amount);
+        wrapper_obligations = Some(obligations);
+    }
+
+    // Collateral vault balance >= collateral credited to positions
+    let (mut collateral_vault_balance, mut recorded_collateral) = (None, None);
+    if let (Some(stake_pool_config), Some(lst_vault)) = (&ctx.accounts.stake_pool_config, &ctx.accounts.lst_vault) {
+        require_keys_eq!(lst_vault.key(), stake_pool_config.lst_vault, ErrorCode::InvalidAccountData);
+        holds &= lst_vault.amount >= stake_pool_config.total_lst;
+        collateral_vault_balance = Some(lst_vault.amount);
+        recorded_collateral = Some(stake_pool_config.total_lst);
+    }
+
+    let report = InvariantReport {
+        stablecoin_supply,
+        accounted_supply,
+        supply_consistent,
+        staking_vault_balance,
+        total_staked,
+        wrapper_vault_balance,
+        wrapper_obligations,
+        holds,
+        collateral_vault_balance,
+        recorded_collateral,
+    };
+
+    if !holds {
+        msg!("Invariant violated: {:?}", report);
+        return err!(ErrorCode::InvariantViolated);
+    }
+
+    emit!(InvariantReportEvent {
+        stablecoin_supply,
+        accounted_supply,
+        staking_vault_balance,
+        total_staked,
+        wrapper_vault_balance,
+        wrapper_obligations,
+        collateral_vault_balance,
+        recorded_collateral,
+    });
+
+    Ok(report)
+}
+
+// -------------------------------------
+// Peg Band Instructions
+// -------------------------------------
+
+/// Create the peg state with the TWAP at $1 and the band not yet enforced.
+pub fn initialize_peg_state(ctx: Context<InitializePegState>) -> Result<()> {
+    let peg_state = &mut ctx.accounts.peg_state;
+    peg_state.price_feed = ctx.accounts.price_feed.key();
+    peg_state.twap_price = PRICE_PRECISION;
+    peg_state.last_update = Clock::get()?.unix_timestamp;
+    peg_state.twap_window_secs = DEFAULT_PEG_TWAP_WINDOW_SECS;
+    peg_state.max_deviation_bps = DEFAULT_PEG_BAND_BPS;
+    peg_state.max_price_age_secs = DEFAULT_MAX_PRICE_AGE_SECS;
+    peg_state.enforced = false;
+    peg_state.last_run_slot = 0;
+    peg_state.bump = ctx.bumps.peg_state;
+
+    Ok(())
+}
+
+/// Configure the peg band, TWAP window and staleness limit, and whether the band gates minting.
+pub fn set_peg_band(ctx: Context<SetPegBand>, max_deviation_bps: u64, twap_window_secs: u64, max_price_age_secs: u64, enforced: bool) -> Result<()> {
+    let peg_state = &mut ctx.accounts.peg_state;
+    peg_state.max_deviation_bps = max_deviation_bps;
+    peg_state.twap_window_secs = twap_window_secs;
+    peg_state.max_price_age_secs = max_price_age_secs;
+    peg_state.enforced = enforced;
+
+    emit!(PegBandUpdatedEvent {
+        max_deviation_bps,
+        twap_window_secs,
+        max_price_age_secs,
+        enforced,
+    });
+
+    Ok(())
+}
+
+/// Fold the stablecoin's current market price into the TWAP. Callable by anyone.
+///
+/// The TWAP moves towards the spot price in proportion to the time elapsed
+/// over the window, so a single manipulated update has a bounded effect.
+pub fn update_peg_twap(ctx: Context<UpdatePegTwap>) -> Result<CrankStatus> {
+    let clock = Clock::get()?;
+    let peg_state = &mut ctx.accounts.peg_state;
+    let status = run_crank(&ctx.accounts.crank_schedule, CrankKind::PegObservation, &mut peg_state.last_run_slot, clock.slot)?;
+    let oracle_price = oracle::read_peg_price(&ctx.accounts.price_feed, peg_state, &ctx.accounts.system_state.oracle_programs)?;
+
+    let now = clock.unix_timestamp;
+    let elapsed = now.saturating_sub(peg_state.last_update).max(0) as u64;
+    let weight = elapsed.min(peg_state.twap_window_secs) as i128;
+    let window = peg_state.twap_window_secs.max(1) as i128;
+    let twap = peg_state.twap_price as i128 + (oracle_price.price as i128 - peg_state.twap_price as i128) * weight / window;
+    peg_state.twap_price = u64::try_from(twap).map_err(|_| ErrorCode::Overflow)?;
+    peg_state.last_update = now;
+
+    emit!(PegTwapUpdatedEvent {
+        spot_price: oracle_price.price,
+        twap_price: peg_state.twap_price,
+        minting_allowed: peg_within_band(peg_state),
+    });
+
+    Ok(status)
+}
+
+/// Whether the peg TWAP is within the band below $1. Above peg is always allowed.
+pub fn peg_within_band(peg_state: &PegState) -> bool {
+    let floor = PRICE_PRECISION as u128 * 10_000u128.saturating_sub(peg_state.max_deviation_bps as u128) / 10_000;
+    peg_state.twap_price as u128 >= floor
+}
+
+/// Refuse new issuance while the stablecoin trades below the peg band.
+///
+/// Only minting is gated: repayments, redemptions and liquidations reduce
+/// supply and must keep working during a depeg.
+pub fn enforce_peg_band(peg_state: &PegState, now: i64) -> Result<()> {
+    if !peg_state.enforced {
+        return Ok(());
+    }
+    let age = now.saturating_sub(peg_state.last_update);
+    require!(age >= 0 && age as u64 <= peg_state.max_price_age_secs, ErrorCode::StalePrice);
+    require!(peg_within_band(peg_state), ErrorCode::MintingBelowPeg);
+
+    Ok(())
+}
+
+// -------------------------------------
+// Collateral Yield Instructions
+// -------------------------------------
+
+/// Set the exchange rate of a yield-bearing collateral and the share of its yield the protocol skims.
+pub fn set_collateral_yield_config(ctx: Context<SetCollateralYieldConfig>, exchange_rate: u128, yield_skim_bps: u64) -> Result<()> {
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    collateral_type.exchange_rate = exchange_rate;
+    collateral_type.yield_skim_bps = yield_skim_bps;
+
+    emit!(CollateralYieldConfigUpdatedEvent {
+        collateral_type: collateral_type.key(),
+        exchange_rate,
+        yield_skim_bps,
+    });
+
+    Ok(())
+}
+
+/// Collateral skimmed from `collateral_amount` of a position's collateral.
+///
+/// The yield is the part of the collateral's underlying value gained since the
+/// position's entry exchange rate, expressed in collateral tokens. Callers
+/// advance the entry rate after skimming. Positions opened before the
+/// collateral was marked yield-bearing have no entry rate and are never skimmed.
+pub fn collateral_yield_skim(user_account: &UserAccount, collateral_type: &CollateralType, collateral_amount: u64) -> Result<u64> {
+    let entry_rate = user_account.entry_exchange_rate;
+    let current_rate = collateral_type.exchange_rate;
+    if collateral_type.yield_skim_bps == 0 || entry_rate == 0 || current_rate <= entry_rate {
+        return Ok(0);
+    }
+
+    let yield_amount = (collateral_amount as u128)
+        .checked_mul(current_rate - entry_rate)
+        .ok_or(ErrorCode::Overflow)?
+        / current_rate;
+    let skim = yield_amount * collateral_type.yield_skim_bps as u128 / 10_000;
+
+    u64::try_from(skim).map_err(|_| ErrorCode::Overflow.into())
+}
+
+// -------------------------------------
+// Vote Escrow Instructions
+// -------------------------------------
+
+/// Create the ve config and the escrow vault holding locked governance tokens.
+pub fn initialize_ve_config(ctx: Context<InitializeVeConfig>) -> Result<()> {
+    let ve_config = &mut ctx.accounts.ve_config;
+    ve_config.escrow_vault = ctx.accounts.escrow_vault.key();
+    ve_config.total_ve = 0;
+    ve_config.escrow_authority_bump = ctx.bumps.escrow_authority;
+    ve_config.bump = ctx.bumps.ve_config;
+
+    Ok(())
+}
+
+/// Lock governance tokens for `duration` seconds in exchange for ve.
+///
+/// ve is granted once at lock time in proportion to the lock length and
+/// stays fixed until the lock expires and is kicked or withdrawn.
+pub fn create_ve_lock(ctx: Context<CreateVeLock>, amount: u64, duration: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner_token_account.to_account_info(),
+        to: ctx.accounts.escrow_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let ve_balance = u64::try_from(amount as u128 * duration as u128 / MAX_VE_LOCK_SECS as u128).map_err(|_| ErrorCode::Overflow)?;
+    let ve_lock = &mut ctx.accounts.ve_lock;
+    ve_lock.owner = ctx.accounts.owner.key();
+    ve_lock.amount = amount;
+    ve_lock.unlock_time = Clock::get()?.unix_timestamp.checked_add(duration as i64).ok_or(ErrorCode::Overflow)?;
+    ve_lock.ve_balance = ve_balance;
+    ve_lock.boosted_staker = Pubkey::default();
+    ve_lock.bump = ctx.bumps.ve_lock;
+
+    let ve_config = &mut ctx.accounts.ve_config;
+    ve_config.total_ve = ve_config.total_ve.checked_add(ve_balance).ok_or(ErrorCode::Overflow)?;
+
+    emit!(VeLockCreatedEvent {
+        owner: ve_lock.owner,
+        amount,
+        unlock_time: ve_lock.unlock_time,
+        ve_balance,
+    });
+
+    Ok(())
+}
+
+/// Withdraw an expired lock. A lock still boosting a staker must be kicked first.
+pub fn withdraw_ve_lock(ctx: Context<WithdrawVeLock>) -> Result<()> {
+    let ve_lock = &ctx.accounts.ve_lock;
+    require!(Clock::get()?.unix_timestamp >= ve_lock.unlock_time, ErrorCode::LockupPeriodNotOver);
+    require_keys_eq!(ve_lock.boosted_staker, Pubkey::default(), ErrorCode::BoostStillApplied);
+
+    let ve_config = &mut ctx.accounts.ve_config;
+    ve_config.total_ve = ve_config.total_ve.saturating_sub(ve_lock.ve_balance);
+
+    let bump = [ve_config.escrow_authority_bump];
+    let seeds: &[&[u8]] = &[b"ve_escrow_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow_vault.to_account_info(),
+        to: ctx.accounts.owner_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, ve_lock.amount)?;
+
+    Ok(())
+}
+
+/// Boost a staker with the owner's ve lock, or refresh an existing boost.
+///
+/// A lock boosts a single staker account at a time.
+pub fn apply_boost(ctx: Context<ApplyBoost>) -> Result<()> {
+    let ve_lock = &mut ctx.accounts.ve_lock;
+    let staker_key = ctx.accounts.staker_account.key();
+    require!(Clock::get()?.unix_timestamp < ve_lock.unlock_time, ErrorCode::LockupPeriodNotOver);
+    require!(
+        ve_lock.boosted_staker == Pubkey::default() || ve_lock.boosted_staker == staker_key,
+        ErrorCode::BoostStillApplied
+    );
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let staker_account = &mut ctx.accounts.staker_account;
+    let boost_bps = boosted_bps(staker_account.staked_balance, reward_pool.total_staked, ve_lock.ve_balance, ctx.accounts.ve_config.total_ve);
+    checkpoint_boost(reward_pool, staker_account, boost_bps)?;
+    ve_lock.boosted_staker = staker_key;
+    staker_account.ve_lock = ve_lock.key();
+
+    emit!(BoostUpdatedEvent {
+        staker: staker_key,
+        ve_lock: ve_lock.key(),
+        boost_bps,
+        working_balance: staker_account.working_balance,
+    });
+
+    Ok(())
+}
+
+/// Drop an expired lock's boost back to the base rate and retire its ve. Callable by anyone.
+pub fn kick_boost(ctx: Context<KickBoost>) -> Result<()> {
+    let ve_lock = &mut ctx.accounts.ve_lock;
+    require!(Clock::get()?.unix_timestamp >= ve_lock.unlock_time, ErrorCode::LockupPeriodNotOver);
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let staker_account = &mut ctx.accounts.staker_account;
+    checkpoint_boost(reward_pool, staker_account, BASE_BOOST_BPS)?;
+    staker_account.ve_lock = Pubkey::default();
+
+    let ve_config = &mut ctx.accounts.ve_config;
+    ve_config.total_ve = ve_config.total_ve.saturating_sub(ve_lock.ve_balance);
+    ve_lock.ve_balance = 0;
+    ve_lock.boosted_staker = Pubkey::default();
+
+    emit!(BoostUpdatedEvent {
+        staker: staker_account.key(),
+        ve_lock: ve_lock.key(),
+        boost_bps: BASE_BOOST_BPS,
+        working_balance: staker_account.working_balance,
+    });
+
+    Ok(())
+}
+
+/// Hand a stake, and the ve lock boosting it, to a new wallet when the owner rotates keys.
+///
+/// The staker account keeps its address, so its lockup, multiplier, boost
+/// and reward debt carry over untouched. The ve lock is keyed by owner, so it
+/// is copied into the new owner's lock PDA and the old one is closed.
+pub fn transfer_stake_ownership(ctx: Context<TransferStakeOwnership>, new_owner: Pubkey) -> Result<()> {
+    let previous_owner = ctx.accounts.owner.key();
+    require!(new_owner != Pubkey::default() && new_owner != previous_owner, ErrorCode::InvalidAccountOwner);
+    let staker_key = ctx.accounts.staker_account.key();
+
+    let moved_lock = match (ctx.accounts.ve_lock.as_ref(), ctx.accounts.new_ve_lock.as_mut(), ctx.bumps.new_ve_lock) {
+        (Some(ve_lock), Some(new_ve_lock), Some(bump)) => {
+            // A lock boosting another of the owner's stakes would leave that stake pointing at a closed lock
+            require!(
+                ve_lock.boosted_staker == Pubkey::default() || ve_lock.boosted_staker == staker_key,
+                ErrorCode::BoostStillApplied
+            );
+            new_ve_lock.owner = new_owner;
+            new_ve_lock.amount = ve_lock.amount;
+            new_ve_lock.unlock_time = ve_lock.unlock_time;
+            new_ve_lock.ve_balance = ve_lock.ve_balance;
+            new_ve_lock.boosted_staker = ve_lock.boosted_staker;
+            new_ve_lock.bump = bump;
+            Some(new_ve_lock.key())
+        }
+        (None, None, _) => None,
+        _ => return err!(ErrorCode::InvalidAccountData),
+    };
+
+    let staker_account = &mut ctx.accounts.staker_account;
+    if staker_account.ve_lock != Pubkey::default() {
+        staker_account.ve_lock = moved_lock.ok_or(ErrorCode::InvalidAccountData)?;
+    }
+    staker_account.owner = new_owner;
+
+    emit!(StakeOwnershipTransferredEvent {
+        staker: staker_key,
+        previous_owner,
+        new_owner,
+        ve_lock: moved_lock.unwrap_or_default(),
+        staked_balance: staker_account.staked_balance,
+    });
+
+    Ok(())
+}
+
+/// Curve-style boost: `min(stake, 0.4 * stake + 0.6 * total_stake * ve / total_ve)`,
+/// returned as working balance per unit of stake (bps).
+pub fn boosted_bps(stake: u64, total_stake: u64, ve_balance: u64, total_ve: u64) -> u64 {
+    if stake == 0 || total_ve == 0 {
+        return BASE_BOOST_BPS;
+    }
+    let base = stake as u128 * BASE_BOOST_BPS as u128;
+    let bonus = total_stake as u128 * ve_balance as u128 / total_ve as u128 * (MAX_BOOST_BPS - BASE_BOOST_BPS) as u128;
+    let working = (base + bonus).min(stake as u128 * MAX_BOOST_BPS as u128);
+    (working / stake as u128) as u64
+}
+
+/// Boost of a staker, defaulting to the base rate before its first checkpoint.
+pub fn staker_boost_bps(staker_account: &StakerAccount) -> u64 {
+    if staker_account.boost_bps == 0 { BASE_BOOST_BPS } else { staker_account.boost_bps }
+}
+
+/// Re-derive a staker's boost from its ve lock's live balance and apply it.
+///
+/// Rewards must already be settled. A boosted staker must pass its lock and
+/// the ve config. A lock that has lapsed is kicked on the spot, retiring its
+/// ve and dropping the staker to the base boost, so nobody has to call
+/// `kick_boost` for the boost to end.
+pub fn refresh_boost(
+    reward_pool: &mut RewardPool,
+    staker_account: &mut StakerAccount,
+    ve_config: Option<&mut VeConfig>,
+    ve_lock: Option<&mut VeLock>,
+    now: i64,
+) -> Result<()> {
+    if staker_account.ve_lock == Pubkey::default() {
+        return set_working_balance(reward_pool, staker_account, BASE_BOOST_BPS);
+    }
+    let (ve_config, ve_lock) = match (ve_config, ve_lock) {
+        (Some(ve_config), Some(ve_lock)) => (ve_config, ve_lock),
+        _ => return err!(ErrorCode::InvalidAccountData),
+    };
+
+    let boost_bps = if now >= ve_lock.unlock_time {
+        ve_config.total_ve = ve_config.total_ve.saturating_sub(ve_lock.ve_balance);
+        ve_lock.ve_balance = 0;
+        ve_lock.boosted_staker = Pubkey::default();
+        staker_account.ve_lock = Pubkey::default();
+        BASE_BOOST_BPS
+    } else {
+        boosted_bps(staker_account.staked_balance, reward_pool.total_staked, ve_lock.ve_balance, ve_config.total_ve)
+    };
+    set_working_balance(reward_pool, staker_account, boost_bps)
+}
+
+/// Settle a staker at its current working balance, then apply a new boost.
+fn checkpoint_boost(reward_pool: &mut RewardPool, staker_account: &mut StakerAccount, boost_bps: u64) -> Result<()> {
+    update_reward_pool(reward_pool, Clock::get()?.unix_timestamp as u64)?;
+    settle_staker_rewards(staker_account, reward_pool)?;
+    set_working_balance(reward_pool, staker_account, boost_bps)
+}
+
+/// Recompute a staker's working balance from its stake and keep the pool total in sync.
+///
+/// Rewards must already be settled at the old working balance.
+pub fn set_working_balance(reward_pool: &mut RewardPool, staker_account: &mut StakerAccount, boost_bps: u64) -> Result<()> {
+    let working_balance = u64::try_from(staker_account.staked_balance as u128 * boost_bps as u128 / MAX_BOOST_BPS as u128)
+        .map_err(|_| ErrorCode::Overflow)?;
+    reward_pool.total_working = reward_pool.total_working
+        .saturating_sub(staker_account.working_balance)
+        .checked_add(working_balance)
+        .ok_or(ErrorCode::Overflow)?;
+    staker_account.working_balance = working_balance;
+    staker_account.boost_bps = boost_bps;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Margin Mode Instructions
+// -------------------------------------
+
+/// Create the owner's margin account, starting in isolated mode.
+pub fn initialize_margin_account(ctx: Context<InitializeMarginAccount>) -> Result<()> {
+    let margin_account = &mut ctx.accounts.margin_account;
+    margin_account.owner = ctx.accounts.owner.key();
+    margin_account.mode = MarginMode::Isolated;
+    margin_account.positions = Vec::new();
+    margin_account.bump = ctx.bumps.margin_account;
+
+    Ok(())
+}
+
+/// Switch the owner's margin mode.
+///
+/// Leaving cross mode requires every position to be removed first, since
+/// each one must be shown healthy on its own before it stands alone again.
+pub fn set_margin_mode(ctx: Context<SetMarginMode>, mode: MarginMode) -> Result<()> {
+    let margin_account = &mut ctx.accounts.margin_account;
+    if mode == MarginMode::Isolated {
+        require!(margin_account.positions.is_empty(), ErrorCode::PositionInCrossMargin);
+    }
+    margin_account.mode = mode;
+
+    emit!(MarginModeChangedEvent {
+        owner: margin_account.owner,
+        mode,
+    });
+
+    Ok(())
+}
+
+/// Pool one of the owner's positions under cross margin.
+pub fn add_cross_position(ctx: Context<UpdateCrossPosition>) -> Result<()> {
+    let margin_account = &mut ctx.accounts.margin_account;
+    let user_account = &mut ctx.accounts.user_account;
+    require!(margin_account.mode == MarginMode::Cross, ErrorCode::InvalidMarginAccount);
+    require!(user_account.margin_account == Pubkey::default(), ErrorCode::PositionInCrossMargin);
+    require!(margin_account.positions.len() < MAX_CROSS_POSITIONS, ErrorCode::TooManyCrossPositions);
+
+    margin_account.positions.push(user_account.key());
+    user_account.margin_account = margin_account.key();
+
+    emit!(CrossPositionUpdatedEvent {
+        owner: margin_account.owner,
+        user: user_account.key(),
+        pooled: true,
+    });
+
+    Ok(())
+}
+
+/// Take a position out of cross margin. It must be healthy on its own.
+pub fn remove_cross_position(ctx: Context<UpdateCrossPosition>) -> Result<()> {
+    let margin_account = &mut ctx.accounts.margin_account;
+    let user_account = &mut ctx.accounts.user_account;
+    require_keys_eq!(user_account.margin_account, margin_account.key(), ErrorCode::InvalidMarginAccount);
+
+    if user_account.stablecoin_balance > 0 {
+        let collateral_type = ctx.accounts.collateral_type.as_deref();
+        let collateral_value = position_collateral_value(user_account, collateral_type, ctx.accounts.price_cache.as_deref())?;
+        let ratio = collateral_value * 100 / user_account.stablecoin_balance as u128;
+        require!(
+            ratio >= liquidation_ratio_floor(user_account, collateral_type) as u128,
+            ErrorCode::InsufficientCollateral
+        );
+    }
+
+    let user_key = user_account.key();
+    margin_account.positions.retain(|position| *position != user_key);
+    user_account.margin_account = Pubkey::default();
+
+    emit!(CrossPositionUpdatedEvent {
+        owner: margin_account.owner,
+        user: user_key,
+        pooled: false,
+    });
+
+    Ok(())
+}
+
+/// Portfolio-level health of a cross-margin account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PortfolioHealth {
+    pub mode: MarginMode,               // Margin mode of the account
+    pub collateral_value: u128,         // Value of the collateral across all pooled positions
+    pub liquidation_value: u128,        // Collateral value discounted by each position's liquidation threshold
+    pub debt: u64,                      // Stablecoin debt across all pooled positions
+    pub health_bps: u64,                // Health factor in bps, where 10_000 is the liquidation threshold
+    pub liquidatable: bool,             // Whether pooled positions can be liquidated
+}
+
+/// Margin mode a position is checked under.
+pub fn margin_mode_of(user_account: &UserAccount, margin_account: Option<&MarginAccount>) -> Result<MarginMode> {
+    if user_account.margin_account == Pubkey::default() {
+        return Ok(MarginMode::Isolated);
+    }
+    let margin_account = margin_account.ok_or(ErrorCode::InvalidMarginAccount)?;
+    Ok(margin_account.mode)
+}
+
+/// Fail unless a position at `current_ratio` can be liquidated under its margin mode.
+///
+/// Isolated positions are checked against their own liquidation threshold;
+/// cross-margin positions only once the whole portfolio is below it.
+pub fn require_liquidatable(
+    user_account: &UserAccount,
+    collateral_type: Option<&CollateralType>,
+    margin_account: Option<&MarginAccount>,
+    remaining_accounts: &[AccountInfo],
+    current_ratio: u64,
+) -> Result<()> {
+    match margin_mode_of(user_account, margin_account)? {
+        MarginMode::Isolated => require!(
+            current_ratio < liquidation_ratio_floor(user_account, collateral_type),
+            ErrorCode::NotEligibleForLiquidation
+        ),
+        MarginMode::Cross => {
+            let margin_account = margin_account.ok_or(ErrorCode::InvalidMarginAccount)?;
+            let portfolio = portfolio_health(margin_account, remaining_accounts)?;
+            require!(portfolio.liquidatable, ErrorCode::NotEligibleForLiquidation);
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the health of every position pooled in a cross-margin account.
+///
+/// `remaining_accounts` must hold a `[user_account, collateral_type, price_cache]`
+/// triple for each pooled position, in the order they are stored. Legacy
+/// positions have no collateral type, so any account may fill those slots.
+pub fn portfolio_health(margin_account: &MarginAccount, remaining_accounts: &[AccountInfo]) -> Result<PortfolioHealth> {
+    require!(remaining_accounts.len() == margin_account.positions.len() * 3, ErrorCode::InvalidMarginAccount);
+
+    let mut collateral_value: u128 = 0;
+    let mut liquidation_value: u128 = 0;
+    let mut debt: u64 = 0;
+    for (position, accounts) in margin_account.positions.iter().zip(remaining_accounts.chunks(3)) {
+        require_keys_eq!(accounts[0].key(), *position, ErrorCode::InvalidMarginAccount);
+        let user_account = load_program_account::<UserAccount>(&accounts[0])?;
+
+        let (collateral_type, price_cache) = if user_account.collateral_type == Pubkey::default() {
+            (None, None)
+        } else {
+            require_keys_eq!(accounts[1].key(), user_account.collateral_type, ErrorCode::InvalidCollateralType);
+            let collateral_type = load_program_account::<CollateralType>(&accounts[1])?;
+            let price_cache = load_program_account::<PriceCache>(&accounts[2])?;
+            require_keys_eq!(price_cache.collateral_type, user_account.collateral_type, ErrorCode::InvalidOracleAccount);
+            (Some(collateral_type), Some(price_cache))
+        };
+
+        let value = position_collateral_value(&user_account, collateral_type.as_ref(), price_cache.as_ref())?;
+        let floor = liquidation_ratio_floor(&user_account, collateral_type.as_ref()).max(1);
+        collateral_value = collateral_value.checked_add(value).ok_or(ErrorCode::Overflow)?;
+        liquidation_value = liquidation_value.checked_add(value * 100 / floor as u128).ok_or(ErrorCode::Overflow)?;
+        debt = debt.checked_add(user_account.stablecoin_balance).ok_or(ErrorCode::Overflow)?;
+    }
+
+    let health_bps = if debt == 0 {
+        u64::MAX
+    } else {
+        u64::try_from(liquidation_value * 10_000 / debt as u128).unwrap_or(u64::MAX)
+    };
+
+    Ok(PortfolioHealth {
+        mode: margin_account.mode,
+        collateral_value,
+        liquidation_value,
+        debt,
+        health_bps,
+        liquidatable: margin_account.mode == MarginMode::Cross && health_bps < 10_000,
+    })
+}
+
+/// Preview the portfolio health of a margin account.
+pub fn preview_portfolio_health(ctx: Context<PreviewPortfolioHealth>) -> Result<PortfolioHealth> {
+    portfolio_health(&ctx.accounts.margin_account, ctx.remaining_accounts)
+}
+
+/// Create the cached portfolio valuation of a margin account.
+///
+/// The cache starts stale; mints against it fail until the first refresh.
+pub fn initialize_portfolio_health(ctx: Context<InitializePortfolioHealth>) -> Result<()> {
+    let portfolio_health = &mut ctx.accounts.portfolio_health;
+    portfolio_health.margin_account = ctx.accounts.margin_account.key();
+    portfolio_health.collateral_value = 0;
+    portfolio_health.liquidation_value = 0;
+    portfolio_health.debt = 0;
+    portfolio_health.health_bps = 0;
+    portfolio_health.positions_hash = [0; 32];
+    portfolio_health.refreshed_at = 0;
+    portfolio_health.bump = ctx.bumps.portfolio_health;
+
+    Ok(())
+}
+
+/// Revalue every position pooled by `user`'s margin account and cache the result.
+///
+/// Takes the same `[user_account, collateral_type, price_cache]` triples as
+/// `portfolio_health`, whose prices must be fresh. Permissionless, so keepers
+/// can keep active portfolios warm.
+pub fn refresh_portfolio(ctx: Context<RefreshPortfolio>, _user: Pubkey) -> Result<PortfolioHealth> {
+    let margin_account = &ctx.accounts.margin_account;
+    let health = portfolio_health(margin_account, ctx.remaining_accounts)?;
+
+    let portfolio_health = &mut ctx.accounts.portfolio_health;
+    portfolio_health.collateral_value = health.collateral_value;
+    portfolio_health.liquidation_value = health.liquidation_value;
+    portfolio_health.debt = health.debt;
+    portfolio_health.health_bps = health.health_bps;
+    portfolio_health.positions_hash = margin_positions_hash(margin_account);
+    portfolio_health.refreshed_at = Clock::get()?.unix_timestamp;
+
+    emit!(PortfolioRefreshedEvent {
+        margin_account: margin_account.key(),
+        collateral_value: health.collateral_value,
+        debt: health.debt,
+        health_bps: health.health_bps,
+    });
+
+    Ok(health)
+}
+
+/// Hash of a margin account's pooled position list, so a cached valuation is
+/// dropped once positions join or leave the portfolio.
+fn margin_positions_hash(margin_account: &MarginAccount) -> [u8; 32] {
+    let keys: Vec<u8> = margin_account.positions.iter().flat_map(|position| position.to_bytes()).collect();
+    hash(&keys).to_bytes()
+}
+
+/// Charge `amount` of new debt against a cached portfolio valuation.
+///
+/// The valuation must be fresh and taken over the current position list, and
+/// the portfolio must stay above `min_health_bps` with the new debt. The debt
+/// is added to the cache, so back-to-back mints cannot reuse the same headroom.
+fn charge_cached_portfolio(
+    portfolio_health: &mut PortfolioHealthCache,
+    margin_account: &MarginAccount,
+    amount: u64,
+    min_health_bps: u64,
+) -> Result<()> {
+    require!(margin_account.mode == MarginMode::Cross, ErrorCode::InvalidMarginAccount);
+    let age = Clock::get()?.unix_timestamp.saturating_sub(portfolio_health.refreshed_at);
+    require!(
+        portfolio_health.refreshed_at > 0
+            && age <= PORTFOLIO_HEALTH_MAX_AGE_SECS
+            && portfolio_health.positions_hash == margin_positions_hash(margin_account),
+        ErrorCode::PortfolioHealthStale
+    );
+
+    let debt = portfolio_health.debt.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let health_bps = u64::try_from(portfolio_health.liquidation_value * 10_000 / debt as u128).unwrap_or(u64::MAX);
+    require!(health_bps >= min_health_bps, ErrorCode::InsufficientCollateral);
+    portfolio_health.debt = debt;
+    portfolio_health.health_bps = health_bps;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Pause State Instructions
+// -------------------------------------
+
+/// Create the pause state with every action enabled.
+pub fn initialize_pause_state(ctx: Context<InitializePauseState>) -> Result<()> {
+    let pause_state = &mut ctx.accounts.pause_state;
+    pause_state.minting_paused = false;
+    pause_state.redemptions_paused = false;
+    pause_state.liquidations_paused = false;
+    pause_state.governance_paused = false;
+    pause_state.emergency_shutdown = false;
+    pause_state.reason = PauseReason::Unspecified;
+    pause_state.updated_by = ctx.accounts.governance_authority.key();
+    pause_state.updated_at = Clock::get()?.unix_timestamp;
+    pause_state.bump = ctx.bumps.pause_state;
+
+    Ok(())
+}
+
+/// Set the pause flags, recording why and by whom.
+pub fn set_pause_state(
+    ctx: Context<SetPauseState>,
+    minting_paused: bool,
+    redemptions_paused: bool,
+    liquidations_paused: bool,
+    governance_paused: bool,
+    emergency_shutdown: bool,
+    reason: PauseReason,
+) -> Result<()> {
+    let actor = ctx.accounts.governance_authority.key();
+    let pause_state = &mut ctx.accounts.pause_state;
+    pause_state.minting_paused = minting_paused;
+    pause_state.redemptions_paused = redemptions_paused;
+    pause_state.liquidations_paused = liquidations_paused;
+    pause_state.governance_paused = governance_paused;
+    pause_state.emergency_shutdown = emergency_shutdown;
+    pause_state.reason = reason;
+    pause_state.updated_by = actor;
+    pause_state.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(PauseStateChangedEvent {
+        minting_paused,
+        redemptions_paused,
+        liquidations_paused,
+        governance_paused,
+        emergency_shutdown,
+        reason,
+        actor,
+    });
+
+    Ok(())
+}
+
+/// Pause or resume new debt against a single collateral type.
+///
+/// Governance can do both; any single guardian can pause, but only
+/// governance can resume. Tripping the pause opens a proposal to re-enable
+/// the collateral, passed as the follow-up proposal.
+pub fn set_collateral_minting_paused(ctx: Context<SetCollateralMintingPaused>, minting_paused: bool) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    if authority != ctx.accounts.system_state.governance_authority {
+        let guardian_set = ctx.accounts.guardian_set.as_ref().ok_or(ErrorCode::RestrictedToGovernance)?;
+        guardian_index(guardian_set, &authority)?;
+        require!(minting_paused, ErrorCode::RestrictedToGovernance);
+    }
+
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    let trips = minting_paused && !collateral_type.minting_paused;
+    collateral_type.minting_paused = minting_paused;
+
+    match ctx.accounts.follow_up_proposal.as_mut() {
+        Some(proposal) => {
+            require!(trips, ErrorCode::IncidentProposalMismatch);
+            let description = format!("Re-enable minting against collateral {}", collateral_type.collateral_mint);
+            open_incident_proposal(
+                proposal,
+                &ctx.accounts.governance,
+                &ctx.accounts.governance_config,
+                &mut ctx.accounts.system_state,
+                authority,
+                collateral_type.key(),
+                collateral_type.key(),
+                description,
+            )?;
+        }
+        None => require!(!trips, ErrorCode::IncidentProposalMismatch),
+    }
+
+    emit!(CollateralMintingPausedEvent {
+        collateral_type: collateral_type.key(),
+        minting_paused,
+        authority,
+    });
+
+    Ok(())
+}
+
+/// Fail if new debt against `collateral_type` is paused.
+///
+/// Every path that mints against a collateral type goes through this check.
+pub fn check_collateral_minting(collateral_type: &CollateralType) -> Result<()> {
+    require!(!collateral_type.minting_paused, ErrorCode::CollateralMintingPaused);
+    Ok(())
+}
+
+/// Fail if `action` is halted by the pause state.
+pub fn check_not_paused(pause_state: &PauseState, action: PausableAction) -> Result<()> {
+    require!(!pause_state.emergency_shutdown, ErrorCode::ProtocolPaused);
+    match action {
+        PausableAction::Mint => require!(!pause_state.minting_paused, ErrorCode::MintingPaused),
+        PausableAction::Redeem => require!(!pause_state.redemptions_paused, ErrorCode::RedemptionsPaused),
+        PausableAction::Liquidate => require!(!pause_state.liquidations_paused, ErrorCode::LiquidationPaused),
+        PausableAction::Governance => require!(!pause_state.governance_paused, ErrorCode::GovernancePaused),
+    }
+
+    Ok(())
+}
+
+/// Fail once `deadline_ts` has passed, so a transaction that lands late reverts instead of executing.
+pub fn check_deadline(deadline_ts: Option<i64>) -> Result<()> {
+    if let Some(deadline_ts) = deadline_ts {
+        require!(Clock::get()?.unix_timestamp <= deadline_ts, ErrorCode::DeadlineExceeded);
+    }
+
+    Ok(())
+}
+
+/// Fail if `action` is halted. Meant to be invoked over CPI by programs
+/// built on the stablecoin before they rely on minting or redemption.
+pub fn require_not_paused(ctx: Context<RequireNotPaused>, action: PausableAction) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, action)
+}
+
+// -------------------------------------
+// Collateral Auction Instructions
+// -------------------------------------
+
+/// Create a collateral type's auction parameters with the defaults.
+pub fn initialize_auction_params(ctx: Context<InitializeAuctionParams>) -> Result<()> {
+    let auction_params = &mut ctx.accounts.auction_params;
+    auction_params.collateral_type = ctx.accounts.collateral_type.key();
+    auction_params.start_premium_bps = DEFAULT_AUCTION_START_PREMIUM_BPS;
+    auction_params.floor_bps = DEFAULT_AUCTION_FLOOR_BPS;
+    auction_params.duration_secs = DEFAULT_AUCTION_DURATION_SECS;
+    auction_params.buy_now_premium_bps = DEFAULT_BUY_NOW_PREMIUM_BPS;
+    auction_params.bump = ctx.bumps.auction_params;
+    auction_params.sealed_bid = false;
+    auction_params.commit_secs = DEFAULT_BID_COMMIT_SECS;
+    auction_params.reveal_secs = DEFAULT_BID_REVEAL_SECS;
+    auction_params.max_lot_size = 0;
+    auction_params.settlement_reserves = Pubkey::default();
+    auction_params.bid_bond_lamports = DEFAULT_BID_BOND_LAMPORTS;
+
+    Ok(())
+}
+
+/// Set a collateral type's Dutch auction curve and buy-now premium.
+pub fn set_auction_params(ctx: Context<SetAuctionParams>, start_premium_bps: u64, floor_bps: u64, duration_secs: u64, buy_now_premium_bps: u64) -> Result<()> {
+    let auction_params = &mut ctx.accounts.auction_params;
+    auction_params.start_premium_bps = start_premium_bps;
+    auction_params.floor_bps = floor_bps;
+    auction_params.duration_secs = duration_secs;
+    auction_params.buy_now_premium_bps = buy_now_premium_bps;
+
+    emit!(AuctionParamsUpdatedEvent {
+        collateral_type: auction_params.collateral_type,
+        start_premium_bps,
+        floor_bps,
+        duration_secs,
+        buy_now_premium_bps,
+    });
+
+    Ok(())
+}
+
+/// Cap the collateral a single auction lot of a collateral type may sell. Zero removes the cap.
+///
+/// Liquidations already split into a series keep splitting at the new cap.
+pub fn set_max_lot_size(ctx: Context<SetAuctionParams>, max_lot_size: u64) -> Result<()> {
+    let auction_params = &mut ctx.accounts.auction_params;
+    auction_params.max_lot_size = max_lot_size;
+
+    emit!(MaxLotSizeUpdatedEvent {
+        collateral_type: auction_params.collateral_type,
+        max_lot_size,
+    });
+
+    Ok(())
+}
+
+/// Settle a collateral type's new auctions in a treasury reserve asset, or in the stablecoin if none is given.
+///
+/// Reserve asset payments are taken 1:1 with the stablecoin, so the asset
+/// must share the stablecoin's decimals. Auctions already running keep the
+/// currency they started with.
+pub fn set_auction_settlement_mint(ctx: Context<SetAuctionSettlementMint>) -> Result<()> {
+    let (settlement_reserves, settlement_mint) = match &ctx.accounts.treasury_reserves {
+        Some(treasury_reserves) => {
+            let reserve_mint = ctx.accounts.reserve_mint.as_ref().ok_or(ErrorCode::InvalidMint)?;
+            require_keys_eq!(reserve_mint.key(), treasury_reserves.reserve_mint, ErrorCode::InvalidMint);
+            require!(reserve_mint.decimals == ctx.accounts.stablecoin_mint.decimals, ErrorCode::InvalidSettlementMint);
+            (treasury_reserves.key(), reserve_mint.key())
+        }
+        None => (Pubkey::default(), ctx.accounts.stablecoin_mint.key()),
+    };
+
+    let auction_params = &mut ctx.accounts.auction_params;
+    auction_params.settlement_reserves = settlement_reserves;
+
+    emit!(AuctionSettlementMintUpdatedEvent {
+        collateral_type: auction_params.collateral_type,
+        settlement_mint,
+        settlement_reserves,
+    });
+
+    Ok(())
+}
+
+/// Switch a collateral type's new auctions between open takes and commit-reveal sealed bids.
+///
+/// Auctions already running keep the mode they started with; bids already
+/// committed keep the bond they escrowed.
+pub fn set_sealed_bid_mode(ctx: Context<SetAuctionParams>, sealed_bid: bool, commit_secs: u64, reveal_secs: u64, bid_bond_lamports: u64) -> Result<()> {
+    let auction_params = &mut ctx.accounts.auction_params;
+    auction_params.sealed_bid = sealed_bid;
+    auction_params.commit_secs = commit_secs;
+    auction_params.reveal_secs = reveal_secs;
+    auction_params.bid_bond_lamports = bid_bond_lamports;
+
+    emit!(SealedBidModeUpdatedEvent {
+        collateral_type: auction_params.collateral_type,
+        sealed_bid,
+        commit_secs,
+        reveal_secs,
+        bid_bond_lamports,
+    });
+
+    Ok(())
+}
+
+/// Seize a liquidatable position's collateral and debt into a Dutch auction.
+pub fn start_collateral_auction(ctx: Context<StartCollateralAuction>) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Liquidate)?;
+
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_type = &ctx.accounts.collateral_type;
+    let debt = user_account.stablecoin_balance;
+    require!(debt > 0 && user_account.collateral_balance > 0, ErrorCode::InvalidAmount);
+
+    let collateral_value = position_collateral_value(user_account, Some(collateral_type), Some(&ctx.accounts.price_cache))?;
+    let current_ratio = u64::try_from(collateral_value * 100 / debt as u128).map_err(|_| ErrorCode::Overflow)?;
+    require_liquidatable(
+        user_account,
+        Some(collateral_type),
+        ctx.accounts.margin_account.as_deref(),
+        ctx.remaining_accounts,
+        current_ratio,
+    )?;
+
+    // Sell at most one capped lot now; the rest waits in a series for sequential lots
+    let collateral_amount = user_account.collateral_balance;
+    let (lot_collateral, lot_debt) = next_auction_lot(&ctx.accounts.auction_params, collateral_amount, debt)?;
+    if lot_collateral < collateral_amount {
+        let auction_series = ctx.accounts.auction_series.as_mut().ok_or(ErrorCode::AuctionSeriesRequired)?;
+        auction_series.position = user_account.key();
+        auction_series.collateral_type = collateral_type.key();
+        auction_series.keeper = ctx.accounts.keeper.key();
+        auction_series.remaining_collateral = collateral_amount - lot_collateral;
+        auction_series.remaining_debt = debt - lot_debt;
+        auction_series.lots_started = 1;
+        auction_series.bump = ctx.bumps.auction_series.ok_or(ErrorCode::AuctionSeriesRequired)?;
+
+        emit!(AuctionSeriesStartedEvent {
+            position: auction_series.position,
+            collateral_type: auction_series.collateral_type,
+            collateral_amount,
+            debt,
+            max_lot_size: ctx.accounts.auction_params.max_lot_size,
+        });
+    }
+
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, collateral_type)?;
+    let auction = &mut ctx.accounts.auction;
+    auction.position = user_account.key();
+    auction.collateral_type = collateral_type.key();
+    auction.keeper = ctx.accounts.keeper.key();
+    auction.bump = ctx.bumps.auction;
+    open_auction_lot(auction, &ctx.accounts.auction_params, oracle_price.price, lot_collateral, lot_debt)?;
+
+    // The position is closed out; its debt is now carried by the auction and any series
+    user_account.collateral_balance = 0;
+    user_account.stablecoin_balance = 0;
+    update_health_band(user_account, &ctx.accounts.system_state, Some(collateral_type), Some(&ctx.accounts.price_cache))?;
+
+    record_ledger_entry(
+        ctx.accounts.position_ledger.as_deref_mut(),
+        LedgerEntryKind::Liquidation,
+        collateral_amount,
+        debt,
+        oracle_price.price,
+    )?;
+
+    emit!(CollateralAuctionStartedEvent {
+        auction: auction.key(),
+        position: auction.position,
+        collateral_type: auction.collateral_type,
+        collateral_amount: auction.collateral_amount,
+        debt: auction.debt,
+        start_price: auction.start_price,
+        floor_price: auction.floor_price,
+    });
+
+    Ok(())
+}
+
+/// Start the next lot of an auction series once the previous lot has settled. Callable by anyone.
+///
+/// The lot's auction account shares its address with the previous lot's, so
+/// a new lot cannot start while one is still open. The series is closed to
+/// its keeper when the last lot starts.
+pub fn start_next_auction_lot(ctx: Context<StartNextAuctionLot>) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Liquidate)?;
+
+    let auction_series = &mut ctx.accounts.auction_series;
+    let (lot_collateral, lot_debt) = next_auction_lot(
+        &ctx.accounts.auction_params,
+        auction_series.remaining_collateral,
+        auction_series.remaining_debt,
+    )?;
+    require!(lot_collateral > 0, ErrorCode::InvalidAmount);
+    auction_series.remaining_collateral -= lot_collateral;
+    auction_series.remaining_debt -= lot_debt;
+    auction_series.lots_started += 1;
+
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, &ctx.accounts.collateral_type)?;
+    let auction = &mut ctx.accounts.auction;
+    auction.position = auction_series.position;
+    auction.collateral_type = auction_series.collateral_type;
+    auction.keeper = ctx.accounts.keeper.key();
+    auction.bump = ctx.bumps.auction;
+    open_auction_lot(auction, &ctx.accounts.auction_params, oracle_price.price, lot_collateral, lot_debt)?;
+
+    emit!(CollateralAuctionStartedEvent {
+        auction: auction.key(),
+        position: auction.position,
+        collateral_type: auction.collateral_type,
+        collateral_amount: auction.collateral_amount,
+        debt: auction.debt,
+        start_price: auction.start_price,
+        floor_price: auction.floor_price,
+    });
+
+    if auction_series.remaining_collateral == 0 {
+        emit!(AuctionSeriesCompletedEvent {
+            position: auction_series.position,
+            lots: auction_series.lots_started,
+        });
+        ctx.accounts.auction_series.close(ctx.accounts.series_keeper.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+/// Collateral and debt of the next lot of a liquidation selling `collateral_amount` against `debt`.
+///
+/// Lots are capped at the collateral's `max_lot_size` and carry a pro-rata
+/// share of the debt, rounded up so the protocol never under-collects; the
+/// last lot carries whatever debt is left.
+fn next_auction_lot(auction_params: &AuctionParams, collateral_amount: u64, debt: u64) -> Result<(u64, u64)> {
+    if auction_params.max_lot_size == 0 || collateral_amount <= auction_params.max_lot_size {
+        return Ok((collateral_amount, debt));
+    }
+
+    let lot_collateral = auction_params.max_lot_size;
+    let lot_debt = math::mul_div_up(debt as u128, lot_collateral as u128, collateral_amount as u128)?.min(debt);
+    Ok((lot_collateral, lot_debt))
+}
+
+/// Price and schedule an auction lot from the collateral's current auction parameters.
+fn open_auction_lot(auction: &mut CollateralAuction, auction_params: &AuctionParams, oracle_price: u64, collateral_amount: u64, debt: u64) -> Result<()> {
+    auction.collateral_amount = collateral_amount;
+    auction.debt = debt;
+    auction.start_price = apply_bps(oracle_price, 10_000 + auction_params.start_premium_bps)?;
+    auction.floor_price = apply_bps(oracle_price, auction_params.floor_bps)?;
+    auction.start_time = Clock::get()?.unix_timestamp;
+    auction.duration_secs = auction_params.duration_secs;
+    auction.sealed_bid = auction_params.sealed_bid;
+    auction.settlement_reserves = auction_params.settlement_reserves;
+    if auction.sealed_bid {
+        auction.commit_end = auction.start_time + auction_params.commit_secs as i64;
+        auction.reveal_end = auction.commit_end + auction_params.reveal_secs as i64;
+    }
+
+    Ok(())
+}
+
+/// Take an auction's lot at the current Dutch price.
+pub fn take_collateral_auction(ctx: Context<SettleCollateralAuction>, max_price: u64, deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    let now = Clock::get()?.unix_timestamp;
+    require_open_auction(&ctx.accounts.auction, now)?;
+    let price = auction_price(&ctx.accounts.auction, now);
+    require!(price <= max_price, ErrorCode::AuctionPriceAboveLimit);
+
+    settle_collateral_auction(ctx, price, false)
+}
+
+/// Take an auction's lot instantly at the live oracle price plus the buy-now premium.
+///
+/// Only available while the Dutch price is still above the buy-now price, so
+/// the protocol trades a slower, higher fill for certainty early in the auction.
+pub fn buy_now(ctx: Context<SettleCollateralAuction>, max_price: u64, deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    require_open_auction(&ctx.accounts.auction, Clock::get()?.unix_timestamp)?;
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, &ctx.accounts.collateral_type)?;
+    let price = apply_bps(oracle_price.price, 10_000 + ctx.accounts.auction_params.buy_now_premium_bps)?;
+    require!(
+        auction_price(&ctx.accounts.auction, Clock::get()?.unix_timestamp) > price,
+        ErrorCode::BuyNowUnavailable
+    );
+    require!(price <= max_price, ErrorCode::AuctionPriceAboveLimit);
+
+    settle_collateral_auction(ctx, price, true)
+}
+
+/// Commit a hidden bid on a sealed-bid auction.
+///
+/// `commitment` is `hash(price || salt || bidder)`, with `price` little-endian
+/// in PRICE_PRECISION, so the bid cannot be read or front-run until revealed.
+/// The collateral's bid bond is escrowed in the bid account until the bid is
+/// outbid, loses at reveal or settles, and is slashed otherwise.
+pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    let auction = &ctx.accounts.auction;
+    let now = Clock::get()?.unix_timestamp;
+    require!(auction.sealed_bid, ErrorCode::NotSealedBidAuction);
+    require!(now < auction.commit_end, ErrorCode::BidPhaseClosed);
+
+    let bond = ctx.accounts.auction_params.bid_bond_lamports;
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+    sealed_bid.auction = auction.key();
+    sealed_bid.bidder = ctx.accounts.bidder.key();
+    sealed_bid.commitment = commitment;
+    sealed_bid.committed_at = now;
+    sealed_bid.bump = ctx.bumps.sealed_bid;
+    sealed_bid.bond = bond;
+    sealed_bid.reveal_end = auction.reveal_end;
+    sealed_bid.revealed = false;
+
+    let cpi_accounts = system_program::Transfer {
+        from: ctx.accounts.bidder.to_account_info(),
+        to: ctx.accounts.sealed_bid.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    system_program::transfer(cpi_ctx, bond)?;
+
+    emit!(BidCommittedEvent {
+        auction: auction.key(),
+        bidder: ctx.accounts.bidder.key(),
+        bond,
+    });
+
+    Ok(())
+}
+
+/// Reveal a committed bid once commits have closed, recording it if it is the best so far.
+///
+/// A bid is valid if it matches the commitment, is at least the auction's
+/// floor price and the bidder holds enough stablecoin to pay for the lot.
+/// Ties go to the earlier reveal. A bid that is not the best is closed with
+/// its bond refunded; a new best bid refunds the bid it displaces, so only the
+/// best bid keeps its bond escrowed until settlement.
+pub fn reveal_bid(ctx: Context<RevealBid>, price: u64, salt: [u8; 32], deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    let now = Clock::get()?.unix_timestamp;
+    let auction = &mut ctx.accounts.auction;
+    require!(now >= auction.commit_end && now < auction.reveal_end, ErrorCode::BidPhaseClosed);
+    require!(!ctx.accounts.sealed_bid.revealed, ErrorCode::BidPhaseClosed);
+
+    let bidder = ctx.accounts.bidder.key();
+    let commitment = hash(&[&price.to_le_bytes()[..], &salt, bidder.as_ref()].concat()).to_bytes();
+    require!(commitment == ctx.accounts.sealed_bid.commitment, ErrorCode::BidCommitmentMismatch);
+
+    let payment = u64::try_from(auction.collateral_amount as u128 * price as u128 / PRICE_PRECISION as u128)
+        .map_err(|_| ErrorCode::Overflow)?;
+    let valid = price >= auction.floor_price && ctx.accounts.bidder_stablecoin_account.amount >= payment;
+    let best = valid && price > auction.best_bid;
+    if best {
+        if auction.best_bid > 0 {
+            let previous_best_bid = ctx.accounts.previous_best_bid.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+            let previous_best_bidder = ctx.accounts.previous_best_bidder.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+            previous_best_bid.close(previous_best_bidder.to_account_info())?;
+        }
+        auction.best_bid = price;
+        auction.best_bidder = bidder;
+        ctx.accounts.sealed_bid.revealed = true;
+    } else {
+        ctx.accounts.sealed_bid.close(ctx.accounts.bidder.to_account_info())?;
+    }
+
+    emit!(BidRevealedEvent {
+        auction: auction.key(),
+        bidder,
+        price,
+        valid,
+        best,
+    });
+
+    Ok(())
+}
+
+/// Settle a sealed-bid auction to its best bidder at the revealed price once reveals have closed.
+pub fn settle_sealed_bid(ctx: Context<SettleCollateralAuction>, deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    let auction = &ctx.accounts.auction;
+    require!(auction.sealed_bid, ErrorCode::NotSealedBidAuction);
+    require!(Clock::get()?.unix_timestamp >= auction.reveal_end, ErrorCode::BidPhaseClosed);
+    require!(auction.best_bid > 0, ErrorCode::NoWinningBid);
+    require_keys_eq!(auction.best_bidder, ctx.accounts.buyer.key(), ErrorCode::NoWinningBid);
+
+    // Settling releases the winner's bond; once slashed the lot is open to takes
+    let winning_bid = ctx.accounts.winning_bid.as_ref().ok_or(ErrorCode::NoWinningBid)?;
+    winning_bid.close(ctx.accounts.buyer.to_account_info())?;
+
+    let price = auction.best_bid;
+    settle_collateral_auction(ctx, price, false)
+}
+
+/// Slash the bond of a sealed bid that was never revealed, or of a winning bid
+/// that was not settled within its grace period. Callable by anyone.
+///
+/// The bond and the bid account's rent go to governance. Losing and outbid
+/// bids were already refunded when they were revealed or displaced, and a
+/// settled winner's bid is closed by the settlement.
+pub fn slash_sealed_bid(ctx: Context<SlashSealedBid>) -> Result<()> {
+    let sealed_bid = &ctx.accounts.sealed_bid;
+    let slashable_at = if sealed_bid.revealed {
+        sealed_bid.reveal_end + SEALED_BID_SETTLE_GRACE_SECS
+    } else {
+        sealed_bid.reveal_end
+    };
+    require!(Clock::get()?.unix_timestamp >= slashable_at, ErrorCode::BidPhaseClosed);
+
+    emit!(SealedBidSlashedEvent {
+        auction: sealed_bid.auction,
+        bidder: sealed_bid.bidder,
+        bond: sealed_bid.bond,
+        revealed: sealed_bid.revealed,
+    });
+
+    sealed_bid.close(ctx.accounts.governance_authority.to_account_info())
+}
+
+/// Fail while a sealed-bid auction's lot is reserved for its commit-reveal round.
+///
+/// Open takes resume once reveals close with no valid bid, or once the best
+/// bidder has let its settlement grace period lapse.
+fn require_open_auction(auction: &CollateralAuction, now: i64) -> Result<()> {
+    if auction.sealed_bid {
+        let reserved_until = if auction.best_bid > 0 {
+            auction.reveal_end + SEALED_BID_SETTLE_GRACE_SECS
+        } else {
+            auction.reveal_end
+        };
+        require!(now >= reserved_until, ErrorCode::SealedBidAuction);
+    }
+    Ok(())
+}
+
+/// Dutch price per collateral token at `now`, decaying linearly from the start price to the floor.
+pub fn auction_price(auction: &CollateralAuction, now: i64) -> u64 {
+    let elapsed = now.saturating_sub(auction.start_time).max(0) as u64;
+    if elapsed >= auction.duration_secs {
+        return auction.floor_price;
+    }
+    let decay = (auction.start_price - auction.floor_price) as u128 * elapsed as u128 / auction.duration_secs as u128;
+    auction.start_price - decay as u64
+}
+
+/// Scale `value` by `bps` / 10_000.
+fn apply_bps(value: u64, bps: u64) -> Result<u64> {
+    u64::try_from(value as u128 * bps as u128 / 10_000).map_err(|_| ErrorCode::Overflow.into())
+}
+
+/// Take the buyer's payment against the auction's debt and credit the lot to the buyer's position.
+///
+/// Stablecoin payments are burned, with any surplus booked to the surplus
+/// buffer and to `auction_surplus_burns`, since burning it repays no debt. Reserve asset payments go to the treasury reserve vault instead:
+/// nothing is burned, so the debt they cover moves to `reserve_backed_debt`,
+/// and the whole payment, surplus included, stays in `liquid_reserves` only.
+/// A shortfall is covered the same either way.
+fn settle_collateral_auction(ctx: Context<SettleCollateralAuction>, price: u64, buy_now: bool) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let payment = u64::try_from(auction.collateral_amount as u128 * price as u128 / PRICE_PRECISION as u128)
+        .map_err(|_| ErrorCode::Overflow)?;
+
+    let settlement_mint = if auction.settlement_reserves == Pubkey::default() {
+        let buyer_stablecoin_account = ctx.accounts.buyer_stablecoin_account.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            from: buyer_stablecoin_account.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::burn(cpi_ctx, payment)?;
+        ctx.accounts.stablecoin_mint.key()
+    } else {
+        let treasury_reserves = ctx.accounts.treasury_reserves.as_mut().ok_or(ErrorCode::InvalidAccountData)?;
+        let reserve_vault = ctx.accounts.reserve_vault.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let buyer_reserve_account = ctx.accounts.buyer_reserve_account.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        require_keys_eq!(reserve_vault.key(), treasury_reserves.reserve_vault, ErrorCode::InvalidAccountData);
+        require_keys_eq!(buyer_reserve_account.mint, treasury_reserves.reserve_mint, ErrorCode::InvalidMint);
+
+        let cpi_accounts = Transfer {
+            from: buyer_reserve_account.to_account_info(),
+            to: reserve_vault.to_account_info(),
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, payment)?;
+
+        treasury_reserves.liquid_reserves = treasury_reserves.liquid_reserves
+            .checked_add(payment)
+            .ok_or(ErrorCode::Overflow)?;
+        treasury_reserves.reserve_mint
+    };
+
+    // Stablecoin proceeds above the debt go to the surplus buffer; a shortfall is covered like a full liquidation
+    let revenue = &mut ctx.accounts.liquidation_revenue;
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    if auction.settlement_reserves != Pubkey::default() {
+        protocol_stats.reserve_backed_debt = protocol_stats.reserve_backed_debt
+            .checked_add(payment.min(auction.debt))
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    let (insurance_covered, bad_debt) = if payment >= auction.debt {
+        if auction.settlement_reserves == Pubkey::default() {
+            let surplus = payment - auction.debt;
+            revenue.surplus_buffer_balance = revenue.surplus_buffer_balance
+                .checked_add(surplus)
+                .ok_or(ErrorCode::Overflow)?;
+            protocol_stats.auction_surplus_burns = protocol_stats.auction_surplus_burns
+                .checked_add(surplus)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        (0, 0)
+    } else {
+        let shortfall = auction.debt - payment;
+        let covered = absorb_shortfall(
+            revenue,
+            &mut ctx.accounts.bad_debt_queue,
+            auction.position,
+            auction.collateral_type,
+            auction.debt,
+            shortfall,
+            ShortfallSource::CollateralAuction,
+        )?;
+        protocol_stats.written_off_debt = protocol_stats.written_off_debt.checked_add(shortfall).ok_or(ErrorCode::Overflow)?;
+        covered
+    };
+    release_collateral_debt(&mut ctx.accounts.collateral_type, protocol_stats, auction.debt);
+    emit_solvency_ratio(protocol_stats, &ctx.accounts.liquidation_revenue, &ctx.accounts.bad_debt_queue);
+
+    let buyer_position = &mut ctx.accounts.buyer_position;
+    buyer_position.collateral_balance = buyer_position.collateral_balance
+        .checked_add(auction.collateral_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    record_ledger_entry(ctx.accounts.buyer_ledger.as_deref_mut(), LedgerEntryKind::Deposit, auction.collateral_amount, 0, price)?;
+
+    emit!(CollateralAuctionSettledEvent {
+        auction: auction.key(),
+        buyer: ctx.accounts.buyer.key(),
+        collateral_amount: auction.collateral_amount,
+        price,
+        payment,
+        settlement_mint,
+        insurance_covered,
+        bad_debt,
+        buy_now,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Treasury Swap Instructions
+// -------------------------------------
+
+/// Instruction every swap adapter exposes to convert an exact input amount.
+pub const SWAP_IX: &str = "global:swap";
+
+/// Escrow treasury stablecoin into a schedule converting it to reserves in time slices.
+pub fn create_swap_schedule(
+    ctx: Context<CreateSwapSchedule>,
+    total_amount: u64,
+    slice_amount: u64,
+    interval_secs: u64,
+    min_output_price: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury_account.to_account_info(),
+        to: ctx.accounts.source_vault.to_account_info(),
+        authority: ctx.accounts.treasury_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, total_amount)?;
+
+    let swap_schedule = &mut ctx.accounts.swap_schedule;
+    swap_schedule.swap_program = ctx.accounts.swap_program.key();
+    swap_schedule.source_vault = ctx.accounts.source_vault.key();
+    swap_schedule.destination_vault = ctx.accounts.destination_vault.key();
+    swap_schedule.total_amount = total_amount;
+    swap_schedule.slice_amount = slice_amount;
+    swap_schedule.interval_secs = interval_secs;
+    swap_schedule.min_output_price = min_output_price;
+    swap_schedule.next_execution_time = Clock::get()?.unix_timestamp;
+    swap_schedule.executed_amount = 0;
+    swap_schedule.received_amount = 0;
+    swap_schedule.cancelled = false;
+    swap_schedule.authority_bump = ctx.bumps.swap_authority;
+
+    emit!(SwapScheduleCreatedEvent {
+        swap_schedule: swap_schedule.key(),
+        destination_vault: swap_schedule.destination_vault,
+        total_amount,
+        slice_amount,
+        interval_secs,
+        min_output_price,
+    });
+
+    Ok(())
+}
+
+/// Execute the next slice of a swap schedule. Callable by any keeper once the interval has passed.
+///
+/// Each slice carries its own limit price, so a bad market only stalls the
+/// schedule instead of filling the whole conversion at a poor price.
+pub fn execute_swap_slice<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteSwapSlice<'info>>, deadline_ts: Option<i64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    let now = Clock::get()?.unix_timestamp;
+    let swap_schedule = &ctx.accounts.swap_schedule;
+    require!(now >= swap_schedule.next_execution_time, ErrorCode::SwapSliceNotDue);
+    let amount = swap_schedule.slice_amount.min(swap_schedule.total_amount - swap_schedule.executed_amount);
+    require!(amount > 0, ErrorCode::SwapScheduleClosed);
+    let min_output = u64::try_from(amount as u128 * swap_schedule.min_output_price as u128 / PRICE_PRECISION as u128)
+        .map_err(|_| ErrorCode::Overflow)?;
+
+    let source_before = ctx.accounts.source_vault.amount;
+    let destination_before = ctx.accounts.destination_vault.amount;
+    acquire_operation_lock(&mut ctx.accounts.system_state)?;
+    invoke_swap_adapter(&ctx.accounts, ctx.remaining_accounts, amount, min_output)?;
+    release_operation_lock(&mut ctx.accounts.system_state);
+
+    // Check the adapter spent exactly the slice and honoured the limit price
+    ctx.accounts.source_vault.reload()?;
+    ctx.accounts.destination_vault.reload()?;
+    let spent = source_before.checked_sub(ctx.accounts.source_vault.amount).ok_or(ErrorCode::Overflow)?;
+    require!(spent == amount, ErrorCode::InvalidAmount);
+    let received = ctx.accounts.destination_vault.amount.checked_sub(destination_before).ok_or(ErrorCode::Overflow)?;
+    require!(received >= min_output, ErrorCode::SwapOutputTooLow);
+
+    let swap_schedule = &mut ctx.accounts.swap_schedule;
+    swap_schedule.executed_amount += amount;
+    swap_schedule.received_amount = swap_schedule.received_amount.checked_add(received).ok_or(ErrorCode::Overflow)?;
+    swap_schedule.next_execution_time = now.checked_add(swap_schedule.interval_secs as i64).ok_or(ErrorCode::Overflow)?;
+
+    emit!(SwapSliceExecutedEvent {
+        swap_schedule: swap_schedule.key(),
+        keeper: ctx.accounts.keeper.key(),
+        amount,
+        received,
+        executed_amount: swap_schedule.executed_amount,
+    });
+
+    Ok(())
+}
+
+/// Cancel a swap schedule, returning the unconverted stablecoin to the treasury.
+pub fn cancel_swap_schedule(ctx: Context<CancelSwapSchedule>) -> Result<()> {
+    let swap_schedule_key = ctx.accounts.swap_schedule.key();
+    let remaining = ctx.accounts.source_vault.amount;
+
+    let bump = [ctx.accounts.swap_schedule.authority_bump];
+    let seeds: &[&[u8]] = &[b"swap_authority", swap_schedule_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.source_vault.to_account_info(),
+        to: ctx.accounts.treasury_account.to_account_info(),
+        authority: ctx.accounts.swap_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, remaining)?;
+
+    ctx.accounts.swap_schedule.cancelled = true;
+
+    emit!(SwapScheduleCancelledEvent {
+        swap_schedule: swap_schedule_key,
+        returned_amount: remaining,
+        executed_amount: ctx.accounts.swap_schedule.executed_amount,
+    });
+
+    Ok(())
+}
+
+/// Call the swap adapter interface, signing as the schedule's swap authority PDA.
+///
+/// Adapters receive `[source_vault, destination_vault, swap_authority, token_program]`
+/// followed by any remaining accounts, with instruction data
+/// `discriminator || amount_in || min_amount_out`.
+fn invoke_swap_adapter<'info>(
+    accounts: &ExecuteSwapSlice<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let mut data = hash(SWAP_IX.as_bytes()).to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new(accounts.source_vault.key(), false),
+        AccountMeta::new(accounts.destination_vault.key(), false),
+        AccountMeta::new_readonly(accounts.swap_authority.key(), true),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    let mut infos = vec![
+        accounts.source_vault.to_account_info(),
+        accounts.destination_vault.to_account_info(),
+        accounts.swap_authority.to_account_info(),
+        accounts.token_program.to_account_info(),
+    ];
+    for account in remaining_accounts {
+        metas.push(if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        });
+        infos.push(account.clone());
+    }
+
+    let ix = Instruction {
+        program_id: accounts.swap_program.key(),
+        accounts: metas,
+        data,
+    };
+    let swap_schedule_key = accounts.swap_schedule.key();
+    let bump = [accounts.swap_schedule.authority_bump];
+    let seeds: &[&[u8]] = &[b"swap_authority", swap_schedule_key.as_ref(), &bump];
+    invoke_signed(&ix, &infos, &[seeds])?;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Bad Debt Queue Instructions
+// -------------------------------------
+
+/// Create the bad debt queue.
+pub fn initialize_bad_debt_queue(ctx: Context<InitializeBadDebtQueue>, debt_auction_price: u64) -> Result<()> {
+    let bad_debt_queue = &mut ctx.accounts.bad_debt_queue;
+    bad_debt_queue.entries = Vec::new();
+    bad_debt_queue.total_outstanding = 0;
+    bad_debt_queue.debt_auction_price = debt_auction_price;
+    bad_debt_queue.bump = ctx.bumps.bad_debt_queue;
+    bad_debt_queue.slashing_pool = Pubkey::default();
+
+    Ok(())
+}
+
+/// Set the most governance tokens debt auctions may sell per stablecoin of bad debt.
+///
+/// Auctions sell at the governance token oracle price; this caps the
+/// dilution if that price is ever read too low.
+pub fn set_debt_auction_price(ctx: Context<SetDebtAuctionPrice>, debt_auction_price: u64) -> Result<()> {
+    ctx.accounts.bad_debt_queue.debt_auction_price = debt_auction_price;
+
+    emit!(DebtAuctionPriceUpdatedEvent {
+        debt_auction_price,
+    });
+
+    Ok(())
+}
+
+/// Set the reward pool whose stakers are slashed to cover bad debt.
+///
+/// Staker slashing cannot run until a pool is configured, so keepers cannot
+/// burn an arbitrary pool's stake or skip the stage with an unrelated one.
+pub fn set_bad_debt_slashing_pool(ctx: Context<SetBadDebtSlashingPool>) -> Result<()> {
+    let slashing_pool = ctx.accounts.reward_pool.key();
+    ctx.accounts.bad_debt_queue.slashing_pool = slashing_pool;
+
+    emit!(BadDebtSlashingPoolUpdatedEvent {
+        slashing_pool,
+    });
+
+    Ok(())
+}
+
+/// Append unbacked debt to the queue. Once the queue is full, new debt is folded into the last entry.
+pub fn enqueue_bad_debt(bad_debt_queue: &mut BadDebtQueue, amount: u64, collateral_type: Pubkey) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    if bad_debt_queue.entries.len() < MAX_BAD_DEBT_ENTRIES {
+        bad_debt_queue.entries.push(BadDebtEntry {
+            amount,
+            collateral_type,
+            created_at: Clock::get()?.unix_timestamp,
+            stakers_slashed: false,
+        });
+    } else {
+        let tail = bad_debt_queue.entries.last_mut().ok_or(ErrorCode::Overflow)?;
+        tail.amount = tail.amount.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    }
+    bad_debt_queue.total_outstanding = bad_debt_queue.total_outstanding.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    emit!(BadDebtQueuedEvent {
+        amount,
+        collateral_type,
+        queue_length: bad_debt_queue.entries.len() as u8,
+        total_outstanding: bad_debt_queue.total_outstanding,
+    });
+
+    Ok(())
+}
+
+/// Absorb the oldest bad debt with the insurance pool. Callable by anyone.
+pub fn absorb_bad_debt_with_insurance(ctx: Context<AbsorbBadDebt>) -> Result<()> {
+    let revenue = &mut ctx.accounts.liquidation_revenue;
+    let head = ctx.accounts.bad_debt_queue.entries.first().ok_or(ErrorCode::BadDebtQueueEmpty)?;
+    let covered = head.amount.min(revenue.insurance_pool_balance);
+    require!(covered > 0, ErrorCode::BadDebtStageUnavailable);
+    draw_insurance(revenue, covered, head.collateral_type, ShortfallSource::BadDebtQueue);
+
+    settle_bad_debt_head(&mut ctx.accounts.bad_debt_queue, revenue, &ctx.accounts.protocol_stats, covered, BadDebtStage::Insurance)
+}
+
+/// Cover the oldest bad debt by slashing the configured pool's staking vault,
+/// once insurance is exhausted. Callable by anyone.
+///
+/// Only stablecoin-denominated stake can be burned against the debt; for
+/// other staking pools the stage is recorded as run without slashing so the
+/// entry can move on to a debt auction.
+pub fn slash_stakers_for_bad_debt(ctx: Context<SlashStakersForBadDebt>) -> Result<()> {
+    require!(ctx.accounts.liquidation_revenue.insurance_pool_balance == 0, ErrorCode::BadDebtStageUnavailable);
+    let head = ctx.accounts.bad_debt_queue.entries.first_mut().ok_or(ErrorCode::BadDebtQueueEmpty)?;
+    require!(!head.stakers_slashed, ErrorCode::BadDebtStageUnavailable);
+    head.stakers_slashed = true;
+    let outstanding = head.amount;
+
+    let slashed = if ctx.accounts.staking_pool.mint == ctx.accounts.stablecoin_mint.key() {
+        let max_slash = (ctx.accounts.staking_pool.amount as u128 * MAX_BAD_DEBT_SLASH_BPS as u128 / 10_000) as u64;
+        outstanding.min(max_slash)
+    } else {
+        0
+    };
+
+    if slashed > 0 {
+        let reward_pool_key = ctx.accounts.reward_pool.key();
+        let bump = [ctx.accounts.reward_pool.staking_authority_bump];
+        let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            from: ctx.accounts.staking_pool.to_account_info(),
+            authority: ctx.accounts.staking_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::burn(cpi_ctx, slashed)?;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.slashed_stake = reward_pool.slashed_stake.checked_add(slashed).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // The burned stake retires stablecoin issued against the written-off debt
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.written_off_debt = protocol_stats.written_off_debt.saturating_sub(slashed);
+
+    settle_bad_debt_head(
+        &mut ctx.accounts.bad_debt_queue,
+        &mut ctx.accounts.liquidation_revenue,
+        &ctx.accounts.protocol_stats,
+        slashed,
+        BadDebtStage::StakerSlashing,
+    )
+}
+
+/// Cover part of the oldest bad debt by buying newly minted governance tokens with stablecoin,
+/// once insurance and staker slashing are exhausted.
+pub fn take_debt_auction(ctx: Context<TakeDebtAuction>, amount: u64, deadline_ts: Option<i64>, min_out: Option<u64>) -> Result<()> {
+    check_deadline(deadline_ts)?;
+    require!(ctx.accounts.liquidation_revenue.insurance_pool_balance == 0, ErrorCode::BadDebtStageUnavailable);
+    let bad_debt_queue = &ctx.accounts.bad_debt_queue;
+    let head = bad_debt_queue.entries.first().ok_or(ErrorCode::BadDebtQueueEmpty)?;
+    require!(head.stakers_slashed, ErrorCode::BadDebtStageUnavailable);
+    let amount = amount.min(head.amount);
+
+    // Sell at the governance token oracle price, but never more tokens per
+    // stablecoin than governance's debt auction price allows
+    let gov_price = gov_oracle::gov_token_price(
+        &ctx.accounts.gov_token_oracle,
+        ctx.accounts.gov_price_feed.as_deref(),
+        &ctx.accounts.system_state.oracle_programs,
+        Clock::get()?.unix_timestamp,
+    )?;
+    let oracle_amount = math::mul_div_down(amount as u128, PRICE_PRECISION as u128, gov_price.price as u128)?;
+    let max_amount = math::mul_div_down(amount as u128, bad_debt_queue.debt_auction_price as u128, PRICE_PRECISION as u128)?;
+    let gov_amount = oracle_amount.min(max_amount);
+    require!(gov_amount >= min_out.unwrap_or(0), ErrorCode::SlippageExceeded);
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.buyer_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, amount)?;
+
+    // The burned payment retires stablecoin issued against the written-off debt
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.written_off_debt = protocol_stats.written_off_debt.saturating_sub(amount);
+
+    mint_gov_tokens(
+        &mut ctx.accounts.gov_token,
+        ctx.accounts.gov_token_authority.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.buyer_gov_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        gov_amount,
+        GovMintPath::DebtAuction,
+    )?;
+
+    settle_bad_debt_head(
+        &mut ctx.accounts.bad_debt_queue,
+        &mut ctx.accounts.liquidation_revenue,
+        &ctx.accounts.protocol_stats,
+        amount,
+        BadDebtStage::DebtAuction,
+    )
+}
+
+/// Repay queued bad debt oldest-first by burning up to `amount` of treasury-held stablecoin.
+/// Restricted to governance.
+///
+/// Skips the insurance, slashing and debt auction stages entirely, so a flush
+/// treasury can clear the queue without diluting governance token holders.
+/// The burn is capped by the outstanding debt and the treasury's allowance to
+/// the revenue authority.
+pub fn treasury_repay_bad_debt(ctx: Context<TreasuryRepayBadDebt>, amount: u64) -> Result<()> {
+    let outstanding_before = ctx.accounts.bad_debt_queue.total_outstanding;
+    require!(outstanding_before > 0, ErrorCode::BadDebtQueueEmpty);
+    let repaid = amount
+        .min(outstanding_before)
+        .min(treasury_allowance(&ctx.accounts.treasury_account, &ctx.accounts.revenue_authority));
+    require!(repaid > 0, ErrorCode::InsufficientFunds);
+
+    let bump = [ctx.bumps.revenue_authority];
+    let seeds: &[&[u8]] = &[b"revenue_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.treasury_account.to_account_info(),
+        authority: ctx.accounts.revenue_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::burn(cpi_ctx, repaid)?;
+
+    // The burned stablecoin was issued against the written-off debt it now repays
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.written_off_debt = protocol_stats.written_off_debt.saturating_sub(repaid);
+
+    let mut unapplied = repaid;
+    let mut entries_cleared: u8 = 0;
+    while unapplied > 0 {
+        let Some(head) = ctx.accounts.bad_debt_queue.entries.first() else {
+            break;
+        };
+        let covered = unapplied.min(head.amount);
+        if covered == head.amount {
+            entries_cleared += 1;
+        }
+        settle_bad_debt_head(
+            &mut ctx.accounts.bad_debt_queue,
+            &mut ctx.accounts.liquidation_revenue,
+            &ctx.accounts.protocol_stats,
+            covered,
+            BadDebtStage::Treasury,
+        )?;
+        unapplied -= covered;
+    }
+
+    emit!(TreasuryBadDebtRepaidEvent {
+        treasury_account: ctx.accounts.treasury_account.key(),
+        requested: amount,
+        burned: repaid,
+        entries_cleared,
+        outstanding_before,
+        total_outstanding: ctx.accounts.bad_debt_queue.total_outstanding,
+        total_bad_debt: ctx.accounts.liquidation_revenue.bad_debt,
+        executed_by: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+/// Reduce the oldest entry by `covered`, popping it once fully covered.
+fn settle_bad_debt_head(
+    bad_debt_queue: &mut BadDebtQueue,
+    revenue: &mut LiquidationRevenue,
+    protocol_stats: &ProtocolStats,
+    covered: u64,
+    stage: BadDebtStage,
+) -> Result<()> {
+    let head = bad_debt_queue.entries.first_mut().ok_or(ErrorCode::BadDebtQueueEmpty)?;
+    head.amount -= covered;
+    let remaining = head.amount;
+    let collateral_type = head.collateral_type;
+    if remaining == 0 {
+        bad_debt_queue.entries.remove(0);
+    }
+    bad_debt_queue.total_outstanding = bad_debt_queue.total_outstanding.saturating_sub(covered);
+    revenue.bad_debt = revenue.bad_debt.saturating_sub(covered);
+
+    emit!(BadDebtCoveredEvent {
+        stage,
+        collateral_type,
+        covered,
+        remaining,
+        total_outstanding: bad_debt_queue.total_outstanding,
+    });
+    emit_solvency_ratio(protocol_stats, revenue, bad_debt_queue);
+
+    Ok(())
+}
+
+/// Cover a realized shortfall on `debt` from the insurance pool and queue the rest as bad debt.
+///
+/// Returns the amounts covered by insurance and left as bad debt.
+pub fn absorb_shortfall(
+    revenue: &mut LiquidationRevenue,
+    bad_debt_queue: &mut BadDebtQueue,
+    position: Pubkey,
+    collateral_type: Pubkey,
+    debt: u64,
+    shortfall: u64,
+    source: ShortfallSource,
+) -> Result<(u64, u64)> {
+    let insurance_covered = shortfall.min(revenue.insurance_pool_balance);
+    draw_insurance(revenue, insurance_covered, collateral_type, source);
+    let bad_debt = shortfall - insurance_covered;
+    revenue.bad_debt = revenue.bad_debt.checked_add(bad_debt).ok_or(ErrorCode::Overflow)?;
+    enqueue_bad_debt(bad_debt_queue, bad_debt, collateral_type)?;
+
+    if bad_debt > 0 {
+        emit!(BadDebtCreatedEvent {
+            source,
+            position,
+            collateral_type,
+            debt,
+            recovered: debt - shortfall,
+            insurance_covered,
+            bad_debt,
+            total_bad_debt: revenue.bad_debt,
+            total_outstanding: bad_debt_queue.total_outstanding,
+        });
+    }
+
+    Ok((insurance_covered, bad_debt))
+}
+
+/// Take `amount` out of the insurance pool, reporting the drawdown.
+fn draw_insurance(revenue: &mut LiquidationRevenue, amount: u64, collateral_type: Pubkey, source: ShortfallSource) {
+    if amount == 0 {
+        return;
+    }
+    let balance_before = revenue.insurance_pool_balance;
+    revenue.insurance_pool_balance -= amount;
+
+    emit!(InsuranceDrawdownEvent {
+        source,
+        collateral_type,
+        amount,
+        balance_before,
+        balance_after: revenue.insurance_pool_balance,
+    });
+}
+
+/// Report the protocol's backing quality after a solvency state transition.
+///
+/// The solvency ratio is the share of all debt, performing debt plus queued
+/// bad debt, that is backed: performing debt counts as backed, and bad debt
+/// is backed up to the insurance pool and surplus buffer. It is 10_000 bps
+/// while the reserves cover all bad debt and degrades below that.
+pub fn emit_solvency_ratio(protocol_stats: &ProtocolStats, revenue: &LiquidationRevenue, bad_debt_queue: &BadDebtQueue) {
+    let performing_debt = protocol_stats.total_collateral_debt as u128;
+    let bad_debt = bad_debt_queue.total_outstanding as u128;
+    let reserves = revenue.insurance_pool_balance as u128 + revenue.surplus_buffer_balance as u128;
+    let total_debt = performing_debt + bad_debt;
+    let solvency_ratio_bps = if total_debt == 0 {
+        math::BPS_DENOMINATOR
+    } else {
+        ((performing_debt + bad_debt.min(reserves)) * math::BPS_DENOMINATOR as u128 / total_debt) as u64
+    };
+
+    emit!(SolvencyRatioUpdatedEvent {
+        performing_debt: protocol_stats.total_collateral_debt,
+        bad_debt_outstanding: bad_debt_queue.total_outstanding,
+        insurance_pool_balance: revenue.insurance_pool_balance,
+        surplus_buffer_balance: revenue.surplus_buffer_balance,
+        solvency_ratio_bps,
+    });
+}
+
+// -------------------------------------
+// Governance Token Oracle Instructions
+// -------------------------------------
+
+/// Create the governance token oracle over an AMM pool, seeding the TWAP with its spot price.
+pub fn initialize_gov_token_oracle(ctx: Context<InitializeGovTokenOracle>, min_quote_liquidity: u64) -> Result<()> {
+    let pyth_feed = match &ctx.accounts.pyth_feed {
+        Some(pyth_feed) => {
+            require!(oracle::probe_price_feed(pyth_feed, &ctx.accounts.system_state.oracle_programs), ErrorCode::InvalidOracleAccount);
+            pyth_feed.key()
+        }
+        None => Pubkey::default(),
+    };
+    let spot_price = gov_oracle::amm_spot_price(&ctx.accounts.gov_vault, &ctx.accounts.quote_vault, min_quote_liquidity)?;
+
+    let gov_token_oracle = &mut ctx.accounts.gov_token_oracle;
+    gov_token_oracle.pyth_feed = pyth_feed;
+    gov_token_oracle.gov_vault = ctx.accounts.gov_vault.key();
+    gov_token_oracle.quote_vault = ctx.accounts.quote_vault.key();
+    gov_token_oracle.twap_price = spot_price;
+    gov_token_oracle.last_update = Clock::get()?.unix_timestamp;
+    gov_token_oracle.twap_window_secs = DEFAULT_GOV_TWAP_WINDOW_SECS;
+    gov_token_oracle.max_spot_move_bps = DEFAULT_GOV_MAX_SPOT_MOVE_BPS;
+    gov_token_oracle.max_divergence_bps = DEFAULT_GOV_MAX_DIVERGENCE_BPS;
+    gov_token_oracle.max_price_age_secs = DEFAULT_MAX_PRICE_AGE_SECS;
+    gov_token_oracle.max_twap_age_secs = DEFAULT_GOV_MAX_TWAP_AGE_SECS;
+    gov_token_oracle.min_quote_liquidity = min_quote_liquidity;
+    gov_token_oracle.fallback_price = 0;
+    gov_token_oracle.bump = ctx.bumps.gov_token_oracle;
+
+    Ok(())
+}
+
+/// Set the TWAP window and the governance token oracle's manipulation and staleness limits.
+pub fn set_gov_token_oracle_params(
+    ctx: Context<SetGovTokenOracleParams>,
+    twap_window_secs: u64,
+    max_spot_move_bps: u64,
+    max_divergence_bps: u64,
+    max_price_age_secs: u64,
+    max_twap_age_secs: u64,
+    min_quote_liquidity: u64,
+) -> Result<()> {
+    let gov_token_oracle = &mut ctx.accounts.gov_token_oracle;
+    gov_token_oracle.twap_window_secs = twap_window_secs;
+    gov_token_oracle.max_spot_move_bps = max_spot_move_bps;
+    gov_token_oracle.max_divergence_bps = max_divergence_bps;
+    gov_token_oracle.max_price_age_secs = max_price_age_secs;
+    gov_token_oracle.max_twap_age_secs = max_twap_age_secs;
+    gov_token_oracle.min_quote_liquidity = min_quote_liquidity;
+
+    emit!(GovTokenOracleParamsUpdatedEvent {
+        twap_window_secs,
+        max_spot_move_bps,
+        max_divergence_bps,
+        max_price_age_secs,
+        max_twap_age_secs,
+        min_quote_liquidity,
+    });
+
+    Ok(())
+}
+
+/// Set the price used when the governance token's sources are unavailable or diverge; 0 disables it.
+pub fn set_gov_token_fallback_price(ctx: Context<SetGovTokenFallbackPrice>, fallback_price: u64) -> Result<()> {
+    ctx.accounts.gov_token_oracle.fallback_price = fallback_price;
+
+    emit!(GovTokenFallbackPriceSetEvent {
+        fallback_price,
+    });
+
+    Ok(())
+}
+
+/// Fold the AMM pool's spot price into the governance token TWAP. Callable by anyone.
+pub fn update_gov_token_twap(ctx: Context<UpdateGovTokenTwap>) -> Result<()> {
+    let gov_token_oracle = &mut ctx.accounts.gov_token_oracle;
+    let spot_price = gov_oracle::amm_spot_price(
+        &ctx.accounts.gov_vault,
+        &ctx.accounts.quote_vault,
+        gov_token_oracle.min_quote_liquidity,
+    )?;
+    let applied_price = gov_oracle::fold_twap(gov_token_oracle, spot_price, Clock::get()?.unix_timestamp)?;
+
+    emit!(GovTokenTwapUpdatedEvent {
+        spot_price,
+        applied_price,
+        twap_price: gov_token_oracle.twap_price,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Debt Note Instructions
+// -------------------------------------
+
+/// Sell a note on `face_amount` of repayments for `price`, paid by the buyer to the position owner.
+///
+/// The note is subordinate to the protocol: it can only ever be repaid by the
+/// owner or seize collateral the position's protocol debt does not need.
+pub fn assign_debt(ctx: Context<AssignDebt>, face_amount: u64, price: u64, maturity: i64) -> Result<()> {
+    require!(maturity > Clock::get()?.unix_timestamp, ErrorCode::InvalidAmount);
+
+    // Every note is secured by the same excess collateral, so the position's
+    // unpaid notes together may never claim more than that excess is worth
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_value = position_collateral_value(user_account, Some(&ctx.accounts.collateral_type), Some(&ctx.accounts.price_cache))?;
+    let required_value = user_account.stablecoin_balance as u128 * user_account.collateral_ratio as u128 / 100;
+    let excess_value = collateral_value.saturating_sub(required_value);
+    let note_obligations = user_account.note_obligations.checked_add(face_amount).ok_or(ErrorCode::Overflow)?;
+    require!(note_obligations as u128 <= excess_value, ErrorCode::InsufficientCollateral);
+    user_account.note_obligations = note_obligations;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.buyer_stablecoin_account.to_account_info(),
+        to: ctx.accounts.owner_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, price)?;
+
+    let debt_note = &mut ctx.accounts.debt_note;
+    debt_note.position = ctx.accounts.user_account.key();
+    debt_note.issuer = ctx.accounts.owner.key();
+    debt_note.holder = ctx.accounts.buyer.key();
+    debt_note.face_amount = face_amount;
+    debt_note.price = price;
+    debt_note.repaid_amount = 0;
+    debt_note.maturity = maturity;
+    debt_note.settled = false;
+
+    emit!(DebtAssignedEvent {
+        debt_note: debt_note.key(),
+        position: debt_note.position,
+        issuer: debt_note.issuer,
+        holder: debt_note.holder,
+        face_amount,
+        price,
+        maturity,
+    });
+
+    Ok(())
+}
+
+/// Repay part of a note's outstanding face value to its current holder.
+pub fn repay_debt_note(ctx: Context<RepayDebtNote>, amount: u64) -> Result<()> {
+    let debt_note = &ctx.accounts.debt_note;
+    let amount = amount.min(debt_note.face_amount - debt_note.repaid_amount);
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.issuer_stablecoin_account.to_account_info(),
+        to: ctx.accounts.holder_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.issuer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let debt_note = &mut ctx.accounts.debt_note;
+    debt_note.repaid_amount += amount;
+    debt_note.settled = debt_note.repaid_amount == debt_note.face_amount;
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.note_obligations = user_account.note_obligations.saturating_sub(amount);
+
+    emit!(DebtNoteRepaidEvent {
+        debt_note: debt_note.key(),
+        holder: debt_note.holder,
+        amount,
+        repaid_amount: debt_note.repaid_amount,
+        settled: debt_note.settled,
+    });
+
+    Ok(())
+}
+
+/// Hand a note to a new holder, who receives all future repayments and seizure rights.
+pub fn transfer_debt_note(ctx: Context<TransferDebtNote>, new_holder: Pubkey) -> Result<()> {
+    let debt_note = &mut ctx.accounts.debt_note;
+    let previous_holder = debt_note.holder;
+    debt_note.holder = new_holder;
+
+    emit!(DebtNoteTransferredEvent {
+        debt_note: debt_note.key(),
+        previous_holder,
+        new_holder,
+    });
+
+    Ok(())
+}
+
+/// Seize collateral for a defaulted note's unpaid face value.
+///
+/// Only collateral above the position's own collateral ratio requirement can
+/// be taken, so the protocol's claim always ranks first. The holder may call
+/// again if the position gains headroom later.
+pub fn seize_note_collateral(ctx: Context<SeizeNoteCollateral>) -> Result<()> {
+    let debt_note = &ctx.accounts.debt_note;
+    require!(Clock::get()?.unix_timestamp > debt_note.maturity, ErrorCode::DebtNoteNotMatured);
+    let outstanding = debt_note.face_amount - debt_note.repaid_amount;
+
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_type = &ctx.accounts.collateral_type;
+    let oracle_price = oracle::read_cached_price(&ctx.accounts.price_cache, collateral_type)?;
+    let collateral_value = position_collateral_value(user_account, Some(collateral_type), Some(&ctx.accounts.price_cache))?;
+    let required_value = user_account.stablecoin_balance as u128 * user_account.collateral_ratio as u128 / 100;
+    let excess_value = collateral_value.saturating_sub(required_value);
+
+    let seized_value = (outstanding as u128).min(excess_value) as u64;
+    let seized_collateral = (seized_value as u128 * PRICE_PRECISION as u128 / oracle_price.price as u128) as u64;
+    require!(seized_collateral > 0, ErrorCode::InsufficientCollateral);
+
+    user_account.collateral_balance -= seized_collateral;
+    user_account.note_obligations = user_account.note_obligations.saturating_sub(seized_value);
+    let holder_position = &mut ctx.accounts.holder_position;
+    holder_position.collateral_balance = holder_position.collateral_balance
+        .checked_add(seized_collateral)
+        .ok_or(ErrorCode::Overflow)?;
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Liquidation, seized_collateral, 0, oracle_price.price)?;
+    record_ledger_entry(ctx.accounts.holder_ledger.as_deref_mut(), LedgerEntryKind::Deposit, seized_collateral, 0, oracle_price.price)?;
+
+    let debt_note = &mut ctx.accounts.debt_note;
+    debt_note.repaid_amount += seized_value;
+    debt_note.settled = debt_note.repaid_amount == debt_note.face_amount;
+
+    emit!(NoteCollateralSeizedEvent {
+        debt_note: debt_note.key(),
+        position: debt_note.position,
+        holder: debt_note.holder,
+        seized_collateral,
+        seized_value,
+        settled: debt_note.settled,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Payment Stream Instructions
+// -------------------------------------
+
+/// Open a stablecoin payment stream to `recipient`; it is funded with `top_up`.
+pub fn create_stream(ctx: Context<CreatePaymentStream>, recipient: Pubkey, rate_per_second: u64, end_time: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(end_time > now, ErrorCode::InvalidLockupPeriod);
+
+    let stream = &mut ctx.accounts.stream;
+    stream.sender = ctx.accounts.sender.key();
+    stream.recipient = recipient;
+    stream.escrow = ctx.accounts.escrow.key();
+    stream.rate_per_second = rate_per_second;
+    stream.start_time = now;
+    stream.end_time = end_time;
+    stream.withdrawn = 0;
+    stream.cancelled = false;
+    stream.authority_bump = ctx.bumps.stream_authority;
+
+    emit!(PaymentStreamCreatedEvent {
+        stream: stream.key(),
+        sender: stream.sender,
+        recipient,
+        rate_per_second,
+        end_time,
+    });
+
+    Ok(())
+}
+
+/// Add funds to a payment stream's escrow.
+pub fn top_up(ctx: Context<TopUpPaymentStream>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(PaymentStreamToppedUpEvent {
+        stream: ctx.accounts.stream.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Withdraw everything streamed to the recipient so far, up to the funds in escrow.
+pub fn withdraw_from_stream(ctx: Context<WithdrawFromPaymentStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let owed = payment_stream_owed(&ctx.accounts.stream, now)?;
+    let amount = owed.min(ctx.accounts.escrow.amount);
+    require!(amount > 0, ErrorCode::NothingVested);
+    ctx.accounts.stream.withdrawn += amount;
+
+    transfer_from_payment_stream(
+        &ctx.accounts.stream,
+        &ctx.accounts.escrow,
+        &ctx.accounts.recipient_token_account,
+        &ctx.accounts.stream_authority,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    emit!(PaymentStreamWithdrawnEvent {
+        stream: ctx.accounts.stream.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Stop a payment stream: the recipient is paid what has accrued and the rest is refunded to the sender.
+pub fn cancel_stream(ctx: Context<CancelPaymentStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let owed = payment_stream_owed(&ctx.accounts.stream, now)?;
+    let paid = owed.min(ctx.accounts.escrow.amount);
+    let refunded = ctx.accounts.escrow.amount - paid;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.withdrawn += paid;
+    stream.end_time = stream.end_time.min(now);
+    stream.cancelled = true;
+
+    if paid > 0 {
+        transfer_from_payment_stream(
+            &ctx.accounts.stream,
+            &ctx.accounts.escrow,
+            &ctx.accounts.recipient_token_account,
+            &ctx.accounts.stream_authority,
+            &ctx.accounts.token_program,
+            paid,
+        )?;
+    }
+    if refunded > 0 {
+        transfer_from_payment_stream(
+            &ctx.accounts.stream,
+            &ctx.accounts.escrow,
+            &ctx.accounts.sender_token_account,
+            &ctx.accounts.stream_authority,
+            &ctx.accounts.token_program,
+            refunded,
+        )?;
+    }
+
+    emit!(PaymentStreamCancelledEvent {
+        stream: ctx.accounts.stream.key(),
+        paid,
+        refunded,
+    });
+
+    Ok(())
+}
+
+/// Amount streamed to the recipient by `now` that has not been withdrawn yet.
+fn payment_stream_owed(stream: &Account<PaymentStream>, now: i64) -> Result<u64> {
+    let elapsed = now.min(stream.end_time).saturating_sub(stream.start_time).max(0) as u64;
+    let streamed = stream.rate_per_second.checked_mul(elapsed).ok_or(ErrorCode::Overflow)?;
+
+    Ok(streamed.saturating_sub(stream.withdrawn))
+}
+
+fn transfer_from_payment_stream<'info>(
+    stream: &Account<'info, PaymentStream>,
+    escrow: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    stream_authority: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let stream_key = stream.key();
+    let bump = [stream.authority_bump];
+    let seeds: &[&[u8]] = &[b"payment_stream_authority", stream_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: escrow.to_account_info(),
+        to: destination.to_account_info(),
+        authority: stream_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+// -------------------------------------
+// Epoch Reporting Instructions
+// -------------------------------------
+
+/// Start revenue reporting with epochs of `epoch_duration` seconds.
+pub fn initialize_epoch_state(ctx: Context<InitializeEpochState>, epoch_duration: i64) -> Result<()> {
+    let epoch_state = &mut ctx.accounts.epoch_state;
+    epoch_state.epoch_duration = epoch_duration;
+    epoch_state.current_epoch = 0;
+    epoch_state.epoch_start = Clock::get()?.unix_timestamp;
+    epoch_state.wrapper_vault = ctx.accounts.wrapper_vault.key();
+    epoch_state.fee_issuance_mark = ctx.accounts.protocol_stats.fee_issuance;
+    epoch_state.liquidation_proceeds_mark = ctx.accounts.liquidation_revenue.total_proceeds;
+    epoch_state.exchange_rate_mark = current_exchange_rate(&ctx.accounts.wrapper_vault, epoch_state.epoch_start)?;
+    epoch_state.last_run_slot = 0;
+    epoch_state.bump = ctx.bumps.epoch_state;
+    epoch_state.revenue_pool = Pubkey::default();
+
+    Ok(())
+}
+
+/// Finalize the current epoch's revenue totals into an `EpochReport` and start the next epoch.
+///
+/// Remaining accounts are the collateral types whose stability fees are
+/// reported as interest; the keeper is expected to pass all of them.
+pub fn close_epoch<'info>(ctx: Context<'_, '_, 'info, 'info, CloseEpoch<'info>>) -> Result<CrankStatus> {
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let epoch_state = &mut ctx.accounts.epoch_state;
+    let status = run_crank(&ctx.accounts.crank_schedule, CrankKind::EpochClose, &mut epoch_state.last_run_slot, clock.slot)?;
+    let epoch_end = epoch_state.epoch_start.checked_add(epoch_state.epoch_duration).ok_or(ErrorCode::Overflow)?;
+    require!(now >= epoch_end, ErrorCode::EpochNotOver);
+
+    let fee_issuance = ctx.accounts.protocol_stats.fee_issuance;
+    let liquidation_proceeds = ctx.accounts.liquidation_revenue.total_proceeds;
+    let exchange_rate = current_exchange_rate(&ctx.accounts.wrapper_vault, now)?;
+
+    // Stability fees accrue on each collateral type's debt for the length of the epoch
+    let elapsed = now.saturating_sub(epoch_state.epoch_start).max(0) as u128;
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut interest_accrued: u128 = 0;
+    for info in ctx.remaining_accounts.iter() {
+        require!(!seen.contains(info.key), ErrorCode::DuplicateAccounts);
+        seen.push(info.key());
+        let collateral_type = load_program_account::<CollateralType>(info)?;
+        interest_accrued = (collateral_type.total_debt as u128)
+            .checked_mul(collateral_type.stability_fee as u128)
+            .and_then(|value| value.checked_mul(elapsed))
+            .map(|value| value / (10_000 * SECONDS_PER_YEAR))
+            .and_then(|value| interest_accrued.checked_add(value))
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Savings are valued at the closing wrapped supply
+    let savings_paid = (ctx.accounts.wrapped_mint.supply as u128)
+        .checked_mul(exchange_rate.saturating_sub(epoch_state.exchange_rate_mark))
+        .ok_or(ErrorCode::Overflow)?
+        / EXCHANGE_RATE_PRECISION;
+
+    let report = &mut ctx.accounts.epoch_report;
+    report.epoch = epoch_state.current_epoch;
+    report.start_time = epoch_state.epoch_start;
+    report.end_time = now;
+    report.mint_fees = fee_issuance.saturating_sub(epoch_state.fee_issuance_mark);
+    report.liquidation_proceeds = liquidation_proceeds.saturating_sub(epoch_state.liquidation_proceeds_mark);
+    report.interest_accrued = u64::try_from(interest_accrued).map_err(|_| ErrorCode::Overflow)?;
+    report.savings_paid = u64::try_from(savings_paid).map_err(|_| ErrorCode::Overflow)?;
+    report.bump = ctx.bumps.epoch_report;
+
+    // Pay the stakers' share of the epoch's mint fees into the configured revenue reward pool,
+    // which keepers cannot skip, or the share would silently stay in the treasury
+    report.staker_revenue = 0;
+    if epoch_state.revenue_pool != Pubkey::default() {
+        let (
+            Some(revenue_pool),
+            Some(revenue_vault),
+            Some(treasury_account),
+            Some(revenue_authority),
+            Some(token_program),
+            Some(revenue_authority_bump),
+        ) = (
+            ctx.accounts.revenue_pool.as_mut(),
+            ctx.accounts.revenue_vault.as_ref(),
+            ctx.accounts.treasury_account.as_ref(),
+            ctx.accounts.revenue_authority.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+            ctx.bumps.revenue_authority,
+        ) else {
+            return err!(ErrorCode::InvalidAccountData);
+        };
+        report.staker_revenue = fund_from_revenue(
+            revenue_pool,
+            revenue_vault,
+            treasury_account,
+            revenue_authority,
+            token_program,
+            revenue_authority_bump,
+            report.mint_fees,
+            now as u64,
+        )?;
+    }
+
+    epoch_state.current_epoch += 1;
+    epoch_state.epoch_start = now;
+    epoch_state.fee_issuance_mark = fee_issuance;
+    epoch_state.liquidation_proceeds_mark = liquidation_proceeds;
+    epoch_state.exchange_rate_mark = exchange_rate;
+
+    emit!(EpochClosedEvent {
+        epoch: report.epoch,
+        start_time: report.start_time,
+        end_time: report.end_time,
+        mint_fees: report.mint_fees,
+        liquidation_proceeds: report.liquidation_proceeds,
+        interest_accrued: report.interest_accrued,
+        savings_paid: report.savings_paid,
+    });
+
+    Ok(status)
+}
+
+/// Wrapper exchange rate accrued up to `now`, without writing it back to the vault.
+fn current_exchange_rate(wrapper_vault: &WrapperVault, now: i64) -> Result<u128> {
+    let mut wrapper_vault = wrapper_vault.clone();
+    accrue_exchange_rate(&mut wrapper_vault, now)?;
+
+    Ok(wrapper_vault.exchange_rate)
+}
+
+// -------------------------------------
+// Liquidation Scan Instructions
+// -------------------------------------
+
+/// Maximum positions scanned per call, so the result fits in return data.
+pub const MAX_SCAN_POSITIONS: usize = 20;
+
+/// A position a liquidator can act on, as returned by `scan_liquidatable`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LiquidationCandidate {
+    pub position: Pubkey,               // User account that can be liquidated
+    pub shortfall: u64,                 // Debt above what the collateral supports at the liquidation threshold
+    pub max_repay: u64,                 // Largest liquidation amount partial_liquidate accepts for the position
+}
+
+/// Shortlist the liquidatable positions among those passed in.
+///
+/// `remaining_accounts` holds a `[user_account, collateral_type, price_cache]`
+/// triple per position, as for `portfolio_health`. Cross-margin positions are
+/// skipped since they are only liquidatable as a whole portfolio.
+pub fn scan_liquidatable(ctx: Context<ScanLiquidatable>) -> Result<Vec<LiquidationCandidate>> {
+    require!(ctx.remaining_accounts.len() % 3 == 0, ErrorCode::InvalidAccountData);
+    require!(ctx.remaining_accounts.len() / 3 <= MAX_SCAN_POSITIONS, ErrorCode::BatchTooLarge);
+
+    let mut candidates = Vec::new();
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        let user_account = load_program_account::<UserAccount>(&accounts[0])?;
+        if user_account.stablecoin_balance == 0 || user_account.margin_account != Pubkey::default() {
+            continue;
+        }
+
+        let (collateral_type, price_cache) = if user_account.collateral_type == Pubkey::default() {
+            (None, None)
+        } else {
+            require_keys_eq!(accounts[1].key(), user_account.collateral_type, ErrorCode::InvalidCollateralType);
+            let collateral_type = load_program_account::<CollateralType>(&accounts[1])?;
+            let price_cache = load_program_account::<PriceCache>(&accounts[2])?;
+            require_keys_eq!(price_cache.collateral_type, user_account.collateral_type, ErrorCode::InvalidOracleAccount);
+            (Some(collateral_type), Some(price_cache))
+        };
+
+        let collateral_value = position_collateral_value(&user_account, collateral_type.as_ref(), price_cache.as_ref())?;
+        let current_ratio = u64::try_from(collateral_value * 100 / user_account.stablecoin_balance as u128).unwrap_or(u64::MAX);
+        let floor = liquidation_ratio_floor(&user_account, collateral_type.as_ref()).max(1);
+        if current_ratio >= floor {
+            continue;
+        }
+
+        // partial_liquidate removes the amount net of the bonus from the collateral balance
+        let bonus_bps = match &collateral_type {
+            Some(collateral_type) => liquidation_bonus_bps(collateral_type, current_ratio),
+            None => LEGACY_LIQUIDATION_BONUS_BPS,
+        };
+        let supported_debt = u64::try_from(collateral_value * 100 / floor as u128).unwrap_or(u64::MAX);
+        let collateral_limit = (user_account.collateral_balance as u128 * 10_000 / 10_000u64.saturating_sub(bonus_bps).max(1) as u128)
+            .min(u64::MAX as u128) as u64;
+
+        candidates.push(LiquidationCandidate {
+            position: accounts[0].key(),
+            shortfall: user_account.stablecoin_balance.saturating_sub(supported_debt),
+            max_repay: user_account.stablecoin_balance.min(collateral_limit),
+        });
+    }
+
+    Ok(candidates)
+}
+
+// -------------------------------------
+// Stake Pool Collateral Instructions
+// -------------------------------------
+
+/// Whitelist the stake pool whose LST backs an LST collateral type.
+///
+/// From then on the collateral is priced from the SOL feed and the pool's
+/// exchange rate by `refresh_stake_pool_price` instead of `refresh_price`.
+pub fn whitelist_stake_pool(ctx: Context<WhitelistStakePool>, unstake_haircut_bps: u64) -> Result<()> {
+    require_keys_eq!(ctx.accounts.collateral_type.receipt_config, Pubkey::default(), ErrorCode::ReceiptPricedCollateral);
+    let balances = stake_pool::read_stake_pool(&ctx.accounts.stake_pool)?;
+    require_keys_eq!(balances.pool_mint, ctx.accounts.pool_mint.key(), ErrorCode::InvalidStakePool);
+
+    let stake_pool_config = &mut ctx.accounts.stake_pool_config;
+    stake_pool_config.collateral_type = ctx.accounts.collateral_type.key();
+    stake_pool_config.stake_pool = ctx.accounts.stake_pool.key();
+    stake_pool_config.stake_pool_program = ctx.accounts.stake_pool_program.key();
+    stake_pool_config.lst_vault = ctx.accounts.lst_vault.key();
+    stake_pool_config.unstake_haircut_bps = unstake_haircut_bps;
+    stake_pool_config.total_lst = 0;
+    stake_pool_config.authority_bump = ctx.bumps.staking_authority;
+    stake_pool_config.bump = ctx.bumps.stake_pool_config;
+
+    ctx.accounts.collateral_type.stake_pool_config = stake_pool_config.key();
+
+    emit!(StakePoolWhitelistedEvent {
+        collateral_type: stake_pool_config.collateral_type,
+        stake_pool: stake_pool_config.stake_pool,
+        unstake_haircut_bps,
+    });
+
+    Ok(())
+}
+
+/// Set the discount applied to an LST's SOL value for unstake latency.
+pub fn set_stake_pool_haircut(ctx: Context<SetStakePoolHaircut>, unstake_haircut_bps: u64) -> Result<()> {
+    let stake_pool_config = &mut ctx.accounts.stake_pool_config;
+    stake_pool_config.unstake_haircut_bps = unstake_haircut_bps;
+
+    emit!(StakePoolWhitelistedEvent {
+        collateral_type: stake_pool_config.collateral_type,
+        stake_pool: stake_pool_config.stake_pool,
+        unstake_haircut_bps,
+    });
+
+    Ok(())
+}
+
+/// Delegate the depositor's SOL into the whitelisted stake pool and credit the LST received as collateral.
+///
+/// Staking yield accrues through the pool's exchange rate and is split with
+/// the surplus buffer by the collateral's yield skim.
+pub fn deposit_sol_collateral(ctx: Context<DepositSolCollateral>, lamports: u64) -> Result<()> {
+    let balance_before = ctx.accounts.lst_vault.amount;
+    stake_pool::deposit_sol(
+        DepositSolAccounts {
+            stake_pool_program: &ctx.accounts.stake_pool_program.to_account_info(),
+            stake_pool: &ctx.accounts.stake_pool.to_account_info(),
+            withdraw_authority: &ctx.accounts.stake_pool_withdraw_authority.to_account_info(),
+            reserve_stake: &ctx.accounts.reserve_stake.to_account_info(),
+            depositor: &ctx.accounts.depositor.to_account_info(),
+            pool_tokens_to: &ctx.accounts.lst_vault.to_account_info(),
+            manager_fee_account: &ctx.accounts.manager_fee_account.to_account_info(),
+            pool_mint: &ctx.accounts.pool_mint.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+        },
+        lamports,
+    )?;
+    ctx.accounts.lst_vault.reload()?;
+    let lst_received = ctx.accounts.lst_vault.amount.checked_sub(balance_before).ok_or(ErrorCode::Overflow)?;
+    require!(lst_received > 0, ErrorCode::InvalidAmount);
+
+    // Bind the position to the LST collateral type backing it
+    let collateral_type = &ctx.accounts.collateral_type;
+    let user_account = &mut ctx.accounts.user_account;
+    if user_account.collateral_type == Pubkey::default() {
+        user_account.collateral_type = collateral_type.key();
+        user_account.entry_exchange_rate = collateral_type.exchange_rate;
+    }
+    require_keys_eq!(user_account.collateral_type, collateral_type.key(), ErrorCode::InvalidCollateralType);
+    user_account.collateral_balance = user_account.collateral_balance.checked_add(lst_received).ok_or(ErrorCode::Overflow)?;
+
+    let stake_pool_config = &mut ctx.accounts.stake_pool_config;
+    stake_pool_config.total_lst = stake_pool_config.total_lst.checked_add(lst_received).ok_or(ErrorCode::Overflow)?;
+
+    let price = ledger_price(user_account, None);
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Deposit, lst_received, 0, price)?;
+
+    emit!(SolCollateralStakedEvent {
+        user: user_account.key(),
+        collateral_type: collateral_type.key(),
+        lamports,
+        lst_received,
+    });
+
+    Ok(())
+}
+
+/// Cache an LST collateral's price as the SOL price times the pool exchange rate, less the unstake haircut.
+pub fn refresh_stake_pool_price(ctx: Context<RefreshStakePoolPrice>) -> Result<()> {
+    let sol_price = oracle::read_price(&ctx.accounts.price_feed, &ctx.accounts.collateral_type, &ctx.accounts.system_state.oracle_programs)?;
+    let balances = stake_pool::read_stake_pool(&ctx.accounts.stake_pool)?;
+    require!(balances.pool_token_supply > 0, ErrorCode::InvalidStakePool);
+
+    // Track the pool's exchange rate so the yield skim sees staking rewards
+    let exchange_rate = (balances.total_lamports as u128)
+        .checked_mul(EXCHANGE_RATE_PRECISION)
+        .ok_or(ErrorCode::Overflow)?
+        / balances.pool_token_supply as u128;
+    ctx.accounts.collateral_type.exchange_rate = exchange_rate;
+
+    let price_cache = &mut ctx.accounts.price_cache;
+    // Never overwrite the cache with an older update
+    if sol_price.publish_time <= price_cache.publish_time {
+        return Ok(());
+    }
+    let haircut_bps = ctx.accounts.stake_pool_config.unstake_haircut_bps;
+    let to_lst = |value: u64| -> Result<u64> {
+        let scaled = (value as u128)
+            .checked_mul(exchange_rate)
+            .and_then(|value| value.checked_mul((10_000 - haircut_bps) as u128))
+            .ok_or(ErrorCode::Overflow)?
+            / (EXCHANGE_RATE_PRECISION * 10_000);
+        u64::try_from(scaled).map_err(|_| ErrorCode::Overflow.into())
+    };
+    price_cache.price = to_lst(sol_price.price)?;
+    price_cache.confidence = to_lst(sol_price.confidence)?;
+    price_cache.publish_time = sol_price.publish_time;
+    price_cache.reference_price = sol_price.price;
+
+    emit!(PriceRefreshedEvent {
+        collateral_type: price_cache.collateral_type,
+        price: price_cache.price,
+        confidence: price_cache.confidence,
+        publish_time: price_cache.publish_time,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Crank Scheduling Instructions
+// -------------------------------------
+
+/// Outcome of a crank run, returned as return data so keepers can schedule the next one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CrankStatus {
+    pub last_run_slot: u64,             // Slot the crank just ran in
+    pub next_eligible_slot: u64,        // First slot the crank may run again in
+}
+
+/// Create the crank schedule with the default interval of every crank.
+pub fn initialize_crank_schedule(ctx: Context<InitializeCrankSchedule>) -> Result<()> {
+    let crank_schedule = &mut ctx.accounts.crank_schedule;
+    crank_schedule.min_interval_slots[CrankKind::InterestIndex as usize] = DEFAULT_INTEREST_CRANK_INTERVAL_SLOTS;
+    crank_schedule.min_interval_slots[CrankKind::RewardPool as usize] = DEFAULT_REWARD_CRANK_INTERVAL_SLOTS;
+    crank_schedule.min_interval_slots[CrankKind::PegObservation as usize] = DEFAULT_PEG_CRANK_INTERVAL_SLOTS;
+    crank_schedule.min_interval_slots[CrankKind::EpochClose as usize] = DEFAULT_EPOCH_CRANK_INTERVAL_SLOTS;
+    crank_schedule.bump = ctx.bumps.crank_schedule;
+
+    Ok(())
+}
+
+/// Set the minimum number of slots between two runs of a crank.
+pub fn set_crank_interval(ctx: Context<SetCrankInterval>, kind: CrankKind, min_interval_slots: u64) -> Result<()> {
+    ctx.accounts.crank_schedule.min_interval_slots[kind as usize] = min_interval_slots;
+
+    emit!(CrankIntervalUpdatedEvent {
+        kind,
+        min_interval_slots,
+    });
+
+    Ok(())
+}
+
+/// Accrue a reward pool's emissions into its accumulator. Callable by anyone.
+///
+/// Stakes and claims accrue the pool themselves; this keeps the accumulator
+/// current for readers between them.
+pub fn crank_reward_pool(ctx: Context<CrankRewardPool>) -> Result<CrankStatus> {
+    let clock = Clock::get()?;
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let status = run_crank(&ctx.accounts.crank_schedule, CrankKind::RewardPool, &mut reward_pool.last_run_slot, clock.slot)?;
+    update_reward_pool(reward_pool, clock.unix_timestamp as u64)?;
+
+    Ok(status)
+}
+
+/// Record a crank run at `slot`, failing if the crank's interval has not passed since its last run.
+fn run_crank(crank_schedule: &CrankSchedule, kind: CrankKind, last_run_slot: &mut u64, slot: u64) -> Result<CrankStatus> {
+    let min_interval_slots = crank_schedule.min_interval_slots[kind as usize];
+    if *last_run_slot != 0 {
+        let next_eligible_slot = last_run_slot.saturating_add(min_interval_slots);
+        require!(slot >= next_eligible_slot && slot > *last_run_slot, ErrorCode::CrankTooSoon);
+    }
+    *last_run_slot = slot;
+
+    Ok(CrankStatus {
+        last_run_slot: slot,
+        next_eligible_slot: slot.saturating_add(min_interval_slots.max(1)),
+    })
+}
+
+// -------------------------------------
+// Revenue Reward Instructions
+// -------------------------------------
+
+/// Switch a reward pool between minted emissions and stablecoin revenue.
+///
+/// Only possible while nothing is staked, so a pool's accumulator never
+/// mixes the two reward tokens. Revenue pools stop emitting; they are paid
+/// by `close_epoch` out of the treasury, which must approve the revenue
+/// authority PDA as delegate for the stakers' share.
+pub fn set_reward_source(ctx: Context<SetRewardSource>, reward_source: RewardSource, revenue_share_bps: u64) -> Result<()> {
+    require!(revenue_share_bps <= math::BPS_DENOMINATOR, ErrorCode::InvalidAmount);
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    update_reward_pool(reward_pool, Clock::get()?.unix_timestamp as u64)?;
+    match reward_source {
+        RewardSource::Emissions => {
+            reward_pool.revenue_share_bps = 0;
+        }
+        RewardSource::Revenue => {
+            let reward_vault = ctx.accounts.reward_vault.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+            reward_pool.reward_vault = reward_vault.key();
+            reward_pool.reward_rate = 0;
+            reward_pool.revenue_share_bps = revenue_share_bps;
+        }
+    }
+    reward_pool.reward_source = reward_source;
+
+    emit!(RewardSourceSetEvent {
+        reward_pool: reward_pool.key(),
+        reward_source,
+        reward_vault: reward_pool.reward_vault,
+        revenue_share_bps: reward_pool.revenue_share_bps,
+    });
+
+    Ok(())
+}
+
+/// Set the revenue reward pool `close_epoch` funds with the stakers' share of mint fees.
+///
+/// Once set, every epoch close must pass the pool and its funding accounts.
+/// Omitting the pool clears it, e.g. before switching it back to emissions.
+pub fn set_epoch_revenue_pool(ctx: Context<SetEpochRevenuePool>) -> Result<()> {
+    let revenue_pool = ctx.accounts.revenue_pool.as_ref().map_or(Pubkey::default(), |revenue_pool| revenue_pool.key());
+    ctx.accounts.epoch_state.revenue_pool = revenue_pool;
+
+    emit!(EpochRevenuePoolUpdatedEvent {
+        revenue_pool,
+    });
+
+    Ok(())
+}
+
+/// Claim revenue rewards in stablecoin from the pool's reward vault.
+pub fn claim_revenue_rewards(ctx: Context<ClaimRevenueRewards>) -> Result<()> {
+    let reward_amount = take_claimable_rewards(
+        &mut ctx.accounts.reward_pool,
+        &mut ctx.accounts.staker_account,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let bump = [ctx.accounts.reward_pool.staking_authority_bump];
+    let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        to: ctx.accounts.destination_reward_account.to_account_info(),
+        authority: ctx.accounts.staking_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, reward_amount)?;
+
+    emit!(RevenueRewardsClaimedEvent {
+        staker_account: ctx.accounts.staker_account.key(),
+        amount: reward_amount,
+    });
+
+    Ok(())
+}
+
+/// Move the stakers' share of `mint_fees` from the treasury into a revenue pool's vault and distribute it.
+///
+/// The transfer is capped by the treasury's balance and the allowance it
+/// delegated to the revenue authority, so a short allowance never blocks the
+/// epoch close. Returns the amount funded.
+#[allow(clippy::too_many_arguments)]
+fn fund_from_revenue<'info>(
+    reward_pool: &mut Account<'info, RewardPool>,
+    reward_vault: &Account<'info, TokenAccount>,
+    treasury_account: &Account<'info, TokenAccount>,
+    revenue_authority: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+    revenue_authority_bump: u8,
+    mint_fees: u64,
+    now: u64,
+) -> Result<u64> {
+    require_keys_eq!(reward_vault.key(), reward_pool.reward_vault, ErrorCode::InvalidAccountData);
+
+    let share = math::mul_div_down(mint_fees as u128, reward_pool.revenue_share_bps as u128, math::BPS_DENOMINATOR as u128)?;
+    let amount = share.min(treasury_allowance(treasury_account, revenue_authority));
+
+    if amount > 0 {
+        let bump = [revenue_authority_bump];
+        let seeds: &[&[u8]] = &[b"revenue_authority", &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: treasury_account.to_account_info(),
+            to: reward_vault.to_account_info(),
+            authority: revenue_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, amount)?;
+
+        distribute_revenue(reward_pool, amount, now)?;
+    }
+
+    emit!(RevenueFundedEvent {
+        reward_pool: reward_pool.key(),
+        staker_share: share,
+        funded: amount,
+        undistributed: reward_pool.undistributed_revenue,
+    });
+
+    Ok(amount)
+}
+
+/// Fold `amount` of funded revenue into the pool's reward accumulator.
+///
+/// Revenue funded while nothing is staked is held back and distributed with
+/// the next funding.
+fn distribute_revenue(reward_pool: &mut RewardPool, amount: u64, now: u64) -> Result<()> {
+    update_reward_pool(reward_pool, now)?;
+
+    let pending = reward_pool.undistributed_revenue.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    if reward_pool.total_working == 0 {
+        reward_pool.undistributed_revenue = pending;
+        return Ok(());
+    }
+
+    let scaled = (pending as u128)
+        .checked_mul(REWARD_PRECISION)
+        .and_then(|value| value.checked_add(reward_pool.reward_residue))
+        .ok_or(ErrorCode::Overflow)?;
+    let total_working = reward_pool.total_working as u128;
+    reward_pool.accumulated_reward_per_share = reward_pool.accumulated_reward_per_share
+        .checked_add(scaled / total_working)
+        .ok_or(ErrorCode::Overflow)?;
+    reward_pool.reward_residue = scaled % total_working;
+    reward_pool.undistributed_revenue = 0;
+
+    Ok(())
+}
+
+// -------------------------------------
+// Position Ledger Instructions
+// -------------------------------------
+
+/// Most entries returned by one `export_position_history` page, within the return data limit.
+pub const MAX_HISTORY_PAGE: u32 = 25;
+
+/// A page of a position's ledger, returned by `export_position_history`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PositionHistoryPage {
+    pub user_account: Pubkey,
+    pub entry_count: u64,               // Entries appended over the ledger's lifetime
+    pub dropped_entries: u64,           // Entries lost while the ledger was full
+    pub start: u32,                     // Index of the first entry in this page
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// Open an opt-in ledger recording every change to a position, with room for `capacity` entries.
+pub fn initialize_position_ledger(ctx: Context<InitializePositionLedger>, capacity: u32) -> Result<()> {
+    require!(capacity > 0 && capacity <= MAX_LEDGER_ALLOCATION, ErrorCode::InvalidAmount);
+
+    let position_ledger = &mut ctx.accounts.position_ledger;
+    position_ledger.user_account = ctx.accounts.user_account.key();
+    position_ledger.capacity = capacity;
+    position_ledger.entry_count = 0;
+    position_ledger.dropped_entries = 0;
+    position_ledger.bump = ctx.bumps.position_ledger;
+    position_ledger.entries = Vec::new();
+
+    Ok(())
+}
+
+/// Grow a position ledger by `additional_entries`; anyone may pay for the extra rent.
+pub fn extend_position_ledger(ctx: Context<ExtendPositionLedger>, additional_entries: u32) -> Result<()> {
+    require!(additional_entries > 0 && additional_entries <= MAX_LEDGER_ALLOCATION, ErrorCode::InvalidAmount);
+
+    let position_ledger = &mut ctx.accounts.position_ledger;
+    position_ledger.capacity = position_ledger.capacity.checked_add(additional_entries).ok_or(ErrorCode::Overflow)?;
+
+    emit!(PositionLedgerExtendedEvent {
+        user_account: position_ledger.user_account,
+        capacity: position_ledger.capacity,
+    });
+
+    Ok(())
+}
+
+/// Return up to `limit` recorded entries of a position's ledger starting at `start`.
+pub fn export_position_history(ctx: Context<ExportPositionHistory>, start: u32, limit: u32) -> Result<PositionHistoryPage> {
+    let position_ledger = &ctx.accounts.position_ledger;
+    let start_index = (start as usize).min(position_ledger.entries.len());
+    let end_index = start_index.saturating_add(limit.min(MAX_HISTORY_PAGE) as usize).min(position_ledger.entries.len());
+
+    Ok(PositionHistoryPage {
+        user_account: position_ledger.user_account,
+        entry_count: position_ledger.entry_count,
+        dropped_entries: position_ledger.dropped_entries,
+        start,
+        entries: position_ledger.entries[start_index..end_index].to_vec(),
+    })
+}
+
+/// Append an entry to a position's ledger, if the owner opted into one.
+///
+/// A full ledger counts the entry as dropped rather than failing, so an
+/// unextended ledger never blocks a liquidation.
+pub fn record_ledger_entry(
+    position_ledger: Option<&mut PositionLedger>,
+    kind: LedgerEntryKind,
+    collateral_amount: u64,
+    debt_amount: u64,
+    price: u64,
+) -> Result<()> {
+    let Some(position_ledger) = position_ledger else {
+        return Ok(());
+    };
+
+    position_ledger.entry_count = position_ledger.entry_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    if position_ledger.entries.len() >= position_ledger.capacity as usize {
+        position_ledger.dropped_entries = position_ledger.dropped_entries.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        emit!(PositionLedgerFullEvent {
+            user_account: position_ledger.user_account,
+            capacity: position_ledger.capacity,
+            dropped_entries: position_ledger.dropped_entries,
+        });
+        return Ok(());
+    }
+
+    position_ledger.entries.push(LedgerEntry {
+        kind,
+        timestamp: Clock::get()?.unix_timestamp,
+        collateral_amount,
+        debt_amount,
+        price,
+    });
+
+    Ok(())
+}
+
+/// Collateral price recorded in a ledger entry: the cached price, one-to-one for legacy positions, 0 if unknown.
+fn ledger_price(user_account: &UserAccount, price_cache: Option<&PriceCache>) -> u64 {
+    match price_cache {
+        Some(price_cache) => price_cache.price,
+        None if user_account.collateral_type == Pubkey::default() => PRICE_PRECISION,
+        None => 0,
+    }
+}
+
+// -------------------------------------
+// Ceiling Controller Instructions
+// -------------------------------------
+
+/// Supply elasticity report of one ceiling controller observation, returned by `adjust_debt_ceiling`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CeilingReport {
+    pub collateral_type: Pubkey,
+    pub epoch: u64,                     // Epoch the observation was made in
+    pub utilization_bps: u64,           // Debt outstanding as a share of the ceiling
+    pub peg_healthy: bool,              // Peg TWAP was fresh and within its band
+    pub high_epochs: u64,               // Consecutive epochs counting toward a raise
+    pub low_epochs: u64,                // Consecutive epochs counting toward a cut
+    pub previous_ceiling: u64,
+    pub debt_ceiling: u64,              // Ceiling after the observation
+}
+
+/// Start steering a collateral's debt ceiling automatically within governance bounds.
+pub fn initialize_ceiling_controller(ctx: Context<InitializeCeilingController>, params: CeilingControllerParams) -> Result<()> {
+    validate_ceiling_controller_params(&params)?;
+
+    let ceiling_controller = &mut ctx.accounts.ceiling_controller;
+    ceiling_controller.collateral_type = ctx.accounts.collateral_type.key();
+    ceiling_controller.params = params;
+    ceiling_controller.high_epochs = 0;
+    ceiling_controller.low_epochs = 0;
+    ceiling_controller.next_epoch = 0;
+    ceiling_controller.bump = ctx.bumps.ceiling_controller;
+
+    emit!(CeilingControllerUpdatedEvent {
+        collateral_type: ceiling_controller.collateral_type,
+        params,
+    });
+
+    Ok(())
+}
+
+/// Replace a ceiling controller's thresholds and bounds, restarting its streaks.
+pub fn set_ceiling_controller(ctx: Context<SetCeilingController>, params: CeilingControllerParams) -> Result<()> {
+    validate_ceiling_controller_params(&params)?;
+
+    let ceiling_controller = &mut ctx.accounts.ceiling_controller;
+    ceiling_controller.params = params;
+    ceiling_controller.high_epochs = 0;
+    ceiling_controller.low_epochs = 0;
+
+    emit!(CeilingControllerUpdatedEvent {
+        collateral_type: ceiling_controller.collateral_type,
+        params,
+    });
+
+    Ok(())
+}
+
+/// Observe a collateral's ceiling utilization once per epoch and step its ceiling. Callable by anyone.
+///
+/// Epochs at or above the raise threshold while the peg is healthy build a
+/// raise streak; epochs at or below the lower threshold build a cut streak;
+/// anything else resets both. Once a streak reaches `required_epochs` the
+/// ceiling moves by `step_bps`, clamped to the governance floor and cap, and
+/// the streak restarts.
+pub fn adjust_debt_ceiling(ctx: Context<AdjustDebtCeiling>) -> Result<CeilingReport> {
+    let epoch = ctx.accounts.epoch_state.current_epoch;
+    let ceiling_controller = &mut ctx.accounts.ceiling_controller;
+    require!(epoch >= ceiling_controller.next_epoch, ErrorCode::CrankTooSoon);
+    ceiling_controller.next_epoch = epoch.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let peg_state = &ctx.accounts.peg_state;
+    let peg_age = now.saturating_sub(peg_state.last_update);
+    let peg_healthy = peg_age >= 0 && peg_age as u64 <= peg_state.max_price_age_secs && peg_within_band(peg_state);
+
+    let collateral_type = &mut ctx.accounts.collateral_type;
+    let params = ceiling_controller.params;
+    let utilization_bps = if collateral_type.debt_ceiling == 0 {
+        math::BPS_DENOMINATOR
+    } else {
+        math::mul_div_down(collateral_type.total_debt as u128, math::BPS_DENOMINATOR as u128, collateral_type.debt_ceiling as u128)?
+    };
+
+    if utilization_bps >= params.raise_utilization_bps && peg_healthy {
+        ceiling_controller.high_epochs += 1;
+        ceiling_controller.low_epochs = 0;
+    } else if utilization_bps <= params.lower_utilization_bps {
+        ceiling_controller.low_epochs += 1;
+        ceiling_controller.high_epochs = 0;
+    } else {
+        ceiling_controller.high_epochs = 0;
+        ceiling_controller.low_epochs = 0;
+    }
+
+    let previous_ceiling = collateral_type.debt_ceiling;
+    let step = math::mul_div_down(previous_ceiling as u128, params.step_bps as u128, math::BPS_DENOMINATOR as u128)?;
+    if ceiling_controller.high_epochs >= params.required_epochs {
+        collateral_type.debt_ceiling = previous_ceiling.saturating_add(step).clamp(params.floor, params.cap);
+        ceiling_controller.high_epochs = 0;
+    } else if ceiling_controller.low_epochs >= params.required_epochs {
+        collateral_type.debt_ceiling = previous_ceiling.saturating_sub(step).clamp(params.floor, params.cap);
+        ceiling_controller.low_epochs = 0;
+    }
+
+    if collateral_type.debt_ceiling != previous_ceiling {
+        emit!(DebtCeilingAdjustedEvent {
+            collateral_type: collateral_type.key(),
+            epoch,
+            utilization_bps,
+            previous_ceiling,
+            debt_ceiling: collateral_type.debt_ceiling,
+        });
+    }
+
+    Ok(CeilingReport {
+        collateral_type: collateral_type.key(),
+        epoch,
+        utilization_bps,
+        peg_healthy,
+        high_epochs: ceiling_controller.high_epochs,
+        low_epochs: ceiling_controller.low_epochs,
+        previous_ceiling,
+        debt_ceiling: collateral_type.debt_ceiling,
+    })
+}
+
+fn validate_ceiling_controller_params(params: &CeilingControllerParams) -> Result<()> {
+    require!(
+        params.lower_utilization_bps < params.raise_utilization_bps && params.raise_utilization_bps <= math::BPS_DENOMINATOR,
+        ErrorCode::InvalidAmount
+    );
+    require!(params.required_epochs > 0, ErrorCode::InvalidAmount);
+    require!(params.step_bps > 0 && params.step_bps <= MAX_CEILING_STEP_BPS, ErrorCode::InvalidAmount);
+    require!(params.floor <= params.cap && params.cap <= MAX_DEBT_CEILING, ErrorCode::InvalidAmount);
+
+    Ok(())
+}
+
+// -------------------------------------
+// Market Maker Facility Instructions
+// -------------------------------------
+
+/// Open a credit line for an approved market maker.
+pub fn open_mm_facility(ctx: Context<OpenMmFacility>, market_maker: Pubkey, terms: MmFacilityTerms) -> Result<()> {
+    validate_mm_facility_terms(&terms)?;
+
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    mm_facility.market_maker = market_maker;
+    mm_facility.terms = terms;
+    mm_facility.outstanding_debt = 0;
+    mm_facility.attestation_hash = [0; 32];
+    mm_facility.attested_value = 0;
+    mm_facility.attested_at = 0;
+    mm_facility.active = true;
+    mm_facility.bump = ctx.bumps.mm_facility;
+
+    emit!(MmFacilityUpdatedEvent {
+        market_maker,
+        terms,
+        active: true,
+    });
+
+    Ok(())
+}
+
+/// Replace a facility's terms. A lower ceiling only blocks new mints; outstanding debt stays redeemable.
+pub fn set_mm_facility_terms(ctx: Context<ManageMmFacility>, terms: MmFacilityTerms) -> Result<()> {
+    require!(!ctx.accounts.system_state.config_frozen, ErrorCode::ConfigFrozen);
+    validate_mm_facility_terms(&terms)?;
+
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    mm_facility.terms = terms;
+
+    emit!(MmFacilityUpdatedEvent {
+        market_maker: mm_facility.market_maker,
+        terms,
+        active: mm_facility.active,
+    });
+
+    Ok(())
+}
+
+/// Suspend or resume a facility's mints.
+///
+/// Suspending must stay possible while the configuration is frozen.
+pub fn set_mm_facility_active(ctx: Context<ManageMmFacility>, active: bool) -> Result<()> {
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    mm_facility.active = active;
+
+    emit!(MmFacilityUpdatedEvent {
+        market_maker: mm_facility.market_maker,
+        terms: mm_facility.terms,
+        active,
+    });
+
+    Ok(())
+}
+
+/// Record an attestation of the collateral backing a facility.
+pub fn attest_mm_collateral(ctx: Context<ManageMmFacility>, attestation_hash: [u8; 32], attested_value: u64) -> Result<()> {
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    mm_facility.attestation_hash = attestation_hash;
+    mm_facility.attested_value = attested_value;
+    mm_facility.attested_at = Clock::get()?.unix_timestamp;
+
+    emit!(MmCollateralAttestedEvent {
+        market_maker: mm_facility.market_maker,
+        attestation_hash,
+        attested_value,
+    });
+
+    Ok(())
+}
+
+/// Mint stablecoin against a market maker's credit line at the facility's fee.
+///
+/// The outstanding debt after the mint must fit under the facility ceiling
+/// and be covered by a fresh collateral attestation at the facility's ratio.
+/// The fee is part of that debt, so the whole amount is booked as facility
+/// debt rather than as fee issuance.
+pub fn mm_facility_mint(ctx: Context<MmFacilityMint>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Mint)?;
+
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    require!(mm_facility.active, ErrorCode::MmFacilityInactive);
+    let attestation_age = Clock::get()?.unix_timestamp.saturating_sub(mm_facility.attested_at);
+    require!(
+        mm_facility.attested_at > 0 && attestation_age <= mm_facility.terms.max_attestation_age_secs as i64,
+        ErrorCode::MmAttestationStale
+    );
+
+    let outstanding_debt = mm_facility.outstanding_debt.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    require!(outstanding_debt <= mm_facility.terms.debt_ceiling, ErrorCode::MmCreditLineExceeded);
+    let required_value = (outstanding_debt as u128)
+        .checked_mul(mm_facility.terms.collateral_ratio_bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / math::BPS_DENOMINATOR as u128;
+    require!(mm_facility.attested_value as u128 >= required_value, ErrorCode::InsufficientCollateral);
+    mm_facility.outstanding_debt = outstanding_debt;
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.mm_facility_debt = protocol_stats.mm_facility_debt.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    let FeeCharge { fee, .. } = math::fee_charge(amount, mm_facility.terms.mint_fee_bps)?;
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        to: ctx.accounts.market_maker_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.market_maker.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::mint_to(cpi_ctx, amount - fee)?;
+
+    if fee > 0 {
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.market_maker.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::mint_to(cpi_ctx, fee)?;
+    }
+
+    emit!(MmFacilityMintEvent {
+        market_maker: ctx.accounts.market_maker.key(),
+        amount,
+        fee,
+        outstanding_debt,
+    });
+
+    Ok(())
+}
+
+/// Redeem stablecoin against a market maker's credit line at the facility's fee.
+///
+/// The fee is paid to the treasury and the rest is burned, reducing the
+/// outstanding debt by the burned amount.
+pub fn mm_facility_redeem(ctx: Context<MmFacilityRedeem>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Redeem)?;
+
+    let mm_facility = &mut ctx.accounts.mm_facility;
+    let FeeCharge { fee, .. } = math::fee_charge(amount, mm_facility.terms.redeem_fee_bps)?;
+    let burned = amount - fee;
+    require!(burned <= mm_facility.outstanding_debt, ErrorCode::InvalidAmount);
+    mm_facility.outstanding_debt -= burned;
+    let protocol_stats = &mut ctx.accounts.protocol_stats;
+    protocol_stats.mm_facility_debt = protocol_stats.mm_facility_debt.saturating_sub(burned);
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.market_maker_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.market_maker.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, burned)?;
+
+    if fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.market_maker_stablecoin_account.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.market_maker.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, fee)?;
+    }
+
+    emit!(MmFacilityRedeemEvent {
+        market_maker: ctx.accounts.market_maker.key(),
+        amount,
+        fee,
+        outstanding_debt: mm_facility.outstanding_debt,
+    });
+
+    Ok(())
+}
+
+fn validate_mm_facility_terms(terms: &MmFacilityTerms) -> Result<()> {
+    require!(terms.mint_fee_bps <= MAX_FEE_BPS && terms.redeem_fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+    require!(terms.debt_ceiling <= MAX_DEBT_CEILING, ErrorCode::InvalidAmount);
+    require!(terms.collateral_ratio_bps >= math::BPS_DENOMINATOR, ErrorCode::InvalidCollateralRatio);
+    require!(terms.max_attestation_age_secs > 0, ErrorCode::InvalidAmount);
+
+    Ok(())
+}
+
+// -------------------------------------
+// Travel Rule Instructions
+// -------------------------------------
+
+/// Approve a VASP signer to record travel-rule attestations.
+pub fn register_travel_rule_attestor(ctx: Context<RegisterTravelRuleAttestor>, attestor: Pubkey) -> Result<()> {
+    let travel_rule_attestor = &mut ctx.accounts.travel_rule_attestor;
+    travel_rule_attestor.attestor = attestor;
+    travel_rule_attestor.bump = ctx.bumps.travel_rule_attestor;
+
+    emit!(TravelRuleAttestorUpdatedEvent { attestor, approved: true });
+
+    Ok(())
+}
+
+/// Revoke a travel-rule attestor. Attestations it already recorded stay valid.
+pub fn remove_travel_rule_attestor(ctx: Context<RemoveTravelRuleAttestor>) -> Result<()> {
+    emit!(TravelRuleAttestorUpdatedEvent {
+        attestor: ctx.accounts.travel_rule_attestor.attestor,
+        approved: false,
+    });
+
+    Ok(())
+}
+
+/// Record the travel-rule reference of an upcoming transfer.
+///
+/// Only the hash of the encrypted payload goes on chain; the payload itself
+/// stays with the originating and beneficiary VASPs.
+pub fn attest_travel_rule(
+    ctx: Context<AttestTravelRule>,
+    memo_hash: [u8; 32],
+    originator: Pubkey,
+    beneficiary: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let travel_rule_attestation = &mut ctx.accounts.travel_rule_attestation;
+    travel_rule_attestation.attestor = ctx.accounts.attestor.key();
+    travel_rule_attestation.memo_hash = memo_hash;
+    travel_rule_attestation.originator = originator;
+    travel_rule_attestation.beneficiary = beneficiary;
+    travel_rule_attestation.amount = amount;
+    travel_rule_attestation.consumed = false;
+    travel_rule_attestation.bump = ctx.bumps.travel_rule_attestation;
+
+    emit!(TravelRuleAttestedEvent {
+        attestor: travel_rule_attestation.attestor,
+        memo_hash,
+        originator,
+        beneficiary,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Transfer stablecoin with a travel-rule memo hash recorded in the emitted event.
+///
+/// When an attestation is passed, the transfer must match its originator,
+/// beneficiary and amount exactly, and the attestation is consumed so the
+/// same reference cannot cover a second transfer.
+pub fn transfer_with_memo(ctx: Context<TransferWithMemo>, amount: u64, memo_hash: [u8; 32]) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let sender = ctx.accounts.sender.key();
+    let recipient = ctx.accounts.recipient_stablecoin_account.owner;
+    let attestor = match ctx.accounts.travel_rule_attestation.as_mut() {
+        Some(travel_rule_attestation) => {
+            require!(!travel_rule_attestation.consumed, ErrorCode::TravelRuleAttestationMismatch);
+            require!(
+                travel_rule_attestation.originator == sender
+                    && travel_rule_attestation.beneficiary == recipient
+                    && travel_rule_attestation.amount == amount,
+                ErrorCode::TravelRuleAttestationMismatch
+            );
+            travel_rule_attestation.consumed = true;
+            Some(travel_rule_attestation.attestor)
+        }
+        None => None,
+    };
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.sender_stablecoin_account.to_account_info(),
+        to: ctx.accounts.recipient_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.sender.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(PaymentMemoEvent {
+        sender,
+        recipient,
+        amount,
+        memo_hash,
+        attestor,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Token Rescue Instructions
+// -------------------------------------
+
+/// Sweep tokens sent by mistake to a program-owned token account.
+///
+/// The stablecoin and governance token can never be rescued, nor can the
+/// treasury's reserve asset, so balances backing debt or owed to users stay
+/// where they are.
+pub fn rescue_tokens(
+    ctx: Context<RescueTokens>,
+    mint: Pubkey,
+    amount: u64,
+    destination: Pubkey,
+    authority: RescueAuthority,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let (gov_token_mint, _) = Pubkey::find_program_address(&[b"gov_token_mint"], ctx.program_id);
+    require!(
+        mint != ctx.accounts.system_state.stablecoin_mint && mint != gov_token_mint,
+        ErrorCode::ProtectedMint
+    );
+    let treasury_reserves = &ctx.accounts.treasury_reserves;
+    require!(
+        mint != treasury_reserves.reserve_mint && ctx.accounts.source_account.key() != treasury_reserves.reserve_vault,
+        ErrorCode::ProtectedMint
+    );
+
+    let wrapper_key;
+    let mut seeds: Vec<&[u8]> = match authority {
+        RescueAuthority::Treasury => vec![b"treasury_authority"],
+        RescueAuthority::GovToken => vec![b"gov_token_authority"],
+        RescueAuthority::VeEscrow => vec![b"ve_escrow_authority"],
+        RescueAuthority::Wrapper(wrapper) => {
+            wrapper_key = wrapper;
+            vec![b"wrapper_authority", wrapper_key.as_ref()]
+        }
+    };
+    let (source_authority, bump) = Pubkey::find_program_address(&seeds, ctx.program_id);
+    require_keys_eq!(ctx.accounts.source_authority.key(), source_authority, ErrorCode::InvalidAccountOwner);
+    require_keys_eq!(ctx.accounts.source_account.owner, source_authority, ErrorCode::InvalidAccountOwner);
+
+    let bump = [bump];
+    seeds.push(&bump);
+    let signer_seeds = &[&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.source_account.to_account_info(),
+        to: ctx.accounts.destination_account.to_account_info(),
+        authority: ctx.accounts.source_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    emit!(TokensRescuedEvent {
+        mint,
+        source: ctx.accounts.source_account.key(),
+        destination,
+        amount,
+        authority,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Redemption Queue Instructions
+// -------------------------------------
+
+/// Create the redemption queue of a treasury's reserves.
+pub fn initialize_redemption_queue(ctx: Context<InitializeRedemptionQueue>, priority_fee_bps: u64) -> Result<()> {
+    require!(priority_fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+
+    let redemption_queue = &mut ctx.accounts.redemption_queue;
+    redemption_queue.treasury_reserves = ctx.accounts.treasury_reserves.key();
+    redemption_queue.escrow_vault = ctx.accounts.escrow_vault.key();
+    redemption_queue.heads = [0; REDEMPTION_LANE_COUNT];
+    redemption_queue.tails = [0; REDEMPTION_LANE_COUNT];
+    redemption_queue.queued_amount = 0;
+    redemption_queue.priority_fee_bps = priority_fee_bps;
+    redemption_queue.bump = ctx.bumps.redemption_queue;
+
+    Ok(())
+}
+
+/// Set the fee charged for the priority lane.
+pub fn set_redemption_priority_fee(ctx: Context<SetRedemptionPriorityFee>, priority_fee_bps: u64) -> Result<()> {
+    require!(priority_fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+    ctx.accounts.redemption_queue.priority_fee_bps = priority_fee_bps;
+
+    emit!(RedemptionPriorityFeeUpdatedEvent {
+        redemption_queue: ctx.accounts.redemption_queue.key(),
+        priority_fee_bps,
+    });
+
+    Ok(())
+}
+
+/// Queue `amount` of stablecoin for redemption against the treasury's reserves.
+///
+/// The redemption fee is taken out of `amount` and the rest is escrowed until
+/// filled or cancelled, redeemed 1:1 in base units of the reserve asset.
+/// `request_id` must be the lane's next id. The priority lane charges the
+/// queue's priority fee on top of `amount`. Both fees are shared with the
+/// partner named by `partner_id`, if any.
+pub fn request_redemption(
+    ctx: Context<RequestRedemption>,
+    amount: u64,
+    lane: RedemptionLane,
+    request_id: u64,
+    partner_id: Option<u64>,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Redeem)?;
+
+    // Reserve-asset redemptions have no collateral type, so the global rate applies
+    let fee = fee_amount(amount, effective_redemption_fee_bps(&ctx.accounts.system_state, None))?;
+    let (redemption_fee, fee_credits_used) = match ctx.accounts.user_account.as_deref_mut() {
+        Some(user_account) => spend_fee_credits(user_account, fee),
+        None => (fee, 0),
+    };
+    let net_amount = amount.checked_sub(redemption_fee).filter(|net| *net > 0).ok_or(ErrorCode::InvalidAmount)?;
+
+    let redemption_queue = &mut ctx.accounts.redemption_queue;
+    require!(request_id == redemption_queue.tails[lane as usize], ErrorCode::InvalidAccountData);
+    redemption_queue.tails[lane as usize] = request_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    redemption_queue.queued_amount = redemption_queue.queued_amount.checked_add(net_amount).ok_or(ErrorCode::Overflow)?;
+
+    let priority_fee = match lane {
+        RedemptionLane::Standard => 0,
+        RedemptionLane::Priority => math::fee_charge(amount, redemption_queue.priority_fee_bps)?.fee,
+    };
+    let treasury_fee = redemption_fee.checked_add(priority_fee).ok_or(ErrorCode::Overflow)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.owner_stablecoin_account.to_account_info(),
+        to: ctx.accounts.escrow_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, net_amount)?;
+
+    if treasury_fee > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_stablecoin_account.to_account_info(),
+            to: ctx.accounts.treasury_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, treasury_fee)?;
+    }
+    accrue_partner_revenue(ctx.accounts.partner.as_mut(), partner_id, amount, treasury_fee)?;
+
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    redemption_request.queue = redemption_queue.key();
+    redemption_request.owner = ctx.accounts.owner.key();
+    redemption_request.lane = lane;
+    redemption_request.id = request_id;
+    redemption_request.destination = ctx.accounts.owner_reserve_account.key();
+    redemption_request.amount = net_amount;
+    redemption_request.remaining = net_amount;
+    redemption_request.cancelled = false;
+    redemption_request.created_at = Clock::get()?.unix_timestamp;
+    redemption_request.bump = ctx.bumps.redemption_request;
+
+    emit!(RedemptionQueuedEvent {
+        owner: redemption_request.owner,
+        lane,
+        request_id,
+        amount: net_amount,
+        priority_fee,
+        queued_amount: redemption_queue.queued_amount,
+        redemption_fee,
+        fee_credits_used,
+    });
+
+    Ok(())
+}
+
+/// Fill the request at the head of its lane from the liquid reserves.
+///
+/// The priority lane is drained first. A request is filled as far as the
+/// liquid reserves allow and stays at the head until complete; cancelled
+/// requests are skipped. Permissionless, so keepers can crank the queue as
+/// strategy recalls and other inflows restore liquidity.
+pub fn fill_redemption(ctx: Context<FillRedemption>) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Redeem)?;
+
+    let redemption_queue = &mut ctx.accounts.redemption_queue;
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    let lane = redemption_request.lane;
+    require!(redemption_request.id == redemption_queue.heads[lane as usize], ErrorCode::RedemptionNotAtHead);
+    if lane == RedemptionLane::Standard {
+        let priority = RedemptionLane::Priority as usize;
+        require!(redemption_queue.heads[priority] == redemption_queue.tails[priority], ErrorCode::RedemptionNotAtHead);
+    }
+
+    let treasury_reserves = &mut ctx.accounts.treasury_reserves;
+    let fill = redemption_request.remaining
+        .min(treasury_reserves.liquid_reserves)
+        .min(ctx.accounts.reserve_vault.amount);
+    require!(fill > 0 || redemption_request.remaining == 0, ErrorCode::InsufficientLiquidReserves);
+
+    if fill > 0 {
+        let bump = [treasury_reserves.authority_bump];
+        let seeds: &[&[u8]] = &[b"treasury_authority", &bump];
+        let signer_seeds = &[seeds];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reserve_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, fill)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+            from: ctx.accounts.escrow_vault.to_account_info(),
+            authority: ctx.accounts.treasury_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::burn(cpi_ctx, fill)?;
+
+        treasury_reserves.liquid_reserves -= fill;
+        redemption_request.remaining -= fill;
+        redemption_queue.queued_amount = redemption_queue.queued_amount.saturating_sub(fill);
+        if let Some(liquidity_monitor) = ctx.accounts.liquidity_monitor.as_deref_mut() {
+            record_redemption_volume(liquidity_monitor, fill, Clock::get()?.unix_timestamp)?;
+        }
+    }
+    if redemption_request.remaining == 0 {
+        redemption_queue.heads[lane as usize] += 1;
+    }
+
+    emit!(RedemptionFilledEvent {
+        owner: redemption_request.owner,
+        lane,
+        request_id: redemption_request.id,
+        filled: fill,
+        remaining: redemption_request.remaining,
+        liquid_reserves: treasury_reserves.liquid_reserves,
+    });
+
+    Ok(())
+}
+
+/// Withdraw the unfilled remainder of a queued redemption.
+///
+/// Amounts already filled stay redeemed, and the priority fee is not refunded.
+pub fn cancel_redemption(ctx: Context<CancelRedemption>) -> Result<()> {
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    require!(!redemption_request.cancelled && redemption_request.remaining > 0, ErrorCode::InvalidAmount);
+    let refund = redemption_request.remaining;
+
+    let bump = [ctx.accounts.treasury_reserves.authority_bump];
+    let seeds: &[&[u8]] = &[b"treasury_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.escrow_vault.to_account_info(),
+        to: ctx.accounts.owner_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.treasury_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, refund)?;
+
+    redemption_request.remaining = 0;
+    redemption_request.cancelled = true;
+    let redemption_queue = &mut ctx.accounts.redemption_queue;
+    redemption_queue.queued_amount = redemption_queue.queued_amount.saturating_sub(refund);
+    let lane = redemption_request.lane as usize;
+    if redemption_queue.heads[lane] == redemption_request.id {
+        redemption_queue.heads[lane] += 1;
+    }
+
+    emit!(RedemptionCancelledEvent {
+        owner: redemption_request.owner,
+        lane: redemption_request.lane,
+        request_id: redemption_request.id,
+        refunded: refund,
+    });
+
+    Ok(())
+}
+
+/// Close a request the queue has moved past, returning its rent to the owner.
+pub fn close_redemption_request(ctx: Context<CloseRedemptionRequest>) -> Result<()> {
+    let redemption_request = &ctx.accounts.redemption_request;
+    require!(redemption_request.remaining == 0, ErrorCode::InvalidAmount);
+    require!(
+        redemption_request.id < ctx.accounts.redemption_queue.heads[redemption_request.lane as usize],
+        ErrorCode::RedemptionNotAtHead
+    );
+
+    Ok(())
+}
+
+// -------------------------------------
+// Lending Receipt Collateral Instructions
+// -------------------------------------
+
+/// Bind a collateral type to the lending market reserve issuing its receipt token.
+pub fn whitelist_receipt_collateral(
+    ctx: Context<WhitelistReceiptCollateral>,
+    market: ReceiptMarket,
+    haircut_bps: u64,
+    max_rate_age_secs: u64,
+) -> Result<()> {
+    require_keys_eq!(ctx.accounts.collateral_type.stake_pool_config, Pubkey::default(), ErrorCode::StakePoolPricedCollateral);
+
+    // Markets that mint a receipt token must mint this collateral's
+    let receipt_mint = receipt::adapter(market).receipt_mint(&ctx.accounts.reserve.try_borrow_data()?)?;
+    if let Some(receipt_mint) = receipt_mint {
+        require_keys_eq!(receipt_mint, ctx.accounts.collateral_type.collateral_mint, ErrorCode::InvalidReceiptAccount);
+    }
+
+    let receipt_config = &mut ctx.accounts.receipt_config;
+    receipt_config.collateral_type = ctx.accounts.collateral_type.key();
+    receipt_config.market = market;
+    receipt_config.market_program = ctx.accounts.market_program.key();
+    receipt_config.reserve = ctx.accounts.reserve.key();
+    receipt_config.haircut_bps = haircut_bps;
+    receipt_config.max_rate_age_secs = max_rate_age_secs;
+    receipt_config.bump = ctx.bumps.receipt_config;
+    ctx.accounts.collateral_type.receipt_config = receipt_config.key();
+
+    emit!(ReceiptCollateralUpdatedEvent {
+        collateral_type: receipt_config.collateral_type,
+        market,
+        reserve: receipt_config.reserve,
+        haircut_bps,
+        max_rate_age_secs,
+    });
+
+    Ok(())
+}
+
+/// Set the haircut and rate freshness limit of a receipt collateral.
+pub fn set_receipt_collateral_params(ctx: Context<SetReceiptCollateralParams>, haircut_bps: u64, max_rate_age_secs: u64) -> Result<()> {
+    let receipt_config = &mut ctx.accounts.receipt_config;
+    receipt_config.haircut_bps = haircut_bps;
+    receipt_config.max_rate_age_secs = max_rate_age_secs;
+
+    emit!(ReceiptCollateralUpdatedEvent {
+        collateral_type: receipt_config.collateral_type,
+        market: receipt_config.market,
+        reserve: receipt_config.reserve,
+        haircut_bps,
+        max_rate_age_secs,
+    });
+
+    Ok(())
+}
+
+/// Refresh the cached price of a receipt collateral from the underlying price and the market's exchange rate.
+///
+/// The underlying price goes through the usual staleness and confidence
+/// checks, and the exchange rate must have been accrued within the config's
+/// `max_rate_age_secs`.
+pub fn refresh_receipt_price(ctx: Context<RefreshReceiptPrice>) -> Result<()> {
+    let underlying_price = oracle::read_price(&ctx.accounts.price_feed, &ctx.accounts.collateral_type, &ctx.accounts.system_state.oracle_programs)?;
+    let receipt_config = &ctx.accounts.receipt_config;
+    let rate = receipt::read_receipt_rate(receipt_config.market, &ctx.accounts.reserve, receipt_config.max_rate_age_secs)?;
+
+    // Track the receipt's exchange rate so the yield skim sees lending interest
+    ctx.accounts.collateral_type.exchange_rate = rate.exchange_rate;
+
+    let price_cache = &mut ctx.accounts.price_cache;
+    // Never overwrite the cache with an older update
+    if underlying_price.publish_time <= price_cache.publish_time {
+        return Ok(());
+    }
+    let haircut_bps = receipt_config.haircut_bps;
+    let to_receipt = |value: u64| -> Result<u64> {
+        let scaled = (value as u128)
+            .checked_mul(rate.exchange_rate)
+            .and_then(|value| value.checked_mul((10_000 - haircut_bps) as u128))
+            .ok_or(ErrorCode::Overflow)?
+            / (EXCHANGE_RATE_PRECISION * 10_000);
+        u64::try_from(scaled).map_err(|_| ErrorCode::Overflow.into())
+    };
+    price_cache.price = to_receipt(underlying_price.price)?;
+    price_cache.confidence = to_receipt(underlying_price.confidence)?;
+    price_cache.publish_time = underlying_price.publish_time;
+    price_cache.reference_price = underlying_price.price;
+
+    emit!(PriceRefreshedEvent {
+        collateral_type: price_cache.collateral_type,
+        price: price_cache.price,
+        confidence: price_cache.confidence,
+        publish_time: price_cache.publish_time,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Auto-Deleverage Instructions
+// -------------------------------------
+
+/// Opt a position into keeper-run protective deleveraging.
+pub fn enable_auto_deleverage(ctx: Context<EnableAutoDeleverage>, params: DeleverageParams) -> Result<()> {
+    validate_deleverage_params(&params)?;
+
+    let deleverage_config = &mut ctx.accounts.deleverage_config;
+    deleverage_config.user_account = ctx.accounts.user_account.key();
+    deleverage_config.params = params;
+    deleverage_config.total_repaid = 0;
+    deleverage_config.last_deleveraged_at = 0;
+    deleverage_config.bump = ctx.bumps.deleverage_config;
+
+    emit_deleverage_config(deleverage_config, true);
+
+    Ok(())
+}
+
+/// Change a position's deleverage trigger, target, slippage or tip.
+pub fn set_auto_deleverage(ctx: Context<ManageAutoDeleverage>, params: DeleverageParams) -> Result<()> {
+    validate_deleverage_params(&params)?;
+
+    let deleverage_config = &mut ctx.accounts.deleverage_config;
+    deleverage_config.params = params;
+
+    emit_deleverage_config(deleverage_config, true);
+
+    Ok(())
+}
+
+/// Opt a position out of auto-deleveraging, closing its config.
+pub fn disable_auto_deleverage(ctx: Context<ManageAutoDeleverage>) -> Result<()> {
+    let deleverage_config = &ctx.accounts.deleverage_config;
+    emit_deleverage_config(deleverage_config, false);
+
+    deleverage_config.close(ctx.accounts.owner.to_account_info())
+}
+
+/// Deleverage an opted-in position whose health fell below its trigger. Callable by any keeper.
+///
+/// The keeper burns `repay_amount` of its own stablecoin against the
+/// position's debt and buys the matching collateral at the oracle price less
+/// `discount_bps`, which may not exceed the owner's slippage limit. The owner
+/// also pays the keeper its tip in collateral. The position must end up
+/// healthier, but no healthier than the owner's target.
+pub fn auto_deleverage(ctx: Context<AutoDeleverage>, repay_amount: u64, discount_bps: u64) -> Result<()> {
+    check_not_paused(&ctx.accounts.pause_state, PausableAction::Liquidate)?;
+    let params = ctx.accounts.deleverage_config.params;
+    require!(discount_bps <= params.max_slippage_bps, ErrorCode::SlippageExceeded);
+
+    let user_account = &mut ctx.accounts.user_account;
+    let collateral_type = &ctx.accounts.collateral_type;
+    let price_cache = &ctx.accounts.price_cache;
+    let health_before = position_health_bps(user_account, Some(collateral_type), Some(price_cache))?;
+    require!(health_before < params.trigger_health_bps, ErrorCode::DeleverageNotTriggered);
+
+    let repaid = repay_amount.min(user_account.stablecoin_balance);
+    require!(repaid > 0, ErrorCode::InvalidAmount);
+    let oracle_price = oracle::read_cached_price(price_cache, collateral_type)?;
+    let collateral_sold = math::mul_div_down(
+        repaid as u128 * (10_000 + discount_bps) as u128,
+        PRICE_PRECISION as u128,
+        oracle_price.price as u128 * 10_000,
+    )?;
+    let tip = math::mul_div_down(repaid as u128 * params.tip_bps as u128, PRICE_PRECISION as u128, oracle_price.price as u128 * 10_000)?;
+    let collateral_out = collateral_sold.checked_add(tip).ok_or(ErrorCode::Overflow)?;
+    require!(collateral_out <= user_account.collateral_balance, ErrorCode::InsufficientCollateral);
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        from: ctx.accounts.keeper_stablecoin_account.to_account_info(),
+        authority: ctx.accounts.keeper.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, repaid)?;
+
+    user_account.stablecoin_balance -= repaid;
+    user_account.collateral_balance -= collateral_out;
+    let health_after = position_health_bps(user_account, Some(collateral_type), Some(price_cache))?;
+    require!(
+        health_after > health_before && (health_after <= params.target_health_bps || user_account.stablecoin_balance == 0),
+        ErrorCode::DeleverageOutOfRange
+    );
+    update_health_band(user_account, &ctx.accounts.system_state, Some(collateral_type), Some(price_cache))?;
+    release_collateral_debt(&mut ctx.accounts.collateral_type, &mut ctx.accounts.protocol_stats, repaid);
+
+    let keeper_position = &mut ctx.accounts.keeper_position;
+    keeper_position.collateral_balance = keeper_position.collateral_balance
+        .checked_add(collateral_out)
+        .ok_or(ErrorCode::Overflow)?;
+    record_ledger_entry(ctx.accounts.position_ledger.as_deref_mut(), LedgerEntryKind::Repay, collateral_out, repaid, oracle_price.price)?;
+    record_ledger_entry(ctx.accounts.keeper_ledger.as_deref_mut(), LedgerEntryKind::Deposit, collateral_out, 0, oracle_price.price)?;
+
+    let deleverage_config = &mut ctx.accounts.deleverage_config;
+    deleverage_config.total_repaid = deleverage_config.total_repaid.checked_add(repaid).ok_or(ErrorCode::Overflow)?;
+    deleverage_config.last_deleveraged_at = Clock::get()?.unix_timestamp;
+
+    emit!(AutoDeleveragedEvent {
+        user: ctx.accounts.user_account.key(),
+        keeper: ctx.accounts.keeper.key(),
+        repaid,
+        collateral_sold,
+        tip,
+        discount_bps,
+        health_before,
+        health_after,
+    });
+
+    Ok(())
+}
+
+fn validate_deleverage_params(params: &DeleverageParams) -> Result<()> {
+    // The trigger must fire before liquidation, and the target must leave room above it
+    require!(
+        params.trigger_health_bps > 10_000 && params.target_health_bps > params.trigger_health_bps,
+        ErrorCode::InvalidDeleverageParams
+    );
+    require!(
+        params.max_slippage_bps <= MAX_DELEVERAGE_SLIPPAGE_BPS && params.tip_bps <= MAX_DELEVERAGE_TIP_BPS,
+        ErrorCode::InvalidDeleverageParams
+    );
+
+    Ok(())
+}
+
+fn emit_deleverage_config(deleverage_config: &DeleverageConfig, enabled: bool) {
+    emit!(AutoDeleverageConfiguredEvent {
+        user: deleverage_config.user_account,
+        enabled,
+        trigger_health_bps: deleverage_config.params.trigger_health_bps,
+        target_health_bps: deleverage_config.params.target_health_bps,
+        max_slippage_bps: deleverage_config.params.max_slippage_bps,
+        tip_bps: deleverage_config.params.tip_bps,
+    });
+}
+
+// -------------------------------------
+// Pool Sunset Instructions
+// -------------------------------------
+
+/// Start winding down a reward pool. Restricted to governance.
+///
+/// Emissions and revenue funding stop at once, with rewards accrued so far
+/// still claimable. New stakes are refused and every staker may withdraw
+/// without the early withdrawal penalty until the deadline, after which
+/// unclaimed rewards are forfeited to the treasury.
+pub fn begin_pool_sunset(ctx: Context<BeginPoolSunset>, withdrawal_window_secs: u64) -> Result<()> {
+    require!(withdrawal_window_secs >= MIN_SUNSET_WINDOW_SECS, ErrorCode::InvalidLockupPeriod);
+    let now = Clock::get()?.unix_timestamp;
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    update_reward_pool(reward_pool, now as u64)?;
+    reward_pool.reward_rate = 0;
+    reward_pool.revenue_share_bps = 0;
+    reward_pool.sunset_deadline = now.checked_add(withdrawal_window_secs as i64).ok_or(ErrorCode::Overflow)?;
+
+    emit!(PoolSunsetStartedEvent {
+        reward_pool: reward_pool.key(),
+        total_staked: reward_pool.total_staked,
+        withdrawal_deadline: reward_pool.sunset_deadline,
+    });
+
+    Ok(())
+}
+
+/// Sweep the unclaimed revenue rewards of a sunset pool to the treasury, once its window has ended.
+/// Restricted to governance.
+pub fn sweep_sunset_rewards(ctx: Context<SweepSunsetRewards>) -> Result<()> {
+    require_sunset_ended(&ctx.accounts.reward_pool)?;
+
+    let swept = sweep_reward_vault(
+        &ctx.accounts.reward_pool,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.treasury_account,
+        &ctx.accounts.staking_authority,
+        &ctx.accounts.token_program,
+    )?;
+
+    emit!(PoolRewardsSweptEvent {
+        reward_pool: ctx.accounts.reward_pool.key(),
+        treasury_account: ctx.accounts.treasury_account.key(),
+        amount: swept,
+    });
+
+    Ok(())
+}
+
+/// Return a staker's remaining stake from a sunset pool to its owner, once the window has ended.
+///
+/// Anyone may call this, so stakers who never withdraw cannot keep the pool
+/// from being closed. The stake only ever goes to the owner's own token
+/// account, shared pro rata with any bad debt slash like a withdrawal.
+pub fn return_sunset_stake(ctx: Context<ReturnSunsetStake>) -> Result<()> {
+    require_sunset_ended(&ctx.accounts.reward_pool)?;
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    let staker_account = &mut ctx.accounts.staker_account;
+    let staked = staker_account.staked_balance;
+    require!(staked > 0, ErrorCode::InsufficientStakingBalance);
+
+    let vault_balance = ctx.accounts.staking_pool.amount
+        .checked_add(reward_pool.unsettled_stake)
+        .ok_or(ErrorCode::Overflow)?;
+    let mut returned = staked;
+    if vault_balance < reward_pool.total_staked {
+        returned = (returned as u128 * vault_balance as u128 / reward_pool.total_staked as u128) as u64;
+    }
+
+    // Mint the rewards `claim_to_stake` credited to this stake into the vault before paying out
+    let reward_pool_key = reward_pool.key();
+    let unsettled_stake = staker_account.unsettled_stake;
+    if unsettled_stake > 0 {
+        let emission_schedule = ctx.accounts.emission_schedule.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let reward_token_mint = ctx.accounts.reward_token_mint.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+        let reward_mint_authority = ctx.accounts.reward_mint_authority.as_ref().ok_or(ErrorCode::InvalidAccountData)?;
+
+        let bump = [emission_schedule.authority_bump];
+        let seeds: &[&[u8]] = &[b"reward_mint_authority", reward_pool_key.as_ref(), &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = MintTo {
+            mint: reward_token_mint.to_account_info(),
+            to: ctx.accounts.staking_pool.to_account_info(),
+            authority: reward_mint_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::mint_to(cpi_ctx, unsettled_stake)?;
+
+        staker_account.unsettled_stake = 0;
+        reward_pool.unsettled_stake = reward_pool.unsettled_stake.checked_sub(unsettled_stake).ok_or(ErrorCode::Overflow)?;
+    }
+
+    let bump = [reward_pool.staking_authority_bump];
+    let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.staking_pool.to_account_info(),
+        to: ctx.accounts.owner_token_account.to_account_info(),
+        authority: ctx.accounts.staking_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, returned)?;
+
+    // Rewards stopped at the deadline, so the stake leaves without settling
+    staker_account.staked_balance = 0;
+    staker_account.unstake_requested_at = 0;
+    reward_pool.total_staked = reward_pool.total_staked.checked_sub(staked).ok_or(ErrorCode::Overflow)?;
+    set_working_balance(reward_pool, staker_account, BASE_BOOST_BPS)?;
+
+    emit!(SunsetStakeReturnedEvent {
+        reward_pool: reward_pool_key,
+        staker: staker_account.key(),
+        owner_token_account: ctx.accounts.owner_token_account.key(),
+        amount: returned,
+        returned_by: ctx.accounts.caller.key(),
+    });
+
+    Ok(())
+}
+
+/// Close a sunset pool once its window has ended and every staker has left. Restricted to governance.
+///
+/// Stakers still in the pool after the deadline are paid out first with
+/// `return_sunset_stake`. Penalties still held by the staking vault go to `residual_destination`,
+/// leftover revenue rewards to the treasury, and the vaults, emission
+/// schedule and pool account are closed.
+pub fn close_sunset_pool(ctx: Context<CloseSunsetPool>) -> Result<()> {
+    require_sunset_ended(&ctx.accounts.reward_pool)?;
+    let reward_pool_key = ctx.accounts.reward_pool.key();
+    let bump = [ctx.accounts.reward_pool.staking_authority_bump];
+    let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+    let signer_seeds = &[seeds];
+
+    let residual = ctx.accounts.staking_pool.amount;
+    if residual > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.staking_pool.to_account_info(),
+            to: ctx.accounts.residual_destination.to_account_info(),
+            authority: ctx.accounts.staking_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, residual)?;
+    }
+    close_pool_vault(&ctx.accounts.staking_pool, &ctx.accounts, signer_seeds)?;
+
+    let swept = match ctx.accounts.reward_pool.reward_source {
+        RewardSource::Revenue => {
+            let (Some(reward_vault), Some(treasury_account)) = (ctx.accounts.reward_vault.as_ref(), ctx.accounts.treasury_account.as_ref()) else {
+                return err!(ErrorCode::InvalidAccountData);
+            };
+            let swept = sweep_reward_vault(
+                &ctx.accounts.reward_pool,
+                reward_vault,
+                treasury_account,
+                &ctx.accounts.staking_authority,
+                &ctx.accounts.token_program,
+            )?;
+            close_pool_vault(reward_vault, &ctx.accounts, signer_seeds)?;
+            swept
+        }
+        RewardSource::Emissions => 0,
+    };
+
+    emit!(PoolSunsetClosedEvent {
+        reward_pool: reward_pool_key,
+        residual_stake: residual,
+        swept_rewards: swept,
+        closed_by: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+fn require_sunset_ended(reward_pool: &RewardPool) -> Result<()> {
+    require!(
+        reward_pool.sunset_deadline != 0 && Clock::get()?.unix_timestamp >= reward_pool.sunset_deadline,
+        ErrorCode::SunsetWindowOpen
+    );
+
+    Ok(())
+}
+
+/// Move a revenue pool's whole reward vault balance to the treasury. Returns the amount swept.
+fn sweep_reward_vault<'info>(
+    reward_pool: &Account<'info, RewardPool>,
+    reward_vault: &Account<'info, TokenAccount>,
+    treasury_account: &Account<'info, TokenAccount>,
+    staking_authority: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let amount = reward_vault.amount;
+    if amount > 0 {
+        let reward_pool_key = reward_pool.key();
+        let bump = [reward_pool.staking_authority_bump];
+        let seeds: &[&[u8]] = &[b"staking_authority", reward_pool_key.as_ref(), &bump];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: reward_vault.to_account_info(),
+            to: treasury_account.to_account_info(),
+            authority: staking_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+        token::transfer(cpi_ctx, amount)?;
+    }
+
+    Ok(amount)
+}
+
+/// Close an emptied vault of the pool, returning its rent to governance.
+fn close_pool_vault<'info>(vault: &Account<'info, TokenAccount>, accounts: &CloseSunsetPool<'info>, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: accounts.governance_authority.to_account_info(),
+        authority: accounts.staking_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::close_account(cpi_ctx)
+}
+
+// -------------------------------------
+// Liquidity Coverage Instructions
+// -------------------------------------
+
+/// Start monitoring the coverage of redemptions by a treasury's liquid reserves.
+pub fn initialize_liquidity_monitor(ctx: Context<InitializeLiquidityMonitor>, alert_bps: u64, floor_bps: u64) -> Result<()> {
+    validate_coverage_thresholds(alert_bps, floor_bps)?;
+
+    let liquidity_monitor = &mut ctx.accounts.liquidity_monitor;
+    liquidity_monitor.treasury_reserves = ctx.accounts.treasury_reserves.key();
+    liquidity_monitor.volume_buckets = [0; LCR_WINDOW_BUCKETS];
+    liquidity_monitor.current_hour = Clock::get()?.unix_timestamp / LCR_BUCKET_SECS;
+    liquidity_monitor.alert_bps = alert_bps;
+    liquidity_monitor.floor_bps = floor_bps;
+    liquidity_monitor.last_coverage_bps = u64::MAX;
+    liquidity_monitor.bump = ctx.bumps.liquidity_monitor;
+
+    Ok(())
+}
+
+/// Set the coverage below which alerts are emitted and the hard floor that unlocks recalls.
+pub fn set_liquidity_thresholds(ctx: Context<SetLiquidityThresholds>, alert_bps: u64, floor_bps: u64) -> Result<()> {
+    validate_coverage_thresholds(alert_bps, floor_bps)?;
+
+    let liquidity_monitor = &mut ctx.accounts.liquidity_monitor;
+    liquidity_monitor.alert_bps = alert_bps;
+    liquidity_monitor.floor_bps = floor_bps;
+
+    emit!(LiquidityThresholdsUpdatedEvent {
+        treasury_reserves: liquidity_monitor.treasury_reserves,
+        alert_bps,
+        floor_bps,
+    });
+
+    Ok(())
+}
+
+fn validate_coverage_thresholds(alert_bps: u64, floor_bps: u64) -> Result<()> {
+    require!(floor_bps > 0 && floor_bps <= alert_bps, ErrorCode::InvalidCoverageThresholds);
+    Ok(())
+}
+
+/// Recompute the liquidity coverage ratio, alerting when it is below governance's threshold. Callable by anyone.
+pub fn check_liquidity_coverage(ctx: Context<CheckLiquidityCoverage>) -> Result<u64> {
+    let liquid_reserves = ctx.accounts.treasury_reserves.liquid_reserves;
+    update_liquidity_coverage(&mut ctx.accounts.liquidity_monitor, liquid_reserves, Clock::get()?.unix_timestamp)
+}
+
+/// Recall reserves from a strategy while coverage is below the hard floor. Callable by anyone.
+///
+/// Recalls what is needed to lift coverage back to the alert threshold,
+/// capped by what the strategy holds; any yield returned comes with it.
+/// The adapter only receives the fixed strategy accounts, never caller
+/// supplied ones, so adapters needing more go through `recall_from_strategy`.
+pub fn recall_for_liquidity_coverage(ctx: Context<RecallForLiquidityCoverage>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let liquidity_monitor = &mut ctx.accounts.liquidity_monitor;
+    let liquid_reserves = ctx.accounts.treasury_reserves.liquid_reserves;
+    let coverage_bps = update_liquidity_coverage(liquidity_monitor, liquid_reserves, now)?;
+    require!(coverage_bps < liquidity_monitor.floor_bps, ErrorCode::LiquidityCoverageAboveFloor);
+
+    let target = math::mul_div_up(trailing_redemption_volume(liquidity_monitor) as u128, liquidity_monitor.alert_bps as u128, 10_000)?;
+    let amount = target.saturating_sub(liquid_reserves).min(ctx.accounts.strategy.deployed);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let balance_before = ctx.accounts.reserve_vault.amount;
+    let adapter_accounts = StrategyAdapterAccounts {
+        adapter_program: ctx.accounts.adapter_program.as_ref(),
+        reserve_vault: ctx.accounts.reserve_vault.as_ref(),
+        strategy_account: ctx.accounts.strategy_account.as_ref(),
+        treasury_authority: ctx.accounts.treasury_authority.as_ref(),
+        token_program: ctx.accounts.token_program.as_ref(),
+        authority_bump: ctx.accounts.treasury_reserves.authority_bump,
+    };
+    acquire_operation_lock(&mut ctx.accounts.system_state)?;
+    call_strategy_adapter(&adapter_accounts, &[], STRATEGY_WITHDRAW_IX, amount)?;
+    release_operation_lock(&mut ctx.accounts.system_state);
+    ctx.accounts.reserve_vault.reload()?;
+    let received = ctx.accounts.reserve_vault.amount.checked_sub(balance_before).ok_or(ErrorCode::Overflow)?;
+    let yield_earned = record_strategy_recall(&mut ctx.accounts.strategy, &mut ctx.accounts.treasury_reserves, received)?;
+
+    ctx.accounts.liquidity_monitor.last_coverage_bps = liquidity_coverage_bps(
+        ctx.accounts.treasury_reserves.liquid_reserves,
+        trailing_redemption_volume(&ctx.accounts.liquidity_monitor),
+    );
+
+    emit!(StrategyRecalledEvent {
+        strategy: ctx.accounts.strategy.key(),
+        amount: received,
+        yield_earned,
+        liquid_reserves: ctx.accounts.treasury_reserves.liquid_reserves,
+        deployed_reserves: ctx.accounts.treasury_reserves.deployed_reserves,
+    });
+
+    Ok(())
+}
+
+/// Roll the monitor to `now` and store the current coverage, emitting an alert below the threshold.
+fn update_liquidity_coverage(liquidity_monitor: &mut LiquidityMonitor, liquid_reserves: u64, now: i64) -> Result<u64> {
+    roll_volume_buckets(liquidity_monitor, now);
+    let trailing_volume = trailing_redemption_volume(liquidity_monitor);
+    let coverage_bps = liquidity_coverage_bps(liquid_reserves, trailing_volume);
+    liquidity_monitor.last_coverage_bps = coverage_bps;
+
+    if coverage_bps < liquidity_monitor.alert_bps {
+        emit!(LiquidityCoverageAlertEvent {
+            treasury_reserves: liquidity_monitor.treasury_reserves,
+            coverage_bps,
+            liquid_reserves,
+            trailing_volume,
+            below_floor: coverage_bps < liquidity_monitor.floor_bps,
+        });
+    }
+
+    Ok(coverage_bps)
+}
+
+/// Add a redemption fill to the current hour's volume bucket.
+pub fn record_redemption_volume(liquidity_monitor: &mut LiquidityMonitor, amount: u64, now: i64) -> Result<()> {
+    roll_volume_buckets(liquidity_monitor, now);
+    let bucket = &mut liquidity_monitor.volume_buckets[(liquidity_monitor.current_hour.rem_euclid(LCR_WINDOW_BUCKETS as i64)) as usize];
+    *bucket = bucket.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    Ok(())
+}
+
+/// Clear the buckets of hours that passed since the last update, so only the trailing window is summed.
+fn roll_volume_buckets(liquidity_monitor: &mut LiquidityMonitor, now: i64) {
+    let hour = now / LCR_BUCKET_SECS;
+    let elapsed = hour.saturating_sub(liquidity_monitor.current_hour).min(LCR_WINDOW_BUCKETS as i64);
+    for offset in 1..=elapsed {
+        let index = (liquidity_monitor.current_hour + offset).rem_euclid(LCR_WINDOW_BUCKETS as i64) as usize;
+        liquidity_monitor.volume_buckets[index] = 0;
+    }
+    liquidity_monitor.current_hour = liquidity_monitor.current_hour.max(hour);
+}
+
+/// Redemptions filled over the trailing window.
+pub fn trailing_redemption_volume(liquidity_monitor: &LiquidityMonitor) -> u64 {
+    liquidity_monitor.volume_buckets.iter().fold(0u64, |total, volume| total.saturating_add(*volume))
+}
+
+/// Liquid reserves as a share of trailing redemption volume (bps); `u64::MAX` while nothing was redeemed.
+pub fn liquidity_coverage_bps(liquid_reserves: u64, trailing_volume: u64) -> u64 {
+    if trailing_volume == 0 {
+        return u64::MAX;
+    }
+    u64::try_from(liquid_reserves as u128 * 10_000 / trailing_volume as u128).unwrap_or(u64::MAX)
+}
+
+// -------------------------------------
+// Partner Instructions
+// -------------------------------------
+
+/// Register an integrator as partner `partner_id`, paying its revenue share to `wallet`.
+pub fn register_partner(ctx: Context<RegisterPartner>, partner_id: u64, wallet: Pubkey, revenue_share_bps: u64) -> Result<()> {
+    require!(revenue_share_bps <= MAX_PARTNER_SHARE_BPS, ErrorCode::InvalidPartnerTerms);
+    require_keys_neq!(wallet, Pubkey::default(), ErrorCode::InvalidPartnerTerms);
+
+    let partner = &mut ctx.accounts.partner;
+    partner.partner_id = partner_id;
+    partner.wallet = wallet;
+    partner.revenue_share_bps = revenue_share_bps;
+    partner.active = true;
+    partner.originated_volume = 0;
+    partner.accrued = 0;
+    partner.total_claimed = 0;
+    partner.bump = ctx.bumps.partner;
+
+    emit!(PartnerTermsUpdatedEvent {
+        partner: partner.key(),
+        partner_id,
+        wallet,
+        revenue_share_bps,
+        active: true,
+    });
+
+    Ok(())
+}
+
+/// Renegotiate a partner's revenue share or stop new flows accruing to it.
+///
+/// Revenue already accrued stays claimable after a partner is deactivated.
+pub fn set_partner_terms(ctx: Context<SetPartnerTerms>, wallet: Pubkey, revenue_share_bps: u64, active: bool) -> Result<()> {
+    require!(revenue_share_bps <= MAX_PARTNER_SHARE_BPS, ErrorCode::InvalidPartnerTerms);
+    require_keys_neq!(wallet, Pubkey::default(), ErrorCode::InvalidPartnerTerms);
+
+    let partner = &mut ctx.accounts.partner;
+    partner.wallet = wallet;
+    partner.revenue_share_bps = revenue_share_bps;
+    partner.active = active;
+
+    emit!(PartnerTermsUpdatedEvent {
+        partner: partner.key(),
+        partner_id: partner.partner_id,
+        wallet,
+        revenue_share_bps,
+        active,
+    });
+
+    Ok(())
+}
+
+/// Pay a partner its accrued revenue share out of the treasury.
+///
+/// Paid through the treasury's revenue delegation, so a claim larger than
+/// the remaining allowance is paid in part and the rest stays accrued.
+pub fn claim_partner_revenue(ctx: Context<ClaimPartnerRevenue>) -> Result<()> {
+    let amount = ctx.accounts.partner.accrued
+        .min(treasury_allowance(&ctx.accounts.treasury_account, &ctx.accounts.revenue_authority));
+    require!(amount > 0, ErrorCode::NoRewardsAvailable);
+
+    let bump = [ctx.bumps.revenue_authority];
+    let seeds: &[&[u8]] = &[b"revenue_authority", &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.treasury_account.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: ctx.accounts.revenue_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+
+    let partner = &mut ctx.accounts.partner;
+    partner.accrued -= amount;
+    partner.total_claimed = partner.total_claimed.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    emit!(PartnerRevenueClaimedEvent {
+        partner: partner.key(),
+        partner_id: partner.partner_id,
+        amount,
+        remaining: partner.accrued,
+    });
+
+    Ok(())
+}
+
+/// Credit the partner named by `partner_id` with its share of `fees` charged on `volume`.
+///
+/// Flows without a partner id accrue nothing; a deactivated partner still
+/// validates but earns no further revenue.
+fn accrue_partner_revenue(partner: Option<&mut Account<Partner>>, partner_id: Option<u64>, volume: u64, fees: u64) -> Result<u64> {
+    let Some(partner_id) = partner_id else {
+        return Ok(0);
+    };
+    let partner = partner.ok_or(ErrorCode::InvalidPartner)?;
+    require!(partner.partner_id == partner_id, ErrorCode::InvalidPartner);
+    if !partner.active {
+        return Ok(0);
+    }
+
+    let share = math::mul_div_down(fees as u128, partner.revenue_share_bps as u128, math::BPS_DENOMINATOR as u128)?;
+    partner.originated_volume = partner.originated_volume.checked_add(volume).ok_or(ErrorCode::Overflow)?;
+    partner.accrued = partner.accrued.checked_add(share).ok_or(ErrorCode::Overflow)?;
+
+    emit!(PartnerRevenueAccruedEvent {
+        partner: partner.key(),
+        partner_id,
+        volume,
+        fees,
+        share,
+        accrued: partner.accrued,
+    });
+
+    Ok(share)
+}
+
+// -------------------------------------
+// Event Definitions
+// -------------------------------------
+
+#[event]
+pub struct ProtocolInitialized {
+    pub collateral_ratio: u64,
+}
+
+#[event]
+pub struct MintStablecoinEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub rounding_remainder: u64,
+    pub fee_credits_used: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct LiquidationEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct StakeEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnstakeDelayedEvent {
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct WithdrawStakeEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposer: Pubkey,
+    pub proposal_id: Pubkey,
+    pub action: ProposalAction,
+    pub voting_period_end: u64,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: Pubkey,
+    pub action: ProposalAction,
+    pub new_collateral_ratio: Option<u64>,
+    pub new_reward_rate: Option<u64>,
+    pub new_minting_fee_rate: Option<u64>,
+    pub new_redemption_fee_rate: Option<u64>,
+}
+
+#[event]
+pub struct ProposalVotedEvent {
+    pub voter: Pubkey,
+    pub proposal_id: Pubkey,
+    pub approved: bool,
+}
+
+#[event]
+pub struct CollateralTypeAddedEvent {
+    pub collateral_mint: Pubkey,
+    pub collateral_ratio: u64,
+}
+
+#[event]
+pub struct FeeCreditsPurchasedEvent {
+    pub user: Pubkey,
+    pub burned: u64,
+    pub credits: u64,
+    pub fee_credits: u64,
+}
+
+#[event]
+pub struct MintStablecoinWithCollateralEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub collateral_type: Pubkey,
+    pub fee: u64,
+    pub premium: u64,
+    pub rounding_remainder: u64,
+    pub fee_credits_used: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct StrategyWhitelistedEvent {
+    pub strategy: Pubkey,
+    pub adapter_program: Pubkey,
+    pub cap: u64,
+}
+
+#[event]
+pub struct StrategyCapUpdatedEvent {
+    pub strategy: Pubkey,
+    pub cap: u64,
+    pub active: bool,
+}
+
+#[event]
+pub struct StrategyDeployedEvent {
+    pub strategy: Pubkey,
+    pub amount: u64,
+    pub liquid_reserves: u64,
+    pub deployed_reserves: u64,
+}
+
+#[event]
+pub struct StrategyRecalledEvent {
+    pub strategy: Pubkey,
+    pub amount: u64,
+    pub yield_earned: u64,
+    pub liquid_reserves: u64,
+    pub deployed_reserves: u64,
+}
+
+#[event]
+pub struct ReserveReportEvent {
+    pub report: Pubkey,
+    pub epoch: u64,
+    pub collateral_value: u64,
+    pub psm_reserves: u64,
+    pub deployed_reserves: u64,
+    pub total_backing: u64,
+    pub report_hash: [u8; 32],
+    pub attestor: Pubkey,
+}
+
+#[event]
+pub struct BlacklistUpdatedEvent {
+    pub wallet: Pubkey,
+    pub blacklisted: bool,
+}
+
+#[event]
+pub struct SavingsRateUpdatedEvent {
+    pub wrapper_vault: Pubkey,
+    pub savings_rate_bps: u64,
+    pub exchange_rate: u128,
+}
+
+#[event]
+pub struct WrapEvent {
+    pub user: Pubkey,
+    pub stablecoin_amount: u64,
+    pub wrapped_amount: u64,
+    pub exchange_rate: u128,
+}
+
+#[event]
+pub struct UnwrapEvent {
+    pub user: Pubkey,
+    pub stablecoin_amount: u64,
+    pub wrapped_amount: u64,
+    pub exchange_rate: u128,
+}
+
+#[event]
+pub struct BondMarketCreatedEvent {
+    pub bond_market: Pubkey,
+    pub quote_mint: Pubkey,
+    pub payout_mint: Pubkey,
+    pub initial_price: u64,
+    pub capacity: u64,
+}
+
+#[event]
+pub struct BondPurchasedEvent {
+    pub bond_market: Pubkey,
+    pub bond_position: Pubkey,
+    pub buyer: Pubkey,
+    pub quote_amount: u64,
+    pub payout: u64,
+    pub price: u64,
+}
+
+#[event]
+pub struct BondRedeemedEvent {
+    pub bond_position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AirdropDistributorCreatedEvent {
+    pub distributor: Pubkey,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub max_num_nodes: u64,
+}
+
+#[event]
+pub struct AirdropClaimedEvent {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VestingStreamCreatedEvent {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub rate_per_second: u64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub total: u64,
+}
+
+#[event]
+pub struct VestingStreamWithdrawnEvent {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct HealthDeteriorated {
+    pub user: Pubkey,
+    pub previous_band: HealthBand,
+    pub new_band: HealthBand,
+    pub health_bps: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct HealthRestored {
+    pub user: Pubkey,
+    pub previous_band: HealthBand,
+    pub new_band: HealthBand,
+    pub health_bps: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct OracleProgramsUpdatedEvent {
+    pub programs: Vec<Pubkey>,
+}
+
+#[event]
+pub struct OracleConfigUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub price_feed: Pubkey,
+    pub max_price_age_secs: u64,
+    pub max_confidence_bps: u64,
+}
+
+#[event]
+pub struct GovTokensMintedEvent {
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub path: GovMintPath,
+    pub remaining_mintable: u64,
+}
+
+#[event]
+pub struct RiskTierUpdatedEvent {
+    pub tier: RiskTier,
+    pub params: RiskParams,
+}
+
+#[event]
+pub struct CollateralOnboardedEvent {
+    pub collateral_type: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub tier: RiskTier,
+    pub params: RiskParams,
+}
+
+#[event]
+pub struct ConcentrationWarningEvent {
+    pub collateral_type: Pubkey,
+    pub share_bps: u64,
+    pub cap_bps: u64,
+}
+
+#[event]
+pub struct CanonicalAccountsUpdatedEvent {
+    pub stablecoin_mint: Pubkey,
+    pub treasury_account: Pubkey,
+}
+
+#[event]
+pub struct ProposalExpiredEvent {
+    pub proposal_id: Pubkey,
+    pub proposer: Pubkey,
+    pub bond_refunded: u64,
+}
+
+#[event]
+pub struct LiquidationBonusCurveUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub base_bonus_bps: u64,
+    pub slope_bps: u64,
+    pub max_bonus_bps: u64,
+}
+
+#[event]
+pub struct ConfigFrozenEvent {
+    pub frozen_at: i64,
+}
+
+#[event]
+pub struct UpgradeChecklistItemCompletedEvent {
+    pub item: UpgradeChecklistItem,
+    pub checklist: u8,
+}
+
+#[event]
+pub struct ConfigUnfrozenEvent {
+    pub frozen_at: i64,
+    pub unfrozen_at: i64,
+}
+
+#[event]
+pub struct LaunchModeUpdatedEvent {
+    pub launch_mode: bool,
+    pub collateral_cap: u64,
+    pub debt_cap: u64,
+}
+
+#[event]
+pub struct ClaimLimitsUpdatedEvent {
+    pub reward_pool: Pubkey,
+    pub min_claim_interval: u64,
+    pub min_claim_amount: u64,
+}
+
+#[event]
+pub struct StakingPoolInitializedEvent {
+    pub reward_pool: Pubkey,
+    pub staking_mint: Pubkey,
+    pub staking_vault: Pubkey,
+}
+
+#[event]
+pub struct LiquidationProceedsDistributedEvent {
+    pub penalty: u64,
+    pub liquidator_share: u64,
+    pub insurance_share: u64,
+    pub surplus_share: u64,
+}
+
+#[event]
+pub struct LiquidationRevenueSharesUpdatedEvent {
+    pub liquidator_share_bps: u64,
+    pub insurance_share_bps: u64,
+    pub surplus_share_bps: u64,
+}
+
+#[event]
+pub struct PositionOpenedEvent {
+    pub user: Pubkey,
+    pub collateral_ratio: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct PriceRefreshedEvent {
+    pub collateral_type: Pubkey,
+    pub price: u64,
+    pub confidence: u64,
+    pub publish_time: i64,
+}
+
+#[event]
+pub struct PriceOverrideSetEvent {
+    pub collateral_type: Pubkey,
+    pub price_floor: u64,
+    pub price_ceiling: u64,
+    pub reference_cap_bps: u64,
+    pub expires_at: i64,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct FullLiquidationEvent {
+    pub user: Pubkey,
+    pub collateral_seized: u64,
+    pub debt: u64,
+    pub debt_repaid: u64,
+    pub insurance_covered: u64,
+    pub bad_debt: u64,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct LiquidationCooldownUpdatedEvent {
+    pub cooldown_secs: u64,
+}
+
+#[event]
+pub struct GuardianSetUpdatedEvent {
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    pub epoch: u64,
+}
+
+#[event]
+pub struct EmergencyActionProposedEvent {
+    pub action_id: u64,
+    pub kind: EmergencyActionKind,
+    pub proposer: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct EmergencyActionExecutedEvent {
+    pub action_id: u64,
+    pub kind: EmergencyActionKind,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct RateSnapshotRecordedEvent {
+    pub epoch: u64,
+    pub stability_fee_index: u128,
+    pub savings_exchange_rate: u128,
+    pub total_debt: u64,
+}
+
+#[event]
+pub struct RateControllerUpdatedEvent {
+    pub min_rate_bps: i64,
+    pub max_rate_bps: i64,
+    pub max_change_per_epoch_bps: u64,
+    pub negative_rate_premium_bps: u64,
+}
+
+#[event]
+pub struct StabilityFeeChangedEvent {
+    pub previous_rate_bps: i64,
+    pub rate_bps: i64,
+    pub stability_fee_index: u128,
+    pub peg_twap_price: u64,
+}
+
+#[event]
+pub struct PermitConsumedEvent {
+    pub user: Pubkey,
+    pub relayer: Pubkey,
+    pub action: PermitAction,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct CampaignCreatedEvent {
+    pub campaign: Pubkey,
+    pub target: CampaignTarget,
+    pub reward_mint: Pubkey,
+    pub reward_rate: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct CampaignFundedEvent {
+    pub campaign: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub funded_amount: u64,
+}
+
+#[event]
+pub struct CampaignJoinedEvent {
+    pub campaign: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub weight: u64,
+}
+
+#[event]
+pub struct CampaignRewardsClaimedEvent {
+    pub campaign: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CampaignTerminatedEvent {
+    pub campaign: Pubkey,
+    pub emitted_amount: u64,
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct FeeRatesUpdatedEvent {
+    pub minting_fee_rate: u64,
+    pub redemption_fee_rate: u64,
+}
+
+#[event]
+pub struct MintSizeFeeUpdatedEvent {
+    pub threshold_bps: u64,
+    pub slope_bps: u64,
+    pub max_surcharge_bps: u64,
+}
+
+#[event]
+pub struct CollateralFeeOverridesUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub mint_fee_bps: Option<u64>,
+    pub redemption_fee_bps: Option<u64>,
+}
+
+#[event]
+pub struct PositionMigratedEvent {
+    pub old_position: Pubkey,
+    pub new_position: Pubkey,
+    pub owner: Pubkey,
+    pub version: u8,
+    pub collateral_balance: u64,
+    pub stablecoin_balance: u64,
+    pub stability_fee_index: u128,
+}
+
+#[event]
+pub struct InvariantReportEvent {
+    pub stablecoin_supply: u64,
+    pub accounted_supply: u64,
+    pub staking_vault_balance: Option<u64>,
+    pub total_staked: Option<u64>,
+    pub wrapper_vault_balance: Option<u64>,
+    pub wrapper_obligations: Option<u64>,
+    pub collateral_vault_balance: Option<u64>,
+    pub recorded_collateral: Option<u64>,
+}
+
+#[event]
+pub struct PegBandUpdatedEvent {
+    pub max_deviation_bps: u64,
+    pub twap_window_secs: u64,
+    pub max_price_age_secs: u64,
+    pub enforced: bool,
+}
+
+#[event]
+pub struct PegTwapUpdatedEvent {
+    pub spot_price: u64,
+    pub twap_price: u64,
+    pub minting_allowed: bool,
+}
+
+#[event]
+pub struct CollateralYieldConfigUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub exchange_rate: u128,
+    pub yield_skim_bps: u64,
+}
+
+#[event]
+pub struct CollateralYieldSkimmedEvent {
+    pub user: Pubkey,
+    pub collateral_type: Pubkey,
+    pub amount: u64,
+    pub entry_exchange_rate: u128,
+    pub exchange_rate: u128,
+}
+
+#[event]
+pub struct VeLockCreatedEvent {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub unlock_time: i64,
+    pub ve_balance: u64,
+}
+
+#[event]
+pub struct BoostUpdatedEvent {
+    pub staker: Pubkey,
+    pub ve_lock: Pubkey,
+    pub boost_bps: u64,
+    pub working_balance: u64,
+}
+
+#[event]
+pub struct StakeOwnershipTransferredEvent {
+    pub staker: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub ve_lock: Pubkey,
+    pub staked_balance: u64,
+}
+
+#[event]
+pub struct SubaccountCreatedEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_type: Pubkey,
+    pub index: u16,
+    pub tag: [u8; 32],
+}
+
+#[event]
+pub struct MarginModeChangedEvent {
+    pub owner: Pubkey,
+    pub mode: MarginMode,
+}
+
+#[event]
+pub struct CrossPositionUpdatedEvent {
+    pub owner: Pubkey,
+    pub user: Pubkey,
+    pub pooled: bool,
+}
+
+#[event]
+pub struct PauseStateChangedEvent {
+    pub minting_paused: bool,
+    pub redemptions_paused: bool,
+    pub liquidations_paused: bool,
+    pub governance_paused: bool,
+    pub emergency_shutdown: bool,
+    pub reason: PauseReason,
+    pub actor: Pubkey,
+}
+
+#[event]
+pub struct CollateralMintingPausedEvent {
+    pub collateral_type: Pubkey,
+    pub minting_paused: bool,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuctionParamsUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub start_premium_bps: u64,
+    pub floor_bps: u64,
+    pub duration_secs: u64,
+    pub buy_now_premium_bps: u64,
+}
+
+#[event]
+pub struct CollateralAuctionStartedEvent {
+    pub auction: Pubkey,
+    pub position: Pubkey,
+    pub collateral_type: Pubkey,
+    pub collateral_amount: u64,
+    pub debt: u64,
+    pub start_price: u64,
+    pub floor_price: u64,
+}
+
+#[event]
+pub struct AuctionSeriesStartedEvent {
+    pub position: Pubkey,
+    pub collateral_type: Pubkey,
+    pub collateral_amount: u64,         // Collateral seized across all lots
+    pub debt: u64,                      // Debt the lots must cover together
+    pub max_lot_size: u64,
+}
+
+#[event]
+pub struct AuctionSeriesCompletedEvent {
+    pub position: Pubkey,
+    pub lots: u64,                      // Lots the seized collateral was split into
+}
+
+#[event]
+pub struct MaxLotSizeUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub max_lot_size: u64,
+}
+
+#[event]
+pub struct CollateralAuctionSettledEvent {
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    pub collateral_amount: u64,
+    pub price: u64,
+    pub payment: u64,
+    pub settlement_mint: Pubkey,
+    pub insurance_covered: u64,
+    pub bad_debt: u64,
+    pub buy_now: bool,
+}
+
+#[event]
+pub struct AuctionSettlementMintUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub settlement_mint: Pubkey,
+    pub settlement_reserves: Pubkey,
+}
+
+#[event]
+pub struct SealedBidModeUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub sealed_bid: bool,
+    pub commit_secs: u64,
+    pub reveal_secs: u64,
+    pub bid_bond_lamports: u64,
+}
+
+#[event]
+pub struct BidCommittedEvent {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct SealedBidSlashedEvent {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub bond: u64,
+    pub revealed: bool,
+}
+
+#[event]
+pub struct BidRevealedEvent {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+    pub valid: bool,
+    pub best: bool,
+}
+
+#[event]
+pub struct SwapScheduleCreatedEvent {
+    pub swap_schedule: Pubkey,
+    pub destination_vault: Pubkey,
+    pub total_amount: u64,
+    pub slice_amount: u64,
+    pub interval_secs: u64,
+    pub min_output_price: u64,
+}
+
+#[event]
+pub struct SwapSliceExecutedEvent {
+    pub swap_schedule: Pubkey,
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub received: u64,
+    pub executed_amount: u64,
+}
+
+#[event]
+pub struct SwapScheduleCancelledEvent {
+    pub swap_schedule: Pubkey,
+    pub returned_amount: u64,
+    pub executed_amount: u64,
+}
+
+#[event]
+pub struct ConfidencePremiumUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub threshold_bps: u64,
+    pub slope_bps: u64,
+    pub max_premium_bps: u64,
+}
+
+#[event]
+pub struct ConfidencePremiumChargedEvent {
+    pub user: Pubkey,
+    pub collateral_type: Pubkey,
+    pub confidence: u64,
+    pub premium_bps: u64,
+    pub premium: u64,
+}
+
+#[event]
+pub struct DebtAuctionPriceUpdatedEvent {
+    pub debt_auction_price: u64,
+}
+
+#[event]
+pub struct BadDebtSlashingPoolUpdatedEvent {
+    pub slashing_pool: Pubkey,
+}
+
+#[event]
+pub struct BadDebtCreatedEvent {
+    pub source: ShortfallSource,
+    pub position: Pubkey,
+    pub collateral_type: Pubkey,
+    pub debt: u64,
+    pub recovered: u64,
+    pub insurance_covered: u64,
+    pub bad_debt: u64,
+    pub total_bad_debt: u64,
+    pub total_outstanding: u64,
+}
+
+#[event]
+pub struct InsuranceDrawdownEvent {
+    pub source: ShortfallSource,
+    pub collateral_type: Pubkey,
+    pub amount: u64,
+    pub balance_before: u64,
+    pub balance_after: u64,
+}
+
+#[event]
+pub struct SolvencyRatioUpdatedEvent {
+    pub performing_debt: u64,
+    pub bad_debt_outstanding: u64,
+    pub insurance_pool_balance: u64,
+    pub surplus_buffer_balance: u64,
+    pub solvency_ratio_bps: u64,
+}
+
+#[event]
+pub struct BadDebtQueuedEvent {
+    pub amount: u64,
+    pub collateral_type: Pubkey,
+    pub queue_length: u8,
+    pub total_outstanding: u64,
+}
+
+#[event]
+pub struct BadDebtCoveredEvent {
+    pub stage: BadDebtStage,
+    pub collateral_type: Pubkey,
+    pub covered: u64,
+    pub remaining: u64,
+    pub total_outstanding: u64,
+}
+
+#[event]
+pub struct TreasuryBadDebtRepaidEvent {
+    pub treasury_account: Pubkey,
+    pub requested: u64,
+    pub burned: u64,
+    pub entries_cleared: u8,
+    pub outstanding_before: u64,
+    pub total_outstanding: u64,
+    pub total_bad_debt: u64,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct DebtAssignedEvent {
+    pub debt_note: Pubkey,
+    pub position: Pubkey,
+    pub issuer: Pubkey,
+    pub holder: Pubkey,
+    pub face_amount: u64,
+    pub price: u64,
+    pub maturity: i64,
+}
+
+#[event]
+pub struct DebtNoteRepaidEvent {
+    pub debt_note: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub repaid_amount: u64,
+    pub settled: bool,
+}
+
+#[event]
+pub struct DebtNoteTransferredEvent {
+    pub debt_note: Pubkey,
+    pub previous_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+#[event]
+pub struct NoteCollateralSeizedEvent {
+    pub debt_note: Pubkey,
+    pub position: Pubkey,
+    pub holder: Pubkey,
+    pub seized_collateral: u64,
+    pub seized_value: u64,
+    pub settled: bool,
+}
+
+#[event]
+pub struct LoyaltyCollectionUpdatedEvent {
+    pub collection_mint: Pubkey,
+    pub fee_discount_bps: u64,
+    pub whitelisted: bool,
+}
+
+#[event]
+pub struct PaymentStreamCreatedEvent {
+    pub stream: Pubkey,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub rate_per_second: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct PaymentStreamToppedUpEvent {
+    pub stream: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PaymentStreamWithdrawnEvent {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PaymentStreamCancelledEvent {
+    pub stream: Pubkey,
+    pub paid: u64,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct EpochClosedEvent {
+    pub epoch: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub mint_fees: u64,
+    pub liquidation_proceeds: u64,
+    pub interest_accrued: u64,
+    pub savings_paid: u64,
+}
+
+#[event]
+pub struct EmissionBudgetUpdatedEvent {
+    pub reward_pool: Pubkey,
+    pub epoch_duration: u64,
+    pub epoch_budget: u64,
+}
+
+#[event]
+pub struct RewardDelegateSetEvent {
+    pub staker_account: Pubkey,
+    pub owner: Pubkey,
+    pub reward_delegate: Pubkey,
+}
+
+#[event]
+pub struct RewardsStakedEvent {
+    pub staker_account: Pubkey,
+    pub amount: u64,
+    pub staked_balance: u64,
+}
+
+#[event]
+pub struct AutoCompoundSetEvent {
+    pub staker_account: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct ClaimToStakeThresholdUpdatedEvent {
+    pub reward_pool: Pubkey,
+    pub threshold: u64,
+}
+
+#[event]
+pub struct OnboardingRatioUpdatedEvent {
+    pub ratio_bonus: u64,
+    pub period_secs: u64,
+}
+
+#[event]
+pub struct StakePoolWhitelistedEvent {
+    pub collateral_type: Pubkey,
+    pub stake_pool: Pubkey,
+    pub unstake_haircut_bps: u64,
+}
+
+#[event]
+pub struct SolCollateralStakedEvent {
+    pub user: Pubkey,
+    pub collateral_type: Pubkey,
+    pub lamports: u64,
+    pub lst_received: u64,
+}
+
+#[event]
+pub struct GovTokenOracleParamsUpdatedEvent {
+    pub twap_window_secs: u64,
+    pub max_spot_move_bps: u64,
+    pub max_divergence_bps: u64,
+    pub max_price_age_secs: u64,
+    pub max_twap_age_secs: u64,
+    pub min_quote_liquidity: u64,
+}
+
+#[event]
+pub struct GovTokenFallbackPriceSetEvent {
+    pub fallback_price: u64,
+}
+
+#[event]
+pub struct GovTokenTwapUpdatedEvent {
+    pub spot_price: u64,
+    pub applied_price: u64,
+    pub twap_price: u64,
+}
+
+#[event]
+pub struct ProposalFinalizedEvent {
+    pub proposal_id: Pubkey,
+    pub approved: bool,
+    pub approval_votes: u32,
+    pub reject_votes: u32,
+    pub executable_at: u64,             // Earliest execution timestamp (0 if rejected)
+}
+
+#[event]
+pub struct ProposalVetoedEvent {
+    pub proposal_id: Pubkey,
+    pub action: ProposalAction,
+    pub vetoed_by: Pubkey,
+}
+
+#[event]
+pub struct ProposalTimingUpdatedEvent {
+    pub action: ProposalAction,
+    pub voting_period_secs: u64,
+    pub execution_delay_secs: u64,
+}
+
+#[event]
+pub struct CrankIntervalUpdatedEvent {
+    pub kind: CrankKind,
+    pub min_interval_slots: u64,
+}
+
+#[event]
+pub struct RewardSourceSetEvent {
+    pub reward_pool: Pubkey,
+    pub reward_source: RewardSource,
+    pub reward_vault: Pubkey,
+    pub revenue_share_bps: u64,
+}
+
+#[event]
+pub struct EpochRevenuePoolUpdatedEvent {
+    pub revenue_pool: Pubkey,
+}
+
+#[event]
+pub struct RevenueFundedEvent {
+    pub reward_pool: Pubkey,
+    pub staker_share: u64,              // Stakers' share of the epoch's mint fees
+    pub funded: u64,                    // Amount moved from the treasury, capped by its allowance
+    pub undistributed: u64,             // Revenue held back until something is staked
+}
+
+#[event]
+pub struct RevenueRewardsClaimedEvent {
+    pub staker_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LiquidatorReimbursedEvent {
+    pub user: Pubkey,
+    pub liquidator: Pubkey,
+    pub amount: u64,                    // Stablecoin paid out of the treasury
+    pub collateral_debit: u64,          // Surplus buffer collateral the payout was drawn from
+}
+
+#[event]
+pub struct LiquidatorReimbursementUpdatedEvent {
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionLedgerExtendedEvent {
+    pub user_account: Pubkey,
+    pub capacity: u32,
+}
+
+#[event]
+pub struct PositionLedgerFullEvent {
+    pub user_account: Pubkey,
+    pub capacity: u32,
+    pub dropped_entries: u64,
+}
+
+#[event]
+pub struct CeilingControllerUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub params: CeilingControllerParams,
+}
+
+#[event]
+pub struct DebtCeilingAdjustedEvent {
+    pub collateral_type: Pubkey,
+    pub epoch: u64,
+    pub utilization_bps: u64,
+    pub previous_ceiling: u64,
+    pub debt_ceiling: u64,
+}
+
+#[event]
+pub struct MmFacilityUpdatedEvent {
+    pub market_maker: Pubkey,
+    pub terms: MmFacilityTerms,
+    pub active: bool,
+}
+
+#[event]
+pub struct MmCollateralAttestedEvent {
+    pub market_maker: Pubkey,
+    pub attestation_hash: [u8; 32],
+    pub attested_value: u64,
+}
+
+#[event]
+pub struct MmFacilityMintEvent {
+    pub market_maker: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub outstanding_debt: u64,
+}
+
+#[event]
+pub struct MmFacilityRedeemEvent {
+    pub market_maker: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub outstanding_debt: u64,
+}
+
+#[event]
+pub struct TravelRuleAttestorUpdatedEvent {
+    pub attestor: Pubkey,
+    pub approved: bool,
+}
+
+#[event]
+pub struct TravelRuleAttestedEvent {
+    pub attestor: Pubkey,
+    pub memo_hash: [u8; 32],
+    pub originator: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PaymentMemoEvent {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub memo_hash: [u8; 32],
+    pub attestor: Option<Pubkey>,           // Attestor of the matched attestation; None for an unverified memo
+}
+
+#[event]
+pub struct TokensRescuedEvent {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub authority: RescueAuthority,
+}
+
+#[event]
+pub struct PortfolioRefreshedEvent {
+    pub margin_account: Pubkey,
+    pub collateral_value: u128,
+    pub debt: u64,
+    pub health_bps: u64,
+}
+
+#[event]
+pub struct RedemptionPriorityFeeUpdatedEvent {
+    pub redemption_queue: Pubkey,
+    pub priority_fee_bps: u64,
+}
+
+#[event]
+pub struct RedemptionQueuedEvent {
+    pub owner: Pubkey,
+    pub lane: RedemptionLane,
+    pub request_id: u64,
+    pub amount: u64,
+    pub priority_fee: u64,
+    pub queued_amount: u64,
+    pub redemption_fee: u64,
+    pub fee_credits_used: u64,
+}
+
+#[event]
+pub struct RedemptionFilledEvent {
+    pub owner: Pubkey,
+    pub lane: RedemptionLane,
+    pub request_id: u64,
+    pub filled: u64,
+    pub remaining: u64,
+    pub liquid_reserves: u64,
+}
+
+#[event]
+pub struct RedemptionCancelledEvent {
+    pub owner: Pubkey,
+    pub lane: RedemptionLane,
+    pub request_id: u64,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct ReceiptCollateralUpdatedEvent {
+    pub collateral_type: Pubkey,
+    pub market: ReceiptMarket,
+    pub reserve: Pubkey,
+    pub haircut_bps: u64,
+    pub max_rate_age_secs: u64,
+}
+
+#[event]
+pub struct AutoDeleverageConfiguredEvent {
+    pub user: Pubkey,
+    pub enabled: bool,
+    pub trigger_health_bps: u64,
+    pub target_health_bps: u64,
+    pub max_slippage_bps: u64,
+    pub tip_bps: u64,
+}
+
+#[event]
+pub struct AutoDeleveragedEvent {
+    pub user: Pubkey,
+    pub keeper: Pubkey,
+    pub repaid: u64,
+    pub collateral_sold: u64,
+    pub tip: u64,
+    pub discount_bps: u64,
+    pub health_before: u64,
+    pub health_after: u64,
+}
+
+#[event]
+pub struct PoolSunsetStartedEvent {
+    pub reward_pool: Pubkey,
+    pub total_staked: u64,
+    pub withdrawal_deadline: i64,
+}
+
+#[event]
+pub struct PoolRewardsSweptEvent {
+    pub reward_pool: Pubkey,
+    pub treasury_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SunsetStakeReturnedEvent {
+    pub reward_pool: Pubkey,
+    pub staker: Pubkey,
+    pub owner_token_account: Pubkey,
+    pub amount: u64,
+    pub returned_by: Pubkey,
+}
+
+#[event]
+pub struct PoolSunsetClosedEvent {
+    pub reward_pool: Pubkey,
+    pub residual_stake: u64,
+    pub swept_rewards: u64,
+    pub closed_by: Pubkey,
+}
+
+#[event]
+pub struct LiquidityThresholdsUpdatedEvent {
+    pub treasury_reserves: Pubkey,
+    pub alert_bps: u64,
+    pub floor_bps: u64,
+}
+
+#[event]
+pub struct LiquidityCoverageAlertEvent {
+    pub treasury_reserves: Pubkey,
+    pub coverage_bps: u64,
+    pub liquid_reserves: u64,
+    pub trailing_volume: u64,
+    pub below_floor: bool,
+}
+
+#[event]
+pub struct PartnerTermsUpdatedEvent {
+    pub partner: Pubkey,
+    pub partner_id: u64,
+    pub wallet: Pubkey,
+    pub revenue_share_bps: u64,
+    pub active: bool,
+}
+
+#[event]
+pub struct PartnerRevenueAccruedEvent {
+    pub partner: Pubkey,
+    pub partner_id: u64,
+    pub volume: u64,
+    pub fees: u64,
+    pub share: u64,
+    pub accrued: u64,
+}
+
+#[event]
+pub struct PartnerRevenueClaimedEvent {
+    pub partner: Pubkey,
+    pub partner_id: u64,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct IncidentProposalOpenedEvent {
+    pub incident_nonce: u64,
+    pub proposal_id: Pubkey,
+    pub trigger: Pubkey,
 }
@@ -4,9 +4,22 @@ use anchor_lang::solana_program::sysvar::clock::Clock;
 pub mod instructions;
 pub mod state;
 pub mod errors;
+pub mod oracle;
+pub mod gov_oracle;
+pub mod math;
+pub mod permit;
+pub mod loyalty;
+pub mod stake_pool;
+pub mod receipt;
+pub mod transfer_hook;
+pub mod transfer_fee;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 use instructions::*;
-use state::{Initialize, MintStablecoin, MintStablecoinWithCollateral, Liquidate, StakeTokens, WithdrawStake, ClaimRewards, ProposalStatus, CreateProposal, VoteOnProposal, AddCollateralType};
+use state::{Initialize, MintStablecoin, MintStablecoinWithCollateral, Liquidate, StakeTokens, WithdrawStake, ClaimRewards, ProposalStatus, CreateProposal, VoteOnProposal, AddCollateralType, InitializeSystemState, InitializeTreasuryReserves, WhitelistStrategy, SetStrategyCap, ManageStrategyFunds, InitializeReserveAttestation, SetReserveAttestor, PublishReserveReport, AddToBlacklist, RemoveFromBlacklist, InitializeWrapperVault, SetSavingsRate, WrapStablecoin, FundWrapperYield, CreateBondMarket, PurchaseBond, RedeemBond, CreateAirdropDistributor, ClaimAirdrop, CreateVestingStream, WithdrawStream, SetHealthBands, RefreshPositionHealth, SetOracleConfig, InitializeRewardPool, InitializeGovToken, MintGovEmissions, InitializeRiskTiers, SetRiskTierParams, OnboardCollateral, RiskTier, RiskParams, RiskParamOverrides, InitializeProtocolStats, SetConcentrationLimit, SetCanonicalAccounts, ExpireProposal, SetLiquidationBonusCurve, ManageConfigFreeze, UpgradeChecklistItem, ManageLaunchMode, SetClaimLimits, InitializeStakingPool, InitializeLiquidationRevenue, SetLiquidationRevenueShares, ValidateCollateralParams, OpenPosition, BatchStake, BatchClaim, InitializePriceCache, RefreshPrice, FullLiquidate, SetLiquidationCooldown, GetProtocolConfig, InitializeGuardianSet, SetGuardianSet, ProposeEmergencyAction, ApproveEmergencyAction, CloseEmergencyAction, EmergencyActionKind, InitializeRateSnapshots, RecordRateSnapshot, InitializePermitNonce, PermitWrapStablecoin, CreateCampaign, FundCampaign, JoinCampaign, SyncCampaignPosition, ClaimCampaignRewards, TerminateCampaign, CampaignTarget, SetFeeRates, SetCollateralFeeOverrides, PreviewFee, MAX_FEE_BPS, MigratePosition, AssertInvariants, InitializePegState, SetPegBand, UpdatePegTwap, SetCollateralYieldConfig, InitializeVeConfig, CreateVeLock, WithdrawVeLock, ApplyBoost, KickBoost, MAX_VE_LOCK_SECS, CreateSubaccount, InitializeMarginAccount, SetMarginMode, UpdateCrossPosition, PreviewPortfolioHealth, MarginMode, InitializePauseState, SetPauseState, RequireNotPaused, PausableAction, InitializeAuctionParams, SetAuctionParams, StartCollateralAuction, SettleCollateralAuction, CreateSwapSchedule, ExecuteSwapSlice, CancelSwapSchedule, SetConfidencePremium, InitializeBadDebtQueue, SetDebtAuctionPrice, SetBadDebtSlashingPool, AbsorbBadDebt, SlashStakersForBadDebt, TakeDebtAuction, AssignDebt, RepayDebtNote, TransferDebtNote, SeizeNoteCollateral, AddLoyaltyCollection, SetLoyaltyDiscount, RemoveLoyaltyCollection, CreatePaymentStream, TopUpPaymentStream, WithdrawFromPaymentStream, CancelPaymentStream, InitializeEpochState, CloseEpoch, InitializeEmissionSchedule, SetEmissionBudget, ScanLiquidatable, SetRewardDelegate, SetOnboardingRatio, WhitelistStakePool, SetStakePoolHaircut, DepositSolCollateral, RefreshStakePoolPrice, CommitBid, RevealBid, SlashSealedBid, SetCollateralMintingPaused, PurchaseFeeCredits, InitializeGovTokenOracle, SetGovTokenOracleParams, SetGovTokenFallbackPrice, UpdateGovTokenTwap, ExecuteProposal, InitializeGovernanceConfig, SetProposalTiming, FinalizeProposal, VetoProposal, ProposalAction, InitializeCrankSchedule, SetCrankInterval, CrankRewardPool, CrankKind, SetRewardSource, ClaimRevenueRewards, RewardSource, SetLiquidatorReimbursement, InitializePositionLedger, ExtendPositionLedger, ExportPositionHistory, InitializeCeilingController, SetCeilingController, AdjustDebtCeiling, CeilingControllerParams, StartNextAuctionLot, OpenMmFacility, ManageMmFacility, MmFacilityMint, MmFacilityRedeem, MmFacilityTerms, RegisterTravelRuleAttestor, RemoveTravelRuleAttestor, AttestTravelRule, TransferWithMemo, RescueTokens, RescueAuthority, PauseReason, InitializePortfolioHealth, RefreshPortfolio, InitializeRedemptionQueue, SetRedemptionPriorityFee, RequestRedemption, FillRedemption, CancelRedemption, CloseRedemptionRequest, RedemptionLane, WhitelistReceiptCollateral, SetReceiptCollateralParams, RefreshReceiptPrice, ReceiptMarket, TreasuryRepayBadDebt, SetPriceOverride, TransferStakeOwnership, EnableAutoDeleverage, ManageAutoDeleverage, AutoDeleverage, DeleverageParams, BeginPoolSunset, SweepSunsetRewards, CloseSunsetPool, InitializeRateController, SetRateControllerParams, SetStabilityFee, NormalizeNegativeStabilityFee, RateControllerParams, SetOraclePrograms, ClaimToStake, SetAutoCompound, SetAuctionSettlementMint, InitializeLiquidityMonitor, SetLiquidityThresholds, CheckLiquidityCoverage, RecallForLiquidityCoverage, RegisterPartner, SetPartnerTerms, ClaimPartnerRevenue, OpenStakerAccount, PermitMintStablecoin, PermitRequestRedemption, SetEpochRevenuePool, ReturnSunsetStake};
+use transfer_hook::{InitializePermissionedMint, InitializeExtraAccountMetaList, SetTransferHookConfig, TransferHook};
+use transfer_fee::{InitializeFeeMint, SetTransferFee, SetFeeTreasury, HarvestTransferFees};
 use errors::ErrorCode;
 
 declare_id!("2oNrfjvaXeRCcU82pMQLN4guMR4jfZsCJLgpKNuCfYDP");
@@ -25,12 +38,32 @@ pub mod stablecoin_protocol {
         instructions::initialize(ctx, collateral_ratio)
     }
 
+    /// Open a position with an optional 32-byte tag included in its events.
+    pub fn open_position(ctx: Context<OpenPosition>, tag: [u8; 32]) -> Result<()> {
+        instructions::open_position(ctx, tag)
+    }
+
+    /// Open an isolated sub-account for a collateral type at the given index.
+    pub fn create_subaccount(ctx: Context<CreateSubaccount>, index: u16, tag: [u8; 32]) -> Result<()> {
+        instructions::create_subaccount(ctx, index, tag)
+    }
+
     // -------------------------------------
     // Minting and Burning Functions
     // -------------------------------------
 
     /// Mint stablecoin with dynamic fee based on the current price.
-    pub fn mint_stablecoin(ctx: Context<MintStablecoin>, amount: u64, current_price: u64) -> Result<()> {
+    ///
+    /// Reverts after `deadline_ts` or if the fee would exceed `max_fee`.
+    /// Fees on the mint are shared with the partner named by `partner_id`, if any.
+    pub fn mint_stablecoin(
+        ctx: Context<MintStablecoin>,
+        amount: u64,
+        current_price: u64,
+        deadline_ts: Option<i64>,
+        max_fee: Option<u64>,
+        partner_id: Option<u64>,
+    ) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero minting amount
         require!(current_price > 0, ErrorCode::InvalidPrice); // Ensure valid current price
 
@@ -39,11 +72,20 @@ pub mod stablecoin_protocol {
             require_keys_eq!(authority.key(), ctx.accounts.user_account.key(), ErrorCode::UnauthorizedOperation);
         }
 
-        instructions::mint_stablecoin(ctx, amount, current_price)
+        instructions::mint_stablecoin(ctx, amount, current_price, deadline_ts, max_fee, partner_id)
     }
 
     /// Mint stablecoin using a specified collateral type.
-    pub fn mint_stablecoin_with_collateral(ctx: Context<MintStablecoinWithCollateral>, amount: u64, collateral_type: Pubkey) -> Result<()> {
+    ///
+    /// Reverts after `deadline_ts` or if the fee and premium would exceed `max_fee`.
+    pub fn mint_stablecoin_with_collateral(
+        ctx: Context<MintStablecoinWithCollateral>,
+        amount: u64,
+        collateral_type: Pubkey,
+        deadline_ts: Option<i64>,
+        max_fee: Option<u64>,
+        partner_id: Option<u64>,
+    ) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero minting amount
 
         // Access control to restrict minting to authorized users if necessary
@@ -51,7 +93,7 @@ pub mod stablecoin_protocol {
             require_keys_eq!(authority.key(), ctx.accounts.user_account.key(), ErrorCode::UnauthorizedOperation);
         }
 
-        instructions::mint_stablecoin_with_collateral(ctx, amount, collateral_type)
+        instructions::mint_stablecoin_with_collateral(ctx, amount, collateral_type, deadline_ts, max_fee, partner_id)
     }
 
     // -------------------------------------
@@ -60,19 +102,52 @@ pub mod stablecoin_protocol {
 
     /// Partially liquidate a user's under-collateralized position.
     pub fn partial_liquidate(ctx: Context<Liquidate>, liquidation_amount: u64) -> Result<()> {
-        require!(liquidation_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero liquidation amount
+        instructions::partial_liquidate(ctx, liquidation_amount)
+    }
 
-        let user_account = &ctx.accounts.user_account;
-        let current_ratio = (user_account.collateral_balance * 100) / user_account.stablecoin_balance;
-        require!(current_ratio < user_account.collateral_ratio, ErrorCode::NotEligibleForLiquidation);
+    /// Liquidate an insolvent position in full, covering the shortfall from insurance.
+    pub fn full_liquidate(ctx: Context<FullLiquidate>) -> Result<()> {
+        instructions::full_liquidate(ctx)
+    }
 
-        instructions::partial_liquidate(ctx, liquidation_amount)
+    /// Set the minimum time between partial liquidations of the same position.
+    pub fn set_liquidation_cooldown(ctx: Context<SetLiquidationCooldown>, cooldown_secs: u64) -> Result<()> {
+        instructions::set_liquidation_cooldown(ctx, cooldown_secs)
+    }
+
+    /// Set the decaying extra collateral ratio required of newly opened positions.
+    pub fn set_onboarding_ratio(ctx: Context<SetOnboardingRatio>, ratio_bonus: u64, period_secs: u64) -> Result<()> {
+        instructions::set_onboarding_ratio(ctx, ratio_bonus, period_secs)
+    }
+
+    /// Initialize the split of liquidation penalties between liquidators and protocol funds.
+    pub fn initialize_liquidation_revenue(ctx: Context<InitializeLiquidationRevenue>, liquidator_share_bps: u64, insurance_share_bps: u64) -> Result<()> {
+        require!(liquidator_share_bps + insurance_share_bps <= 10_000, ErrorCode::InvalidAmount); // Shares cannot exceed the penalty
+
+        instructions::initialize_liquidation_revenue(ctx, liquidator_share_bps, insurance_share_bps)
+    }
+
+    /// Update the split of liquidation penalties.
+    pub fn set_liquidation_revenue_shares(ctx: Context<SetLiquidationRevenueShares>, liquidator_share_bps: u64, insurance_share_bps: u64) -> Result<()> {
+        require!(liquidator_share_bps + insurance_share_bps <= 10_000, ErrorCode::InvalidAmount); // Shares cannot exceed the penalty
+
+        instructions::set_liquidation_revenue_shares(ctx, liquidator_share_bps, insurance_share_bps)
+    }
+
+    /// Set the stablecoin reimbursed to liquidators per partial liquidation, up to `MAX_LIQUIDATOR_REIMBURSEMENT`.
+    pub fn set_liquidator_reimbursement(ctx: Context<SetLiquidatorReimbursement>, amount: u64) -> Result<()> {
+        instructions::set_liquidator_reimbursement(ctx, amount)
     }
 
     // -------------------------------------
     // Staking Functions
     // -------------------------------------
 
+    /// Open an empty stake account in a reward pool.
+    pub fn open_staker_account(ctx: Context<OpenStakerAccount>) -> Result<()> {
+        instructions::open_staker_account(ctx)
+    }
+
     /// Stake tokens to earn rewards with lock-up periods.
     pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64, lockup_period: u64) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero staking amount
@@ -81,6 +156,23 @@ pub mod stablecoin_protocol {
         instructions::stake_tokens(ctx, amount, lockup_period)
     }
 
+    /// Initialize the staking reward pool with its emission rate and minimum stake.
+    pub fn initialize_reward_pool(ctx: Context<InitializeRewardPool>, reward_rate: u64, min_stake_amount: u64) -> Result<()> {
+        require!(min_stake_amount > 0, ErrorCode::InvalidAmount); // A minimum stake is required to protect the accumulator
+
+        instructions::initialize_reward_pool(ctx, reward_rate, min_stake_amount)
+    }
+
+    /// Create the PDA-owned staking vault of a reward pool.
+    pub fn initialize_staking_pool(ctx: Context<InitializeStakingPool>) -> Result<()> {
+        instructions::initialize_staking_pool(ctx)
+    }
+
+    /// Set the reward claim cooldown and minimum claim size.
+    pub fn set_claim_limits(ctx: Context<SetClaimLimits>, min_claim_interval: u64, min_claim_amount: u64) -> Result<()> {
+        instructions::set_claim_limits(ctx, min_claim_interval, min_claim_amount)
+    }
+
     /// Withdraw staked tokens with optional early withdrawal penalty.
     pub fn withdraw_stake(ctx: Context<WithdrawStake>, amount: u64) -> Result<()> {
         require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero withdrawal amount
@@ -97,12 +189,57 @@ pub mod stablecoin_protocol {
         let staker_account = &ctx.accounts.staker_account;
         let current_time = Clock::get()?.unix_timestamp as u64;
 
-        // Ensure that enough time has passed since the last claim
-        require!(current_time > staker_account.last_reward_claim, ErrorCode::RewardsAlreadyClaimed);
+        // Ensure that the claim cooldown has passed since the last claim
+        let next_claim_time = staker_account.last_reward_claim.saturating_add(ctx.accounts.reward_pool.min_claim_interval);
+        require!(current_time >= next_claim_time, ErrorCode::ClaimCooldownActive);
 
         instructions::claim_rewards(ctx)
     }
 
+    /// Stake for many custodial beneficiaries in one transaction.
+    pub fn batch_stake<'info>(ctx: Context<'_, '_, 'info, 'info, BatchStake<'info>>, amounts: Vec<u64>, lockup_period: u64) -> Result<()> {
+        require!(lockup_period > 0, ErrorCode::InvalidLockupPeriod); // Ensure valid lock-up period
+
+        instructions::batch_stake(ctx, amounts, lockup_period)
+    }
+
+    /// Claim rewards for many custodial beneficiaries in one transaction.
+    pub fn batch_claim<'info>(ctx: Context<'_, '_, 'info, 'info, BatchClaim<'info>>) -> Result<()> {
+        instructions::batch_claim(ctx)
+    }
+
+    /// Allow a delegate's token accounts to receive a staker's rewards.
+    pub fn set_reward_delegate(ctx: Context<SetRewardDelegate>, reward_delegate: Pubkey) -> Result<()> {
+        instructions::set_reward_delegate(ctx, reward_delegate)
+    }
+
+    /// Stake an opted-in staker's dust rewards without a token transfer.
+    pub fn claim_to_stake(ctx: Context<ClaimToStake>) -> Result<()> {
+        instructions::claim_to_stake(ctx)
+    }
+
+    /// Opt in or out of having dust rewards staked by `claim_to_stake`.
+    pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+        instructions::set_auto_compound(ctx, enabled)
+    }
+
+    /// Set the largest pending reward `claim_to_stake` may stake.
+    pub fn set_claim_to_stake_threshold(ctx: Context<SetClaimLimits>, threshold: u64) -> Result<()> {
+        instructions::set_claim_to_stake_threshold(ctx, threshold)
+    }
+
+    /// Budget a reward pool's emissions per epoch, minted by a program PDA.
+    pub fn initialize_emission_schedule(ctx: Context<InitializeEmissionSchedule>, epoch_duration: u64, epoch_budget: u64) -> Result<()> {
+        require!(epoch_duration > 0, ErrorCode::InvalidAmount); // Ensure epochs have a length
+
+        instructions::initialize_emission_schedule(ctx, epoch_duration, epoch_budget)
+    }
+
+    /// Change a reward pool's per-epoch emission budget.
+    pub fn set_emission_budget(ctx: Context<SetEmissionBudget>, epoch_budget: u64) -> Result<()> {
+        instructions::set_emission_budget(ctx, epoch_budget)
+    }
+
     // -------------------------------------
     // Governance Functions
     // -------------------------------------
@@ -111,6 +248,7 @@ pub mod stablecoin_protocol {
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
         description: String,
+        action: ProposalAction,
         new_collateral_ratio: Option<u64>,
         new_reward_rate: Option<u64>,
     ) -> Result<()> {
@@ -121,7 +259,26 @@ pub mod stablecoin_protocol {
             require!(collateral_ratio > 100, ErrorCode::InvalidCollateralRatio); // Make sure ratio is above 100%
         }
 
-        instructions::create_proposal(ctx, description, new_collateral_ratio, new_reward_rate)
+        instructions::create_proposal(ctx, description, action, new_collateral_ratio, new_reward_rate)
+    }
+
+    /// Propose a new collateral ratio from a validated template.
+    pub fn propose_collateral_ratio_change(ctx: Context<CreateProposal>, new_collateral_ratio: u64) -> Result<()> {
+        instructions::propose_collateral_ratio_change(ctx, new_collateral_ratio)
+    }
+
+    /// Propose a new reward rate from a validated template.
+    pub fn propose_reward_rate_change(ctx: Context<CreateProposal>, new_reward_rate: u64) -> Result<()> {
+        instructions::propose_reward_rate_change(ctx, new_reward_rate)
+    }
+
+    /// Propose new minting and/or redemption fee rates from a validated template.
+    pub fn propose_fee_change(
+        ctx: Context<CreateProposal>,
+        new_minting_fee_rate: Option<u64>,
+        new_redemption_fee_rate: Option<u64>,
+    ) -> Result<()> {
+        instructions::propose_fee_change(ctx, new_minting_fee_rate, new_redemption_fee_rate)
     }
 
     /// Vote on an existing proposal.
@@ -132,14 +289,1460 @@ pub mod stablecoin_protocol {
         instructions::vote_on_proposal(ctx, approve)
     }
 
+    /// Conclude a proposal whose voting period has ended. Callable by anyone.
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        instructions::finalize_proposal(ctx)
+    }
+
+    /// Cancel a proposal before execution (governance authority only).
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        instructions::veto_proposal(ctx)
+    }
+
+    /// Apply an approved proposal after its timelock if its targeted parameters are unchanged. Callable by anyone.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
+
+    /// Expire stale pending proposals and refund their bonds. Callable by anyone.
+    pub fn expire_proposal<'info>(ctx: Context<'_, '_, 'info, 'info, ExpireProposal<'info>>) -> Result<()> {
+        instructions::expire_proposal(ctx)
+    }
+
+    /// Create the governance config with default per-kind proposal timings (governance authority only).
+    pub fn initialize_governance_config(ctx: Context<InitializeGovernanceConfig>) -> Result<()> {
+        instructions::initialize_governance_config(ctx)
+    }
+
+    /// Set the voting period and timelock of a proposal kind (governance authority only).
+    pub fn set_proposal_timing(
+        ctx: Context<SetProposalTiming>,
+        action: ProposalAction,
+        voting_period_secs: u64,
+        execution_delay_secs: u64,
+    ) -> Result<()> {
+        instructions::set_proposal_timing(ctx, action, voting_period_secs, execution_delay_secs)
+    }
+
     // -------------------------------------
     // Multi-collateral Functions
     // -------------------------------------
 
     /// Add a new collateral type to the protocol.
     pub fn add_collateral_type(ctx: Context<AddCollateralType>, collateral_ratio: u64) -> Result<()> {
-        require!(collateral_ratio > 100, ErrorCode::InvalidCollateralRatio); // Ensure reasonable collateral ratio
+        require!(collateral_ratio > 101, ErrorCode::InvalidCollateralRatio); // Leave room for a liquidation threshold above 100%
 
         instructions::add_collateral_type(ctx, collateral_ratio)
     }
-}
\ No newline at end of file
+
+    // -------------------------------------
+    // Treasury Strategy Functions
+    // -------------------------------------
+
+    /// Initialize the system state with the governance authority.
+    pub fn initialize_system_state(ctx: Context<InitializeSystemState>, governance_authority: Pubkey) -> Result<()> {
+        instructions::initialize_system_state(ctx, governance_authority)
+    }
+
+    /// Pin the canonical stablecoin mint and treasury account.
+    pub fn set_canonical_accounts(ctx: Context<SetCanonicalAccounts>) -> Result<()> {
+        instructions::set_canonical_accounts(ctx)
+    }
+
+    /// Initialize the treasury reserves used by yield strategies.
+    pub fn initialize_treasury_reserves(ctx: Context<InitializeTreasuryReserves>, min_liquidity_bps: u64) -> Result<()> {
+        require!(min_liquidity_bps <= 10_000, ErrorCode::InvalidAmount); // Liquidity floor cannot exceed 100%
+
+        instructions::initialize_treasury_reserves(ctx, min_liquidity_bps)
+    }
+
+    /// Whitelist a yield strategy adapter with a deployment cap.
+    pub fn whitelist_strategy(ctx: Context<WhitelistStrategy>, cap: u64) -> Result<()> {
+        require!(cap > 0, ErrorCode::InvalidAmount); // A zero cap strategy is useless
+
+        instructions::whitelist_strategy(ctx, cap)
+    }
+
+    /// Update a strategy's cap or disable new deployments to it.
+    pub fn set_strategy_cap(ctx: Context<SetStrategyCap>, cap: u64, active: bool) -> Result<()> {
+        instructions::set_strategy_cap(ctx, cap, active)
+    }
+
+    /// Deploy idle treasury reserves into a whitelisted strategy.
+    pub fn deploy_to_strategy<'info>(ctx: Context<'_, '_, '_, 'info, ManageStrategyFunds<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero deployment
+
+        instructions::deploy_to_strategy(ctx, amount)
+    }
+
+    /// Recall reserves from a strategy back to the treasury vault.
+    pub fn recall_from_strategy<'info>(ctx: Context<'_, '_, '_, 'info, ManageStrategyFunds<'info>>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero recall
+
+        instructions::recall_from_strategy(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Reserve Attestation Functions
+    // -------------------------------------
+
+    /// Initialize reserve attestation with the attestor role.
+    pub fn initialize_reserve_attestation(ctx: Context<InitializeReserveAttestation>, attestor: Pubkey) -> Result<()> {
+        instructions::initialize_reserve_attestation(ctx, attestor)
+    }
+
+    /// Rotate the attestor allowed to publish reserve reports.
+    pub fn set_reserve_attestor(ctx: Context<SetReserveAttestor>, attestor: Pubkey) -> Result<()> {
+        instructions::set_reserve_attestor(ctx, attestor)
+    }
+
+    /// Publish the reserve report for the current epoch.
+    pub fn publish_reserve_report(ctx: Context<PublishReserveReport>, collateral_value: u64, report_hash: [u8; 32]) -> Result<()> {
+        require!(report_hash != [0u8; 32], ErrorCode::InvalidAccountData); // A report must reference an attestation document
+
+        instructions::publish_reserve_report(ctx, collateral_value, report_hash)
+    }
+
+    // -------------------------------------
+    // Compliance Functions
+    // -------------------------------------
+
+    /// Blacklist a wallet from transferring the permissioned stablecoin.
+    pub fn add_to_blacklist(ctx: Context<AddToBlacklist>, wallet: Pubkey) -> Result<()> {
+        instructions::add_to_blacklist(ctx, wallet)
+    }
+
+    /// Remove a wallet from the blacklist.
+    pub fn remove_from_blacklist(ctx: Context<RemoveFromBlacklist>) -> Result<()> {
+        instructions::remove_from_blacklist(ctx)
+    }
+
+    /// Create the permissioned Token-2022 stablecoin mint with this program as its transfer hook.
+    pub fn initialize_permissioned_mint(ctx: Context<InitializePermissionedMint>, decimals: u8) -> Result<()> {
+        transfer_hook::initialize_permissioned_mint(ctx, decimals)
+    }
+
+    /// Write the extra account metas used to resolve blacklist PDAs on transfer.
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        transfer_hook::initialize_extra_account_meta_list(ctx)
+    }
+
+    /// Toggle blacklist screening on the permissioned mint.
+    pub fn set_transfer_hook_config(ctx: Context<SetTransferHookConfig>, screening_enabled: bool) -> Result<()> {
+        transfer_hook::set_transfer_hook_config(ctx, screening_enabled)
+    }
+
+    /// Transfer hook executed by Token-2022 on every permissioned stablecoin transfer.
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        transfer_hook::transfer_hook(ctx, amount)
+    }
+
+    /// Create a Token-2022 stablecoin mint charging a transfer fee.
+    pub fn initialize_fee_mint(ctx: Context<InitializeFeeMint>, decimals: u8, transfer_fee_bps: u16, maximum_fee: u64) -> Result<()> {
+        require!(transfer_fee_bps as u64 <= MAX_FEE_BPS, ErrorCode::FeeTooHigh); // Bound the fee like any other
+
+        transfer_fee::initialize_fee_mint(ctx, decimals, transfer_fee_bps, maximum_fee)
+    }
+
+    /// Change the stablecoin transfer fee, or set it to zero.
+    pub fn set_transfer_fee(ctx: Context<SetTransferFee>, transfer_fee_bps: u16, maximum_fee: u64) -> Result<()> {
+        require!(transfer_fee_bps as u64 <= MAX_FEE_BPS, ErrorCode::FeeTooHigh); // Bound the fee like any other
+
+        transfer_fee::set_transfer_fee(ctx, transfer_fee_bps, maximum_fee)
+    }
+
+    /// Set the treasury token account transfer fees are harvested to.
+    pub fn set_fee_treasury(ctx: Context<SetFeeTreasury>) -> Result<()> {
+        transfer_fee::set_fee_treasury(ctx)
+    }
+
+    /// Harvest withheld transfer fees into the treasury. Callable by anyone.
+    pub fn harvest_transfer_fees<'info>(ctx: Context<'_, '_, '_, 'info, HarvestTransferFees<'info>>) -> Result<()> {
+        transfer_fee::harvest_transfer_fees(ctx)
+    }
+
+    // -------------------------------------
+    // Wrapped Yield Token Functions
+    // -------------------------------------
+
+    /// Initialize the wrapper vault issuing the yield-bearing wrapped token.
+    pub fn initialize_wrapper_vault(ctx: Context<InitializeWrapperVault>, savings_rate_bps: u64) -> Result<()> {
+        require!(savings_rate_bps <= 10_000, ErrorCode::InvalidAmount); // Cap the savings rate at 100% APR
+
+        instructions::initialize_wrapper_vault(ctx, savings_rate_bps)
+    }
+
+    /// Update the savings rate accrued by the wrapped token.
+    pub fn set_savings_rate(ctx: Context<SetSavingsRate>, savings_rate_bps: u64) -> Result<()> {
+        require!(savings_rate_bps <= 10_000, ErrorCode::InvalidAmount); // Cap the savings rate at 100% APR
+
+        instructions::set_savings_rate(ctx, savings_rate_bps)
+    }
+
+    /// Wrap stablecoin into the yield-bearing wrapped token.
+    pub fn wrap(ctx: Context<WrapStablecoin>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero wrap amount
+
+        instructions::wrap(ctx, amount)
+    }
+
+    /// Unwrap the wrapped token back into stablecoin, reverting after `deadline_ts` or below `min_out`.
+    pub fn unwrap(ctx: Context<WrapStablecoin>, wrapped_amount: u64, deadline_ts: Option<i64>, min_out: Option<u64>) -> Result<()> {
+        require!(wrapped_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero unwrap amount
+
+        instructions::unwrap(ctx, wrapped_amount, deadline_ts, min_out)
+    }
+
+    /// Fund the wrapper vault with stablecoin backing accrued yield.
+    pub fn fund_wrapper_yield(ctx: Context<FundWrapperYield>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero funding amount
+
+        instructions::fund_wrapper_yield(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Bond Market Functions
+    // -------------------------------------
+
+    /// Create a bond market selling protocol tokens at a discount.
+    pub fn create_bond_market(ctx: Context<CreateBondMarket>, params: BondMarketParams) -> Result<()> {
+        require!(params.capacity > 0, ErrorCode::InvalidAmount); // Ensure the market can sell something
+
+        instructions::create_bond_market(ctx, params)
+    }
+
+    /// Purchase a vesting bond with stablecoin or LP tokens.
+    pub fn purchase_bond(ctx: Context<PurchaseBond>, quote_amount: u64, max_price: u64) -> Result<()> {
+        require!(quote_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero purchase
+
+        instructions::purchase_bond(ctx, quote_amount, max_price)
+    }
+
+    /// Redeem the vested part of a bond.
+    pub fn redeem_bond(ctx: Context<RedeemBond>) -> Result<()> {
+        instructions::redeem_bond(ctx)
+    }
+
+    // -------------------------------------
+    // Airdrop Functions
+    // -------------------------------------
+
+    /// Create a merkle airdrop distributor.
+    pub fn create_airdrop_distributor(ctx: Context<CreateAirdropDistributor>, merkle_root: [u8; 32], max_num_nodes: u64) -> Result<()> {
+        require!(max_num_nodes > 0 && max_num_nodes <= 80_000, ErrorCode::InvalidAmount); // Keep the bitmap within account size limits
+
+        instructions::create_airdrop_distributor(ctx, merkle_root, max_num_nodes)
+    }
+
+    /// Claim an airdrop allocation with a merkle proof.
+    pub fn claim_airdrop(ctx: Context<ClaimAirdrop>, index: u64, amount: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero claim
+
+        instructions::claim_airdrop(ctx, index, amount, proof)
+    }
+
+    // -------------------------------------
+    // Vesting Stream Functions
+    // -------------------------------------
+
+    /// Create a contributor vesting stream funded from the treasury.
+    pub fn create_vesting_stream(
+        ctx: Context<CreateVestingStream>,
+        recipient: Pubkey,
+        rate_per_second: u64,
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require!(rate_per_second > 0, ErrorCode::InvalidAmount); // Ensure the stream pays something
+        require!(start_time < end_time, ErrorCode::InvalidLockupPeriod); // Ensure a non-empty stream window
+
+        instructions::create_vesting_stream(ctx, recipient, rate_per_second, start_time, cliff_time, end_time)
+    }
+
+    /// Withdraw the streamed amount as the recipient.
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        instructions::withdraw_stream(ctx)
+    }
+
+    // -------------------------------------
+    // Position Health Functions
+    // -------------------------------------
+
+    /// Configure the health bands used for position notifications.
+    pub fn set_health_bands(ctx: Context<SetHealthBands>, warning_bps: u64, critical_bps: u64) -> Result<()> {
+        require!(warning_bps > critical_bps, ErrorCode::InvalidCollateralRatio); // Bands must be ordered
+
+        instructions::set_health_bands(ctx, warning_bps, critical_bps)
+    }
+
+    /// Re-evaluate a position's health band, emitting an event if it crossed one.
+    pub fn refresh_position_health(ctx: Context<RefreshPositionHealth>) -> Result<()> {
+        instructions::refresh_position_health(ctx)
+    }
+
+    // -------------------------------------
+    // Oracle Configuration Functions
+    // -------------------------------------
+
+    /// Tune the price feed staleness and confidence limits of a collateral type.
+    pub fn set_oracle_config(ctx: Context<SetOracleConfig>, max_price_age_secs: u64, max_confidence_bps: u64) -> Result<()> {
+        require!(max_price_age_secs > 0, ErrorCode::InvalidAmount); // A zero age would reject every price
+        require!(max_confidence_bps <= 10_000, ErrorCode::InvalidAmount); // Confidence is relative to price
+
+        instructions::set_oracle_config(ctx, max_price_age_secs, max_confidence_bps)
+    }
+
+    /// Replace the whitelist of oracle programs price feeds must be owned by.
+    pub fn set_oracle_programs(ctx: Context<SetOraclePrograms>, programs: Vec<Pubkey>) -> Result<()> {
+        instructions::set_oracle_programs(ctx, programs)
+    }
+
+    /// Create the price cache of a collateral type.
+    pub fn initialize_price_cache(ctx: Context<InitializePriceCache>) -> Result<()> {
+        instructions::initialize_price_cache(ctx)
+    }
+
+    /// Refresh a collateral's cached oracle price. Callable by anyone.
+    pub fn refresh_price(ctx: Context<RefreshPrice>) -> Result<()> {
+        instructions::refresh_price(ctx)
+    }
+
+    /// Clamp a collateral's valuation to a floor, ceiling and reference-price cap for `duration_secs`.
+    pub fn set_price_override(
+        ctx: Context<SetPriceOverride>,
+        price_floor: u64,
+        price_ceiling: u64,
+        reference_cap_bps: u64,
+        duration_secs: u64,
+    ) -> Result<()> {
+        instructions::set_price_override(ctx, price_floor, price_ceiling, reference_cap_bps, duration_secs)
+    }
+
+    /// Lift a collateral's valuation override early.
+    pub fn clear_price_override(ctx: Context<SetPriceOverride>) -> Result<()> {
+        instructions::clear_price_override(ctx)
+    }
+
+    // -------------------------------------
+    // Governance Token Functions
+    // -------------------------------------
+
+    /// Create the governance token mint under a program PDA.
+    pub fn initialize_gov_token(ctx: Context<InitializeGovToken>) -> Result<()> {
+        instructions::initialize_gov_token(ctx)
+    }
+
+    /// Mint governance token emissions within the supply cap.
+    pub fn mint_gov_emissions(ctx: Context<MintGovEmissions>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero emission
+
+        instructions::mint_gov_emissions(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Risk Tier Functions
+    // -------------------------------------
+
+    /// Initialize the risk tier presets with their default parameter bundles.
+    pub fn initialize_risk_tiers(ctx: Context<InitializeRiskTiers>) -> Result<()> {
+        instructions::initialize_risk_tiers(ctx)
+    }
+
+    /// Update the default parameter bundle of a risk tier.
+    pub fn set_risk_tier_params(ctx: Context<SetRiskTierParams>, tier: RiskTier, params: RiskParams) -> Result<()> {
+        instructions::set_risk_tier_params(ctx, tier, params)
+    }
+
+    /// Set the liquidation bonus curve of a collateral type.
+    pub fn set_liquidation_bonus_curve(ctx: Context<SetLiquidationBonusCurve>, base_bonus_bps: u64, slope_bps: u64, max_bonus_bps: u64) -> Result<()> {
+        require!(base_bonus_bps <= max_bonus_bps && max_bonus_bps < 10_000, ErrorCode::InvalidAmount); // Bonus is bounded below the liquidated amount
+
+        instructions::set_liquidation_bonus_curve(ctx, base_bonus_bps, slope_bps, max_bonus_bps)
+    }
+
+    /// Dry-run a proposed collateral configuration and return the outcome of each check.
+    pub fn validate_collateral_params(ctx: Context<ValidateCollateralParams>, params: RiskParams) -> Result<CollateralParamsReport> {
+        instructions::validate_collateral_params(ctx, params)
+    }
+
+    /// Onboard a collateral type from a risk tier with optional overrides.
+    pub fn onboard_collateral(ctx: Context<OnboardCollateral>, tier: RiskTier, overrides: RiskParamOverrides) -> Result<()> {
+        instructions::onboard_collateral(ctx, tier, overrides)
+    }
+
+    // -------------------------------------
+    // Collateral Concentration Functions
+    // -------------------------------------
+
+    /// Initialize protocol stats and the collateral concentration limit.
+    pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>, cap_bps: u64, warning_bps: u64, floor: u64) -> Result<()> {
+        require!(cap_bps <= 10_000 && warning_bps <= cap_bps, ErrorCode::InvalidAmount); // Warning must trigger before the cap
+
+        instructions::initialize_protocol_stats(ctx, cap_bps, warning_bps, floor)
+    }
+
+    /// Update the collateral concentration limit.
+    pub fn set_concentration_limit(ctx: Context<SetConcentrationLimit>, cap_bps: u64, warning_bps: u64, floor: u64) -> Result<()> {
+        require!(cap_bps <= 10_000 && warning_bps <= cap_bps, ErrorCode::InvalidAmount); // Warning must trigger before the cap
+
+        instructions::set_concentration_limit(ctx, cap_bps, warning_bps, floor)
+    }
+
+    // -------------------------------------
+    // Upgrade Freeze Functions
+    // -------------------------------------
+
+    /// Freeze parameter changes and new debt around a program upgrade.
+    pub fn freeze_config_for_upgrade(ctx: Context<ManageConfigFreeze>) -> Result<()> {
+        instructions::freeze_config_for_upgrade(ctx)
+    }
+
+    /// Tick off an item of the on-chain upgrade checklist.
+    pub fn complete_upgrade_checklist_item(ctx: Context<ManageConfigFreeze>, item: UpgradeChecklistItem) -> Result<()> {
+        instructions::complete_upgrade_checklist_item(ctx, item)
+    }
+
+    /// Lift the upgrade freeze after the timelock, once the checklist is complete.
+    pub fn unfreeze_config(ctx: Context<ManageConfigFreeze>) -> Result<()> {
+        instructions::unfreeze_config(ctx)
+    }
+
+    // -------------------------------------
+    // Guarded Launch Functions
+    // -------------------------------------
+
+    /// Enable the guarded launch with per-wallet collateral and debt caps.
+    pub fn set_launch_mode(ctx: Context<ManageLaunchMode>, collateral_cap: u64, debt_cap: u64) -> Result<()> {
+        require!(collateral_cap > 0 && debt_cap > 0, ErrorCode::InvalidAmount); // Zero caps would block every position
+
+        instructions::set_launch_mode(ctx, collateral_cap, debt_cap)
+    }
+
+    /// Graduate from the guarded launch, lifting the per-wallet caps.
+    pub fn graduate_launch_mode(ctx: Context<ManageLaunchMode>) -> Result<()> {
+        instructions::graduate_launch_mode(ctx)
+    }
+
+    // -------------------------------------
+    // Config View Functions
+    // -------------------------------------
+
+    /// Return the effective protocol configuration, optionally including one collateral type.
+    pub fn get_protocol_config(ctx: Context<GetProtocolConfig>) -> Result<ProtocolConfigView> {
+        instructions::get_protocol_config(ctx)
+    }
+
+    // -------------------------------------
+    // Guardian Functions
+    // -------------------------------------
+
+    /// Initialize the M-of-N guardian set.
+    pub fn initialize_guardian_set(ctx: Context<InitializeGuardianSet>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::initialize_guardian_set(ctx, guardians, threshold)
+    }
+
+    /// Replace the guardian set and its threshold.
+    pub fn set_guardian_set(ctx: Context<SetGuardianSet>, guardians: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::set_guardian_set(ctx, guardians, threshold)
+    }
+
+    /// Open an emergency action as a guardian.
+    pub fn propose_emergency_action(ctx: Context<ProposeEmergencyAction>, kind: EmergencyActionKind) -> Result<()> {
+        instructions::propose_emergency_action(ctx, kind)
+    }
+
+    /// Approve an emergency action as a guardian; executes at the threshold.
+    pub fn approve_emergency_action(ctx: Context<ApproveEmergencyAction>) -> Result<()> {
+        instructions::approve_emergency_action(ctx)
+    }
+
+    /// Close an executed or expired emergency action. Callable by anyone.
+    pub fn close_emergency_action(ctx: Context<CloseEmergencyAction>) -> Result<()> {
+        instructions::close_emergency_action(ctx)
+    }
+
+    // -------------------------------------
+    // Rate Snapshot Functions
+    // -------------------------------------
+
+    /// Create the per-epoch rate and index snapshot ring buffer.
+    pub fn initialize_rate_snapshots(ctx: Context<InitializeRateSnapshots>) -> Result<()> {
+        instructions::initialize_rate_snapshots(ctx)
+    }
+
+    /// Record this epoch's rate and index snapshot. Callable by anyone.
+    pub fn record_rate_snapshot(ctx: Context<RecordRateSnapshot>) -> Result<CrankStatus> {
+        instructions::record_rate_snapshot(ctx)
+    }
+
+    // -------------------------------------
+    // Rate Controller Functions
+    // -------------------------------------
+
+    /// Create the rate controller bounding the global stability fee.
+    pub fn initialize_rate_controller(ctx: Context<InitializeRateController>, params: RateControllerParams) -> Result<()> {
+        instructions::initialize_rate_controller(ctx, params)
+    }
+
+    /// Change the global stability fee bounds, per-epoch change cap and negative-rate peg premium.
+    pub fn set_rate_controller_params(ctx: Context<SetRateControllerParams>, params: RateControllerParams) -> Result<()> {
+        instructions::set_rate_controller_params(ctx, params)
+    }
+
+    /// Set the global stability fee in bps per year; negative values pay borrowers.
+    pub fn set_stability_fee(ctx: Context<SetStabilityFee>, rate_bps: i64) -> Result<()> {
+        instructions::set_stability_fee(ctx, rate_bps)
+    }
+
+    /// Reset a negative stability fee to zero once the stablecoin is back near its peg. Callable by anyone.
+    pub fn normalize_negative_stability_fee(ctx: Context<NormalizeNegativeStabilityFee>) -> Result<()> {
+        instructions::normalize_negative_stability_fee(ctx)
+    }
+
+    // -------------------------------------
+    // Permit Functions
+    // -------------------------------------
+
+    /// Create a wallet's permit nonce account. Payable by anyone.
+    pub fn initialize_permit_nonce(ctx: Context<InitializePermitNonce>) -> Result<()> {
+        instructions::initialize_permit_nonce(ctx)
+    }
+
+    /// Relay a wrap authorized by an ed25519-signed permit in the same transaction.
+    pub fn permit_wrap(ctx: Context<PermitWrapStablecoin>, amount: u64, nonce: u64, expiry: i64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero wrap amount
+
+        instructions::permit_wrap(ctx, amount, nonce, expiry)
+    }
+
+    /// Relay an unwrap authorized by an ed25519-signed permit in the same transaction.
+    pub fn permit_unwrap(ctx: Context<PermitWrapStablecoin>, wrapped_amount: u64, nonce: u64, expiry: i64) -> Result<()> {
+        require!(wrapped_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero unwrap amount
+
+        instructions::permit_unwrap(ctx, wrapped_amount, nonce, expiry)
+    }
+
+    /// Relay a mint authorized by an ed25519-signed permit in the same transaction.
+    pub fn permit_mint(ctx: Context<PermitMintStablecoin>, amount: u64, max_fee: u64, nonce: u64, expiry: i64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero mint amount
+
+        instructions::permit_mint(ctx, amount, max_fee, nonce, expiry)
+    }
+
+    /// Relay a redemption request authorized by an ed25519-signed permit in the same transaction.
+    pub fn permit_request_redemption(
+        ctx: Context<PermitRequestRedemption>,
+        amount: u64,
+        lane: RedemptionLane,
+        request_id: u64,
+        nonce: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        instructions::permit_request_redemption(ctx, amount, lane, request_id, nonce, expiry)
+    }
+
+    // -------------------------------------
+    // Campaign Functions
+    // -------------------------------------
+
+    /// Create a time-boxed incentive campaign over a staking pool or collateral type.
+    pub fn create_campaign(ctx: Context<CreateCampaign>, target: CampaignTarget, reward_rate: u64, start_time: i64, end_time: i64) -> Result<()> {
+        require!(reward_rate > 0, ErrorCode::InvalidAmount); // Ensure the campaign emits rewards
+
+        instructions::create_campaign(ctx, target, reward_rate, start_time, end_time)
+    }
+
+    /// Deposit rewards into a campaign. Callable by anyone.
+    pub fn fund_campaign(ctx: Context<FundCampaign>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero funding
+
+        instructions::fund_campaign(ctx, amount)
+    }
+
+    /// Enroll a staker or user account in a campaign.
+    pub fn join_campaign(ctx: Context<JoinCampaign>) -> Result<()> {
+        instructions::join_campaign(ctx)
+    }
+
+    /// Settle a campaign position and pick up its current weight. Callable by anyone.
+    pub fn sync_campaign_position(ctx: Context<SyncCampaignPosition>) -> Result<()> {
+        instructions::sync_campaign_position(ctx)
+    }
+
+    /// Claim settled campaign rewards.
+    pub fn claim_campaign_rewards(ctx: Context<ClaimCampaignRewards>) -> Result<()> {
+        instructions::claim_campaign_rewards(ctx)
+    }
+
+    /// End a campaign early and refund its unemitted rewards.
+    pub fn terminate_campaign(ctx: Context<TerminateCampaign>) -> Result<()> {
+        instructions::terminate_campaign(ctx)
+    }
+
+    // -------------------------------------
+    // Fee Functions
+    // -------------------------------------
+
+    /// Whitelist an NFT collection as a loyalty pass with a mint fee discount.
+    pub fn add_loyalty_collection(ctx: Context<AddLoyaltyCollection>, fee_discount_bps: u64) -> Result<()> {
+        require!(fee_discount_bps <= 10_000, ErrorCode::InvalidAmount); // Discount cannot exceed the fee
+
+        instructions::add_loyalty_collection(ctx, fee_discount_bps)
+    }
+
+    /// Change a loyalty collection's mint fee discount.
+    pub fn set_loyalty_discount(ctx: Context<SetLoyaltyDiscount>, fee_discount_bps: u64) -> Result<()> {
+        require!(fee_discount_bps <= 10_000, ErrorCode::InvalidAmount); // Discount cannot exceed the fee
+
+        instructions::set_loyalty_discount(ctx, fee_discount_bps)
+    }
+
+    /// Remove a collection from the loyalty pass whitelist.
+    pub fn remove_loyalty_collection(ctx: Context<RemoveLoyaltyCollection>) -> Result<()> {
+        instructions::remove_loyalty_collection(ctx)
+    }
+
+    /// Set a collateral type's oracle confidence insurance premium curve.
+    pub fn set_confidence_premium(ctx: Context<SetConfidencePremium>, threshold_bps: u64, slope_bps: u64, max_premium_bps: u64) -> Result<()> {
+        require!(max_premium_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh); // Premium is bounded like any fee
+
+        instructions::set_confidence_premium(ctx, threshold_bps, slope_bps, max_premium_bps)
+    }
+
+    /// Set the global mint and redemption fee rates (bps).
+    pub fn set_fee_rates(ctx: Context<SetFeeRates>, minting_fee_rate: u64, redemption_fee_rate: u64) -> Result<()> {
+        require!(minting_fee_rate <= MAX_FEE_BPS && redemption_fee_rate <= MAX_FEE_BPS, ErrorCode::FeeTooHigh); // Cap fees
+
+        instructions::set_fee_rates(ctx, minting_fee_rate, redemption_fee_rate)
+    }
+
+    /// Set the progressive fee on single mints taking a large share of a collateral's remaining debt ceiling.
+    pub fn set_mint_size_fee(ctx: Context<SetFeeRates>, threshold_bps: u64, slope_bps: u64, max_surcharge_bps: u64) -> Result<()> {
+        require!(threshold_bps <= 10_000, ErrorCode::InvalidAmount); // Threshold is a share of the headroom
+        require!(max_surcharge_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh); // Cap the curve
+
+        instructions::set_mint_size_fee(ctx, threshold_bps, slope_bps, max_surcharge_bps)
+    }
+
+    /// Override the mint and redemption fees of a collateral type.
+    pub fn set_collateral_fee_overrides(ctx: Context<SetCollateralFeeOverrides>, mint_fee_bps: Option<u64>, redemption_fee_bps: Option<u64>) -> Result<()> {
+        require!(
+            mint_fee_bps.unwrap_or(0) <= MAX_FEE_BPS && redemption_fee_bps.unwrap_or(0) <= MAX_FEE_BPS,
+            ErrorCode::FeeTooHigh
+        ); // Cap fees
+
+        instructions::set_collateral_fee_overrides(ctx, mint_fee_bps, redemption_fee_bps)
+    }
+
+    /// Preview the mint fee on an amount, optionally for a specific collateral type.
+    pub fn preview_mint_fee(ctx: Context<PreviewFee>, amount: u64) -> Result<FeePreview> {
+        instructions::preview_mint_fee(ctx, amount)
+    }
+
+    /// Preview the redemption fee on an amount, optionally for a specific collateral type.
+    pub fn preview_redemption_fee(ctx: Context<PreviewFee>, amount: u64) -> Result<FeePreview> {
+        instructions::preview_redemption_fee(ctx, amount)
+    }
+
+    /// Burn stablecoin for discounted fee credits on a position.
+    pub fn purchase_fee_credits(ctx: Context<PurchaseFeeCredits>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero burn
+
+        instructions::purchase_fee_credits(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Position Migration Functions
+    // -------------------------------------
+
+    /// Migrate a v1 user account to a v2 position PDA, closing the v1 account.
+    pub fn migrate_position(ctx: Context<MigratePosition>) -> Result<()> {
+        instructions::migrate_position(ctx)
+    }
+
+    // -------------------------------------
+    // Invariant Functions
+    // -------------------------------------
+
+    /// Verify the core accounting invariants, failing loudly if any is broken. Callable by anyone.
+    pub fn assert_invariants(ctx: Context<AssertInvariants>) -> Result<InvariantReport> {
+        instructions::assert_invariants(ctx)
+    }
+
+    // -------------------------------------
+    // Peg Band Functions
+    // -------------------------------------
+
+    /// Create the peg state tracking the stablecoin's market TWAP.
+    pub fn initialize_peg_state(ctx: Context<InitializePegState>) -> Result<()> {
+        instructions::initialize_peg_state(ctx)
+    }
+
+    /// Configure the peg band gating new issuance.
+    pub fn set_peg_band(ctx: Context<SetPegBand>, max_deviation_bps: u64, twap_window_secs: u64, max_price_age_secs: u64, enforced: bool) -> Result<()> {
+        require!(max_deviation_bps <= 10_000, ErrorCode::InvalidAmount); // Band cannot exceed 100%
+        require!(twap_window_secs > 0, ErrorCode::InvalidAmount); // Window must be non-zero
+
+        instructions::set_peg_band(ctx, max_deviation_bps, twap_window_secs, max_price_age_secs, enforced)
+    }
+
+    /// Update the peg TWAP from the stablecoin's market price feed. Callable by anyone.
+    pub fn update_peg_twap(ctx: Context<UpdatePegTwap>) -> Result<CrankStatus> {
+        instructions::update_peg_twap(ctx)
+    }
+
+    // -------------------------------------
+    // Collateral Yield Functions
+    // -------------------------------------
+
+    /// Set a yield-bearing collateral's exchange rate and the protocol's share of its yield.
+    pub fn set_collateral_yield_config(ctx: Context<SetCollateralYieldConfig>, exchange_rate: u128, yield_skim_bps: u64) -> Result<()> {
+        require!(yield_skim_bps <= 10_000, ErrorCode::InvalidAmount); // Skim cannot exceed the yield
+
+        instructions::set_collateral_yield_config(ctx, exchange_rate, yield_skim_bps)
+    }
+
+    // -------------------------------------
+    // Vote Escrow Functions
+    // -------------------------------------
+
+    /// Create the ve config and its governance token escrow.
+    pub fn initialize_ve_config(ctx: Context<InitializeVeConfig>) -> Result<()> {
+        instructions::initialize_ve_config(ctx)
+    }
+
+    /// Lock governance tokens for ve.
+    pub fn create_ve_lock(ctx: Context<CreateVeLock>, amount: u64, duration: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero lock
+        require!(duration > 0 && duration <= MAX_VE_LOCK_SECS, ErrorCode::InvalidLockupPeriod); // Bound the lock length
+
+        instructions::create_ve_lock(ctx, amount, duration)
+    }
+
+    /// Withdraw the tokens of an expired, unboosting ve lock.
+    pub fn withdraw_ve_lock(ctx: Context<WithdrawVeLock>) -> Result<()> {
+        instructions::withdraw_ve_lock(ctx)
+    }
+
+    /// Boost a staker's rewards with the owner's ve lock.
+    pub fn apply_boost(ctx: Context<ApplyBoost>) -> Result<()> {
+        instructions::apply_boost(ctx)
+    }
+
+    /// Downgrade the boost of an expired ve lock. Callable by anyone.
+    pub fn kick_boost(ctx: Context<KickBoost>) -> Result<()> {
+        instructions::kick_boost(ctx)
+    }
+
+    /// Move a stake and its ve lock to `new_owner`, preserving lockups, boost and reward debt.
+    pub fn transfer_stake_ownership(ctx: Context<TransferStakeOwnership>, new_owner: Pubkey) -> Result<()> {
+        instructions::transfer_stake_ownership(ctx, new_owner)
+    }
+
+    // -------------------------------------
+    // Margin Mode Functions
+    // -------------------------------------
+
+    /// Create the owner's margin account in isolated mode.
+    pub fn initialize_margin_account(ctx: Context<InitializeMarginAccount>) -> Result<()> {
+        instructions::initialize_margin_account(ctx)
+    }
+
+    /// Switch between isolated and cross margin.
+    pub fn set_margin_mode(ctx: Context<SetMarginMode>, mode: MarginMode) -> Result<()> {
+        instructions::set_margin_mode(ctx, mode)
+    }
+
+    /// Pool a position under cross margin.
+    pub fn add_cross_position(ctx: Context<UpdateCrossPosition>) -> Result<()> {
+        instructions::add_cross_position(ctx)
+    }
+
+    /// Take a healthy position out of cross margin.
+    pub fn remove_cross_position(ctx: Context<UpdateCrossPosition>) -> Result<()> {
+        instructions::remove_cross_position(ctx)
+    }
+
+    /// Preview the portfolio health of a margin account.
+    pub fn preview_portfolio_health(ctx: Context<PreviewPortfolioHealth>) -> Result<PortfolioHealth> {
+        instructions::preview_portfolio_health(ctx)
+    }
+
+    /// Create the cached portfolio valuation of a margin account.
+    pub fn initialize_portfolio_health(ctx: Context<InitializePortfolioHealth>) -> Result<()> {
+        instructions::initialize_portfolio_health(ctx)
+    }
+
+    /// Revalue a user's cross-margin portfolio and cache it for mints to consult.
+    pub fn refresh_portfolio(ctx: Context<RefreshPortfolio>, user: Pubkey) -> Result<PortfolioHealth> {
+        instructions::refresh_portfolio(ctx, user)
+    }
+
+    // -------------------------------------
+    // Pause State Functions
+    // -------------------------------------
+
+    /// Create the pause state with every action enabled.
+    pub fn initialize_pause_state(ctx: Context<InitializePauseState>) -> Result<()> {
+        instructions::initialize_pause_state(ctx)
+    }
+
+    /// Pause or resume minting, redemptions, liquidations and governance, or enter emergency shutdown.
+    pub fn set_pause_state(
+        ctx: Context<SetPauseState>,
+        minting_paused: bool,
+        redemptions_paused: bool,
+        liquidations_paused: bool,
+        governance_paused: bool,
+        emergency_shutdown: bool,
+        reason: PauseReason,
+    ) -> Result<()> {
+        instructions::set_pause_state(ctx, minting_paused, redemptions_paused, liquidations_paused, governance_paused, emergency_shutdown, reason)
+    }
+
+    /// Pause or resume minting against a single collateral type. Guardians can only pause.
+    pub fn set_collateral_minting_paused(ctx: Context<SetCollateralMintingPaused>, minting_paused: bool) -> Result<()> {
+        instructions::set_collateral_minting_paused(ctx, minting_paused)
+    }
+
+    /// Fail if the action is paused. Intended for CPI from integrating programs.
+    pub fn require_not_paused(ctx: Context<RequireNotPaused>, action: PausableAction) -> Result<()> {
+        instructions::require_not_paused(ctx, action)
+    }
+
+    // -------------------------------------
+    // Collateral Auction Functions
+    // -------------------------------------
+
+    /// Create a collateral type's auction parameters with the defaults.
+    pub fn initialize_auction_params(ctx: Context<InitializeAuctionParams>) -> Result<()> {
+        instructions::initialize_auction_params(ctx)
+    }
+
+    /// Set a collateral type's Dutch auction curve and buy-now premium.
+    pub fn set_auction_params(ctx: Context<SetAuctionParams>, start_premium_bps: u64, floor_bps: u64, duration_secs: u64, buy_now_premium_bps: u64) -> Result<()> {
+        require!(floor_bps <= 10_000 + start_premium_bps, ErrorCode::InvalidAmount); // Price must decay, not rise
+        require!(duration_secs > 0, ErrorCode::InvalidAmount); // Decay window must be non-zero
+
+        instructions::set_auction_params(ctx, start_premium_bps, floor_bps, duration_secs, buy_now_premium_bps)
+    }
+
+    /// Cap the collateral a single auction lot of a collateral type may sell.
+    pub fn set_max_lot_size(ctx: Context<SetAuctionParams>, max_lot_size: u64) -> Result<()> {
+        instructions::set_max_lot_size(ctx, max_lot_size)
+    }
+
+    /// Settle a collateral type's auctions in a treasury reserve asset instead of the stablecoin.
+    pub fn set_auction_settlement_mint(ctx: Context<SetAuctionSettlementMint>) -> Result<()> {
+        instructions::set_auction_settlement_mint(ctx)
+    }
+
+    /// Auction off a liquidatable position's collateral. Callable by anyone.
+    pub fn start_collateral_auction(ctx: Context<StartCollateralAuction>) -> Result<()> {
+        instructions::start_collateral_auction(ctx)
+    }
+
+    /// Start the next lot of a liquidation split into an auction series. Callable by anyone.
+    pub fn start_next_auction_lot(ctx: Context<StartNextAuctionLot>) -> Result<()> {
+        instructions::start_next_auction_lot(ctx)
+    }
+
+    /// Take an auction's lot at the current Dutch price.
+    pub fn take_collateral_auction(ctx: Context<SettleCollateralAuction>, max_price: u64, deadline_ts: Option<i64>) -> Result<()> {
+        instructions::take_collateral_auction(ctx, max_price, deadline_ts)
+    }
+
+    /// Take an auction's lot at the oracle price plus the buy-now premium.
+    pub fn buy_now(ctx: Context<SettleCollateralAuction>, max_price: u64, deadline_ts: Option<i64>) -> Result<()> {
+        instructions::buy_now(ctx, max_price, deadline_ts)
+    }
+
+    /// Toggle a collateral type's auctions between open takes and commit-reveal sealed bids.
+    pub fn set_sealed_bid_mode(ctx: Context<SetAuctionParams>, sealed_bid: bool, commit_secs: u64, reveal_secs: u64, bid_bond_lamports: u64) -> Result<()> {
+        require!(!sealed_bid || (commit_secs > 0 && reveal_secs > 0), ErrorCode::InvalidAmount); // Both phases must be non-zero
+
+        instructions::set_sealed_bid_mode(ctx, sealed_bid, commit_secs, reveal_secs, bid_bond_lamports)
+    }
+
+    /// Commit a hidden bid on a sealed-bid auction, reverting after `deadline_ts`.
+    pub fn commit_bid(ctx: Context<CommitBid>, commitment: [u8; 32], deadline_ts: Option<i64>) -> Result<()> {
+        instructions::commit_bid(ctx, commitment, deadline_ts)
+    }
+
+    /// Reveal a committed bid once the commit phase has closed, reverting after `deadline_ts`.
+    pub fn reveal_bid(ctx: Context<RevealBid>, price: u64, salt: [u8; 32], deadline_ts: Option<i64>) -> Result<()> {
+        instructions::reveal_bid(ctx, price, salt, deadline_ts)
+    }
+
+    /// Settle a sealed-bid auction to its best revealed bidder, reverting after `deadline_ts`.
+    pub fn settle_sealed_bid(ctx: Context<SettleCollateralAuction>, deadline_ts: Option<i64>) -> Result<()> {
+        instructions::settle_sealed_bid(ctx, deadline_ts)
+    }
+
+    /// Slash the bond of an unrevealed or unsettled sealed bid. Callable by anyone.
+    pub fn slash_sealed_bid(ctx: Context<SlashSealedBid>) -> Result<()> {
+        instructions::slash_sealed_bid(ctx)
+    }
+
+    // -------------------------------------
+    // Treasury Swap Functions
+    // -------------------------------------
+
+    /// Schedule a time-sliced conversion of treasury stablecoin into reserves.
+    pub fn create_swap_schedule(ctx: Context<CreateSwapSchedule>, total_amount: u64, slice_amount: u64, interval_secs: u64, min_output_price: u64) -> Result<()> {
+        require!(total_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero conversion
+        require!(slice_amount > 0 && slice_amount <= total_amount, ErrorCode::InvalidAmount); // Slice must fit the total
+        require!(min_output_price > 0, ErrorCode::InvalidPrice); // Every slice needs a limit price
+
+        instructions::create_swap_schedule(ctx, total_amount, slice_amount, interval_secs, min_output_price)
+    }
+
+    /// Execute the next due slice of a swap schedule, reverting after `deadline_ts`. Callable by anyone.
+    pub fn execute_swap_slice<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteSwapSlice<'info>>, deadline_ts: Option<i64>) -> Result<()> {
+        instructions::execute_swap_slice(ctx, deadline_ts)
+    }
+
+    /// Cancel a swap schedule and return the unconverted stablecoin.
+    pub fn cancel_swap_schedule(ctx: Context<CancelSwapSchedule>) -> Result<()> {
+        instructions::cancel_swap_schedule(ctx)
+    }
+
+    // -------------------------------------
+    // Bad Debt Queue Functions
+    // -------------------------------------
+
+    /// Create the bad debt queue.
+    pub fn initialize_bad_debt_queue(ctx: Context<InitializeBadDebtQueue>, debt_auction_price: u64) -> Result<()> {
+        instructions::initialize_bad_debt_queue(ctx, debt_auction_price)
+    }
+
+    /// Set the governance tokens debt auctions sell per stablecoin.
+    pub fn set_debt_auction_price(ctx: Context<SetDebtAuctionPrice>, debt_auction_price: u64) -> Result<()> {
+        instructions::set_debt_auction_price(ctx, debt_auction_price)
+    }
+
+    /// Set the reward pool slashed for bad debt.
+    pub fn set_bad_debt_slashing_pool(ctx: Context<SetBadDebtSlashingPool>) -> Result<()> {
+        instructions::set_bad_debt_slashing_pool(ctx)
+    }
+
+    /// Absorb the oldest bad debt with the insurance pool. Callable by anyone.
+    pub fn absorb_bad_debt_with_insurance(ctx: Context<AbsorbBadDebt>) -> Result<()> {
+        instructions::absorb_bad_debt_with_insurance(ctx)
+    }
+
+    /// Slash stakers for the oldest bad debt once insurance is exhausted. Callable by anyone.
+    pub fn slash_stakers_for_bad_debt(ctx: Context<SlashStakersForBadDebt>) -> Result<()> {
+        instructions::slash_stakers_for_bad_debt(ctx)
+    }
+
+    /// Buy governance tokens with stablecoin to cover the oldest bad debt, receiving at least `min_out`.
+    pub fn take_debt_auction(ctx: Context<TakeDebtAuction>, amount: u64, deadline_ts: Option<i64>, min_out: Option<u64>) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero bid
+
+        instructions::take_debt_auction(ctx, amount, deadline_ts, min_out)
+    }
+
+    /// Burn up to `amount` of treasury stablecoin against the bad debt queue, oldest entry first.
+    pub fn treasury_repay_bad_debt(ctx: Context<TreasuryRepayBadDebt>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero repayment
+
+        instructions::treasury_repay_bad_debt(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Governance Token Oracle Functions
+    // -------------------------------------
+
+    /// Create the governance token oracle over an AMM pool and, if listed, a Pyth feed.
+    pub fn initialize_gov_token_oracle(ctx: Context<InitializeGovTokenOracle>, min_quote_liquidity: u64) -> Result<()> {
+        instructions::initialize_gov_token_oracle(ctx, min_quote_liquidity)
+    }
+
+    /// Set the governance token oracle's TWAP window and manipulation limits.
+    pub fn set_gov_token_oracle_params(
+        ctx: Context<SetGovTokenOracleParams>,
+        twap_window_secs: u64,
+        max_spot_move_bps: u64,
+        max_divergence_bps: u64,
+        max_price_age_secs: u64,
+        max_twap_age_secs: u64,
+        min_quote_liquidity: u64,
+    ) -> Result<()> {
+        require!(twap_window_secs > 0, ErrorCode::InvalidAmount); // Averaging window must be non-zero
+        require!(max_spot_move_bps <= 10_000, ErrorCode::InvalidAmount); // Clamp cannot exceed the TWAP itself
+
+        instructions::set_gov_token_oracle_params(ctx, twap_window_secs, max_spot_move_bps, max_divergence_bps, max_price_age_secs, max_twap_age_secs, min_quote_liquidity)
+    }
+
+    /// Set the governance fallback price of the governance token.
+    pub fn set_gov_token_fallback_price(ctx: Context<SetGovTokenFallbackPrice>, fallback_price: u64) -> Result<()> {
+        instructions::set_gov_token_fallback_price(ctx, fallback_price)
+    }
+
+    /// Update the governance token TWAP from its AMM pool. Callable by anyone.
+    pub fn update_gov_token_twap(ctx: Context<UpdateGovTokenTwap>) -> Result<()> {
+        instructions::update_gov_token_twap(ctx)
+    }
+
+    // -------------------------------------
+    // Debt Note Functions
+    // -------------------------------------
+
+    /// Sell a note on repayments of a position's owner at a discount.
+    pub fn assign_debt(ctx: Context<AssignDebt>, amount: u64, price: u64, maturity: i64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero face value
+        require!(price <= amount, ErrorCode::InvalidPrice); // Notes trade at or below face value
+
+        instructions::assign_debt(ctx, amount, price, maturity)
+    }
+
+    /// Repay a debt note's holder.
+    pub fn repay_debt_note(ctx: Context<RepayDebtNote>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero repayment
+
+        instructions::repay_debt_note(ctx, amount)
+    }
+
+    /// Transfer a debt note to a new holder.
+    pub fn transfer_debt_note(ctx: Context<TransferDebtNote>, new_holder: Pubkey) -> Result<()> {
+        instructions::transfer_debt_note(ctx, new_holder)
+    }
+
+    /// Seize excess collateral for a defaulted debt note.
+    pub fn seize_note_collateral(ctx: Context<SeizeNoteCollateral>) -> Result<()> {
+        instructions::seize_note_collateral(ctx)
+    }
+
+    // -------------------------------------
+    // Payment Stream Functions
+    // -------------------------------------
+
+    /// Open a stablecoin payment stream, e.g. for payroll.
+    pub fn create_stream(ctx: Context<CreatePaymentStream>, recipient: Pubkey, rate_per_second: u64, end_time: i64) -> Result<()> {
+        require!(rate_per_second > 0, ErrorCode::InvalidAmount); // Ensure the stream pays something
+
+        instructions::create_stream(ctx, recipient, rate_per_second, end_time)
+    }
+
+    /// Fund a payment stream.
+    pub fn top_up(ctx: Context<TopUpPaymentStream>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero top-up
+
+        instructions::top_up(ctx, amount)
+    }
+
+    /// Withdraw the streamed amount as the recipient.
+    pub fn withdraw_from_stream(ctx: Context<WithdrawFromPaymentStream>) -> Result<()> {
+        instructions::withdraw_from_stream(ctx)
+    }
+
+    /// Cancel a payment stream as its sender.
+    pub fn cancel_stream(ctx: Context<CancelPaymentStream>) -> Result<()> {
+        instructions::cancel_stream(ctx)
+    }
+
+    // -------------------------------------
+    // Epoch Reporting Functions
+    // -------------------------------------
+
+    /// Start per-epoch revenue reporting.
+    pub fn initialize_epoch_state(ctx: Context<InitializeEpochState>, epoch_duration: i64) -> Result<()> {
+        require!(epoch_duration > 0, ErrorCode::InvalidAmount); // Ensure epochs have a length
+
+        instructions::initialize_epoch_state(ctx, epoch_duration)
+    }
+
+    /// Finalize the current epoch's revenue into an EpochReport.
+    pub fn close_epoch<'info>(ctx: Context<'_, '_, 'info, 'info, CloseEpoch<'info>>) -> Result<CrankStatus> {
+        instructions::close_epoch(ctx)
+    }
+
+    // -------------------------------------
+    // Liquidation Scan Functions
+    // -------------------------------------
+
+    /// Return the liquidatable positions among the remaining accounts, for liquidator bots.
+    pub fn scan_liquidatable(ctx: Context<ScanLiquidatable>) -> Result<Vec<LiquidationCandidate>> {
+        instructions::scan_liquidatable(ctx)
+    }
+
+    // -------------------------------------
+    // Stake Pool Collateral Functions
+    // -------------------------------------
+
+    /// Whitelist the stake pool backing an LST collateral type.
+    pub fn whitelist_stake_pool(ctx: Context<WhitelistStakePool>, unstake_haircut_bps: u64) -> Result<()> {
+        require!(unstake_haircut_bps < 10_000, ErrorCode::InvalidAmount); // Haircut cannot wipe out the collateral value
+
+        instructions::whitelist_stake_pool(ctx, unstake_haircut_bps)
+    }
+
+    /// Set the unstake latency haircut of a stake pool collateral.
+    pub fn set_stake_pool_haircut(ctx: Context<SetStakePoolHaircut>, unstake_haircut_bps: u64) -> Result<()> {
+        require!(unstake_haircut_bps < 10_000, ErrorCode::InvalidAmount); // Haircut cannot wipe out the collateral value
+
+        instructions::set_stake_pool_haircut(ctx, unstake_haircut_bps)
+    }
+
+    /// Stake SOL through the whitelisted stake pool and deposit the LST as collateral.
+    pub fn deposit_sol_collateral(ctx: Context<DepositSolCollateral>, lamports: u64) -> Result<()> {
+        require!(lamports > 0, ErrorCode::InvalidAmount); // Ensure non-zero deposit
+
+        instructions::deposit_sol_collateral(ctx, lamports)
+    }
+
+    /// Refresh the cached price of a stake pool collateral.
+    pub fn refresh_stake_pool_price(ctx: Context<RefreshStakePoolPrice>) -> Result<()> {
+        instructions::refresh_stake_pool_price(ctx)
+    }
+
+    // -------------------------------------
+    // Testing Functions
+    // -------------------------------------
+
+    /// Route instructions the program does not declare to the testing
+    /// instructions, which only exist in `testing` builds.
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        #[cfg(feature = "testing")]
+        {
+            testing::dispatch(program_id, accounts, data)
+        }
+        #[cfg(not(feature = "testing"))]
+        {
+            let _ = (program_id, accounts, data);
+            Err(anchor_lang::error::ErrorCode::InstructionFallbackNotFound.into())
+        }
+    }
+
+    // -------------------------------------
+    // Crank Scheduling Functions
+    // -------------------------------------
+
+    /// Create the crank schedule with default per-crank intervals (governance authority only).
+    pub fn initialize_crank_schedule(ctx: Context<InitializeCrankSchedule>) -> Result<()> {
+        instructions::initialize_crank_schedule(ctx)
+    }
+
+    /// Set the minimum slots between two runs of a crank (governance authority only).
+    pub fn set_crank_interval(ctx: Context<SetCrankInterval>, kind: CrankKind, min_interval_slots: u64) -> Result<()> {
+        instructions::set_crank_interval(ctx, kind, min_interval_slots)
+    }
+
+    /// Accrue a reward pool's emissions. Callable by anyone; returns the next eligible slot.
+    pub fn crank_reward_pool(ctx: Context<CrankRewardPool>) -> Result<CrankStatus> {
+        instructions::crank_reward_pool(ctx)
+    }
+
+    // -------------------------------------
+    // Revenue Reward Functions
+    // -------------------------------------
+
+    /// Select minted emissions or protocol revenue as a reward pool's source (governance authority only).
+    pub fn set_reward_source(ctx: Context<SetRewardSource>, reward_source: RewardSource, revenue_share_bps: u64) -> Result<()> {
+        instructions::set_reward_source(ctx, reward_source, revenue_share_bps)
+    }
+
+    /// Set the revenue reward pool funded at each epoch close (governance authority only).
+    pub fn set_epoch_revenue_pool(ctx: Context<SetEpochRevenuePool>) -> Result<()> {
+        instructions::set_epoch_revenue_pool(ctx)
+    }
+
+    /// Claim stablecoin rewards from a revenue reward pool.
+    pub fn claim_revenue_rewards(ctx: Context<ClaimRevenueRewards>) -> Result<()> {
+        let staker_account = &ctx.accounts.staker_account;
+        let current_time = Clock::get()?.unix_timestamp as u64;
+
+        // Ensure that the claim cooldown has passed since the last claim
+        let next_claim_time = staker_account.last_reward_claim.saturating_add(ctx.accounts.reward_pool.min_claim_interval);
+        require!(current_time >= next_claim_time, ErrorCode::ClaimCooldownActive);
+
+        instructions::claim_revenue_rewards(ctx)
+    }
+
+    // -------------------------------------
+    // Position Ledger Functions
+    // -------------------------------------
+
+    /// Opt a position into an on-chain ledger of its mints, repayments, deposits and liquidations.
+    pub fn initialize_position_ledger(ctx: Context<InitializePositionLedger>, capacity: u32) -> Result<()> {
+        instructions::initialize_position_ledger(ctx, capacity)
+    }
+
+    /// Add room for more entries to a position ledger.
+    pub fn extend_position_ledger(ctx: Context<ExtendPositionLedger>, additional_entries: u32) -> Result<()> {
+        instructions::extend_position_ledger(ctx, additional_entries)
+    }
+
+    /// Export a page of a position's ledger as return data.
+    pub fn export_position_history(ctx: Context<ExportPositionHistory>, start: u32, limit: u32) -> Result<PositionHistoryPage> {
+        instructions::export_position_history(ctx, start, limit)
+    }
+
+    // -------------------------------------
+    // Ceiling Controller Functions
+    // -------------------------------------
+
+    /// Let a collateral's debt ceiling follow its utilization within governance bounds.
+    pub fn initialize_ceiling_controller(ctx: Context<InitializeCeilingController>, params: CeilingControllerParams) -> Result<()> {
+        instructions::initialize_ceiling_controller(ctx, params)
+    }
+
+    /// Update a ceiling controller's thresholds and bounds (governance authority only).
+    pub fn set_ceiling_controller(ctx: Context<SetCeilingController>, params: CeilingControllerParams) -> Result<()> {
+        instructions::set_ceiling_controller(ctx, params)
+    }
+
+    /// Observe a collateral's ceiling utilization for the current epoch and step the ceiling. Callable by anyone.
+    pub fn adjust_debt_ceiling(ctx: Context<AdjustDebtCeiling>) -> Result<CeilingReport> {
+        instructions::adjust_debt_ceiling(ctx)
+    }
+
+    // -------------------------------------
+    // Market Maker Facility Functions
+    // -------------------------------------
+
+    /// Open a credit line for an approved market maker (governance authority only).
+    pub fn open_mm_facility(ctx: Context<OpenMmFacility>, market_maker: Pubkey, terms: MmFacilityTerms) -> Result<()> {
+        instructions::open_mm_facility(ctx, market_maker, terms)
+    }
+
+    /// Update a market maker facility's ceiling, fees and collateral terms (governance authority only).
+    pub fn set_mm_facility_terms(ctx: Context<ManageMmFacility>, terms: MmFacilityTerms) -> Result<()> {
+        instructions::set_mm_facility_terms(ctx, terms)
+    }
+
+    /// Suspend or resume a market maker facility's mints (governance authority only).
+    pub fn set_mm_facility_active(ctx: Context<ManageMmFacility>, active: bool) -> Result<()> {
+        instructions::set_mm_facility_active(ctx, active)
+    }
+
+    /// Record the hash and value of a market maker's collateral attestation (governance authority only).
+    pub fn attest_mm_collateral(ctx: Context<ManageMmFacility>, attestation_hash: [u8; 32], attested_value: u64) -> Result<()> {
+        instructions::attest_mm_collateral(ctx, attestation_hash, attested_value)
+    }
+
+    /// Mint stablecoin against the caller's market maker credit line.
+    pub fn mm_facility_mint(ctx: Context<MmFacilityMint>, amount: u64) -> Result<()> {
+        instructions::mm_facility_mint(ctx, amount)
+    }
+
+    /// Redeem stablecoin against the caller's market maker credit line.
+    pub fn mm_facility_redeem(ctx: Context<MmFacilityRedeem>, amount: u64) -> Result<()> {
+        instructions::mm_facility_redeem(ctx, amount)
+    }
+
+    // -------------------------------------
+    // Travel Rule Functions
+    // -------------------------------------
+
+    /// Approve a VASP signer to record travel-rule attestations (governance authority only).
+    pub fn register_travel_rule_attestor(ctx: Context<RegisterTravelRuleAttestor>, attestor: Pubkey) -> Result<()> {
+        instructions::register_travel_rule_attestor(ctx, attestor)
+    }
+
+    /// Revoke a travel-rule attestor (governance authority only).
+    pub fn remove_travel_rule_attestor(ctx: Context<RemoveTravelRuleAttestor>) -> Result<()> {
+        instructions::remove_travel_rule_attestor(ctx)
+    }
+
+    /// Record the travel-rule reference of an upcoming transfer (approved attestors only).
+    pub fn attest_travel_rule(
+        ctx: Context<AttestTravelRule>,
+        memo_hash: [u8; 32],
+        originator: Pubkey,
+        beneficiary: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require!(memo_hash != [0u8; 32], ErrorCode::InvalidAccountData); // An attestation must reference a travel-rule record
+        instructions::attest_travel_rule(ctx, memo_hash, originator, beneficiary, amount)
+    }
+
+    /// Transfer stablecoin with a travel-rule memo hash, optionally verified against an attestation.
+    pub fn transfer_with_memo(ctx: Context<TransferWithMemo>, amount: u64, memo_hash: [u8; 32]) -> Result<()> {
+        require!(memo_hash != [0u8; 32], ErrorCode::InvalidAccountData); // A memo transfer must carry a reference
+        instructions::transfer_with_memo(ctx, amount, memo_hash)
+    }
+
+    // -------------------------------------
+    // Token Rescue Functions
+    // -------------------------------------
+
+    /// Sweep non-protocol tokens sent by mistake to a program-owned token account (governance authority only).
+    pub fn rescue_tokens(
+        ctx: Context<RescueTokens>,
+        mint: Pubkey,
+        amount: u64,
+        destination: Pubkey,
+        authority: RescueAuthority,
+    ) -> Result<()> {
+        instructions::rescue_tokens(ctx, mint, amount, destination, authority)
+    }
+
+    // -------------------------------------
+    // Redemption Queue Functions
+    // -------------------------------------
+
+    /// Create the redemption queue of a treasury's reserves (governance authority only).
+    pub fn initialize_redemption_queue(ctx: Context<InitializeRedemptionQueue>, priority_fee_bps: u64) -> Result<()> {
+        instructions::initialize_redemption_queue(ctx, priority_fee_bps)
+    }
+
+    /// Set the fee charged for the priority redemption lane (governance authority only).
+    pub fn set_redemption_priority_fee(ctx: Context<SetRedemptionPriorityFee>, priority_fee_bps: u64) -> Result<()> {
+        instructions::set_redemption_priority_fee(ctx, priority_fee_bps)
+    }
+
+    /// Queue stablecoin for redemption against the treasury's liquid reserves.
+    pub fn request_redemption(
+        ctx: Context<RequestRedemption>,
+        amount: u64,
+        lane: RedemptionLane,
+        request_id: u64,
+        partner_id: Option<u64>,
+    ) -> Result<()> {
+        instructions::request_redemption(ctx, amount, lane, request_id, partner_id)
+    }
+
+    /// Fill the redemption at the head of its lane from the liquid reserves.
+    pub fn fill_redemption(ctx: Context<FillRedemption>) -> Result<()> {
+        instructions::fill_redemption(ctx)
+    }
+
+    /// Withdraw the unfilled remainder of a queued redemption.
+    pub fn cancel_redemption(ctx: Context<CancelRedemption>) -> Result<()> {
+        instructions::cancel_redemption(ctx)
+    }
+
+    /// Close a finished redemption request and reclaim its rent.
+    pub fn close_redemption_request(ctx: Context<CloseRedemptionRequest>) -> Result<()> {
+        instructions::close_redemption_request(ctx)
+    }
+
+    // -------------------------------------
+    // Lending Receipt Collateral Functions
+    // -------------------------------------
+
+    /// Bind a collateral type to the Kamino reserve or marginfi bank issuing its receipt.
+    pub fn whitelist_receipt_collateral(
+        ctx: Context<WhitelistReceiptCollateral>,
+        market: ReceiptMarket,
+        haircut_bps: u64,
+        max_rate_age_secs: u64,
+    ) -> Result<()> {
+        require!(haircut_bps < 10_000, ErrorCode::InvalidAmount); // Haircut cannot wipe out the collateral value
+        require!(max_rate_age_secs > 0, ErrorCode::InvalidAmount); // A zero window would reject every refresh
+        require!(max_rate_age_secs <= receipt::MAX_RATE_AGE_SECS, ErrorCode::InvalidAmount); // Stale rates would overvalue the receipt
+
+        instructions::whitelist_receipt_collateral(ctx, market, haircut_bps, max_rate_age_secs)
+    }
+
+    /// Set the haircut and rate freshness limit of a receipt collateral.
+    pub fn set_receipt_collateral_params(ctx: Context<SetReceiptCollateralParams>, haircut_bps: u64, max_rate_age_secs: u64) -> Result<()> {
+        require!(haircut_bps < 10_000, ErrorCode::InvalidAmount); // Haircut cannot wipe out the collateral value
+        require!(max_rate_age_secs > 0, ErrorCode::InvalidAmount); // A zero window would reject every refresh
+        require!(max_rate_age_secs <= receipt::MAX_RATE_AGE_SECS, ErrorCode::InvalidAmount); // Stale rates would overvalue the receipt
+
+        instructions::set_receipt_collateral_params(ctx, haircut_bps, max_rate_age_secs)
+    }
+
+    /// Refresh the cached price of a lending receipt collateral.
+    pub fn refresh_receipt_price(ctx: Context<RefreshReceiptPrice>) -> Result<()> {
+        instructions::refresh_receipt_price(ctx)
+    }
+
+    // -------------------------------------
+    // Auto-Deleverage Functions
+    // -------------------------------------
+
+    /// Opt a position into keeper-run deleveraging below `trigger_health_bps`.
+    pub fn enable_auto_deleverage(ctx: Context<EnableAutoDeleverage>, params: DeleverageParams) -> Result<()> {
+        instructions::enable_auto_deleverage(ctx, params)
+    }
+
+    /// Change a position's auto-deleverage settings.
+    pub fn set_auto_deleverage(ctx: Context<ManageAutoDeleverage>, params: DeleverageParams) -> Result<()> {
+        instructions::set_auto_deleverage(ctx, params)
+    }
+
+    /// Opt a position out of auto-deleveraging.
+    pub fn disable_auto_deleverage(ctx: Context<ManageAutoDeleverage>) -> Result<()> {
+        instructions::disable_auto_deleverage(ctx)
+    }
+
+    /// Repay part of an opted-in position's debt, buying its collateral at up to `discount_bps` off the oracle price.
+    pub fn auto_deleverage(ctx: Context<AutoDeleverage>, repay_amount: u64, discount_bps: u64) -> Result<()> {
+        require!(repay_amount > 0, ErrorCode::InvalidAmount); // Ensure non-zero repayment
+
+        instructions::auto_deleverage(ctx, repay_amount, discount_bps)
+    }
+
+    // -------------------------------------
+    // Pool Sunset Functions
+    // -------------------------------------
+
+    /// Stop a reward pool's emissions and open a penalty-free withdrawal window of `withdrawal_window_secs`.
+    pub fn begin_pool_sunset(ctx: Context<BeginPoolSunset>, withdrawal_window_secs: u64) -> Result<()> {
+        instructions::begin_pool_sunset(ctx, withdrawal_window_secs)
+    }
+
+    /// Sweep a sunset pool's unclaimed revenue rewards to the treasury after its deadline.
+    pub fn sweep_sunset_rewards(ctx: Context<SweepSunsetRewards>) -> Result<()> {
+        instructions::sweep_sunset_rewards(ctx)
+    }
+
+    /// Return a lingering stake in a sunset pool to its owner after the deadline.
+    pub fn return_sunset_stake(ctx: Context<ReturnSunsetStake>) -> Result<()> {
+        instructions::return_sunset_stake(ctx)
+    }
+
+    /// Close an emptied sunset pool and its vaults after its deadline.
+    pub fn close_sunset_pool(ctx: Context<CloseSunsetPool>) -> Result<()> {
+        instructions::close_sunset_pool(ctx)
+    }
+
+    // -------------------------------------
+    // Liquidity Coverage Functions
+    // -------------------------------------
+
+    /// Start monitoring redemption coverage of a treasury's liquid reserves.
+    pub fn initialize_liquidity_monitor(ctx: Context<InitializeLiquidityMonitor>, alert_bps: u64, floor_bps: u64) -> Result<()> {
+        instructions::initialize_liquidity_monitor(ctx, alert_bps, floor_bps)
+    }
+
+    /// Set the coverage alert threshold and recall floor.
+    pub fn set_liquidity_thresholds(ctx: Context<SetLiquidityThresholds>, alert_bps: u64, floor_bps: u64) -> Result<()> {
+        instructions::set_liquidity_thresholds(ctx, alert_bps, floor_bps)
+    }
+
+    /// Recompute the liquidity coverage ratio, alerting below the threshold.
+    pub fn check_liquidity_coverage(ctx: Context<CheckLiquidityCoverage>) -> Result<u64> {
+        instructions::check_liquidity_coverage(ctx)
+    }
+
+    /// Recall strategy funds while coverage is below the hard floor.
+    pub fn recall_for_liquidity_coverage(ctx: Context<RecallForLiquidityCoverage>) -> Result<()> {
+        instructions::recall_for_liquidity_coverage(ctx)
+    }
+
+    // -------------------------------------
+    // Partner Functions
+    // -------------------------------------
+
+    /// Register a partner integrator with a negotiated revenue share.
+    pub fn register_partner(ctx: Context<RegisterPartner>, partner_id: u64, wallet: Pubkey, revenue_share_bps: u64) -> Result<()> {
+        instructions::register_partner(ctx, partner_id, wallet, revenue_share_bps)
+    }
+
+    /// Update a partner's payout wallet, revenue share and status.
+    pub fn set_partner_terms(ctx: Context<SetPartnerTerms>, wallet: Pubkey, revenue_share_bps: u64, active: bool) -> Result<()> {
+        instructions::set_partner_terms(ctx, wallet, revenue_share_bps, active)
+    }
+
+    /// Claim a partner's accrued revenue share from the treasury.
+    pub fn claim_partner_revenue(ctx: Context<ClaimPartnerRevenue>) -> Result<()> {
+        instructions::claim_partner_revenue(ctx)
+    }
+}
@@ -0,0 +1,80 @@
+// loyalty.rs
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::ErrorCode;
+
+/// Metaplex Token Metadata program.
+pub mod token_metadata {
+    use anchor_lang::declare_id;
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+// Byte offsets of the Metaplex `Metadata` account layout
+const KEY_LEN: usize = 1;
+const UPDATE_AUTHORITY_LEN: usize = 32;
+const MINT_LEN: usize = 32;
+const CREATOR_LEN: usize = 32 + 1 + 1;
+// Account key of a v1 metadata account
+const METADATA_V1_KEY: u8 = 4;
+
+/// Check that `owner` holds an NFT from a verified collection, returning the collection mint.
+///
+/// The token account must hold exactly one token of the NFT mint, and the
+/// metadata must be the canonical Metaplex PDA of that mint with a verified
+/// collection, so a holder cannot point at metadata they made up.
+pub fn verify_loyalty_pass(token_account: &TokenAccount, metadata: &AccountInfo, owner: &Pubkey) -> Result<Pubkey> {
+    require_keys_eq!(token_account.owner, *owner, ErrorCode::InvalidLoyaltyPass);
+    require!(token_account.amount == 1, ErrorCode::InvalidLoyaltyPass);
+
+    let (expected_metadata, _) = Pubkey::find_program_address(
+        &[b"metadata", token_metadata::ID.as_ref(), token_account.mint.as_ref()],
+        &token_metadata::ID,
+    );
+    require_keys_eq!(metadata.key(), expected_metadata, ErrorCode::InvalidLoyaltyPass);
+    require_keys_eq!(*metadata.owner, token_metadata::ID, ErrorCode::InvalidLoyaltyPass);
+
+    parse_verified_collection(&metadata.try_borrow_data()?)?.ok_or_else(|| ErrorCode::InvalidLoyaltyPass.into())
+}
+
+/// Parse the verified collection out of a Metaplex `Metadata` account, if any.
+fn parse_verified_collection(data: &[u8]) -> Result<Option<Pubkey>> {
+    require!(data.first() == Some(&METADATA_V1_KEY), ErrorCode::InvalidLoyaltyPass);
+    let mut offset = KEY_LEN + UPDATE_AUTHORITY_LEN + MINT_LEN;
+
+    // name, symbol and uri are length-prefixed strings
+    for _ in 0..3 {
+        offset += 4 + read_u32(data, offset)? as usize;
+    }
+    offset += 2; // seller_fee_basis_points
+
+    if read_u8(data, offset)? == 1 {
+        let creators = read_u32(data, offset + 1)? as usize;
+        offset += 1 + 4 + creators * CREATOR_LEN;
+    } else {
+        offset += 1;
+    }
+    offset += 2; // primary_sale_happened, is_mutable
+
+    // edition_nonce and token_standard are optional single bytes
+    for _ in 0..2 {
+        offset += if read_u8(data, offset)? == 1 { 2 } else { 1 };
+    }
+
+    if read_u8(data, offset)? != 1 {
+        return Ok(None);
+    }
+    let verified = read_u8(data, offset + 1)? == 1;
+    let key = data.get(offset + 2..offset + 34).ok_or(ErrorCode::InvalidLoyaltyPass)?;
+    Ok(verified.then(|| Pubkey::try_from(key).unwrap()))
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8> {
+    data.get(offset).copied().ok_or_else(|| ErrorCode::InvalidLoyaltyPass.into())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or(ErrorCode::InvalidLoyaltyPass)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
@@ -0,0 +1,53 @@
+// math.rs
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+/// Denominator of every basis-point rate.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Fixed-point precision of the reward-per-share accumulators.
+///
+/// At 1e12 a stake of a single base unit still accrues a non-zero share of
+/// one base unit of rewards spread over up to 1e12 units of stake.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// Rounding policy: amounts the protocol charges (fees, premiums) round up in
+// the protocol's favor; amounts credited to users (shares, redemptions) round
+// down. Every such computation goes through here so the policy is applied
+// the same way everywhere.
+
+/// A fee rounded up, with the part of a base unit the rounding added.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FeeCharge {
+    pub fee: u64,                       // Fee actually charged, rounded up
+    pub rounding_remainder: u64,        // Amount the fee exceeds the exact fee by, in 1/10_000ths of a base unit
+}
+
+/// Fee charged on `amount` at `fee_bps`, rounded up in the protocol's favor.
+pub fn fee_charge(amount: u64, fee_bps: u64) -> Result<FeeCharge> {
+    let numerator = (amount as u128).checked_mul(fee_bps as u128).ok_or(ErrorCode::Overflow)?;
+    let remainder = (numerator % BPS_DENOMINATOR as u128) as u64;
+    let fee = mul_div_up(amount as u128, fee_bps as u128, BPS_DENOMINATOR as u128)?;
+
+    Ok(FeeCharge {
+        fee,
+        rounding_remainder: if remainder == 0 { 0 } else { BPS_DENOMINATOR - remainder },
+    })
+}
+
+/// `value * numerator / denominator`, rounded down; used for amounts credited to users.
+pub fn mul_div_down(value: u128, numerator: u128, denominator: u128) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::Overflow);
+    let product = value.checked_mul(numerator).ok_or(ErrorCode::Overflow)?;
+    u64::try_from(product / denominator).map_err(|_| ErrorCode::Overflow.into())
+}
+
+/// `value * numerator / denominator`, rounded up; used for amounts charged by the protocol.
+pub fn mul_div_up(value: u128, numerator: u128, denominator: u128) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::Overflow);
+    let product = value.checked_mul(numerator).ok_or(ErrorCode::Overflow)?;
+    let quotient = product / denominator + u128::from(product % denominator != 0);
+    u64::try_from(quotient).map_err(|_| ErrorCode::Overflow.into())
+}
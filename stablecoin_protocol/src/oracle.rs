@@ -0,0 +1,182 @@
+// oracle.rs
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{CollateralType, PegState, PriceCache};
+
+/// Fixed-point precision of normalized oracle prices (USD per whole collateral token).
+pub const PRICE_PRECISION: u64 = 1_000_000;
+
+/// Default maximum age of a price update for newly added collateral.
+pub const DEFAULT_MAX_PRICE_AGE_SECS: u64 = 60;
+/// Default maximum confidence interval, relative to price, for newly added collateral.
+pub const DEFAULT_MAX_CONFIDENCE_BPS: u64 = 200;
+
+// Byte offsets of the Pyth receiver `PriceUpdateV2` account layout
+const DISCRIMINATOR_LEN: usize = 8;
+const WRITE_AUTHORITY_LEN: usize = 32;
+const FEED_ID_LEN: usize = 32;
+
+/// Oracle price normalized to `PRICE_PRECISION`.
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub confidence: u64,
+    pub publish_time: i64,
+}
+
+/// Read the collateral's price feed, enforcing its staleness and confidence limits.
+///
+/// Every price read in the program goes through here so the per-collateral
+/// `max_price_age_secs` and `max_confidence_bps` are always applied.
+pub fn read_price(price_feed: &AccountInfo, collateral_type: &CollateralType, oracle_programs: &[Pubkey]) -> Result<OraclePrice> {
+    require_keys_eq!(price_feed.key(), collateral_type.price_feed, ErrorCode::InvalidOracleAccount);
+    check_feed_owner(price_feed, oracle_programs)?;
+
+    let oracle_price = parse_price_update(&price_feed.try_borrow_data()?)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let age = now.saturating_sub(oracle_price.publish_time);
+    require!(age >= 0 && age as u64 <= collateral_type.max_price_age_secs, ErrorCode::StalePrice);
+
+    require!(oracle_price.price > 0, ErrorCode::InvalidPrice);
+    let confidence_bps = (oracle_price.confidence as u128 * 10_000) / oracle_price.price as u128;
+    require!(confidence_bps <= collateral_type.max_confidence_bps as u128, ErrorCode::PriceConfidenceTooWide);
+
+    Ok(oracle_price)
+}
+
+/// Read a collateral's cached price, enforcing its staleness limit.
+///
+/// Confidence was already checked when the cache was refreshed, so hot
+/// paths only pay for a freshness check instead of a full parse.
+pub fn read_cached_price(price_cache: &PriceCache, collateral_type: &CollateralType) -> Result<OraclePrice> {
+    let now = Clock::get()?.unix_timestamp;
+    let age = now.saturating_sub(price_cache.publish_time);
+    require!(age >= 0 && age as u64 <= collateral_type.max_price_age_secs, ErrorCode::StalePrice);
+    require!(price_cache.price > 0, ErrorCode::InvalidPrice);
+
+    Ok(OraclePrice {
+        price: override_price(price_cache, now),
+        confidence: price_cache.confidence,
+        publish_time: price_cache.publish_time,
+    })
+}
+
+/// Clamp a cached price to governance's valuation override while it is in force.
+///
+/// The reference cap is applied first, so a depegging LST can be valued at
+/// `min(oracle, 0.95 * SOL)`, then the absolute ceiling and floor.
+pub fn override_price(price_cache: &PriceCache, now: i64) -> u64 {
+    let mut price = price_cache.price;
+    if now >= price_cache.override_expires_at {
+        return price;
+    }
+    if price_cache.reference_cap_bps > 0 {
+        let cap = price_cache.reference_price as u128 * price_cache.reference_cap_bps as u128 / 10_000;
+        price = price.min(cap as u64);
+    }
+    if price_cache.price_ceiling > 0 {
+        price = price.min(price_cache.price_ceiling);
+    }
+    price.max(price_cache.price_floor)
+}
+
+/// Read the stablecoin's own market price feed, enforcing the peg state's staleness limit.
+pub fn read_peg_price(price_feed: &AccountInfo, peg_state: &PegState, oracle_programs: &[Pubkey]) -> Result<OraclePrice> {
+    require_keys_eq!(price_feed.key(), peg_state.price_feed, ErrorCode::InvalidOracleAccount);
+
+    read_feed_price(price_feed, peg_state.max_price_age_secs, oracle_programs)
+}
+
+/// Read a price feed that is not tied to a collateral type, enforcing `max_price_age_secs`.
+///
+/// The caller is responsible for checking the feed is the one it expects.
+pub fn read_feed_price(price_feed: &AccountInfo, max_price_age_secs: u64, oracle_programs: &[Pubkey]) -> Result<OraclePrice> {
+    check_feed_owner(price_feed, oracle_programs)?;
+    let oracle_price = parse_price_update(&price_feed.try_borrow_data()?)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let age = now.saturating_sub(oracle_price.publish_time);
+    require!(age >= 0 && age as u64 <= max_price_age_secs, ErrorCode::StalePrice);
+
+    Ok(oracle_price)
+}
+
+/// Whether an account parses as a fully verified price update with a positive price.
+///
+/// Staleness and confidence are not checked, so this is only suitable for
+/// validating configuration, never for valuing collateral.
+pub fn probe_price_feed(price_feed: &AccountInfo, oracle_programs: &[Pubkey]) -> bool {
+    if check_feed_owner(price_feed, oracle_programs).is_err() {
+        return false;
+    }
+    match price_feed.try_borrow_data() {
+        Ok(data) => parse_price_update(&data).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Require a price feed to be owned by one of governance's whitelisted oracle programs.
+///
+/// Anyone can create an account with a valid-looking price layout, so the
+/// owner is what actually ties a feed to the oracle network.
+pub fn check_feed_owner(price_feed: &AccountInfo, oracle_programs: &[Pubkey]) -> Result<()> {
+    require!(
+        *price_feed.owner != Pubkey::default() && oracle_programs.contains(price_feed.owner),
+        ErrorCode::InvalidOracleAccount
+    );
+
+    Ok(())
+}
+
+/// Parse a fully verified Pyth `PriceUpdateV2` account into a normalized price.
+fn parse_price_update(data: &[u8]) -> Result<OraclePrice> {
+    // Only fully verified updates are accepted, encoded as a single `1` byte
+    let mut offset = DISCRIMINATOR_LEN + WRITE_AUTHORITY_LEN;
+    require!(data.get(offset) == Some(&1), ErrorCode::InvalidOracleAccount);
+    offset += 1 + FEED_ID_LEN;
+
+    let price = read_i64(data, offset)?;
+    let confidence = read_u64(data, offset + 8)?;
+    let exponent = read_i32(data, offset + 16)?;
+    let publish_time = read_i64(data, offset + 20)?;
+    require!(price > 0, ErrorCode::InvalidPrice);
+
+    // A positive raw price can still round to zero once rescaled
+    let price = normalize(price as u64, exponent)?;
+    require!(price > 0, ErrorCode::InvalidPrice);
+
+    Ok(OraclePrice {
+        price,
+        confidence: normalize(confidence, exponent)?,
+        publish_time,
+    })
+}
+
+/// Rescale a raw oracle value with the given exponent to `PRICE_PRECISION`.
+fn normalize(value: u64, exponent: i32) -> Result<u64> {
+    let shift = exponent + 6;
+    let scaled = if shift >= 0 {
+        (value as u128).checked_mul(10u128.pow(shift as u32)).ok_or(ErrorCode::Overflow)?
+    } else {
+        value as u128 / 10u128.pow((-shift) as u32)
+    };
+    u64::try_from(scaled).map_err(|_| ErrorCode::Overflow.into())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64> {
+    let bytes = data.get(offset..offset + 8).ok_or(ErrorCode::InvalidOracleAccount)?;
+    Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = data.get(offset..offset + 8).ok_or(ErrorCode::InvalidOracleAccount)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32> {
+    let bytes = data.get(offset..offset + 4).ok_or(ErrorCode::InvalidOracleAccount)?;
+    Ok(i32::from_le_bytes(bytes.try_into().unwrap()))
+}
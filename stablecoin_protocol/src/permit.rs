@@ -0,0 +1,71 @@
+// permit.rs
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::errors::ErrorCode;
+use crate::state::RedemptionLane;
+
+// Layout of the ed25519 program instruction data
+const SIGNATURE_COUNT_LEN: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+// Offsets of a signature, key or message located in the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Action a user authorizes a relayer to submit on their behalf.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PermitAction {
+    Wrap { wrapper_vault: Pubkey, amount: u64 },
+    Unwrap { wrapper_vault: Pubkey, wrapped_amount: u64 },
+    Mint { user_account: Pubkey, amount: u64, max_fee: u64 },
+    Redeem { redemption_queue: Pubkey, amount: u64, lane: RedemptionLane },
+}
+
+/// Off-chain message a user signs to authorize a relayed action.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PermitMessage {
+    pub program_id: Pubkey,             // Binds the permit to this program
+    pub action: PermitAction,           // Exact action being authorized
+    pub nonce: u64,                     // Must equal the user's current permit nonce
+    pub expiry: i64,                    // Timestamp after which the permit is void
+}
+
+/// Check that the instruction preceding the current one is an ed25519
+/// verification of `message` signed by `signer`.
+///
+/// The ed25519 program has already verified the signature by the time this
+/// runs; only its inputs need to be matched against the expected permit.
+pub fn verify_permit(instructions_sysvar: &AccountInfo, signer: &Pubkey, message: &PermitMessage) -> Result<()> {
+    require!(Clock::get()?.unix_timestamp <= message.expiry, ErrorCode::PermitExpired);
+    require_keys_eq!(message.program_id, crate::ID, ErrorCode::InvalidPermit);
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ErrorCode::InvalidPermit);
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(ed25519_ix.program_id, ed25519_program::ID, ErrorCode::InvalidPermit);
+    require!(ed25519_ix.accounts.is_empty(), ErrorCode::InvalidPermit);
+
+    let data = &ed25519_ix.data;
+    require!(data.len() >= SIGNATURE_COUNT_LEN + SIGNATURE_OFFSETS_LEN && data[0] == 1, ErrorCode::InvalidPermit);
+
+    // Signature, key and message must all live in the ed25519 instruction itself
+    let offsets = &data[SIGNATURE_COUNT_LEN..SIGNATURE_COUNT_LEN + SIGNATURE_OFFSETS_LEN];
+    let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+    require!(
+        read_u16(2) == CURRENT_INSTRUCTION && read_u16(6) == CURRENT_INSTRUCTION && read_u16(12) == CURRENT_INSTRUCTION,
+        ErrorCode::InvalidPermit
+    );
+    let pubkey_offset = read_u16(4) as usize;
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+
+    let pubkey = data.get(pubkey_offset..pubkey_offset + PUBKEY_LEN).ok_or(ErrorCode::InvalidPermit)?;
+    require!(pubkey == signer.as_ref(), ErrorCode::InvalidPermit);
+
+    let signed_message = data.get(message_offset..message_offset + message_size).ok_or(ErrorCode::InvalidPermit)?;
+    require!(signed_message == message.try_to_vec()?.as_slice(), ErrorCode::InvalidPermit);
+
+    Ok(())
+}
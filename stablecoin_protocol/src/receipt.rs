@@ -0,0 +1,161 @@
+// receipt.rs
+
+//! Valuation of lending market receipt tokens used as collateral.
+//!
+//! A receipt token is worth a growing amount of the underlying asset, so its
+//! price is the underlying price times the upstream market's exchange rate.
+//! Each market gets a `ReceiptAdapter` that reads that rate straight out of
+//! the market's own reserve or bank account, along with how long ago the
+//! market last accrued interest into it.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::instructions::EXCHANGE_RATE_PRECISION;
+use crate::state::ReceiptMarket;
+
+/// Average slot time used to turn a slot-based accrual age into seconds.
+pub const MS_PER_SLOT: u64 = 400;
+/// Longest accrual age governance may accept for a receipt's exchange rate.
+pub const MAX_RATE_AGE_SECS: u64 = 86_400;
+
+/// Exchange rate of a receipt token read from its upstream market.
+#[derive(Clone, Copy, Debug)]
+pub struct ReceiptRate {
+    pub exchange_rate: u128,            // Underlying per receipt token, scaled by EXCHANGE_RATE_PRECISION
+    pub age_secs: u64,                  // Time since the market last accrued interest into the rate
+}
+
+/// Reads receipt exchange rates out of one lending market's accounts.
+pub trait ReceiptAdapter {
+    /// Mint of the receipt token the account issues, if the market has one.
+    fn receipt_mint(&self, data: &[u8]) -> Result<Option<Pubkey>>;
+
+    /// Current exchange rate of the receipt token and the age of its last accrual.
+    fn read_rate(&self, data: &[u8], clock: &Clock) -> Result<ReceiptRate>;
+}
+
+/// Adapter for the given market.
+pub fn adapter(market: ReceiptMarket) -> &'static dyn ReceiptAdapter {
+    match market {
+        ReceiptMarket::Kamino => &KaminoReserve,
+        ReceiptMarket::Marginfi => &MarginfiBank,
+    }
+}
+
+/// Read the rate of the receipt token issued by `reserve`, enforcing `max_age_secs`.
+pub fn read_receipt_rate(market: ReceiptMarket, reserve: &AccountInfo, max_age_secs: u64) -> Result<ReceiptRate> {
+    let rate = adapter(market).read_rate(&reserve.try_borrow_data()?, &Clock::get()?)?;
+    require!(rate.age_secs <= max_age_secs, ErrorCode::StalePrice);
+    require!(rate.exchange_rate > 0, ErrorCode::InvalidPrice);
+
+    Ok(rate)
+}
+
+// -------------------------------------
+// Kamino Lend
+// -------------------------------------
+
+// Byte offsets of the Kamino lending `Reserve` account layout
+const KAMINO_LAST_UPDATE_SLOT: usize = 16;
+const KAMINO_LAST_UPDATE_STALE: usize = 24;
+const KAMINO_AVAILABLE_AMOUNT: usize = 224;
+const KAMINO_BORROWED_AMOUNT_SF: usize = 232;
+const KAMINO_PROTOCOL_FEES_SF: usize = 344;
+const KAMINO_REFERRER_FEES_SF: usize = 360;
+const KAMINO_PENDING_REFERRER_FEES_SF: usize = 376;
+const KAMINO_COLLATERAL_MINT: usize = 2560;
+const KAMINO_COLLATERAL_SUPPLY: usize = 2592;
+// Fractional bits of Kamino's scaled fractions
+const KAMINO_SF_SHIFT: u32 = 60;
+
+/// Kamino reserve, whose kTokens are the collateral mint of the reserve.
+pub struct KaminoReserve;
+
+impl ReceiptAdapter for KaminoReserve {
+    fn receipt_mint(&self, data: &[u8]) -> Result<Option<Pubkey>> {
+        let bytes = data.get(KAMINO_COLLATERAL_MINT..KAMINO_COLLATERAL_MINT + 32).ok_or(ErrorCode::InvalidReceiptAccount)?;
+        Ok(Some(Pubkey::try_from(bytes).unwrap()))
+    }
+
+    fn read_rate(&self, data: &[u8], clock: &Clock) -> Result<ReceiptRate> {
+        require!(data.get(KAMINO_LAST_UPDATE_STALE) == Some(&0), ErrorCode::StalePrice);
+        let last_update_slot = read_u64(data, KAMINO_LAST_UPDATE_SLOT)?;
+
+        // Total liquidity is what is in the vault plus what is lent out, less fees owed to the market
+        let pending_referrer_fees_sf = read_u128(data, KAMINO_PENDING_REFERRER_FEES_SF)?;
+        let fees_sf = read_u128(data, KAMINO_PROTOCOL_FEES_SF)?
+            .checked_add(read_u128(data, KAMINO_REFERRER_FEES_SF)?)
+            .and_then(|fees| fees.checked_add(pending_referrer_fees_sf))
+            .ok_or(ErrorCode::Overflow)?;
+        let total_liquidity_sf = ((read_u64(data, KAMINO_AVAILABLE_AMOUNT)? as u128) << KAMINO_SF_SHIFT)
+            .checked_add(read_u128(data, KAMINO_BORROWED_AMOUNT_SF)?)
+            .ok_or(ErrorCode::Overflow)?
+            .saturating_sub(fees_sf);
+        let collateral_supply = read_u64(data, KAMINO_COLLATERAL_SUPPLY)?;
+        require!(collateral_supply > 0, ErrorCode::InvalidReceiptAccount);
+
+        let exchange_rate = (total_liquidity_sf >> KAMINO_SF_SHIFT)
+            .checked_mul(EXCHANGE_RATE_PRECISION)
+            .ok_or(ErrorCode::Overflow)?
+            / collateral_supply as u128;
+
+        Ok(ReceiptRate {
+            exchange_rate,
+            age_secs: clock.slot.saturating_sub(last_update_slot).saturating_mul(MS_PER_SLOT) / 1_000,
+        })
+    }
+}
+
+// -------------------------------------
+// marginfi
+// -------------------------------------
+
+// Byte offsets of the marginfi `Bank` account layout
+const MARGINFI_ASSET_SHARE_VALUE: usize = 80;
+const MARGINFI_LAST_UPDATE: usize = 288;
+// Fractional bits of marginfi's I80F48 fixed-point values
+const MARGINFI_FRACTIONAL_BITS: u32 = 48;
+
+/// marginfi bank, valuing deposit shares at the bank's asset share value.
+///
+/// Banks do not mint a receipt token, so the collateral mint is bound to the
+/// bank by governance when it is whitelisted.
+pub struct MarginfiBank;
+
+impl ReceiptAdapter for MarginfiBank {
+    fn receipt_mint(&self, _data: &[u8]) -> Result<Option<Pubkey>> {
+        Ok(None)
+    }
+
+    fn read_rate(&self, data: &[u8], clock: &Clock) -> Result<ReceiptRate> {
+        let asset_share_value = read_u128(data, MARGINFI_ASSET_SHARE_VALUE)?;
+        require!((asset_share_value as i128) > 0, ErrorCode::InvalidReceiptAccount);
+        let last_update = read_i64(data, MARGINFI_LAST_UPDATE)?;
+
+        let exchange_rate = asset_share_value
+            .checked_mul(EXCHANGE_RATE_PRECISION)
+            .ok_or(ErrorCode::Overflow)?
+            >> MARGINFI_FRACTIONAL_BITS;
+
+        Ok(ReceiptRate {
+            exchange_rate,
+            age_secs: clock.unix_timestamp.saturating_sub(last_update).max(0) as u64,
+        })
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = data.get(offset..offset + 8).ok_or(ErrorCode::InvalidReceiptAccount)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64> {
+    let bytes = data.get(offset..offset + 8).ok_or(ErrorCode::InvalidReceiptAccount)?;
+    Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Result<u128> {
+    let bytes = data.get(offset..offset + 16).ok_or(ErrorCode::InvalidReceiptAccount)?;
+    Ok(u128::from_le_bytes(bytes.try_into().unwrap()))
+}
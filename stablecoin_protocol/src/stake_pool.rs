@@ -0,0 +1,94 @@
+// stake_pool.rs
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+use crate::errors::ErrorCode;
+
+/// Index of `DepositSol` in the SPL stake pool instruction enum.
+pub const DEPOSIT_SOL_IX: u8 = 14;
+
+// Byte offsets of the SPL stake pool `StakePool` account layout
+const ACCOUNT_TYPE_LEN: usize = 1;
+const AUTHORITIES_LEN: usize = 32 * 3;
+const WITHDRAW_BUMP_LEN: usize = 1;
+const POOL_MINT_OFFSET: usize = ACCOUNT_TYPE_LEN + AUTHORITIES_LEN + WITHDRAW_BUMP_LEN + 32 + 32;
+const TOTAL_LAMPORTS_OFFSET: usize = POOL_MINT_OFFSET + 32 + 32 + 32;
+// Account type of an initialized stake pool
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+
+/// Balances of a stake pool, from which the LST's SOL value is derived.
+#[derive(Clone, Copy, Debug)]
+pub struct StakePoolBalances {
+    pub pool_mint: Pubkey,
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+}
+
+/// Parse the pool mint and balances out of an SPL stake pool account.
+pub fn read_stake_pool(stake_pool: &AccountInfo) -> Result<StakePoolBalances> {
+    let data = stake_pool.try_borrow_data()?;
+    require!(data.first() == Some(&STAKE_POOL_ACCOUNT_TYPE), ErrorCode::InvalidStakePool);
+    require!(data.len() >= TOTAL_LAMPORTS_OFFSET + 16, ErrorCode::InvalidStakePool);
+
+    let pool_mint = Pubkey::try_from(&data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32]).unwrap();
+    let total_lamports = u64::from_le_bytes(data[TOTAL_LAMPORTS_OFFSET..TOTAL_LAMPORTS_OFFSET + 8].try_into().unwrap());
+    let pool_token_supply = u64::from_le_bytes(data[TOTAL_LAMPORTS_OFFSET + 8..TOTAL_LAMPORTS_OFFSET + 16].try_into().unwrap());
+
+    Ok(StakePoolBalances { pool_mint, total_lamports, pool_token_supply })
+}
+
+/// Accounts of a stake pool `DepositSol` CPI.
+pub struct DepositSolAccounts<'a, 'info> {
+    pub stake_pool_program: &'a AccountInfo<'info>,
+    pub stake_pool: &'a AccountInfo<'info>,
+    pub withdraw_authority: &'a AccountInfo<'info>,
+    pub reserve_stake: &'a AccountInfo<'info>,
+    pub depositor: &'a AccountInfo<'info>,
+    pub pool_tokens_to: &'a AccountInfo<'info>,
+    pub manager_fee_account: &'a AccountInfo<'info>,
+    pub pool_mint: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+/// Deposit `lamports` of the depositor's SOL into the stake pool for pool tokens.
+///
+/// The pool tokens account also acts as referrer, so any referral fee comes
+/// back to the same vault.
+pub fn deposit_sol(accounts: DepositSolAccounts, lamports: u64) -> Result<()> {
+    let mut data = vec![DEPOSIT_SOL_IX];
+    data.extend_from_slice(&lamports.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: accounts.stake_pool_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.stake_pool.key(), false),
+            AccountMeta::new_readonly(accounts.withdraw_authority.key(), false),
+            AccountMeta::new(accounts.reserve_stake.key(), false),
+            AccountMeta::new(accounts.depositor.key(), true),
+            AccountMeta::new(accounts.pool_tokens_to.key(), false),
+            AccountMeta::new(accounts.manager_fee_account.key(), false),
+            AccountMeta::new(accounts.pool_tokens_to.key(), false),
+            AccountMeta::new(accounts.pool_mint.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+        ],
+        data,
+    };
+    let account_infos = [
+        accounts.stake_pool.clone(),
+        accounts.withdraw_authority.clone(),
+        accounts.reserve_stake.clone(),
+        accounts.depositor.clone(),
+        accounts.pool_tokens_to.clone(),
+        accounts.manager_fee_account.clone(),
+        accounts.pool_mint.clone(),
+        accounts.system_program.clone(),
+        accounts.token_program.clone(),
+        accounts.stake_pool_program.clone(),
+    ];
+    anchor_lang::solana_program::program::invoke(&ix, &account_infos)?;
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 // state.rs
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount, Mint};
 
+use crate::errors::ErrorCode;
+
 // -------------------------------------
 // User Account Structure
 // -------------------------------------
@@ -13,6 +17,25 @@ pub struct UserAccount {
     pub collateral_ratio: u64,          // The required collateral ratio (e.g., 150%)
     pub last_liquidation_time: u64,     // Timestamp of the last liquidation action
     pub last_mint_time: u64,            // Timestamp of the last minting action
+    pub health_band: HealthBand,        // Health band the position was last observed in
+    pub collateral_type: Pubkey,        // Collateral type backing the position (default if unassigned)
+    pub tag: [u8; 32],                  // Free-form position tag set at creation (e.g. institutional sub-account id)
+    pub last_liquidation_ratio: u64,    // Collateral ratio the position was left at by the last liquidation
+    pub entry_exchange_rate: u128,      // Collateral exchange rate when the position was bound to its collateral type
+    pub owner: Pubkey,                  // Wallet that opened the position
+    pub subaccount_index: u16,          // Index of the sub-account among the owner's positions in its collateral type
+    pub margin_account: Pubkey,         // Cross-margin account the position is pooled in (default if isolated)
+    pub opened_at: i64,                 // Timestamp the position was opened, for the onboarding collateral ratio
+    pub fee_credits: u64,               // Prepaid fee credits, spent before mint and redemption fees are charged
+    pub note_obligations: u64,          // Unpaid face value of debt notes secured by the position's excess collateral
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum HealthBand {
+    Healthy,
+    Warning,
+    Critical,
+    Liquidatable,
 }
 
 // -------------------------------------
@@ -24,6 +47,7 @@ pub struct Governance {
     pub volatility_threshold: u64,      // Threshold to adjust collateral ratio
     pub reward_adjustment_rate: u64,    // Rate for adjusting rewards based on proposals
     pub minimum_approval_threshold: u32, // Minimum number of approval votes needed
+    pub bump: u8,                       // Bump of the governance PDA
 }
 
 // -------------------------------------
@@ -37,7 +61,16 @@ pub struct StakerAccount {
     pub lockup_period: u64,             // Lock-up period in seconds
     pub early_withdrawal_penalty: u64,  // Penalty for withdrawing before lock-up period
     pub reward_multiplier: u64,         // Multiplier for calculating rewards (based on lock-up or staking duration)
-    pub auto_compound: bool,            // Indicates if rewards should be auto-compounded
+    pub auto_compound: bool,            // Opted in to having dust rewards staked by `claim_to_stake`
+    pub reward_per_share_paid: u128,    // Pool accumulator value at the staker's last settlement
+    pub working_balance: u64,           // Boosted balance rewards accrue on
+    pub boost_bps: u64,                 // Working balance per unit of stake at the last boost checkpoint (bps)
+    pub ve_lock: Pubkey,                // ve lock boosting this stake (default if none)
+    pub owner: Pubkey,                  // Staker, set by the first stake; rewards can only be claimed to its accounts
+    pub reward_delegate: Pubkey,        // Wallet whose token accounts may also receive rewards (default if none)
+    pub unstake_requested_at: i64,      // Timestamp of the pending unstake request made during an incident (0 if none)
+    pub reward_pool: Pubkey,            // Reward pool the stake is held in, set by the first stake
+    pub unsettled_stake: u64,           // Rewards staked by `claim_to_stake`, minted into the vault on the next withdrawal
 }
 
 // -------------------------------------
@@ -48,7 +81,47 @@ pub struct RewardPool {
     pub total_staked: u64,              // Total amount of tokens staked in the pool
     pub reward_rate: u64,               // Reward rate (e.g., tokens rewarded per second)
     pub last_update_time: u64,          // Timestamp of the last reward rate update
-    pub accumulated_reward_per_share: u64, // Accumulated reward per share (used for calculating rewards)
+    pub accumulated_reward_per_share: u128, // Accumulated reward per unit of working balance, scaled by REWARD_PRECISION
+    pub min_stake_amount: u64,          // Minimum stake, so tiny first deposits cannot skew the accumulator
+    pub reward_mint: Pubkey,            // Mint rewards are paid in
+    pub min_claim_interval: u64,        // Minimum seconds between two claims by the same staker
+    pub min_claim_amount: u64,          // Minimum reward amount a claim must pay out
+    pub staking_vault: Pubkey,          // Token account holding staked tokens, owned by the staking authority PDA
+    pub staking_authority_bump: u8,     // Bump of the staking authority PDA
+    pub total_working: u64,             // Sum of staker working balances, the reward accumulator's denominator
+    pub reward_residue: u128,           // Emitted rewards (scaled by REWARD_PRECISION) not yet folded into the accumulator
+    pub system_state: Pubkey,           // System state whose incident flag governs withdrawals from the pool
+    pub last_run_slot: u64,             // Slot the reward pool crank last ran in
+    pub reward_source: RewardSource,    // Whether rewards are minted emissions or protocol revenue
+    pub reward_vault: Pubkey,           // Stablecoin vault paying revenue rewards, owned by the staking authority PDA
+    pub revenue_share_bps: u64,         // Share of each epoch's mint fees moved into the reward vault (bps)
+    pub undistributed_revenue: u64,     // Revenue funded while nothing was staked, distributed with the next funding
+    pub sunset_deadline: i64,           // End of the penalty-free withdrawal window of a pool being wound down (0 if active)
+    pub claim_to_stake_threshold: u64,  // Largest pending reward `claim_to_stake` may stake instead of paying out (0 disables)
+    pub unsettled_stake: u64,           // Staked rewards counted in `total_staked` but not yet minted into the vault
+    pub slashed_stake: u64,             // Stake burned from the vault to cover bad debt, shared pro rata by withdrawals
+}
+
+pub const MIN_SUNSET_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;     // Shortest withdrawal window a pool sunset may give stakers
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RewardSource {
+    Emissions,                          // Minted reward tokens at the pool's reward rate
+    Revenue,                            // Stablecoin from the fee split, funded at epoch close
+}
+
+// -------------------------------------
+// Emission Schedule Structure
+// -------------------------------------
+#[account]
+pub struct EmissionSchedule {
+    pub reward_pool: Pubkey,            // Reward pool whose claims the schedule budgets
+    pub epoch_duration: u64,            // Length of an emission epoch in seconds
+    pub epoch_budget: u64,              // Maximum rewards minted per epoch
+    pub epoch_start: u64,               // Timestamp the current emission epoch started
+    pub minted_this_epoch: u64,         // Rewards minted so far in the current epoch
+    pub authority_bump: u8,             // Bump of the reward mint authority PDA
+    pub bump: u8,                       // Bump of the emission schedule PDA
 }
 
 // -------------------------------------
@@ -64,6 +137,22 @@ pub struct Proposal {
     pub status: ProposalStatus,         // Current status (Pending, Approved, Rejected)
     pub proposer: Pubkey,               // Address of the proposer
     pub voting_period_end: u64,         // Timestamp when the voting period ends
+    pub bond: u64,                      // Lamports posted by the proposer, refunded on expiry
+    pub governance: Pubkey,             // Governance account the proposal changes
+    pub base_checksum: [u8; 32],        // Checksum of the targeted parameters' values at creation
+    pub executed: bool,                 // Whether the approved changes have been applied
+    pub action: ProposalAction,         // Kind of action, which selects the voting period and timelock
+    pub execution_delay_secs: u64,      // Timelock between approval and execution, fixed at creation
+    pub executable_at: u64,             // Earliest execution timestamp, set once the proposal is approved
+    pub incident_nonce: Option<u64>,    // Circuit breaker trip the proposal follows up on, if opened by one
+    pub new_minting_fee_rate: Option<u64>, // Proposed new minting fee rate (bps), set by fee change templates
+    pub new_redemption_fee_rate: Option<u64>, // Proposed new redemption fee rate (bps), set by fee change templates
+    pub incident_collateral_type: Pubkey, // Collateral an incident follow-up re-enables (default if it clears a declared incident)
+}
+
+impl Proposal {
+    /// Account space, with room for a 200 byte description.
+    pub const SPACE: usize = 8 + 4 + 200 + 9 + 9 + 4 + 4 + 1 + 32 + 8 + 8 + 32 + 32 + 1 + 1 + 8 + 8 + 9 + 9 + 9 + 32;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -71,8 +160,51 @@ pub enum ProposalStatus {
     Pending,
     Approved,
     Rejected,
+    Expired,
+    Vetoed,
+}
+
+/// Kind of action a proposal takes; each kind has its own voting period and timelock.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProposalAction {
+    ParameterChange,                    // Changes the collateral ratio or reward rate
+    FeeChange,                          // Sets the minting and redemption fee rates on execution
+    CollateralOnboarding,               // Signals the onboarding of a collateral type
+    EmergencyShutdown,                  // Signals an emergency shutdown; passes unless vetoed
+    IncidentFollowUp,                   // Recovers from a circuit breaker trip; opened only by the trip
+}
+
+pub const PROPOSAL_ACTION_COUNT: usize = 5;
+
+/// Voting period and timelock of one proposal kind.
+///
+/// A zero voting period approves the proposal at creation, leaving the
+/// timelock as the window in which governance can veto it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ProposalTiming {
+    pub voting_period_secs: u64,        // Voting window after creation
+    pub execution_delay_secs: u64,      // Delay between approval and execution
+}
+
+#[account]
+pub struct GovernanceConfig {
+    pub governance: Pubkey,             // Governance account the configuration applies to
+    pub timings: [ProposalTiming; PROPOSAL_ACTION_COUNT], // Timing of each kind, indexed by ProposalAction
+    pub bump: u8,                       // Bump of the governance config PDA
 }
 
+pub const PROPOSAL_VOTING_PERIOD_SECS: u64 = 3 * 24 * 60 * 60; // Voting window of a new proposal
+pub const PROPOSAL_EXPIRY_GRACE_SECS: u64 = 24 * 60 * 60;      // Delay after voting ends before a pending proposal can expire
+pub const PROPOSAL_BOND_LAMPORTS: u64 = 100_000_000;           // Bond posted with every proposal (0.1 SOL)
+pub const DEFAULT_PARAMETER_CHANGE_DELAY_SECS: u64 = 24 * 60 * 60;      // Parameter changes execute a day after approval
+pub const DEFAULT_FEE_CHANGE_VOTING_SECS: u64 = 2 * 24 * 60 * 60;       // Fee changes are voted on for two days
+pub const DEFAULT_FEE_CHANGE_DELAY_SECS: u64 = 24 * 60 * 60;            // and execute a day after approval
+pub const DEFAULT_ONBOARDING_VOTING_SECS: u64 = 7 * 24 * 60 * 60;       // Collateral onboarding is voted on for a week
+pub const DEFAULT_ONBOARDING_DELAY_SECS: u64 = 2 * 24 * 60 * 60;        // and executes two days after approval
+pub const DEFAULT_EMERGENCY_VETO_WINDOW_SECS: u64 = 60 * 60;            // Emergency shutdowns pass at once and can be vetoed for an hour
+pub const DEFAULT_INCIDENT_VOTING_SECS: u64 = 2 * 24 * 60 * 60;         // Incident follow-ups are voted on for two days
+pub const DEFAULT_INCIDENT_DELAY_SECS: u64 = 0;                         // and carry no timelock, as they only signal
+
 // -------------------------------------
 // Collateral Type Structure
 // -------------------------------------
@@ -83,6 +215,24 @@ pub struct CollateralType {
     pub price_feed: Pubkey,             // Address of the price feed account
     pub liquidation_threshold: u64,     // The threshold below which liquidation can occur
     pub stability_fee: u64,             // Stability fee or interest rate for borrowing against this collateral
+    pub max_price_age_secs: u64,        // Maximum age of a price update before it is considered stale
+    pub max_confidence_bps: u64,        // Maximum oracle confidence interval relative to price (bps)
+    pub liquidation_penalty_bps: u64,   // Penalty charged on liquidated collateral (bps)
+    pub debt_ceiling: u64,              // Maximum stablecoin debt that can be backed by this collateral
+    pub risk_tier: RiskTier,            // Risk tier the parameters were derived from
+    pub total_debt: u64,                // Stablecoin debt currently minted against this collateral
+    pub liquidation_bonus_slope_bps: u64, // Extra bonus per unit of depth below the liquidation threshold (bps)
+    pub max_liquidation_bonus_bps: u64, // Upper bound of the liquidation bonus (bps)
+    pub mint_fee_bps: Option<u64>,      // Mint fee override; falls back to the global minting fee rate
+    pub redemption_fee_bps: Option<u64>, // Redemption fee override; falls back to the global redemption fee rate
+    pub exchange_rate: u128,            // Underlying value per collateral token for yield-bearing collateral, scaled by EXCHANGE_RATE_PRECISION (0 if not yield-bearing)
+    pub yield_skim_bps: u64,            // Share of exchange-rate yield skimmed into the surplus buffer when collateral leaves a position
+    pub confidence_premium_threshold_bps: u64, // Oracle confidence (bps of price) above which mints pay an insurance premium
+    pub confidence_premium_slope_bps: u64, // Premium per unit of confidence above the threshold (bps)
+    pub max_confidence_premium_bps: u64, // Upper bound of the insurance premium (bps)
+    pub stake_pool_config: Pubkey,      // Stake pool config pricing this LST collateral (default if oracle-priced)
+    pub minting_paused: bool,           // New debt against this collateral is halted, independently of the protocol pause
+    pub receipt_config: Pubkey,         // Receipt config pricing this lending receipt collateral (default if oracle-priced)
 }
 
 // -------------------------------------
@@ -92,127 +242,4926 @@ pub struct CollateralType {
 pub struct SystemState {
     pub staking_paused: bool,           // Indicates if staking is currently paused
     pub governance_authority: Pubkey,   // The current governance authority for the protocol
-    pub global_stability_fee: u64,      // Global stability fee for borrowing
-    pub minting_fee_rate: u64,          // Fee rate applied when minting stablecoins
+    pub global_stability_fee: i64,      // Global stability fee for borrowing (bps per year; negative pays borrowers)
+    pub minting_fee_rate: u64,          // Fee rate applied when minting stablecoins (bps)
+    pub health_warning_bps: u64,        // Health factor (bps) below which a position enters the warning band
+    pub health_critical_bps: u64,       // Health factor (bps) below which a position enters the critical band
+    pub operation_locked: bool,         // Mutex held for the duration of multi-CPI flows
+    pub stablecoin_mint: Pubkey,        // Canonical stablecoin mint
+    pub treasury_account: Pubkey,       // Canonical treasury token account receiving minting fees
+    pub config_frozen: bool,            // Blocks parameter changes and new debt around program upgrades
+    pub frozen_at: i64,                 // Timestamp the configuration was frozen
+    pub upgrade_checklist: u8,          // Bitmask of completed upgrade checklist items
+    pub launch_mode: bool,              // Guarded launch: per-wallet caps are enforced
+    pub launch_collateral_cap: u64,     // Maximum collateral per wallet during the guarded launch
+    pub launch_debt_cap: u64,           // Maximum stablecoin debt per wallet during the guarded launch
+    pub liquidation_cooldown_secs: u64, // Minimum time between partial liquidations of the same position
+    pub redemption_fee_rate: u64,       // Fee rate applied when redeeming stablecoins (bps)
+    pub onboarding_ratio_bonus: u64,    // Extra collateral ratio (percentage points) required of a newly opened position
+    pub onboarding_period_secs: u64,    // Time over which the onboarding bonus decays to zero
+    pub incident_active: bool,          // Guardians declared an incident; safety module exits are delayed
+    pub incident_declared_at: i64,      // Timestamp the current incident was declared
+    pub bump: u8,                       // Bump of the system state PDA
+    pub size_fee_threshold_bps: u64,    // Share of a collateral's remaining debt ceiling headroom a single mint may take before the size surcharge
+    pub size_fee_slope_bps: u64,        // Surcharge (bps) per 100% of headroom taken above the threshold
+    pub max_size_fee_bps: u64,          // Cap on the size surcharge (bps)
+    pub oracle_programs: [Pubkey; MAX_ORACLE_PROGRAMS], // Programs allowed to own price feeds (Pyth receiver, Switchboard); unused slots are default
+    pub next_incident_nonce: u64,       // Nonce assigned to the next circuit breaker trip and its follow-up proposal
+}
+
+pub const MAX_FEE_BPS: u64 = 1_000;                              // Upper bound of any mint or redemption fee (10%)
+pub const MAX_ORACLE_PROGRAMS: usize = 4;                       // Slots in the oracle program whitelist
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpgradeChecklistItem {
+    StateSnapshotted,                   // Account state captured before the redeploy
+    BufferVerified,                     // Program buffer verified against the audited build
+    LayoutMigrated,                     // Account layouts migrated or confirmed unchanged
 }
 
+pub const UPGRADE_CHECKLIST_COMPLETE: u8 = 0b111;               // All checklist items completed
+pub const CONFIG_UNFREEZE_TIMELOCK_SECS: i64 = 24 * 60 * 60;   // Minimum time the configuration stays frozen
+pub const INCIDENT_UNSTAKE_DELAY_SECS: i64 = 7 * 24 * 60 * 60; // Wait between requesting and completing an unstake during an incident
+
 // -------------------------------------
-// Contexts for Instructions
+// Treasury Reserves Structure
 // -------------------------------------
+#[account]
+pub struct TreasuryReserves {
+    pub reserve_mint: Pubkey,           // Mint of the reserve asset (e.g., USDC)
+    pub reserve_vault: Pubkey,          // Token account holding the liquid reserves
+    pub liquid_reserves: u64,           // Reserves held in the vault and available for redemptions
+    pub deployed_reserves: u64,         // Reserves currently deployed across yield strategies
+    pub min_liquidity_bps: u64,         // Minimum share of total reserves that must stay liquid (bps)
+    pub authority_bump: u8,             // Bump of the treasury authority PDA
+    pub bump: u8,                       // Bump of the treasury reserves PDA
+}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = payer, space = 8 + 8)]
-    pub governance: Account<'info, Governance>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+// -------------------------------------
+// Yield Strategy Structure
+// -------------------------------------
+#[account]
+pub struct YieldStrategy {
+    pub adapter_program: Pubkey,        // Whitelisted program implementing the strategy adapter interface
+    pub strategy_account: Pubkey,       // Position or vault account held by the strategy program
+    pub cap: u64,                       // Maximum amount of reserves that can be deployed to this strategy
+    pub deployed: u64,                  // Amount of reserves currently deployed to this strategy
+    pub active: bool,                   // Whether new deployments to this strategy are allowed
+    pub treasury_reserves: Pubkey,      // Treasury reserves whose vault funds the strategy
 }
 
-#[derive(Accounts)]
-pub struct MintStablecoin<'info> {
-    #[account(mut)]
-    pub user_account: Account<'info, UserAccount>,
-    #[account(mut)]
-    pub user_stablecoin_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub stablecoin_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub treasury_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub payer: Signer<'info>,
-    pub optional_authority: Option<Signer<'info>>,
+// -------------------------------------
+// Reserve Attestation Structures
+// -------------------------------------
+#[account]
+pub struct ReserveAttestation {
+    pub attestor: Pubkey,               // Role allowed to publish reserve reports
+    pub treasury_reserves: Pubkey,      // Treasury reserves the reports are taken against
+    pub last_report_epoch: u64,         // Epoch of the most recently published report
+    pub report_count: u64,              // Number of reports published so far
+}
 
+#[account]
+pub struct ReserveReport {
+    pub epoch: u64,                     // Epoch the report covers
+    pub collateral_value: u64,          // Attested value of user collateral backing the stablecoin
+    pub psm_reserves: u64,              // Liquid treasury/PSM reserves at publication time
+    pub deployed_reserves: u64,         // Reserves deployed to yield strategies at publication time
+    pub total_backing: u64,             // Sum of all backing components
+    pub report_hash: [u8; 32],          // Hash of the full off-chain attestation report
+    pub attestor: Pubkey,               // Attestor that published the report
+    pub published_at: i64,              // Timestamp the report was published
 }
 
-#[derive(Accounts)]
-pub struct Liquidate<'info> {
-    #[account(mut)]
-    pub user_account: Account<'info, UserAccount>,
-    #[account(mut)]
-    pub liquidator_collateral_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub payer: Signer<'info>,
+// -------------------------------------
+// Blacklist Entry Structure
+// -------------------------------------
+#[account]
+pub struct BlacklistEntry {
+    pub wallet: Pubkey,                 // Wallet barred from holding or moving the permissioned stablecoin
+    pub added_at: i64,                  // Timestamp the wallet was blacklisted
 }
 
-#[derive(Accounts)]
-pub struct StakeTokens<'info> {
-    #[account(mut)]
-    pub staker_account: Account<'info, StakerAccount>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub staking_pool: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub payer: Signer<'info>,
+// -------------------------------------
+// Wrapper Vault Structure
+// -------------------------------------
+#[account]
+pub struct WrapperVault {
+    pub stablecoin_mint: Pubkey,        // Mint of the stablecoin accepted by the wrapper
+    pub wrapped_mint: Pubkey,           // Mint of the yield-bearing wrapped token
+    pub vault: Pubkey,                  // Token account holding the wrapped stablecoin
+    pub savings_rate_bps: u64,          // Annual savings rate accrued into the exchange rate (bps)
+    pub exchange_rate: u128,            // Stablecoin per wrapped token, scaled by EXCHANGE_RATE_PRECISION
+    pub last_accrual_time: i64,         // Timestamp of the last exchange-rate accrual
+    pub authority_bump: u8,             // Bump of the wrapper authority PDA
 }
 
-#[derive(Accounts)]
-pub struct WithdrawStake<'info> {
-    #[account(mut)]
-    pub staker_account: Account<'info, StakerAccount>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub staking_pool: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub clock: Sysvar<'info, Clock>,
-    pub payer: Signer<'info>,
+// -------------------------------------
+// Bond Market Structures
+// -------------------------------------
+#[account]
+pub struct BondMarket {
+    pub quote_mint: Pubkey,             // Mint paid by buyers (stablecoin or LP token)
+    pub payout_mint: Pubkey,            // Mint of the protocol token sold at a discount
+    pub payout_vault: Pubkey,           // Vault holding protocol tokens available to bonds
+    pub pol_account: Pubkey,            // Protocol-owned liquidity account receiving proceeds
+    pub last_price: u64,                // Price (quote per payout token, BOND_PRICE_PRECISION) after the last purchase
+    pub min_price: u64,                 // Floor the price can never decay below
+    pub decay_bps_per_day: u64,         // Daily decay of the price while no bonds are purchased
+    pub price_bump_bps: u64,            // Price increase applied after every purchase
+    pub last_price_update: i64,         // Timestamp of the last price update
+    pub vesting_period: i64,            // Vesting duration of purchased bonds, in seconds
+    pub capacity: u64,                  // Maximum amount of protocol tokens the market can sell
+    pub total_sold: u64,                // Protocol tokens sold so far
+    pub authority_bump: u8,             // Bump of the bond authority PDA
+    pub total_redeemed: u64,            // Protocol tokens paid out to bond holders so far
 }
 
-#[derive(Accounts)]
-pub struct ClaimRewards<'info> {
-    #[account(mut)]
-    pub staker_account: Account<'info, StakerAccount>,
-    #[account(mut)]
-    pub user_reward_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub reward_token_mint: Account<'info, Mint>,
-    pub reward_mint_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+#[account]
+pub struct BondPosition {
+    pub owner: Pubkey,                  // Buyer entitled to the vested payout
+    pub bond_market: Pubkey,            // Market the bond was purchased from
+    pub payout_total: u64,              // Protocol tokens bought with the bond
+    pub payout_claimed: u64,            // Protocol tokens already redeemed
+    pub vesting_start: i64,             // Timestamp vesting started
+    pub vesting_end: i64,               // Timestamp the bond is fully vested
 }
 
-#[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    #[account(init, payer = proposer, space = 8 + 200 + 32 + 4 + 4 + 1 + 32)]
-    pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    #[account(mut)] // Make sure the proposer is mutable since it is paying for the account creation
-    pub proposer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+// -------------------------------------
+// Airdrop Distributor Structure
+// -------------------------------------
+#[account]
+pub struct AirdropDistributor {
+    pub merkle_root: [u8; 32],          // Root of the merkle tree of (index, claimant, amount) leaves
+    pub mint: Pubkey,                   // Mint of the distributed token
+    pub vault: Pubkey,                  // Token account funding the claims
+    pub max_num_nodes: u64,             // Number of leaves in the tree
+    pub num_claimed: u64,               // Number of leaves claimed so far
+    pub total_claimed: u64,             // Amount of tokens claimed so far
+    pub authority_bump: u8,             // Bump of the airdrop authority PDA
+    pub claimed_bitmap: Vec<u8>,        // One bit per leaf, set once the leaf is claimed
 }
 
-#[derive(Accounts)]
-pub struct VoteOnProposal<'info> {
-    #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
-    #[account(mut)]
-    pub governance: Account<'info, Governance>,
-    pub voter: Signer<'info>,
+// -------------------------------------
+// Vesting Stream Structure
+// -------------------------------------
+#[account]
+pub struct VestingStream {
+    pub recipient: Pubkey,              // Contributor receiving the stream
+    pub mint: Pubkey,                   // Mint paid out by the stream (stablecoin or protocol token)
+    pub escrow: Pubkey,                 // Token account holding the unstreamed funds
+    pub rate_per_second: u64,           // Amount released every second after the start
+    pub start_time: i64,                // Timestamp the stream starts accruing
+    pub cliff_time: i64,                // Nothing can be withdrawn before this timestamp
+    pub end_time: i64,                  // Timestamp the stream stops accruing
+    pub withdrawn: u64,                 // Amount already withdrawn by the recipient
+    pub authority_bump: u8,             // Bump of the stream authority PDA
 }
 
-#[derive(Accounts)]
-pub struct AddCollateralType<'info> {
-    #[account(init, payer = payer, space = 8 + 32 + 8 + 32)]
-    pub collateral_type: Account<'info, CollateralType>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+// -------------------------------------
+// Governance Token Structure
+// -------------------------------------
+pub const GOV_TOKEN_DECIMALS: u8 = 6;                           // Decimals of the governance token
+pub const GOV_TOKEN_MAX_SUPPLY: u64 = 1_000_000_000 * 1_000_000; // Hard cap on supply (1 billion tokens)
+
+#[account]
+pub struct GovToken {
+    pub mint: Pubkey,                   // Mint of the protocol governance token
+    pub remaining_mintable: u64,        // Tokens that can still be minted before the max supply is reached
+    pub total_emitted: u64,             // Tokens minted through emissions
+    pub total_auctioned: u64,           // Tokens minted through debt auctions
+    pub authority_bump: u8,             // Bump of the mint authority PDA
 }
 
-#[derive(Accounts)]
-pub struct MintStablecoinWithCollateral<'info> {
-    #[account(mut)]
-    pub user_account: Account<'info, UserAccount>,
-    #[account(mut)]
-    pub user_stablecoin_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub stablecoin_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub collateral_type: Account<'info, CollateralType>,
-    pub token_program: Program<'info, Token>,
-    pub payer: Signer<'info>,
-    pub optional_authority: Option<Signer<'info>>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GovMintPath {
+    Emissions,
+    DebtAuction,
+}
+
+// -------------------------------------
+// Risk Tier Structures
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RiskTier {
+    Stable,
+    Major,
+    Volatile,
+    Exotic,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RiskParams {
+    pub collateral_ratio: u64,          // Collateral ratio required to mint (e.g., 150%)
+    pub liquidation_threshold: u64,     // Ratio below which the position can be liquidated
+    pub liquidation_penalty_bps: u64,   // Penalty charged on liquidated collateral (bps)
+    pub debt_ceiling: u64,              // Maximum stablecoin debt backed by the collateral
+    pub stability_fee: u64,             // Stability fee charged on the debt
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RiskParamOverrides {
+    pub collateral_ratio: Option<u64>,
+    pub liquidation_threshold: Option<u64>,
+    pub liquidation_penalty_bps: Option<u64>,
+    pub debt_ceiling: Option<u64>,
+    pub stability_fee: Option<u64>,
+}
+
+#[account]
+pub struct RiskTierConfig {
+    pub tiers: [RiskParams; 4],         // Default parameter bundle per risk tier, indexed by RiskTier
+}
+
+// -------------------------------------
+// Protocol Stats Structure
+// -------------------------------------
+#[account]
+pub struct ProtocolStats {
+    pub total_collateral_debt: u64,     // Debt minted against all collateral types combined
+    pub concentration_cap_bps: u64,     // Maximum share of the debt a single collateral may back (bps)
+    pub concentration_warning_bps: u64, // Share at which a concentration warning is emitted (bps)
+    pub concentration_floor: u64,       // Total debt below which the cap is not enforced (bootstrap)
+    pub fee_issuance: u64,              // Stablecoin minted as fees on collateral-typed mints
+    pub written_off_debt: u64,          // Collateral-typed debt released without being burned (insurance or bad debt)
+    pub legacy_issuance: u64,           // Debt minted through the legacy untyped mint path and not yet released
+    pub fee_credit_burns: u64,          // Stablecoin burned to buy fee credits, retiring supply without repaying debt
+    pub mm_facility_debt: u64,          // Debt outstanding on market maker credit lines, fees included
+    pub reserve_backed_debt: u64,       // Debt settled by auctions paid in the reserve asset, whose stablecoin stays in circulation
+    pub auction_surplus_burns: u64,     // Stablecoin auction proceeds above the debt, burned without repaying any debt
+}
+
+// -------------------------------------
+// Liquidation Revenue Structure
+// -------------------------------------
+/// Ledger of the protocol's share of liquidation penalties.
+///
+/// Seized collateral stays in the collateral vaults, so the insurance pool
+/// and surplus buffer are claims on those vaults rather than token accounts
+/// of their own. Paying out of them (bad debt cover, reimbursements) moves
+/// treasury stablecoin and debits the matching balance here.
+#[account]
+pub struct LiquidationRevenue {
+    pub liquidator_share_bps: u64,      // Share of the liquidation penalty paid to the liquidator (bps)
+    pub insurance_share_bps: u64,       // Share of the liquidation penalty routed to the insurance pool (bps)
+    pub surplus_share_bps: u64,         // Share of the liquidation penalty routed to the surplus buffer (bps)
+    pub insurance_pool_balance: u64,    // Penalty revenue accrued to the insurance pool
+    pub surplus_buffer_balance: u64,    // Penalty revenue accrued to the surplus buffer
+    pub bad_debt: u64,                  // Debt left unbacked after full liquidations exhausted the insurance pool
+    pub total_proceeds: u64,            // Lifetime penalty revenue kept by the protocol (insurance + surplus shares)
+    pub liquidator_reimbursement: u64,  // Stablecoin paid to the liquidator per partial liquidation for its transaction costs
+    pub total_reimbursed: u64,          // Lifetime stablecoin reimbursed to liquidators
+}
+
+pub const MAX_LIQUIDATOR_REIMBURSEMENT: u64 = 5_000_000;        // Upper bound of the per-liquidation reimbursement ($5)
+
+// -------------------------------------
+// Price Cache Structure
+// -------------------------------------
+pub const MAX_PRICE_OVERRIDE_SECS: u64 = 7 * 24 * 60 * 60;     // Longest a valuation override may stay in force
+
+#[account]
+pub struct PriceCache {
+    pub collateral_type: Pubkey,        // Collateral type the cached price belongs to
+    pub price: u64,                     // Last validated price, normalized to PRICE_PRECISION
+    pub confidence: u64,                // Confidence interval of the cached price
+    pub publish_time: i64,              // Oracle publish time of the cached price
+    pub bump: u8,                       // Bump of the price cache PDA
+    pub reference_price: u64,           // Raw feed price at the last refresh (SOL for LSTs, the underlying for receipts)
+    pub price_floor: u64,               // Valuation override floor (0 if none)
+    pub price_ceiling: u64,             // Valuation override ceiling (0 if none)
+    pub reference_cap_bps: u64,         // Valuation override cap as a share of the reference price (0 if none)
+    pub override_expires_at: i64,       // Time the valuation override lapses (0 if none is set)
+}
+
+// -------------------------------------
+// Guardian Set Structure
+// -------------------------------------
+pub const MAX_GUARDIANS: usize = 10;                            // Upper bound on the guardian set size
+pub const EMERGENCY_ACTION_TTL_SECS: i64 = 60 * 60;             // Lifetime of an unexecuted emergency action
+
+#[account]
+pub struct GuardianSet {
+    pub guardians: Vec<Pubkey>,         // Guardian keys, indexed by approval bitmap position
+    pub threshold: u8,                  // Distinct guardian approvals required to execute an action
+    pub epoch: u64,                     // Incremented whenever the set changes, invalidating open actions
+    pub next_action_id: u64,            // Id assigned to the next emergency action
+    pub bump: u8,                       // Bump of the guardian set PDA
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmergencyActionKind {
+    PauseStaking,                       // Halt staking
+    ResumeStaking,                      // Resume staking
+    ResetOperationLock,                 // Clear an operation lock left set by an aborted flow
+    DeclareIncident,                    // Delay safety module exits ahead of a possible slash
+    ClearIncident,                      // Restore immediate safety module exits
+}
+
+#[account]
+pub struct EmergencyAction {
+    pub action_id: u64,                 // Sequential id within the guardian set
+    pub kind: EmergencyActionKind,      // Action executed once the threshold is reached
+    pub proposer: Pubkey,               // Guardian that opened the action, refunded on cleanup
+    pub guardian_epoch: u64,            // Guardian set epoch the approvals were collected under
+    pub approvals: u16,                 // Bitmap of guardian indices that approved
+    pub expires_at: i64,                // Timestamp after which the action can no longer execute
+    pub executed: bool,                 // Whether the action has executed
+}
+
+// -------------------------------------
+// Rate Snapshot Structure
+// -------------------------------------
+pub const RATE_SNAPSHOT_CAPACITY: usize = 64;                   // Epochs retained before the oldest snapshot is overwritten
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct RateSnapshot {
+    pub epoch: u64,                     // Epoch the snapshot was taken in
+    pub timestamp: i64,                 // Time the snapshot was taken
+    pub stability_fee_index: u128,      // Cumulative stability fee index, scaled by EXCHANGE_RATE_PRECISION
+    pub savings_exchange_rate: u128,    // Savings wrapper exchange rate, scaled by EXCHANGE_RATE_PRECISION
+    pub total_debt: u64,                // Total collateral-backed debt outstanding
+}
+
+#[account]
+pub struct RateSnapshots {
+    pub snapshots: [RateSnapshot; RATE_SNAPSHOT_CAPACITY], // Ring buffer of per-epoch snapshots
+    pub head: u16,                      // Slot the next snapshot is written to
+    pub count: u16,                     // Number of populated slots
+    pub stability_fee_index: u128,      // Running stability fee index, scaled by EXCHANGE_RATE_PRECISION
+    pub last_index_update: i64,         // Timestamp the running index was last accrued to
+    pub last_run_slot: u64,             // Slot the snapshot crank last ran in
+}
+
+// -------------------------------------
+// Rate Controller Structure
+// -------------------------------------
+pub const MAX_NEGATIVE_STABILITY_FEE_BPS: i64 = 200;           // Deepest negative fee governance may allow (-2% per year)
+pub const MAX_STABILITY_FEE_BPS: i64 = 10_000;                  // Highest fee governance may allow (100% per year)
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RateControllerParams {
+    pub min_rate_bps: i64,              // Lowest global stability fee (bps per year); negative pays borrowers
+    pub max_rate_bps: i64,              // Highest global stability fee (bps per year)
+    pub max_change_per_epoch_bps: u64,  // Largest total move of the fee within one epoch (bps per year)
+    pub negative_rate_premium_bps: u64, // Peg TWAP premium above $1 required to set a negative fee (bps)
+}
+
+#[account]
+pub struct RateController {
+    pub params: RateControllerParams,   // Governance bounds on the global stability fee
+    pub epoch: u64,                     // Epoch the change budget is tracked for
+    pub epoch_start_rate_bps: i64,      // Global stability fee at the first change of that epoch
+    pub bump: u8,                       // Bump of the rate controller PDA
+}
+
+// -------------------------------------
+// Permit Nonce Structure
+// -------------------------------------
+#[account]
+pub struct PermitNonce {
+    pub user: Pubkey,                   // Wallet whose signed permits this account sequences
+    pub nonce: u64,                     // Nonce the next permit must carry
+    pub bump: u8,                       // Bump of the nonce PDA
+}
+
+// -------------------------------------
+// Campaign Structures
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CampaignTarget {
+    StakingPool(Pubkey),                // Reward pool; weight is the staked balance
+    Collateral(Pubkey),                 // Collateral type; weight is the debt minted against it
+}
+
+#[account]
+pub struct Campaign {
+    pub target: CampaignTarget,         // Pool or collateral whose participants are incentivized
+    pub reward_mint: Pubkey,            // Mint the campaign pays rewards in
+    pub reward_vault: Pubkey,           // Token account holding campaign funds, owned by the campaign authority PDA
+    pub reward_rate: u64,               // Rewards emitted per second across the whole target
+    pub start_time: i64,                // Timestamp emissions begin
+    pub end_time: i64,                  // Timestamp emissions stop
+    pub last_update_time: i64,          // Timestamp the accumulator was last advanced to
+    pub accumulated_reward_per_share: u128, // Rewards per unit of weight, scaled by REWARD_PRECISION
+    pub funded_amount: u64,             // Total rewards deposited into the vault
+    pub emitted_amount: u64,            // Rewards released into the accumulator so far
+    pub distributed_amount: u64,        // Rewards paid out to participants
+    pub terminated: bool,               // Whether governance ended the campaign early
+    pub authority_bump: u8,             // Bump of the campaign authority PDA
+    pub joined_weight: u64,             // Sum of joined positions' recorded weights, the base emissions are shared over
+}
+
+#[account]
+pub struct CampaignPosition {
+    pub campaign: Pubkey,               // Campaign the position participates in
+    pub position: Pubkey,               // Staker or user account providing the weight
+    pub owner: Pubkey,                  // Wallet that joined and receives the rewards
+    pub weight: u64,                    // Weight at the last settlement; increases only count after a sync
+    pub reward_per_share_paid: u128,    // Campaign accumulator value at the last settlement
+    pub pending_rewards: u64,           // Settled rewards not yet claimed
+    pub bump: u8,                       // Bump of the position PDA
+}
+
+// -------------------------------------
+// Position V2 Structure
+// -------------------------------------
+pub const POSITION_VERSION: u8 = 2;                             // Layout version written by migrate_position
+
+/// Layout of a v1 user account, as deployed before any field was appended to `UserAccount`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyUserAccount {
+    pub collateral_balance: u64,        // The amount of collateral deposited
+    pub stablecoin_balance: u64,        // The amount of stablecoin minted
+    pub collateral_ratio: u64,          // The required collateral ratio (e.g., 150%)
+    pub last_liquidation_time: u64,     // Timestamp of the last liquidation action
+    pub last_mint_time: u64,            // Timestamp of the last minting action
+}
+
+#[account]
+pub struct PositionV2 {
+    pub version: u8,                    // Layout version of the position
+    pub owner: Pubkey,                  // Wallet that migrated and controls the position
+    pub migrated_from: Pubkey,          // Address of the v1 user account this position replaced
+    pub collateral_balance: u64,        // The amount of collateral deposited
+    pub stablecoin_balance: u64,        // The amount of stablecoin minted
+    pub collateral_ratio: u64,          // The required collateral ratio (e.g., 150%)
+    pub collateral_type: Pubkey,        // Collateral type backing the position (default if unassigned)
+    pub health_band: HealthBand,        // Health band the position was last observed in
+    pub tag: [u8; 32],                  // Free-form position tag carried over from v1
+    pub last_liquidation_time: u64,     // Timestamp of the last liquidation action
+    pub last_mint_time: u64,            // Timestamp of the last minting action
+    pub last_liquidation_ratio: u64,    // Collateral ratio the position was left at by the last liquidation
+    pub stability_fee_index: u128,      // Stability fee index the debt was last accrued to
+    pub entry_exchange_rate: u128,      // Collateral exchange rate when the position was bound to its collateral type
+    pub migrated_at: i64,               // Timestamp of the migration
+    pub bump: u8,                       // Bump of the position PDA
+}
+
+// -------------------------------------
+// Peg State Structure
+// -------------------------------------
+pub const DEFAULT_PEG_BAND_BPS: u64 = 200;                      // Default maximum TWAP deviation below $1 before minting halts
+pub const DEFAULT_PEG_TWAP_WINDOW_SECS: u64 = 30 * 60;          // Default averaging window of the peg TWAP
+
+#[account]
+pub struct PegState {
+    pub price_feed: Pubkey,             // Oracle feed of the stablecoin's market price
+    pub twap_price: u64,                // Time-weighted market price, scaled by PRICE_PRECISION
+    pub last_update: i64,               // Timestamp the TWAP was last updated
+    pub twap_window_secs: u64,          // Averaging window of the TWAP
+    pub max_deviation_bps: u64,         // Maximum TWAP deviation below $1 at which minting is still allowed
+    pub max_price_age_secs: u64,        // Maximum age of the feed and the TWAP when minting
+    pub enforced: bool,                 // Whether the band gates minting
+    pub last_run_slot: u64,             // Slot the TWAP crank last ran in
+    pub bump: u8,                       // Bump of the peg state PDA
+}
+
+// -------------------------------------
+// Vote Escrow Structures
+// -------------------------------------
+pub const MAX_VE_LOCK_SECS: u64 = 4 * 365 * 24 * 60 * 60;      // Longest lock; earns one ve per locked token
+pub const BASE_BOOST_BPS: u64 = 4_000;                          // Working balance of unboosted stake (40%)
+pub const MAX_BOOST_BPS: u64 = 10_000;                          // Working balance of fully boosted stake (100%)
+
+#[account]
+pub struct VeConfig {
+    pub escrow_vault: Pubkey,           // Token account holding locked governance tokens
+    pub total_ve: u64,                  // Sum of ve balances of unexpired, unkicked locks
+    pub escrow_authority_bump: u8,      // Bump of the escrow authority PDA
+    pub bump: u8,                       // Bump of the ve config PDA
+}
+
+#[account]
+pub struct VeLock {
+    pub owner: Pubkey,                  // Wallet that locked the tokens
+    pub amount: u64,                    // Governance tokens locked
+    pub unlock_time: i64,               // Timestamp the tokens can be withdrawn
+    pub ve_balance: u64,                // ve granted for the lock, amount scaled by lock duration
+    pub boosted_staker: Pubkey,         // Staker account the lock boosts (default if none)
+    pub bump: u8,                       // Bump of the lock PDA
+}
+
+// -------------------------------------
+// Margin Account Structure
+// -------------------------------------
+pub const MAX_CROSS_POSITIONS: usize = 8;                       // Positions a single cross-margin account can pool
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarginMode {
+    Isolated,                           // Each position is checked against its own collateral
+    Cross,                              // Pooled positions share all their collateral against all their debt
+}
 
+#[account]
+pub struct MarginAccount {
+    pub owner: Pubkey,                  // Wallet whose positions the account pools
+    pub mode: MarginMode,               // Margin mode of the owner's pooled positions
+    pub positions: Vec<Pubkey>,         // Positions pooled under cross margin
+    pub bump: u8,                       // Bump of the margin account PDA
+}
+
+pub const PORTFOLIO_HEALTH_MAX_AGE_SECS: i64 = 60;              // Oldest cached portfolio valuation a mint may rely on
+
+/// Portfolio valuation cached by the `refresh_portfolio` crank, so cross-margin
+/// mints need not load and price every pooled position.
+#[account]
+pub struct PortfolioHealthCache {
+    pub margin_account: Pubkey,         // Margin account the valuation covers
+    pub collateral_value: u128,         // Value of the collateral across all pooled positions
+    pub liquidation_value: u128,        // Collateral value discounted by each position's liquidation threshold
+    pub debt: u64,                      // Stablecoin debt across all pooled positions, raised by mints since the refresh
+    pub health_bps: u64,                // Health factor in bps at the last refresh
+    pub positions_hash: [u8; 32],       // Hash of the pooled position list the valuation was taken over
+    pub refreshed_at: i64,              // Timestamp of the last refresh
+    pub bump: u8,                       // Bump of the portfolio health PDA
+}
+
+// -------------------------------------
+// Pause State Structure
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PausableAction {
+    Mint,                               // Issuing new stablecoin
+    Redeem,                             // Redeeming stablecoin for collateral
+    Liquidate,                          // Liquidating positions and starting collateral auctions
+    Governance,                         // Creating, voting on and executing proposals
+}
+
+/// Why the pause flags were last changed, for integrators to show users.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PauseReason {
+    Unspecified,                        // No reason given
+    Maintenance,                        // Planned upgrade or migration
+    OracleFailure,                      // Price feeds are stale or unreliable
+    MarketStress,                       // Extreme volatility or a depeg
+    SecurityIncident,                   // Suspected exploit under investigation
+}
+
+/// Compact pause flags, kept apart from the system state so integrating
+/// programs can check them without deserializing the full configuration.
+#[account]
+pub struct PauseState {
+    pub minting_paused: bool,           // New issuance is halted
+    pub redemptions_paused: bool,       // Redemptions are halted
+    pub liquidations_paused: bool,      // Liquidations and collateral auctions are halted
+    pub governance_paused: bool,        // Proposal creation, voting and execution are halted
+    pub emergency_shutdown: bool,       // Protocol is in emergency shutdown; every action is halted
+    pub reason: PauseReason,            // Reason given with the last flag change
+    pub updated_by: Pubkey,             // Authority that made the last flag change
+    pub updated_at: i64,                // Timestamp of the last flag change
+    pub bump: u8,                       // Bump of the pause state PDA
+}
+
+// -------------------------------------
+// Collateral Auction Structures
+// -------------------------------------
+pub const DEFAULT_AUCTION_START_PREMIUM_BPS: u64 = 2_000;       // Auctions open 20% above the oracle price
+pub const DEFAULT_AUCTION_FLOOR_BPS: u64 = 8_000;               // and decay to 80% of it
+pub const DEFAULT_AUCTION_DURATION_SECS: u64 = 60 * 60;         // over one hour
+pub const DEFAULT_BUY_NOW_PREMIUM_BPS: u64 = 300;               // Buy-now price is 3% above the live oracle price
+pub const DEFAULT_BID_COMMIT_SECS: u64 = 5 * 60;                // Sealed bids are committed for five minutes
+pub const DEFAULT_BID_REVEAL_SECS: u64 = 5 * 60;                // then revealed over the next five
+pub const SEALED_BID_SETTLE_GRACE_SECS: i64 = 10 * 60;          // Winner's exclusive settlement window before the lot reopens to open takes
+pub const DEFAULT_BID_BOND_LAMPORTS: u64 = 1_000_000_000;       // Bond escrowed with every sealed bid (1 SOL)
+
+#[account]
+pub struct AuctionParams {
+    pub collateral_type: Pubkey,        // Collateral type the parameters apply to
+    pub start_premium_bps: u64,         // Premium over the oracle price the Dutch price starts at (bps)
+    pub floor_bps: u64,                 // Share of the starting oracle price the Dutch price decays to (bps)
+    pub duration_secs: u64,             // Time the Dutch price takes to decay to the floor
+    pub buy_now_premium_bps: u64,       // Premium over the live oracle price of an instant buy-now settlement (bps)
+    pub bump: u8,                       // Bump of the auction params PDA
+    pub sealed_bid: bool,               // New auctions run a commit-reveal round instead of open takes
+    pub commit_secs: u64,               // Length of a sealed-bid auction's commit phase
+    pub reveal_secs: u64,               // Length of a sealed-bid auction's reveal phase, starting when commits close
+    pub max_lot_size: u64,              // Most collateral a single auction lot may sell (0 for no cap)
+    pub settlement_reserves: Pubkey,    // Treasury reserves whose reserve asset (e.g., USDC) settles new auctions (default settles in the stablecoin)
+    pub bid_bond_lamports: u64,         // Bond a sealed bid escrows on commit, slashed if it is never revealed or the winner never settles
+}
+
+#[account]
+pub struct CollateralAuction {
+    pub position: Pubkey,               // Position whose collateral is auctioned
+    pub collateral_type: Pubkey,        // Collateral type of the lot
+    pub keeper: Pubkey,                 // Keeper that started the auction, refunded its rent on settlement
+    pub collateral_amount: u64,         // Collateral in the lot
+    pub debt: u64,                      // Stablecoin debt the proceeds must cover
+    pub start_price: u64,               // Dutch price per collateral token at the start, in PRICE_PRECISION
+    pub floor_price: u64,               // Dutch price per collateral token once fully decayed, in PRICE_PRECISION
+    pub start_time: i64,                // Timestamp the auction started
+    pub duration_secs: u64,             // Time the Dutch price takes to decay to the floor
+    pub bump: u8,                       // Bump of the auction PDA
+    pub sealed_bid: bool,               // Auction is settled by commit-reveal rather than open takes
+    pub commit_end: i64,                // Timestamp bid commitments close and reveals open
+    pub reveal_end: i64,                // Timestamp reveals close and the best bidder may settle
+    pub best_bid: u64,                  // Highest valid revealed price per collateral token, in PRICE_PRECISION
+    pub best_bidder: Pubkey,            // Bidder of the highest valid revealed price
+    pub settlement_reserves: Pubkey,    // Treasury reserves whose reserve asset the lot is paid in (default if paid in the stablecoin)
+}
+
+#[account]
+pub struct AuctionSeries {
+    pub position: Pubkey,               // Position whose seized collateral is sold in sequential lots
+    pub collateral_type: Pubkey,        // Collateral type of the lots
+    pub keeper: Pubkey,                 // Keeper that started the series, refunded its rent once the last lot starts
+    pub remaining_collateral: u64,      // Collateral not yet put up in a lot
+    pub remaining_debt: u64,            // Debt the remaining lots must cover
+    pub lots_started: u64,              // Lots started so far
+    pub bump: u8,                       // Bump of the series PDA
+}
+
+#[account]
+pub struct SealedBid {
+    pub auction: Pubkey,                // Auction the bid is for
+    pub bidder: Pubkey,                 // Bidder that committed
+    pub commitment: [u8; 32],           // hash(price || salt || bidder)
+    pub committed_at: i64,              // Timestamp the commitment was made
+    pub bump: u8,                       // Bump of the sealed bid PDA
+    pub bond: u64,                      // Lamports escrowed in the bid account on top of its rent
+    pub reveal_end: i64,                // Reveal deadline of the auction, kept so the bid can be slashed after the auction closes
+    pub revealed: bool,                 // Whether the bid was revealed; only the best revealed bid stays open
+}
+
+// -------------------------------------
+// Treasury Swap Schedule Structure
+// -------------------------------------
+#[account]
+pub struct SwapSchedule {
+    pub swap_program: Pubkey,           // Swap adapter program executing each slice
+    pub source_vault: Pubkey,           // Escrow holding the treasury stablecoin still to be converted
+    pub destination_vault: Pubkey,      // Reserve token account receiving the swapped assets
+    pub total_amount: u64,              // Stablecoin the schedule may convert in total
+    pub slice_amount: u64,              // Stablecoin converted per slice
+    pub interval_secs: u64,             // Minimum time between slices
+    pub min_output_price: u64,          // Minimum output received per stablecoin, in PRICE_PRECISION
+    pub next_execution_time: i64,       // Earliest timestamp the next slice can execute
+    pub executed_amount: u64,           // Stablecoin converted so far
+    pub received_amount: u64,           // Reserve assets received so far
+    pub cancelled: bool,                // Whether governance cancelled the schedule
+    pub authority_bump: u8,             // Bump of the swap authority PDA
+}
+
+// -------------------------------------
+// Bad Debt Queue Structure
+// -------------------------------------
+pub const MAX_BAD_DEBT_ENTRIES: usize = 16;                     // Entries the queue holds before folding new debt into the tail
+pub const MAX_BAD_DEBT_SLASH_BPS: u64 = 3_000;                  // Share of the staking vault one bad debt entry can slash
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BadDebtStage {
+    Insurance,                          // Absorbed by the insurance pool
+    StakerSlashing,                     // Covered by burning slashed stake
+    DebtAuction,                        // Covered by selling newly minted governance tokens
+    Treasury,                           // Repaid by burning treasury-held stablecoin
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShortfallSource {
+    FullLiquidation,                    // Liquidation repaid less than the position's debt
+    CollateralAuction,                  // Auction proceeds fell short of the lot's debt
+    BadDebtQueue,                       // Queued bad debt absorbed after the fact
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BadDebtEntry {
+    pub amount: u64,                    // Unbacked debt still outstanding in the entry
+    pub collateral_type: Pubkey,        // Collateral type of the liquidated position (default for legacy positions)
+    pub created_at: i64,                // Timestamp the debt was queued
+    pub stakers_slashed: bool,          // Whether the staker slashing stage already ran for the entry
+}
+
+#[account]
+pub struct BadDebtQueue {
+    pub entries: Vec<BadDebtEntry>,     // Outstanding bad debt, oldest first
+    pub total_outstanding: u64,         // Sum of the entries' outstanding amounts
+    pub debt_auction_price: u64,        // Governance tokens sold per stablecoin of bad debt, in PRICE_PRECISION
+    pub bump: u8,                       // Bump of the queue PDA
+    pub slashing_pool: Pubkey,          // Reward pool whose stakers are slashed for bad debt, set by governance
+}
+
+// -------------------------------------
+// Debt Note Structure
+// -------------------------------------
+/// Transferable claim on a position owner's promise to repay, secured by the
+/// position's collateral above what its protocol debt requires.
+#[account]
+pub struct DebtNote {
+    pub position: Pubkey,               // Position whose excess collateral secures the note
+    pub issuer: Pubkey,                 // Position owner who sold the note and owes its repayments
+    pub holder: Pubkey,                 // Current holder entitled to repayments and seizure
+    pub face_amount: u64,               // Stablecoin the issuer owes the holder
+    pub price: u64,                     // Stablecoin the first holder paid for the note
+    pub repaid_amount: u64,             // Face value recovered so far, by repayment or seizure
+    pub maturity: i64,                  // Timestamp after which unpaid face value can be seized
+    pub settled: bool,                  // Whether the face value was fully recovered
+}
+
+// -------------------------------------
+// Loyalty Collection Structure
+// -------------------------------------
+#[account]
+pub struct LoyaltyCollection {
+    pub collection_mint: Pubkey,        // Verified NFT collection accepted as a loyalty pass
+    pub fee_discount_bps: u64,          // Discount applied to the mint fee of pass holders (bps of the fee)
+    pub bump: u8,                       // Bump of the whitelist entry PDA
+}
+
+// -------------------------------------
+// Payment Stream Structure
+// -------------------------------------
+#[account]
+pub struct PaymentStream {
+    pub sender: Pubkey,                 // Payer who funds the stream and can cancel it
+    pub recipient: Pubkey,              // Payee the stablecoin streams to
+    pub escrow: Pubkey,                 // Stablecoin account holding the unstreamed funds
+    pub rate_per_second: u64,           // Stablecoin released every second after the start
+    pub start_time: i64,                // Timestamp the stream starts accruing
+    pub end_time: i64,                  // Timestamp the stream stops accruing
+    pub withdrawn: u64,                 // Amount already paid out to the recipient
+    pub cancelled: bool,                // Set once the sender cancels the stream
+    pub authority_bump: u8,             // Bump of the payment stream authority PDA
+}
+
+// -------------------------------------
+// Epoch Reporting Structures
+// -------------------------------------
+#[account]
+pub struct EpochState {
+    pub epoch_duration: i64,            // Length of a reporting epoch in seconds
+    pub current_epoch: u64,             // Index of the epoch currently accumulating
+    pub epoch_start: i64,               // Timestamp the current epoch started
+    pub wrapper_vault: Pubkey,          // Savings wrapper whose yield is reported as savings paid
+    pub fee_issuance_mark: u64,         // ProtocolStats.fee_issuance at the start of the epoch
+    pub liquidation_proceeds_mark: u64, // LiquidationRevenue.total_proceeds at the start of the epoch
+    pub exchange_rate_mark: u128,       // Savings wrapper exchange rate at the start of the epoch
+    pub last_run_slot: u64,             // Slot the epoch close crank last ran in
+    pub bump: u8,                       // Bump of the epoch state PDA
+    pub revenue_pool: Pubkey,           // Revenue reward pool every close must fund, or default when none is configured
+}
+
+#[account]
+pub struct EpochReport {
+    pub epoch: u64,                     // Index of the reported epoch
+    pub start_time: i64,                // Timestamp the epoch started
+    pub end_time: i64,                  // Timestamp the epoch was closed
+    pub mint_fees: u64,                 // Mint fees and confidence premiums issued to the treasury
+    pub liquidation_proceeds: u64,      // Liquidation penalties kept by the protocol
+    pub interest_accrued: u64,          // Stability fees accrued on outstanding collateral debt
+    pub savings_paid: u64,              // Yield accrued to savings wrapper holders
+    pub staker_revenue: u64,            // Mint fees moved into a revenue reward pool at close
+    pub bump: u8,                       // Bump of the epoch report PDA
+}
+
+// -------------------------------------
+// Stake Pool Collateral Structure
+// -------------------------------------
+#[account]
+pub struct StakePoolConfig {
+    pub collateral_type: Pubkey,        // LST collateral type deposits are credited to
+    pub stake_pool: Pubkey,             // Whitelisted SPL stake pool SOL is delegated into
+    pub stake_pool_program: Pubkey,     // Program owning the stake pool
+    pub lst_vault: Pubkey,              // Token account holding the LST received, owned by the staking authority PDA
+    pub unstake_haircut_bps: u64,       // Discount on the LST's SOL value for unstake latency (bps)
+    pub total_lst: u64,                 // LST received for deposited SOL
+    pub authority_bump: u8,             // Bump of the SOL staking authority PDA
+    pub bump: u8,                       // Bump of the stake pool config PDA
+}
+
+// -------------------------------------
+// Governance Token Oracle Structure
+// -------------------------------------
+pub const DEFAULT_GOV_TWAP_WINDOW_SECS: u64 = 30 * 60;          // AMM TWAP averages over half an hour
+pub const DEFAULT_GOV_MAX_SPOT_MOVE_BPS: u64 = 500;             // A single update may pull the TWAP towards at most 5% away from it
+pub const DEFAULT_GOV_MAX_DIVERGENCE_BPS: u64 = 300;            // Pyth and the TWAP must agree within 3%
+pub const DEFAULT_GOV_MAX_TWAP_AGE_SECS: u64 = 10 * 60;         // TWAP must have been updated in the last ten minutes
+
+#[account]
+pub struct GovTokenOracle {
+    pub pyth_feed: Pubkey,              // Pyth feed of the governance token (default if unlisted)
+    pub gov_vault: Pubkey,              // AMM pool reserve of the governance token
+    pub quote_vault: Pubkey,            // AMM pool reserve of the stablecoin
+    pub twap_price: u64,                // Time-weighted AMM price, in PRICE_PRECISION
+    pub last_update: i64,               // Timestamp the TWAP was last updated
+    pub twap_window_secs: u64,          // Averaging window of the TWAP
+    pub max_spot_move_bps: u64,         // Maximum distance of a folded spot price from the TWAP (bps)
+    pub max_divergence_bps: u64,        // Maximum disagreement between Pyth and the TWAP (bps)
+    pub max_price_age_secs: u64,        // Maximum age of the Pyth price
+    pub max_twap_age_secs: u64,         // Maximum age of the TWAP
+    pub min_quote_liquidity: u64,       // Minimum stablecoin reserve for the pool to be sampled
+    pub fallback_price: u64,            // Governance price used when the sources are unavailable or diverge (0 if none)
+    pub bump: u8,                       // Bump of the governance token oracle PDA
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GovPriceSource {
+    Combined,                           // Average of the Pyth price and the AMM TWAP
+    AmmTwap,                            // AMM TWAP alone, for a token without a Pyth feed
+    Fallback,                           // Governance fallback price
+}
+
+// -------------------------------------
+// Crank Schedule Structure
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CrankKind {
+    InterestIndex,                      // record_rate_snapshot
+    RewardPool,                         // crank_reward_pool
+    PegObservation,                     // update_peg_twap
+    EpochClose,                         // close_epoch
+}
+
+pub const CRANK_KIND_COUNT: usize = 4;
+pub const DEFAULT_INTEREST_CRANK_INTERVAL_SLOTS: u64 = 9_000;   // About an hour between index accruals
+pub const DEFAULT_REWARD_CRANK_INTERVAL_SLOTS: u64 = 150;       // About a minute between reward pool updates
+pub const DEFAULT_PEG_CRANK_INTERVAL_SLOTS: u64 = 25;           // About ten seconds between peg observations
+pub const DEFAULT_EPOCH_CRANK_INTERVAL_SLOTS: u64 = 0;          // Epoch closes are already bounded by the epoch length
+
+#[account]
+pub struct CrankSchedule {
+    pub min_interval_slots: [u64; CRANK_KIND_COUNT], // Minimum slots between two runs of each crank, indexed by CrankKind
+    pub bump: u8,                       // Bump of the crank schedule PDA
+}
+
+// -------------------------------------
+// Position Ledger Structures
+// -------------------------------------
+#[account]
+pub struct PositionLedger {
+    pub user_account: Pubkey,           // Position whose history the ledger records
+    pub capacity: u32,                  // Entries the account has space for
+    pub entry_count: u64,               // Entries appended over the ledger's lifetime, including dropped ones
+    pub dropped_entries: u64,           // Entries not recorded because the ledger was full
+    pub bump: u8,                       // Bump of the ledger PDA
+    pub entries: Vec<LedgerEntry>,      // Recorded entries, oldest first
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LedgerEntry {
+    pub kind: LedgerEntryKind,          // Operation that changed the position
+    pub timestamp: i64,                 // Time of the operation
+    pub collateral_amount: u64,         // Collateral moved in or out of the position
+    pub debt_amount: u64,               // Debt added to or removed from the position
+    pub price: u64,                     // Collateral price at the operation, scaled by PRICE_PRECISION (0 if unknown)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LedgerEntryKind {
+    Mint,                               // Debt added
+    Repay,                              // Debt removed by the owner, or by an auto-deleverage it opted into with the collateral sold
+    Deposit,                            // Collateral added, including collateral won as a liquidator, buyer or note holder
+    Liquidation,                        // Collateral seized and debt removed by a liquidation, auction or note seizure
+}
+
+pub const POSITION_LEDGER_BASE_SPACE: usize = 8 + 32 + 4 + 8 + 8 + 1 + 4; // Discriminator, fixed fields and the entries length prefix
+pub const LEDGER_ENTRY_SPACE: usize = 1 + 8 + 8 + 8 + 8;       // Serialized size of one entry
+pub const MAX_LEDGER_ALLOCATION: u32 = 300;                     // Most entries allocated or added in one instruction (10 KiB realloc limit)
+
+// -------------------------------------
+// Ceiling Controller Structures
+// -------------------------------------
+#[account]
+pub struct CeilingController {
+    pub collateral_type: Pubkey,        // Collateral whose debt ceiling the controller steers
+    pub params: CeilingControllerParams, // Governance-set thresholds and bounds
+    pub high_epochs: u64,               // Consecutive epochs observed above the raise threshold with a healthy peg
+    pub low_epochs: u64,                // Consecutive epochs observed below the lower threshold
+    pub next_epoch: u64,                // Earliest epoch the next observation may run in
+    pub bump: u8,                       // Bump of the controller PDA
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CeilingControllerParams {
+    pub raise_utilization_bps: u64,     // Utilization of the ceiling at or above which an epoch counts toward a raise
+    pub lower_utilization_bps: u64,     // Utilization at or below which an epoch counts toward a cut
+    pub required_epochs: u64,           // Consecutive epochs needed before the ceiling moves
+    pub step_bps: u64,                  // Size of one adjustment, relative to the current ceiling
+    pub floor: u64,                     // Lowest ceiling the controller may set
+    pub cap: u64,                       // Highest ceiling the controller may set
+}
+
+pub const MAX_CEILING_STEP_BPS: u64 = 2_500;                    // Largest single automatic ceiling adjustment (25%)
+
+// -------------------------------------
+// Market Maker Facility Structures
+// -------------------------------------
+#[account]
+pub struct MmFacility {
+    pub market_maker: Pubkey,           // Approved address allowed to draw on the facility
+    pub terms: MmFacilityTerms,         // Governance-set credit line terms
+    pub outstanding_debt: u64,          // Stablecoin minted through the facility and not yet redeemed
+    pub attestation_hash: [u8; 32],     // Hash of the latest off-chain collateral attestation (e.g. T-bill custody statement)
+    pub attested_value: u64,            // Collateral value the latest attestation reports, in stablecoin units
+    pub attested_at: i64,               // Timestamp the latest attestation was recorded
+    pub active: bool,                   // Facility may mint; redemptions stay open while inactive
+    pub bump: u8,                       // Bump of the facility PDA
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MmFacilityTerms {
+    pub debt_ceiling: u64,              // Credit line: most stablecoin the facility may have outstanding
+    pub mint_fee_bps: u64,              // Fee on facility mints (bps)
+    pub redeem_fee_bps: u64,            // Fee on facility redemptions (bps)
+    pub collateral_ratio_bps: u64,      // Attested collateral value required per unit of outstanding debt (bps)
+    pub max_attestation_age_secs: u64,  // Oldest attestation a mint may rely on
+}
+
+// -------------------------------------
+// Travel Rule Structures
+// -------------------------------------
+#[account]
+pub struct TravelRuleAttestor {
+    pub attestor: Pubkey,               // VASP signer approved to attest travel-rule records
+    pub bump: u8,                       // Bump of the attestor PDA
+}
+
+#[account]
+pub struct TravelRuleAttestation {
+    pub attestor: Pubkey,               // Approved attestor that recorded the attestation
+    pub memo_hash: [u8; 32],            // Hash of the encrypted travel-rule payload held by the VASPs
+    pub originator: Pubkey,             // Wallet the attested transfer must come from
+    pub beneficiary: Pubkey,            // Wallet the attested transfer must pay
+    pub amount: u64,                    // Exact amount the attested transfer must move
+    pub consumed: bool,                 // A transfer has already been matched against this attestation
+    pub bump: u8,                       // Bump of the attestation PDA
+}
+
+// -------------------------------------
+// Token Rescue Structure
+// -------------------------------------
+/// Program PDA that owns a token account tokens are rescued from.
+///
+/// Staking, campaign, airdrop and stream authorities are left out on
+/// purpose: their vaults hold user funds in arbitrary mints.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RescueAuthority {
+    Treasury,                           // treasury_authority; never the reserve mint
+    GovToken,                           // gov_token_authority
+    VeEscrow,                           // ve_escrow_authority
+    Wrapper(Pubkey),                    // wrapper_authority of the given savings wrapper
+}
+
+// -------------------------------------
+// Redemption Queue Structures
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RedemptionLane {
+    Standard,                           // Filled in arrival order once the priority lane is empty
+    Priority,                           // Filled first, for the queue's priority fee
+}
+
+pub const REDEMPTION_LANE_COUNT: usize = 2;
+
+/// FIFO queue of stablecoin redemptions against the treasury's liquid
+/// reserves, filled as reserves return instead of failing large redemptions.
+#[account]
+pub struct RedemptionQueue {
+    pub treasury_reserves: Pubkey,      // Reserves the queued redemptions are paid from
+    pub escrow_vault: Pubkey,           // Stablecoin account holding queued redemptions, owned by the treasury authority PDA
+    pub heads: [u64; REDEMPTION_LANE_COUNT], // Id of the next request to fill, per lane
+    pub tails: [u64; REDEMPTION_LANE_COUNT], // Id the next request will get, per lane
+    pub queued_amount: u64,             // Stablecoin escrowed and not yet filled or cancelled
+    pub priority_fee_bps: u64,          // Fee for the priority lane, paid to the treasury (bps)
+    pub bump: u8,                       // Bump of the queue PDA
+}
+
+#[account]
+pub struct RedemptionRequest {
+    pub queue: Pubkey,                  // Queue the request waits in
+    pub owner: Pubkey,                  // Wallet that queued the redemption
+    pub lane: RedemptionLane,           // Lane the request waits in
+    pub id: u64,                        // Position of the request within its lane
+    pub destination: Pubkey,            // Owner's reserve token account receiving the fills
+    pub amount: u64,                    // Stablecoin escrowed when the request was queued
+    pub remaining: u64,                 // Stablecoin still waiting to be filled
+    pub cancelled: bool,                // Owner withdrew the unfilled remainder
+    pub created_at: i64,                // Timestamp the request was queued
+    pub bump: u8,                       // Bump of the request PDA
+}
+
+// -------------------------------------
+// Lending Receipt Collateral Structures
+// -------------------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReceiptMarket {
+    Kamino,                             // Kamino Lend reserve; receipts are its kTokens
+    Marginfi,                           // marginfi bank; receipts are valued at its asset share value
+}
+
+#[account]
+pub struct ReceiptCollateralConfig {
+    pub collateral_type: Pubkey,        // Receipt collateral type the config prices
+    pub market: ReceiptMarket,          // Lending market the receipt is issued by
+    pub market_program: Pubkey,         // Program owning the reserve or bank
+    pub reserve: Pubkey,                // Reserve or bank account the exchange rate is read from
+    pub haircut_bps: u64,               // Discount on the receipt's underlying value for withdrawal and utilization risk (bps)
+    pub max_rate_age_secs: u64,         // Oldest upstream interest accrual a refresh accepts
+    pub bump: u8,                       // Bump of the receipt config PDA
+}
+
+// -------------------------------------
+// Auto-Deleverage Structures
+// -------------------------------------
+pub const MAX_DELEVERAGE_SLIPPAGE_BPS: u64 = 1_000;             // Largest collateral discount an owner may allow keepers
+pub const MAX_DELEVERAGE_TIP_BPS: u64 = 100;                    // Largest keeper tip an owner may offer, relative to the debt repaid
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct DeleverageParams {
+    pub trigger_health_bps: u64,        // Health factor (bps) below which keepers may deleverage
+    pub target_health_bps: u64,         // Health factor (bps) a deleverage may restore the position to, at most
+    pub max_slippage_bps: u64,          // Largest discount to the oracle price the collateral may be sold at (bps)
+    pub tip_bps: u64,                   // Keeper tip paid from the position's collateral, relative to the debt repaid (bps)
+}
+
+#[account]
+pub struct DeleverageConfig {
+    pub user_account: Pubkey,           // Position keepers may deleverage
+    pub params: DeleverageParams,       // Owner's trigger, target, slippage and tip
+    pub total_repaid: u64,              // Debt repaid by keepers over the config's lifetime
+    pub last_deleveraged_at: i64,       // Timestamp of the last keeper deleverage (0 if none)
+    pub bump: u8,                       // Bump of the deleverage config PDA
+}
+
+// -------------------------------------
+// Liquidity Coverage Structure
+// -------------------------------------
+pub const LCR_WINDOW_BUCKETS: usize = 24;                       // Hourly buckets of trailing redemption volume
+pub const LCR_BUCKET_SECS: i64 = 60 * 60;                       // Length of a redemption volume bucket
+
+/// Coverage of the trailing 24h redemption volume by the treasury's liquid reserves.
+#[account]
+pub struct LiquidityMonitor {
+    pub treasury_reserves: Pubkey,      // Reserves whose liquidity is monitored
+    pub volume_buckets: [u64; LCR_WINDOW_BUCKETS], // Redemptions filled per hour, indexed by hour modulo the window
+    pub current_hour: i64,              // Hour (unix time / LCR_BUCKET_SECS) of the latest bucket
+    pub alert_bps: u64,                 // Coverage (liquid reserves / 24h volume, bps) below which alerts are emitted
+    pub floor_bps: u64,                 // Coverage below which anyone may recall deployed reserves
+    pub last_coverage_bps: u64,         // Coverage at the last check (u64::MAX with no volume)
+    pub bump: u8,                       // Bump of the monitor PDA
+}
+
+// -------------------------------------
+// Partner Accounts
+// -------------------------------------
+pub const MAX_PARTNER_SHARE_BPS: u64 = 5_000;                   // Largest share of fees a partner can negotiate (50%)
+
+/// Integrator (wallet app, aggregator) earning a share of the fees on flows it originates.
+#[account]
+pub struct Partner {
+    pub partner_id: u64,                // Id integrators pass into mint and redeem
+    pub wallet: Pubkey,                 // Wallet the partner claims its revenue with
+    pub revenue_share_bps: u64,         // Share of the fees charged on originated flows
+    pub active: bool,                   // Whether new flows accrue revenue to the partner
+    pub originated_volume: u64,         // Stablecoin minted and redeemed through the partner
+    pub accrued: u64,                   // Revenue share claimable from the treasury
+    pub total_claimed: u64,             // Revenue share paid out so far
+    pub bump: u8,                       // Bump of the partner PDA
+}
+
+// -------------------------------------
+// Contexts for Instructions
+// -------------------------------------
+
+/// Whether `recipient` is the staker or its reward delegate.
+pub fn is_reward_recipient(staker_account: &StakerAccount, recipient: &Pubkey) -> bool {
+    staker_account.owner != Pubkey::default()
+        && (*recipient == staker_account.owner
+            || (staker_account.reward_delegate != Pubkey::default() && *recipient == staker_account.reward_delegate))
+}
+
+/// Whether `token_account` belongs to the staker or its reward delegate.
+pub fn is_reward_destination(staker_account: &StakerAccount, token_account: &TokenAccount) -> bool {
+    is_reward_recipient(staker_account, &token_account.owner)
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = payer, space = 8 + 8 + 8 + 8 + 4 + 1, seeds = [b"governance"], bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(init, payer = payer, space = 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 8 + 16 + 32 + 2 + 32 + 8 + 8 + 8)]
+    pub user_account: Account<'info, UserAccount>,
+    pub governance: Account<'info, Governance>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintStablecoin<'info> {
+    #[account(mut, constraint = user_account.owner == payer.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    /// Payer's stablecoin ATA, created on a first mint
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = stablecoin_mint,
+        associated_token::authority = payer,
+        constraint = user_stablecoin_account.key() != treasury_account.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub user_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = stablecoin_mint,
+        address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount
+    )]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    /// Collateral type of the position; required once the position has one
+    #[account(address = user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+    /// Partner originating the mint; required when a partner id is passed
+    #[account(mut)]
+    pub partner: Option<Account<'info, Partner>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub optional_authority: Option<Signer<'info>>,
+
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    /// Liquidator's own position, credited with its share of the penalty
+    #[account(
+        mut,
+        constraint = liquidator_account.owner == payer.key() @ ErrorCode::Unauthorized,
+        constraint = liquidator_account.key() != user_account.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub liquidator_account: Account<'info, UserAccount>,
+    #[account(constraint = collateral_type.key() == user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+    #[account(constraint = price_cache.collateral_type == user_account.collateral_type @ ErrorCode::InvalidOracleAccount)]
+    pub price_cache: Option<Account<'info, PriceCache>>,
+    #[account(address = user_account.margin_account @ ErrorCode::InvalidMarginAccount)]
+    pub margin_account: Option<Account<'info, MarginAccount>>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    pub system_state: Account<'info, SystemState>,
+    /// Liquidator's stablecoin account receiving the cost reimbursement
+    #[account(mut, token::mint = system_state.stablecoin_mint)]
+    pub liquidator_stablecoin_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: PDA the treasury approves as delegate for protocol payouts
+    #[account(seeds = [b"revenue_authority"], bump)]
+    pub revenue_authority: Option<UncheckedAccount<'info>>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    pub token_program: Program<'info, Token>,
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FullLiquidate<'info> {
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Liquidator's own position, credited with the seized collateral
+    #[account(
+        mut,
+        constraint = liquidator_account.owner == payer.key() @ ErrorCode::Unauthorized,
+        constraint = liquidator_account.key() != user_account.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub liquidator_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    /// Opt-in ledger of the liquidator's position
+    #[account(mut, seeds = [b"position_ledger", liquidator_account.key().as_ref()], bump = liquidator_ledger.bump)]
+    pub liquidator_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = payer)]
+    pub liquidator_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = collateral_type.key() == user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+    #[account(constraint = price_cache.collateral_type == user_account.collateral_type @ ErrorCode::InvalidOracleAccount)]
+    pub price_cache: Option<Account<'info, PriceCache>>,
+    #[account(address = user_account.margin_account @ ErrorCode::InvalidMarginAccount)]
+    pub margin_account: Option<Account<'info, MarginAccount>>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenStakerAccount<'info> {
+    #[account(init, payer = owner, space = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 16 + 8 + 8 + 32 + 32 + 32 + 8 + 32 + 8)]
+    pub staker_account: Account<'info, StakerAccount>,
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(
+        mut,
+        constraint = staker_account.reward_pool == Pubkey::default() || staker_account.reward_pool == reward_pool.key()
+            @ ErrorCode::InvalidAccountData
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        mut,
+        token::mint = staking_pool.mint,
+        token::authority = payer,
+        constraint = user_token_account.key() != staking_pool.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub payer: Signer<'info>,
+    /// ve config and the staker's ve lock, required while the stake is boosted
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Option<Account<'info, VeConfig>>,
+    #[account(mut, address = staker_account.ve_lock @ ErrorCode::InvalidAccountData)]
+    pub ve_lock: Option<Account<'info, VeLock>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        mut,
+        has_one = reward_pool @ ErrorCode::InvalidAccountData,
+        constraint = staker_account.owner == payer.key() @ ErrorCode::Unauthorized
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(address = staking_pool.mint @ ErrorCode::InvalidMint)]
+    pub staking_mint: Account<'info, Mint>,
+    /// Payer's staking token ATA receiving the stake, created if it was closed since staking
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = staking_mint,
+        associated_token::authority = payer,
+        constraint = user_token_account.key() != staking_pool.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    /// CHECK: PDA owning the staking vault, signs withdrawals
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    #[account(address = reward_pool.system_state @ ErrorCode::InvalidAccountData)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Required while the stake holds rewards staked by `claim_to_stake`, which are minted into the vault
+    #[account(mut, address = reward_pool.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_token_mint: Option<Account<'info, Mint>>,
+    #[account(seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    /// CHECK: PDA holding mint authority over the reward mint
+    #[account(seeds = [b"reward_mint_authority", reward_pool.key().as_ref()], bump)]
+    pub reward_mint_authority: Option<UncheckedAccount<'info>>,
+    /// ve config and the staker's ve lock, required while the stake is boosted
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Option<Account<'info, VeConfig>>,
+    #[account(mut, address = staker_account.ve_lock @ ErrorCode::InvalidAccountData)]
+    pub ve_lock: Option<Account<'info, VeLock>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut, has_one = reward_pool @ ErrorCode::InvalidAccountData)]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut, constraint = reward_pool.reward_source == RewardSource::Emissions @ ErrorCode::RewardSourceMismatch)]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: The staker or its reward delegate, whose reward ATA receives the claim
+    #[account(constraint = is_reward_recipient(&staker_account, &reward_recipient.key()) @ ErrorCode::InvalidAccountOwner)]
+    pub reward_recipient: UncheckedAccount<'info>,
+    /// Recipient's reward ATA, created on a first claim
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = reward_recipient
+    )]
+    pub destination_reward_account: Account<'info, TokenAccount>,
+    #[account(mut, address = reward_pool.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_token_mint: Account<'info, Mint>,
+    #[account(mut, seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    /// CHECK: PDA holding mint authority over the reward mint
+    #[account(seeds = [b"reward_mint_authority", reward_pool.key().as_ref()], bump = emission_schedule.authority_bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// ve config and the staker's ve lock, required while the stake is boosted
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Option<Account<'info, VeConfig>>,
+    #[account(mut, address = staker_account.ve_lock @ ErrorCode::InvalidAccountData)]
+    pub ve_lock: Option<Account<'info, VeLock>>,
+    /// Funds the reward ATA on a first claim; claims stay permissionless, any wallet may pay
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimToStake<'info> {
+    #[account(
+        mut,
+        has_one = reward_pool @ ErrorCode::InvalidAccountData,
+        constraint = staker_account.auto_compound @ ErrorCode::AutoCompoundDisabled
+    )]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut, constraint = reward_pool.reward_source == RewardSource::Emissions @ ErrorCode::RewardSourceMismatch)]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// Rewards can only be staked into a vault of the reward token itself
+    #[account(
+        address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData,
+        constraint = staking_pool.mint == reward_pool.reward_mint @ ErrorCode::InvalidMint
+    )]
+    pub staking_pool: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    /// ve config and the staker's ve lock, required while the stake is boosted
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Option<Account<'info, VeConfig>>,
+    #[account(mut, address = staker_account.ve_lock @ ErrorCode::InvalidAccountData)]
+    pub ve_lock: Option<Account<'info, VeLock>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(init, payer = proposer, space = Proposal::SPACE)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(seeds = [b"governance_config", governance.key().as_ref()], bump = governance_config.bump)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    /// Required by fee change proposals, whose base checksum covers the current fee rates
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Option<Account<'info, SystemState>>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(mut)] // Make sure the proposer is mutable since it is paying for the account creation
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VoteOnProposal<'info> {
+    #[account(mut, has_one = governance @ ErrorCode::InvalidAccountData)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, has_one = governance @ ErrorCode::InvalidAccountData)]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut, seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    /// Required by fee change proposals, whose rates it receives, and by follow-ups clearing an incident
+    #[account(mut, seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Option<Account<'info, SystemState>>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    /// Required by follow-ups re-enabling a paused collateral
+    #[account(mut, address = proposal.incident_collateral_type @ ErrorCode::InvalidAccountData)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+}
+
+#[derive(Accounts)]
+pub struct AddCollateralType<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 9 + 9 + 16 + 8 + 8 + 8 + 8 + 32 + 1 + 32)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintStablecoinWithCollateral<'info> {
+    #[account(mut, constraint = user_account.owner == payer.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    /// Payer's stablecoin ATA, created on a first mint
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = stablecoin_mint,
+        associated_token::authority = payer
+    )]
+    pub user_stablecoin_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, token::mint = stablecoin_mint, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub optional_authority: Option<Signer<'info>>,
+    /// Loyalty pass NFT held by the payer, for a mint fee discount
+    pub loyalty_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    /// CHECK: Metaplex metadata of the loyalty pass, verified in the handler
+    pub loyalty_metadata: Option<UncheckedAccount<'info>>,
+    #[account(seeds = [b"loyalty_collection", loyalty_collection.collection_mint.as_ref()], bump = loyalty_collection.bump)]
+    pub loyalty_collection: Option<Account<'info, LoyaltyCollection>>,
+    /// Margin account and cached valuation a cross-margin position mints against
+    #[account(address = user_account.margin_account @ ErrorCode::InvalidMarginAccount)]
+    pub margin_account: Option<Account<'info, MarginAccount>>,
+    #[account(mut, seeds = [b"portfolio_health", user_account.margin_account.as_ref()], bump = portfolio_health.bump)]
+    pub portfolio_health: Option<Account<'info, PortfolioHealthCache>>,
+    /// Partner originating the mint; required when a partner id is passed
+    #[account(mut)]
+    pub partner: Option<Box<Account<'info, Partner>>>,
+}
+
+
+#[derive(Accounts)]
+pub struct InitializeSystemState<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 1 + 32 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 1 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 8 + 8 + 8 + 32 * MAX_ORACLE_PROGRAMS + 8,
+        seeds = [b"system_state"],
+        bump
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCanonicalAccounts<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(token::mint = stablecoin_mint)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasuryReserves<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 1, seeds = [b"treasury_reserves"], bump)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the reserve vault and signs strategy CPIs
+    #[account(seeds = [b"treasury_authority"], bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(constraint = reserve_vault.owner == treasury_authority.key() @ ErrorCode::InvalidAccountOwner)]
+    pub reserve_vault: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WhitelistStrategy<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 8 + 8 + 1 + 32)]
+    pub strategy: Account<'info, YieldStrategy>,
+    #[account(seeds = [b"treasury_reserves"], bump = treasury_reserves.bump)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: Executable program implementing the strategy adapter interface
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+    /// CHECK: Position account owned by the strategy program
+    #[account(owner = adapter_program.key() @ ErrorCode::InvalidAccountOwner)]
+    pub strategy_account: UncheckedAccount<'info>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetStrategyCap<'info> {
+    #[account(mut)]
+    pub strategy: Account<'info, YieldStrategy>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageStrategyFunds<'info> {
+    #[account(mut, has_one = reserve_vault)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the reserve vault and signs strategy CPIs
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reserve_vault: Account<'info, TokenAccount>,
+    #[account(mut, has_one = adapter_program, has_one = strategy_account, has_one = treasury_reserves)]
+    pub strategy: Account<'info, YieldStrategy>,
+    /// CHECK: Whitelisted adapter program, pinned by the strategy account
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+    /// CHECK: Position account owned by the strategy program, pinned by the strategy account
+    #[account(mut)]
+    pub strategy_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump = system_state.bump,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeReserveAttestation<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 8 + 8)]
+    pub reserve_attestation: Account<'info, ReserveAttestation>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReserveAttestor<'info> {
+    #[account(mut)]
+    pub reserve_attestation: Account<'info, ReserveAttestation>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PublishReserveReport<'info> {
+    #[account(mut, has_one = attestor @ ErrorCode::Unauthorized, has_one = treasury_reserves)]
+    pub reserve_attestation: Account<'info, ReserveAttestation>,
+    #[account(
+        init,
+        payer = attestor,
+        space = 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 8,
+        seeds = [b"reserve_report", reserve_attestation.key().as_ref(), &Clock::get()?.epoch.to_le_bytes()],
+        bump
+    )]
+    pub reserve_report: Account<'info, ReserveReport>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddToBlacklist<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8, seeds = [b"blacklist", wallet.as_ref()], bump)]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromBlacklist<'info> {
+    #[account(mut, close = governance_authority, seeds = [b"blacklist", blacklist_entry.wallet.as_ref()], bump)]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWrapperVault<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 32 + 8 + 16 + 8 + 1)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    /// CHECK: PDA that owns the vault and mints the wrapped token
+    #[account(seeds = [b"wrapper_authority", wrapper_vault.key().as_ref()], bump)]
+    pub wrapper_authority: UncheckedAccount<'info>,
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, mint::decimals = stablecoin_mint.decimals, mint::authority = wrapper_authority)]
+    pub wrapped_mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, token::mint = stablecoin_mint, token::authority = wrapper_authority)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSavingsRate<'info> {
+    #[account(mut)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WrapStablecoin<'info> {
+    #[account(mut, has_one = vault, has_one = wrapped_mint)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    /// CHECK: PDA that owns the vault and mints the wrapped token
+    #[account(seeds = [b"wrapper_authority", wrapper_vault.key().as_ref()], bump = wrapper_vault.authority_bump)]
+    pub wrapper_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub wrapped_mint: Account<'info, Mint>,
+    #[account(address = wrapper_vault.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    /// User's stablecoin ATA, created on a first unwrap
+    #[account(init_if_needed, payer = user, associated_token::mint = stablecoin_mint, associated_token::authority = user)]
+    pub user_stablecoin_account: Account<'info, TokenAccount>,
+    /// User's wrapped token ATA, created on a first wrap
+    #[account(init_if_needed, payer = user, associated_token::mint = wrapped_mint, associated_token::authority = user)]
+    pub user_wrapped_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundWrapperYield<'info> {
+    #[account(has_one = vault)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = wrapper_vault.stablecoin_mint,
+        token::authority = funder,
+        constraint = funder_stablecoin_account.key() != vault.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub funder_stablecoin_account: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateBondMarket<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 * 4 + 8 * 4 + 8 + 8 + 8 + 8 + 1 + 8)]
+    pub bond_market: Account<'info, BondMarket>,
+    /// CHECK: PDA that owns the payout vault
+    #[account(seeds = [b"bond_authority", bond_market.key().as_ref()], bump)]
+    pub bond_authority: UncheckedAccount<'info>,
+    pub quote_mint: Account<'info, Mint>,
+    pub payout_mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, token::mint = payout_mint, token::authority = bond_authority)]
+    pub payout_vault: Account<'info, TokenAccount>,
+    #[account(token::mint = quote_mint)]
+    pub pol_account: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseBond<'info> {
+    #[account(mut, has_one = pol_account, has_one = payout_vault)]
+    pub bond_market: Account<'info, BondMarket>,
+    #[account(init, payer = buyer, space = 8 + 32 + 32 + 8 + 8 + 8 + 8)]
+    pub bond_position: Account<'info, BondPosition>,
+    #[account(
+        mut,
+        token::mint = bond_market.quote_mint,
+        token::authority = buyer,
+        constraint = buyer_quote_account.key() != pol_account.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub buyer_quote_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub pol_account: Account<'info, TokenAccount>,
+    pub payout_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemBond<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized, has_one = bond_market)]
+    pub bond_position: Account<'info, BondPosition>,
+    #[account(mut, has_one = payout_vault)]
+    pub bond_market: Account<'info, BondMarket>,
+    /// CHECK: PDA that owns the payout vault
+    #[account(seeds = [b"bond_authority", bond_market.key().as_ref()], bump = bond_market.authority_bump)]
+    pub bond_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payout_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = bond_market.payout_mint,
+        constraint = owner_payout_account.key() != payout_vault.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub owner_payout_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], max_num_nodes: u64)]
+pub struct CreateAirdropDistributor<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 4 + ((max_num_nodes as usize + 7) / 8)
+    )]
+    pub distributor: Account<'info, AirdropDistributor>,
+    /// CHECK: PDA that owns the airdrop vault
+    #[account(seeds = [b"airdrop_authority", distributor.key().as_ref()], bump)]
+    pub airdrop_authority: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, token::mint = mint, token::authority = airdrop_authority)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut, has_one = vault)]
+    pub distributor: Account<'info, AirdropDistributor>,
+    /// CHECK: PDA that owns the airdrop vault
+    #[account(seeds = [b"airdrop_authority", distributor.key().as_ref()], bump = distributor.authority_bump)]
+    pub airdrop_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = distributor.mint, token::authority = claimant)]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+    pub claimant: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVestingStream<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1)]
+    pub stream: Account<'info, VestingStream>,
+    /// CHECK: PDA that owns the stream escrow
+    #[account(seeds = [b"stream_authority", stream.key().as_ref()], bump)]
+    pub stream_authority: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, token::mint = mint, token::authority = stream_authority)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = governance_authority)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    #[account(mut, has_one = recipient @ ErrorCode::Unauthorized, has_one = escrow)]
+    pub stream: Account<'info, VestingStream>,
+    /// CHECK: PDA that owns the stream escrow
+    #[account(seeds = [b"stream_authority", stream.key().as_ref()], bump = stream.authority_bump)]
+    pub stream_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = stream.mint,
+        constraint = recipient_token_account.key() != escrow.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetHealthBands<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshPositionHealth<'info> {
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+    pub system_state: Account<'info, SystemState>,
+    /// Collateral type of the position, for its liquidation threshold
+    #[account(address = user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+    #[account(constraint = price_cache.collateral_type == user_account.collateral_type @ ErrorCode::InvalidOracleAccount)]
+    pub price_cache: Option<Account<'info, PriceCache>>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracleConfig<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    /// CHECK: Price feed account the collateral is valued with
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOraclePrograms<'info> {
+    #[account(
+        mut,
+        seeds = [b"system_state"],
+        bump = system_state.bump,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRewardPool<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 8 + 8 + 8 + 16 + 8 + 32 + 8 + 8 + 32 + 1 + 8 + 16 + 32 + 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 8)]
+    pub reward_pool: Account<'info, RewardPool>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovToken<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8 + 8 + 8 + 1, seeds = [b"gov_token"], bump)]
+    pub gov_token: Account<'info, GovToken>,
+    /// CHECK: PDA holding mint authority over the governance token
+    #[account(seeds = [b"gov_token_authority"], bump)]
+    pub gov_token_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = governance_authority,
+        seeds = [b"gov_token_mint"],
+        bump,
+        mint::decimals = GOV_TOKEN_DECIMALS,
+        mint::authority = gov_token_authority
+    )]
+    pub gov_token_mint: Account<'info, Mint>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintGovEmissions<'info> {
+    #[account(mut, seeds = [b"gov_token"], bump, has_one = mint)]
+    pub gov_token: Account<'info, GovToken>,
+    /// CHECK: PDA holding mint authority over the governance token
+    #[account(seeds = [b"gov_token_authority"], bump = gov_token.authority_bump)]
+    pub gov_token_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRiskTiers<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 4 * (8 * 5), seeds = [b"risk_tiers"], bump)]
+    pub risk_tier_config: Account<'info, RiskTierConfig>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRiskTierParams<'info> {
+    #[account(mut, seeds = [b"risk_tiers"], bump)]
+    pub risk_tier_config: Account<'info, RiskTierConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OnboardCollateral<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 9 + 9 + 16 + 8 + 8 + 8 + 8 + 32 + 1 + 32)]
+    pub collateral_type: Account<'info, CollateralType>,
+    pub collateral_mint: Account<'info, Mint>,
+    /// CHECK: Price feed account the collateral is valued with, parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"risk_tiers"], bump)]
+    pub risk_tier_config: Account<'info, RiskTierConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolStats<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetConcentrationLimit<'info> {
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Receives the bond refund, pinned to the proposal's proposer
+    #[account(mut, address = proposal.proposer @ ErrorCode::Unauthorized)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidationBonusCurve<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageConfigFreeze<'info> {
+    #[account(mut, has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageLaunchMode<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimLimits<'info> {
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStakingPool<'info> {
+    #[account(mut, constraint = reward_pool.staking_vault == Pubkey::default() @ ErrorCode::AlreadyInitialized)]
+    pub reward_pool: Account<'info, RewardPool>,
+    pub staking_mint: Account<'info, Mint>,
+    /// CHECK: PDA owning the staking vault
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = governance_authority,
+        associated_token::mint = staking_mint,
+        associated_token::authority = staking_authority
+    )]
+    pub staking_pool: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLiquidationRevenue<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidationRevenueShares<'info> {
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ValidateCollateralParams<'info> {
+    pub collateral_mint: Account<'info, Mint>,
+    /// CHECK: Candidate price feed, only parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct BatchStake<'info> {
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    pub custodian: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BatchClaim<'info> {
+    #[account(mut, constraint = reward_pool.reward_source == RewardSource::Emissions @ ErrorCode::RewardSourceMismatch)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, address = reward_pool.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_token_mint: Account<'info, Mint>,
+    #[account(mut, seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    /// CHECK: PDA holding mint authority over the reward mint
+    #[account(seeds = [b"reward_mint_authority", reward_pool.key().as_ref()], bump = emission_schedule.authority_bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePriceCache<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8,
+        seeds = [b"price_cache", collateral_type.key().as_ref()],
+        bump
+    )]
+    pub price_cache: Account<'info, PriceCache>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshPrice<'info> {
+    #[account(mut, seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    pub collateral_type: Account<'info, CollateralType>,
+    /// CHECK: Validated against the collateral type and parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceOverride<'info> {
+    #[account(mut, seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidationCooldown<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOnboardingRatio<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetProtocolConfig<'info> {
+    pub governance: Account<'info, Governance>,
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGuardianSet<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 4 + 32 * MAX_GUARDIANS + 1 + 8 + 8 + 1,
+        seeds = [b"guardian_set"],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardianSet<'info> {
+    #[account(mut, seeds = [b"guardian_set"], bump = guardian_set.bump)]
+    pub guardian_set: Account<'info, GuardianSet>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeEmergencyAction<'info> {
+    #[account(mut, seeds = [b"guardian_set"], bump = guardian_set.bump)]
+    pub guardian_set: Account<'info, GuardianSet>,
+    #[account(
+        init,
+        payer = guardian,
+        space = 8 + 8 + 1 + 32 + 8 + 2 + 8 + 1,
+        seeds = [b"emergency_action", guardian_set.next_action_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub emergency_action: Account<'info, EmergencyAction>,
+    #[account(mut)]
+    pub system_state: Account<'info, SystemState>,
+    /// Follow-up proposal opened at the next incident nonce; required when the action declares an incident
+    #[account(
+        init,
+        payer = guardian,
+        space = Proposal::SPACE,
+        seeds = [b"incident_proposal", system_state.next_incident_nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub follow_up_proposal: Option<Account<'info, Proposal>>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(seeds = [b"governance_config", governance.key().as_ref()], bump = governance_config.bump)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveEmergencyAction<'info> {
+    #[account(seeds = [b"guardian_set"], bump = guardian_set.bump)]
+    pub guardian_set: Account<'info, GuardianSet>,
+    #[account(mut)]
+    pub emergency_action: Account<'info, EmergencyAction>,
+    #[account(mut)]
+    pub system_state: Account<'info, SystemState>,
+    /// Follow-up proposal opened at the next incident nonce; required when this approval declares an incident
+    #[account(
+        init,
+        payer = guardian,
+        space = Proposal::SPACE,
+        seeds = [b"incident_proposal", system_state.next_incident_nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub follow_up_proposal: Option<Account<'info, Proposal>>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(seeds = [b"governance_config", governance.key().as_ref()], bump = governance_config.bump)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEmergencyAction<'info> {
+    #[account(mut, close = proposer, has_one = proposer @ ErrorCode::Unauthorized)]
+    pub emergency_action: Account<'info, EmergencyAction>,
+    /// CHECK: Receives the rent of the closed action, pinned by the action
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRateSnapshots<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + (8 + 8 + 16 + 16 + 8) * RATE_SNAPSHOT_CAPACITY + 2 + 2 + 16 + 8 + 8,
+        seeds = [b"rate_snapshots"],
+        bump
+    )]
+    pub rate_snapshots: Box<Account<'info, RateSnapshots>>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordRateSnapshot<'info> {
+    #[account(mut, seeds = [b"rate_snapshots"], bump)]
+    pub rate_snapshots: Box<Account<'info, RateSnapshots>>,
+    #[account(seeds = [b"crank_schedule"], bump = crank_schedule.bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+    #[account(mut)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRateController<'info> {
+    #[account(init, payer = governance_authority, space = 8 + (8 + 8 + 8 + 8) + 8 + 8 + 1, seeds = [b"rate_controller"], bump)]
+    pub rate_controller: Account<'info, RateController>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRateControllerParams<'info> {
+    #[account(mut, seeds = [b"rate_controller"], bump = rate_controller.bump)]
+    pub rate_controller: Account<'info, RateController>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStabilityFee<'info> {
+    #[account(mut, seeds = [b"rate_controller"], bump = rate_controller.bump)]
+    pub rate_controller: Account<'info, RateController>,
+    #[account(mut, seeds = [b"rate_snapshots"], bump)]
+    pub rate_snapshots: Box<Account<'info, RateSnapshots>>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct NormalizeNegativeStabilityFee<'info> {
+    #[account(mut, seeds = [b"rate_snapshots"], bump)]
+    pub rate_snapshots: Box<Account<'info, RateSnapshots>>,
+    #[account(seeds = [b"rate_controller"], bump = rate_controller.bump)]
+    pub rate_controller: Account<'info, RateController>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(mut, seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePermitNonce<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"permit_nonce", user.key().as_ref()],
+        bump
+    )]
+    pub permit_nonce: Account<'info, PermitNonce>,
+    /// CHECK: Wallet the nonce account is created for, does not need to sign
+    pub user: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Relayed wrap or unwrap authorized by an ed25519-signed permit.
+///
+/// The user's token accounts must have approved the wrapper authority as
+/// delegate, so the relayer never needs the user's signature on-chain.
+#[derive(Accounts)]
+pub struct PermitWrapStablecoin<'info> {
+    #[account(mut, has_one = vault, has_one = wrapped_mint)]
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    /// CHECK: PDA that owns the vault, mints the wrapped token and acts as delegate
+    #[account(seeds = [b"wrapper_authority", wrapper_vault.key().as_ref()], bump = wrapper_vault.authority_bump)]
+    pub wrapper_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub wrapped_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = wrapper_vault.stablecoin_mint, token::authority = user)]
+    pub user_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = wrapped_mint, token::authority = user)]
+    pub user_wrapped_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"permit_nonce", user.key().as_ref()], bump = permit_nonce.bump)]
+    pub permit_nonce: Account<'info, PermitNonce>,
+    /// CHECK: Wallet that signed the permit, verified against the ed25519 instruction
+    pub user: UncheckedAccount<'info>,
+    pub relayer: Signer<'info>,
+    /// CHECK: Instructions sysvar, used to find the ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Relayed mint against an isolated position, authorized by an ed25519-signed permit.
+#[derive(Accounts)]
+pub struct PermitMintStablecoin<'info> {
+    #[account(mut, constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = user)]
+    pub user_stablecoin_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, token::mint = stablecoin_mint, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump, constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(mut, seeds = [b"permit_nonce", user.key().as_ref()], bump = permit_nonce.bump)]
+    pub permit_nonce: Account<'info, PermitNonce>,
+    /// CHECK: Wallet that signed the permit, verified against the ed25519 instruction
+    pub user: UncheckedAccount<'info>,
+    pub relayer: Signer<'info>,
+    /// CHECK: Instructions sysvar, used to find the ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Relayed redemption request, authorized by an ed25519-signed permit.
+///
+/// The user's stablecoin account must have approved the treasury authority
+/// as delegate.
+#[derive(Accounts)]
+#[instruction(amount: u64, lane: RedemptionLane, request_id: u64)]
+pub struct PermitRequestRedemption<'info> {
+    #[account(mut, seeds = [b"redemption_queue", treasury_reserves.key().as_ref()], bump = redemption_queue.bump)]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + 32 + 32 + 1 + 8 + 32 + 8 + 8 + 1 + 8 + 1,
+        seeds = [b"redemption_request", redemption_queue.key().as_ref(), &[lane as u8], &request_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the escrow vault and acts as the user's delegate
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, address = redemption_queue.escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = user)]
+    pub user_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(address = treasury_reserves.reserve_mint @ ErrorCode::InvalidMint)]
+    pub reserve_mint: Account<'info, Mint>,
+    /// User's reserve asset ATA receiving the fills, created on a first request
+    #[account(init_if_needed, payer = relayer, associated_token::mint = reserve_mint, associated_token::authority = user)]
+    pub user_reserve_account: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(mut, seeds = [b"permit_nonce", user.key().as_ref()], bump = permit_nonce.bump)]
+    pub permit_nonce: Account<'info, PermitNonce>,
+    /// User's position, whose fee credits are spent on the redemption fee when passed
+    #[account(mut, constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Option<Account<'info, UserAccount>>,
+    /// CHECK: Wallet that signed the permit, verified against the ed25519 instruction
+    pub user: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    /// CHECK: Instructions sysvar, used to find the ed25519 signature instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCampaign<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 33 + 32 + 32 + 8 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 1 + 1 + 8)]
+    pub campaign: Account<'info, Campaign>,
+    /// CHECK: PDA owning the campaign reward vault
+    #[account(seeds = [b"campaign_authority", campaign.key().as_ref()], bump)]
+    pub campaign_authority: UncheckedAccount<'info>,
+    pub reward_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = governance_authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = campaign_authority
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundCampaign<'info> {
+    #[account(mut, has_one = reward_vault @ ErrorCode::InvalidAccountData, constraint = !campaign.terminated @ ErrorCode::CampaignTerminated)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = campaign.reward_mint, token::authority = funder)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct JoinCampaign<'info> {
+    #[account(mut, constraint = !campaign.terminated @ ErrorCode::CampaignTerminated)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 32 + 8 + 16 + 8 + 1,
+        seeds = [b"campaign_position", campaign.key().as_ref(), position_account.key().as_ref()],
+        bump
+    )]
+    pub campaign_position: Account<'info, CampaignPosition>,
+    /// CHECK: Staker or user account providing the weight, validated in the handler against the campaign and owner
+    pub position_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncCampaignPosition<'info> {
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        has_one = campaign,
+        seeds = [b"campaign_position", campaign.key().as_ref(), position_account.key().as_ref()],
+        bump = campaign_position.bump
+    )]
+    pub campaign_position: Account<'info, CampaignPosition>,
+    /// CHECK: Staker or user account the position was opened for, pinned by the PDA seeds
+    pub position_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCampaignRewards<'info> {
+    #[account(mut, has_one = reward_vault @ ErrorCode::InvalidAccountData)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        has_one = campaign,
+        has_one = owner @ ErrorCode::Unauthorized,
+        seeds = [b"campaign_position", campaign.key().as_ref(), position_account.key().as_ref()],
+        bump = campaign_position.bump
+    )]
+    pub campaign_position: Account<'info, CampaignPosition>,
+    /// CHECK: Staker or user account the position was opened for, pinned by the PDA seeds
+    pub position_account: UncheckedAccount<'info>,
+    /// CHECK: PDA owning the campaign reward vault
+    #[account(seeds = [b"campaign_authority", campaign.key().as_ref()], bump = campaign.authority_bump)]
+    pub campaign_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(address = campaign.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_mint: Account<'info, Mint>,
+    /// Owner's reward ATA, created on a first claim
+    #[account(init_if_needed, payer = owner, associated_token::mint = reward_mint, associated_token::authority = owner)]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TerminateCampaign<'info> {
+    #[account(mut, has_one = reward_vault @ ErrorCode::InvalidAccountData, constraint = !campaign.terminated @ ErrorCode::CampaignTerminated)]
+    pub campaign: Account<'info, Campaign>,
+    /// CHECK: PDA owning the campaign reward vault
+    #[account(seeds = [b"campaign_authority", campaign.key().as_ref()], bump = campaign.authority_bump)]
+    pub campaign_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = campaign.reward_mint)]
+    pub refund_account: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRates<'info> {
+    #[account(
+        mut,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralFeeOverrides<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewFee<'info> {
+    pub system_state: Account<'info, SystemState>,
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseFeeCredits<'info> {
+    #[account(mut, constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = owner)]
+    pub owner_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    pub system_state: Account<'info, SystemState>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePosition<'info> {
+    /// CHECK: v1 user account, decoded and closed in the handler. Its keypair
+    /// must sign, since v1 positions record no owner
+    #[account(mut, signer, owner = crate::ID @ ErrorCode::InvalidAccountData)]
+    pub user_account: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 1 + 32 + 8 + 8 + 8 + 16 + 16 + 8 + 1,
+        seeds = [b"position", user_account.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, PositionV2>,
+    #[account(seeds = [b"rate_snapshots"], bump)]
+    pub rate_snapshots: Box<Account<'info, RateSnapshots>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AssertInvariants<'info> {
+    #[account(address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    pub system_state: Account<'info, SystemState>,
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+    pub staking_vault: Option<Account<'info, TokenAccount>>,
+    pub wrapper_vault: Option<Account<'info, WrapperVault>>,
+    pub wrapper_token_vault: Option<Account<'info, TokenAccount>>,
+    pub wrapped_mint: Option<Account<'info, Mint>>,
+    pub stake_pool_config: Option<Account<'info, StakePoolConfig>>,
+    pub lst_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePegState<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1, seeds = [b"peg_state"], bump)]
+    pub peg_state: Account<'info, PegState>,
+    /// CHECK: Oracle feed of the stablecoin's market price, parsed when the TWAP is updated
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPegBand<'info> {
+    #[account(mut, seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePegTwap<'info> {
+    #[account(mut, seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(seeds = [b"crank_schedule"], bump = crank_schedule.bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+    /// CHECK: Checked against the peg state and parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralYieldConfig<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVeConfig<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 8 + 1 + 1, seeds = [b"ve_config"], bump)]
+    pub ve_config: Account<'info, VeConfig>,
+    /// CHECK: PDA owning the ve escrow vault
+    #[account(seeds = [b"ve_escrow_authority"], bump)]
+    pub escrow_authority: UncheckedAccount<'info>,
+    #[account(seeds = [b"gov_token_mint"], bump)]
+    pub gov_token_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = governance_authority,
+        associated_token::mint = gov_token_mint,
+        associated_token::authority = escrow_authority
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVeLock<'info> {
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump, has_one = escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub ve_config: Account<'info, VeConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 8 + 8 + 8 + 32 + 1,
+        seeds = [b"ve_lock", owner.key().as_ref()],
+        bump
+    )]
+    pub ve_lock: Account<'info, VeLock>,
+    #[account(mut)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow_vault.mint, token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVeLock<'info> {
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump, has_one = escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub ve_config: Account<'info, VeConfig>,
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized, seeds = [b"ve_lock", owner.key().as_ref()], bump = ve_lock.bump, close = owner)]
+    pub ve_lock: Account<'info, VeLock>,
+    /// CHECK: PDA owning the ve escrow vault
+    #[account(seeds = [b"ve_escrow_authority"], bump = ve_config.escrow_authority_bump)]
+    pub escrow_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(address = escrow_vault.mint @ ErrorCode::InvalidMint)]
+    pub locked_mint: Account<'info, Mint>,
+    /// Owner's ATA receiving the unlocked tokens, created if it was closed since locking
+    #[account(init_if_needed, payer = owner, associated_token::mint = locked_mint, associated_token::authority = owner)]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyBoost<'info> {
+    #[account(seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Account<'info, VeConfig>,
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized, seeds = [b"ve_lock", owner.key().as_ref()], bump = ve_lock.bump)]
+    pub ve_lock: Account<'info, VeLock>,
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized, has_one = reward_pool @ ErrorCode::InvalidAccountData)]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct KickBoost<'info> {
+    #[account(mut, seeds = [b"ve_config"], bump = ve_config.bump)]
+    pub ve_config: Account<'info, VeConfig>,
+    #[account(mut, seeds = [b"ve_lock", ve_lock.owner.as_ref()], bump = ve_lock.bump)]
+    pub ve_lock: Account<'info, VeLock>,
+    #[account(mut, address = ve_lock.boosted_staker @ ErrorCode::InvalidAccountData, has_one = reward_pool @ ErrorCode::InvalidAccountData)]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_owner: Pubkey)]
+pub struct TransferStakeOwnership<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized)]
+    pub staker_account: Account<'info, StakerAccount>,
+    /// Owner's ve lock, closed once moved; required if it boosts the staker
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized, seeds = [b"ve_lock", owner.key().as_ref()], bump = ve_lock.bump, close = owner)]
+    pub ve_lock: Option<Account<'info, VeLock>>,
+    /// The ve lock re-keyed to the new owner, given together with `ve_lock`
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 8 + 8 + 8 + 32 + 1,
+        seeds = [b"ve_lock", new_owner.as_ref()],
+        bump
+    )]
+    pub new_ve_lock: Option<Account<'info, VeLock>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreateSubaccount<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 32 + 8 + 16 + 32 + 2 + 32 + 8 + 8 + 8,
+        seeds = [b"subaccount", owner.key().as_ref(), collateral_type.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarginAccount<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 1 + 4 + 32 * MAX_CROSS_POSITIONS + 1,
+        seeds = [b"margin_account", owner.key().as_ref()],
+        bump
+    )]
+    pub margin_account: Account<'info, MarginAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMarginMode<'info> {
+    #[account(mut, seeds = [b"margin_account", owner.key().as_ref()], bump = margin_account.bump)]
+    pub margin_account: Account<'info, MarginAccount>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCrossPosition<'info> {
+    #[account(mut, seeds = [b"margin_account", owner.key().as_ref()], bump = margin_account.bump)]
+    pub margin_account: Account<'info, MarginAccount>,
+    #[account(mut, constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(constraint = collateral_type.key() == user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Option<Account<'info, CollateralType>>,
+    #[account(constraint = price_cache.collateral_type == user_account.collateral_type @ ErrorCode::InvalidOracleAccount)]
+    pub price_cache: Option<Account<'info, PriceCache>>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewPortfolioHealth<'info> {
+    pub margin_account: Account<'info, MarginAccount>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePortfolioHealth<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 32 + 16 + 16 + 8 + 8 + 32 + 8 + 1,
+        seeds = [b"portfolio_health", margin_account.key().as_ref()],
+        bump
+    )]
+    pub portfolio_health: Account<'info, PortfolioHealthCache>,
+    pub margin_account: Account<'info, MarginAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct RefreshPortfolio<'info> {
+    #[account(
+        mut,
+        seeds = [b"portfolio_health", margin_account.key().as_ref()],
+        bump = portfolio_health.bump,
+        has_one = margin_account @ ErrorCode::InvalidMarginAccount
+    )]
+    pub portfolio_health: Account<'info, PortfolioHealthCache>,
+    #[account(seeds = [b"margin_account", user.as_ref()], bump = margin_account.bump)]
+    pub margin_account: Account<'info, MarginAccount>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePauseState<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 1 + 1 + 1 + 1 + 1 + 1 + 32 + 8 + 1, seeds = [b"pause_state"], bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseState<'info> {
+    #[account(mut, seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    /// Pausing must stay possible while the configuration is frozen
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCollateralMintingPaused<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    /// Pausing must stay possible while the configuration is frozen
+    #[account(mut, seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    /// Lets a single guardian pause without waiting for governance
+    #[account(seeds = [b"guardian_set"], bump = guardian_set.bump)]
+    pub guardian_set: Option<Account<'info, GuardianSet>>,
+    /// Follow-up proposal opened at the next incident nonce; required when this call pauses minting
+    #[account(
+        init,
+        payer = authority,
+        space = Proposal::SPACE,
+        seeds = [b"incident_proposal", system_state.next_incident_nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub follow_up_proposal: Option<Account<'info, Proposal>>,
+    #[account(seeds = [b"governance"], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(seeds = [b"governance_config", governance.key().as_ref()], bump = governance_config.bump)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequireNotPaused<'info> {
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAuctionParams<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 32 + 8,
+        seeds = [b"auction_params", collateral_type.key().as_ref()],
+        bump
+    )]
+    pub auction_params: Account<'info, AuctionParams>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuctionParams<'info> {
+    #[account(mut, seeds = [b"auction_params", auction_params.collateral_type.as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuctionSettlementMint<'info> {
+    #[account(mut, seeds = [b"auction_params", auction_params.collateral_type.as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    /// Reserves whose reserve asset settles new auctions; omit to settle in the stablecoin
+    pub treasury_reserves: Option<Account<'info, TreasuryReserves>>,
+    /// Reserve asset mint, required with the reserves and checked against them in the handler
+    pub reserve_mint: Option<Account<'info, Mint>>,
+    #[account(address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartCollateralAuction<'info> {
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 32 + 32,
+        seeds = [b"collateral_auction", user_account.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, CollateralAuction>,
+    #[account(seeds = [b"auction_params", collateral_type.key().as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    #[account(mut, constraint = user_account.collateral_type == collateral_type.key() @ ErrorCode::InvalidCollateralType)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    #[account(address = user_account.margin_account @ ErrorCode::InvalidMarginAccount)]
+    pub margin_account: Option<Account<'info, MarginAccount>>,
+    /// Series tracking the rest of the collateral; required when it exceeds the lot cap
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1,
+        seeds = [b"auction_series", user_account.key().as_ref()],
+        bump
+    )]
+    pub auction_series: Option<Account<'info, AuctionSeries>>,
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCollateralAuction<'info> {
+    #[account(
+        mut,
+        close = keeper,
+        seeds = [b"collateral_auction", auction.position.as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, CollateralAuction>,
+    #[account(seeds = [b"auction_params", collateral_type.key().as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    #[account(mut, address = auction.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// Position of the buyer credited with the lot's collateral
+    #[account(
+        mut,
+        constraint = buyer_position.owner == buyer.key() @ ErrorCode::Unauthorized,
+        constraint = buyer_position.collateral_type == auction.collateral_type @ ErrorCode::InvalidCollateralType
+    )]
+    pub buyer_position: Account<'info, UserAccount>,
+    /// Opt-in ledger of the buyer's position
+    #[account(mut, seeds = [b"position_ledger", buyer_position.key().as_ref()], bump = buyer_ledger.bump)]
+    pub buyer_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    /// Required when the lot is paid in the stablecoin
+    #[account(mut, token::mint = stablecoin_mint, token::authority = buyer)]
+    pub buyer_stablecoin_account: Option<Account<'info, TokenAccount>>,
+    /// Required when the lot is paid in a reserve asset; the vault and payer are checked in the handler
+    #[account(mut, address = auction.settlement_reserves @ ErrorCode::InvalidAccountData)]
+    pub treasury_reserves: Option<Account<'info, TreasuryReserves>>,
+    #[account(mut)]
+    pub reserve_vault: Option<Account<'info, TokenAccount>>,
+    #[account(mut, token::authority = buyer)]
+    pub buyer_reserve_account: Option<Account<'info, TokenAccount>>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    pub system_state: Account<'info, SystemState>,
+    /// CHECK: Receives the auction account's rent
+    #[account(mut, address = auction.keeper @ ErrorCode::Unauthorized)]
+    pub keeper: UncheckedAccount<'info>,
+    /// Winning sealed bid, required to settle a sealed-bid auction; its bond is refunded to the buyer
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", auction.key().as_ref(), buyer.key().as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Option<Account<'info, SealedBid>>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 1,
+        seeds = [b"sealed_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    #[account(seeds = [b"collateral_auction", auction.position.as_ref()], bump = auction.bump)]
+    pub auction: Account<'info, CollateralAuction>,
+    #[account(seeds = [b"auction_params", auction.collateral_type.as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump,
+        has_one = auction
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    #[account(mut, seeds = [b"collateral_auction", auction.position.as_ref()], bump = auction.bump)]
+    pub auction: Account<'info, CollateralAuction>,
+    /// Current best bid, required when this reveal outbids it so its bond is refunded
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", auction.key().as_ref(), auction.best_bidder.as_ref()],
+        bump = previous_best_bid.bump
+    )]
+    pub previous_best_bid: Option<Account<'info, SealedBid>>,
+    /// CHECK: Bidder of the current best bid, receives its refunded bond
+    #[account(mut, address = auction.best_bidder @ ErrorCode::Unauthorized)]
+    pub previous_best_bidder: Option<UncheckedAccount<'info>>,
+    /// Stablecoin the bidder must hold to cover the revealed bid
+    #[account(
+        token::authority = bidder,
+        constraint = bidder_stablecoin_account.mint == system_state.stablecoin_mint @ ErrorCode::InvalidMint
+    )]
+    pub bidder_stablecoin_account: Account<'info, TokenAccount>,
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SlashSealedBid<'info> {
+    #[account(mut)]
+    pub sealed_bid: Account<'info, SealedBid>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    /// CHECK: Receives the slashed bond and the bid account's rent
+    #[account(mut, address = system_state.governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub governance_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSwapSchedule<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1)]
+    pub swap_schedule: Account<'info, SwapSchedule>,
+    /// CHECK: PDA owning the schedule's stablecoin escrow and signing swap CPIs
+    #[account(seeds = [b"swap_authority", swap_schedule.key().as_ref()], bump)]
+    pub swap_authority: UncheckedAccount<'info>,
+    #[account(address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = governance_authority,
+        associated_token::mint = stablecoin_mint,
+        associated_token::authority = swap_authority
+    )]
+    pub source_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    pub destination_vault: Account<'info, TokenAccount>,
+    /// CHECK: Executable program implementing the swap adapter interface
+    #[account(executable)]
+    pub swap_program: UncheckedAccount<'info>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    /// Authority of the treasury account funding the schedule
+    pub treasury_authority: Signer<'info>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSwapSlice<'info> {
+    #[account(
+        mut,
+        has_one = swap_program @ ErrorCode::InvalidAccountData,
+        has_one = source_vault @ ErrorCode::InvalidAccountData,
+        has_one = destination_vault @ ErrorCode::InvalidAccountData,
+        constraint = !swap_schedule.cancelled @ ErrorCode::SwapScheduleClosed
+    )]
+    pub swap_schedule: Account<'info, SwapSchedule>,
+    /// CHECK: PDA owning the escrow, signs the swap CPI
+    #[account(seeds = [b"swap_authority", swap_schedule.key().as_ref()], bump = swap_schedule.authority_bump)]
+    pub swap_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination_vault: Account<'info, TokenAccount>,
+    /// CHECK: Swap adapter pinned by the schedule
+    #[account(executable)]
+    pub swap_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub system_state: Account<'info, SystemState>,
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapSchedule<'info> {
+    #[account(
+        mut,
+        has_one = source_vault @ ErrorCode::InvalidAccountData,
+        constraint = !swap_schedule.cancelled @ ErrorCode::SwapScheduleClosed
+    )]
+    pub swap_schedule: Account<'info, SwapSchedule>,
+    /// CHECK: PDA owning the escrow
+    #[account(seeds = [b"swap_authority", swap_schedule.key().as_ref()], bump = swap_schedule.authority_bump)]
+    pub swap_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfidencePremium<'info> {
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeBadDebtQueue<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 4 + MAX_BAD_DEBT_ENTRIES * (8 + 32 + 8 + 1) + 8 + 8 + 1 + 32,
+        seeds = [b"bad_debt_queue"],
+        bump
+    )]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDebtAuctionPrice<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBadDebtSlashingPool<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(constraint = reward_pool.system_state == system_state.key() @ ErrorCode::InvalidAccountData)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        seeds = [b"system_state"],
+        bump = system_state.bump,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AbsorbBadDebt<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+#[derive(Accounts)]
+pub struct SlashStakersForBadDebt<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, address = bad_debt_queue.slashing_pool @ ErrorCode::InvalidAccountData)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    /// CHECK: PDA owning the staking vault, signs the slashing burn
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TakeDebtAuction<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, seeds = [b"gov_token"], bump, has_one = mint)]
+    pub gov_token: Account<'info, GovToken>,
+    /// CHECK: PDA holding mint authority over the governance token
+    #[account(seeds = [b"gov_token_authority"], bump = gov_token.authority_bump)]
+    pub gov_token_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub buyer_gov_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = buyer)]
+    pub buyer_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"gov_token_oracle"], bump = gov_token_oracle.bump)]
+    pub gov_token_oracle: Account<'info, GovTokenOracle>,
+    /// CHECK: Pyth feed of the governance token, required if listed; checked by the governance token oracle
+    pub gov_price_feed: Option<UncheckedAccount<'info>>,
+    pub system_state: Account<'info, SystemState>,
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TreasuryRepayBadDebt<'info> {
+    #[account(mut, seeds = [b"bad_debt_queue"], bump = bad_debt_queue.bump)]
+    pub bad_debt_queue: Account<'info, BadDebtQueue>,
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA the treasury approves as delegate, signs the repayment burn
+    #[account(seeds = [b"revenue_authority"], bump)]
+    pub revenue_authority: UncheckedAccount<'info>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovTokenOracle<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"gov_token_oracle"],
+        bump
+    )]
+    pub gov_token_oracle: Account<'info, GovTokenOracle>,
+    #[account(seeds = [b"gov_token"], bump)]
+    pub gov_token: Account<'info, GovToken>,
+    #[account(constraint = gov_vault.mint == gov_token.mint @ ErrorCode::InvalidMint)]
+    pub gov_vault: Account<'info, TokenAccount>,
+    #[account(constraint = quote_vault.mint == system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    /// CHECK: Pyth feed of the governance token, if listed; probed by the oracle module
+    pub pyth_feed: Option<UncheckedAccount<'info>>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGovTokenOracleParams<'info> {
+    #[account(mut, seeds = [b"gov_token_oracle"], bump = gov_token_oracle.bump)]
+    pub gov_token_oracle: Account<'info, GovTokenOracle>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGovTokenFallbackPrice<'info> {
+    #[account(mut, seeds = [b"gov_token_oracle"], bump = gov_token_oracle.bump)]
+    pub gov_token_oracle: Account<'info, GovTokenOracle>,
+    /// The fallback must stay settable while the configuration is frozen
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGovTokenTwap<'info> {
+    #[account(mut, seeds = [b"gov_token_oracle"], bump = gov_token_oracle.bump)]
+    pub gov_token_oracle: Account<'info, GovTokenOracle>,
+    #[account(address = gov_token_oracle.gov_vault @ ErrorCode::InvalidAccountData)]
+    pub gov_vault: Account<'info, TokenAccount>,
+    #[account(address = gov_token_oracle.quote_vault @ ErrorCode::InvalidAccountData)]
+    pub quote_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct AssignDebt<'info> {
+    #[account(init, payer = buyer, space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1)]
+    pub debt_note: Account<'info, DebtNote>,
+    #[account(
+        mut,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = user_account.collateral_type != Pubkey::default() @ ErrorCode::InvalidCollateralType
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(address = user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = owner)]
+    pub owner_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = buyer)]
+    pub buyer_stablecoin_account: Account<'info, TokenAccount>,
+    pub system_state: Account<'info, SystemState>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RepayDebtNote<'info> {
+    #[account(mut, has_one = issuer @ ErrorCode::Unauthorized, constraint = !debt_note.settled @ ErrorCode::DebtNoteSettled)]
+    pub debt_note: Account<'info, DebtNote>,
+    #[account(mut, address = debt_note.position @ ErrorCode::InvalidAccountData)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = issuer)]
+    pub issuer_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = debt_note.holder)]
+    pub holder_stablecoin_account: Account<'info, TokenAccount>,
+    pub system_state: Account<'info, SystemState>,
+    pub issuer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferDebtNote<'info> {
+    #[account(mut, has_one = holder @ ErrorCode::Unauthorized, constraint = !debt_note.settled @ ErrorCode::DebtNoteSettled)]
+    pub debt_note: Account<'info, DebtNote>,
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SeizeNoteCollateral<'info> {
+    #[account(mut, has_one = holder @ ErrorCode::Unauthorized, constraint = !debt_note.settled @ ErrorCode::DebtNoteSettled)]
+    pub debt_note: Account<'info, DebtNote>,
+    #[account(mut, address = debt_note.position @ ErrorCode::InvalidAccountData)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(address = user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// Position of the holder credited with the seized collateral
+    #[account(
+        mut,
+        constraint = holder_position.owner == holder.key() @ ErrorCode::Unauthorized,
+        constraint = holder_position.collateral_type == user_account.collateral_type @ ErrorCode::InvalidCollateralType
+    )]
+    pub holder_position: Account<'info, UserAccount>,
+    /// Opt-in ledger of the holder's position
+    #[account(mut, seeds = [b"position_ledger", holder_position.key().as_ref()], bump = holder_ledger.bump)]
+    pub holder_ledger: Option<Account<'info, PositionLedger>>,
+    pub holder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddLoyaltyCollection<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"loyalty_collection", collection_mint.key().as_ref()],
+        bump
+    )]
+    pub loyalty_collection: Account<'info, LoyaltyCollection>,
+    pub collection_mint: Account<'info, Mint>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLoyaltyDiscount<'info> {
+    #[account(mut, seeds = [b"loyalty_collection", loyalty_collection.collection_mint.as_ref()], bump = loyalty_collection.bump)]
+    pub loyalty_collection: Account<'info, LoyaltyCollection>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLoyaltyCollection<'info> {
+    #[account(
+        mut,
+        close = governance_authority,
+        seeds = [b"loyalty_collection", loyalty_collection.collection_mint.as_ref()],
+        bump = loyalty_collection.bump
+    )]
+    pub loyalty_collection: Account<'info, LoyaltyCollection>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePaymentStream<'info> {
+    #[account(init, payer = sender, space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1)]
+    pub stream: Account<'info, PaymentStream>,
+    /// CHECK: PDA that owns the stream escrow
+    #[account(seeds = [b"payment_stream_authority", stream.key().as_ref()], bump)]
+    pub stream_authority: UncheckedAccount<'info>,
+    #[account(address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(init, payer = sender, token::mint = stablecoin_mint, token::authority = stream_authority)]
+    pub escrow: Account<'info, TokenAccount>,
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpPaymentStream<'info> {
+    #[account(has_one = escrow, constraint = !stream.cancelled @ ErrorCode::StreamCancelled)]
+    pub stream: Account<'info, PaymentStream>,
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow.mint, token::authority = funder)]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromPaymentStream<'info> {
+    #[account(mut, has_one = recipient @ ErrorCode::Unauthorized, has_one = escrow)]
+    pub stream: Account<'info, PaymentStream>,
+    /// CHECK: PDA that owns the stream escrow
+    #[account(seeds = [b"payment_stream_authority", stream.key().as_ref()], bump = stream.authority_bump)]
+    pub stream_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        constraint = recipient_token_account.key() != escrow.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub recipient: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPaymentStream<'info> {
+    #[account(
+        mut,
+        has_one = sender @ ErrorCode::Unauthorized,
+        has_one = escrow,
+        constraint = !stream.cancelled @ ErrorCode::StreamCancelled
+    )]
+    pub stream: Account<'info, PaymentStream>,
+    /// CHECK: PDA that owns the stream escrow
+    #[account(seeds = [b"payment_stream_authority", stream.key().as_ref()], bump = stream.authority_bump)]
+    pub stream_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow.mint, token::authority = stream.recipient)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow.mint, token::authority = sender)]
+    pub sender_token_account: Account<'info, TokenAccount>,
+    pub sender: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeEpochState<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 8 + 8 + 8 + 32 + 8 + 8 + 16 + 8 + 1 + 32, seeds = [b"epoch_state"], bump)]
+    pub epoch_state: Account<'info, EpochState>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEpoch<'info> {
+    #[account(mut, seeds = [b"epoch_state"], bump = epoch_state.bump, has_one = wrapper_vault)]
+    pub epoch_state: Account<'info, EpochState>,
+    #[account(seeds = [b"crank_schedule"], bump = crank_schedule.bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"epoch_report", epoch_state.current_epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch_report: Account<'info, EpochReport>,
+    #[account(seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    pub wrapper_vault: Account<'info, WrapperVault>,
+    #[account(address = wrapper_vault.wrapped_mint @ ErrorCode::InvalidMint)]
+    pub wrapped_mint: Account<'info, Mint>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    /// Revenue reward pool funded with the stakers' share of the epoch's mint fees; required once configured
+    #[account(
+        mut,
+        address = epoch_state.revenue_pool @ ErrorCode::InvalidAccountData,
+        constraint = revenue_pool.reward_source == RewardSource::Revenue @ ErrorCode::RewardSourceMismatch
+    )]
+    pub revenue_pool: Option<Box<Account<'info, RewardPool>>>,
+    /// Reward vault of the revenue pool, checked in the handler
+    #[account(mut)]
+    pub revenue_vault: Option<Account<'info, TokenAccount>>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Option<Account<'info, TokenAccount>>,
+    /// CHECK: PDA the treasury approves as delegate for the stakers' share
+    #[account(seeds = [b"revenue_authority"], bump)]
+    pub revenue_authority: Option<UncheckedAccount<'info>>,
+    pub token_program: Option<Program<'info, Token>>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeEmissionSchedule<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 1,
+        seeds = [b"emission_schedule", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: PDA that must hold mint authority over the reward mint
+    #[account(seeds = [b"reward_mint_authority", reward_pool.key().as_ref()], bump)]
+    pub reward_mint_authority: UncheckedAccount<'info>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmissionBudget<'info> {
+    #[account(mut, seeds = [b"emission_schedule", emission_schedule.reward_pool.as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ScanLiquidatable {}
+
+#[derive(Accounts)]
+pub struct SetRewardDelegate<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized)]
+    pub staker_account: Account<'info, StakerAccount>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    #[account(mut, has_one = owner @ ErrorCode::Unauthorized)]
+    pub staker_account: Account<'info, StakerAccount>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WhitelistStakePool<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1,
+        seeds = [b"stake_pool_config", collateral_type.key().as_ref()],
+        bump
+    )]
+    pub stake_pool_config: Account<'info, StakePoolConfig>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    /// CHECK: Owned by the stake pool program; its pool mint is checked in the handler
+    #[account(owner = stake_pool_program.key() @ ErrorCode::InvalidStakePool)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: Program the stake pool belongs to
+    #[account(executable)]
+    pub stake_pool_program: UncheckedAccount<'info>,
+    /// CHECK: PDA that owns the LST vault
+    #[account(seeds = [b"sol_staking_authority", collateral_type.key().as_ref()], bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(address = collateral_type.collateral_mint @ ErrorCode::InvalidMint)]
+    pub pool_mint: Account<'info, Mint>,
+    #[account(init, payer = governance_authority, token::mint = pool_mint, token::authority = staking_authority)]
+    pub lst_vault: Account<'info, TokenAccount>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakePoolHaircut<'info> {
+    #[account(mut, seeds = [b"stake_pool_config", stake_pool_config.collateral_type.as_ref()], bump = stake_pool_config.bump)]
+    pub stake_pool_config: Account<'info, StakePoolConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSolCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_pool_config", collateral_type.key().as_ref()],
+        bump = stake_pool_config.bump,
+        has_one = collateral_type,
+        has_one = stake_pool,
+        has_one = stake_pool_program,
+        has_one = lst_vault
+    )]
+    pub stake_pool_config: Account<'info, StakePoolConfig>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(mut, constraint = user_account.owner == depositor.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    /// CHECK: Whitelisted stake pool, validated by the stake pool program
+    #[account(mut)]
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: Stake pool withdraw authority, validated by the stake pool program
+    pub stake_pool_withdraw_authority: UncheckedAccount<'info>,
+    /// CHECK: Stake pool reserve stake account, validated by the stake pool program
+    #[account(mut)]
+    pub reserve_stake: UncheckedAccount<'info>,
+    /// CHECK: Stake pool manager fee account, validated by the stake pool program
+    #[account(mut)]
+    pub manager_fee_account: UncheckedAccount<'info>,
+    #[account(mut, address = collateral_type.collateral_mint @ ErrorCode::InvalidMint)]
+    pub pool_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub lst_vault: Account<'info, TokenAccount>,
+    /// CHECK: Whitelisted stake pool program
+    pub stake_pool_program: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshStakePoolPrice<'info> {
+    #[account(
+        seeds = [b"stake_pool_config", collateral_type.key().as_ref()],
+        bump = stake_pool_config.bump,
+        has_one = collateral_type,
+        has_one = stake_pool
+    )]
+    pub stake_pool_config: Account<'info, StakePoolConfig>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(mut, seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// CHECK: Whitelisted stake pool, parsed by the stake pool module
+    pub stake_pool: UncheckedAccount<'info>,
+    /// CHECK: SOL price feed, validated against the collateral type and parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernanceConfig<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 16 * PROPOSAL_ACTION_COUNT + 1,
+        seeds = [b"governance_config", governance.key().as_ref()],
+        bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    pub governance: Account<'info, Governance>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetProposalTiming<'info> {
+    #[account(mut, seeds = [b"governance_config", governance_config.governance.as_ref()], bump = governance_config.bump)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    /// Vetoes must stay possible while the configuration is frozen
+    #[account(
+        seeds = [b"system_state"],
+        bump = system_state.bump,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCrankSchedule<'info> {
+    #[account(init, payer = governance_authority, space = 8 + 8 * CRANK_KIND_COUNT + 1, seeds = [b"crank_schedule"], bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCrankInterval<'info> {
+    #[account(mut, seeds = [b"crank_schedule"], bump = crank_schedule.bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankRewardPool<'info> {
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(seeds = [b"crank_schedule"], bump = crank_schedule.bump)]
+    pub crank_schedule: Account<'info, CrankSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardSource<'info> {
+    #[account(
+        mut,
+        constraint = reward_pool.total_staked == 0 @ ErrorCode::RewardPoolNotEmpty,
+        constraint = reward_pool.sunset_deadline == 0 @ ErrorCode::PoolSunsetting
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: PDA owning the pool's vaults
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    /// Stablecoin vault paying revenue rewards; required for the revenue source
+    #[account(
+        token::mint = system_state.stablecoin_mint,
+        token::authority = staking_authority
+    )]
+    pub reward_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRevenueRewards<'info> {
+    #[account(mut, has_one = reward_pool @ ErrorCode::InvalidAccountData)]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(
+        mut,
+        has_one = reward_vault @ ErrorCode::InvalidAccountData,
+        constraint = reward_pool.reward_source == RewardSource::Revenue @ ErrorCode::RewardSourceMismatch
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: PDA owning the reward vault
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        constraint = is_reward_destination(&staker_account, &destination_reward_account) @ ErrorCode::InvalidAccountOwner
+    )]
+    pub destination_reward_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidatorReimbursement<'info> {
+    #[account(mut, seeds = [b"liquidation_revenue"], bump)]
+    pub liquidation_revenue: Account<'info, LiquidationRevenue>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(capacity: u32)]
+pub struct InitializePositionLedger<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = POSITION_LEDGER_BASE_SPACE + capacity as usize * LEDGER_ENTRY_SPACE,
+        seeds = [b"position_ledger", user_account.key().as_ref()],
+        bump
+    )]
+    pub position_ledger: Account<'info, PositionLedger>,
+    #[account(constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(additional_entries: u32)]
+pub struct ExtendPositionLedger<'info> {
+    #[account(
+        mut,
+        seeds = [b"position_ledger", position_ledger.user_account.as_ref()],
+        bump = position_ledger.bump,
+        realloc = POSITION_LEDGER_BASE_SPACE + (position_ledger.capacity as usize + additional_entries as usize) * LEDGER_ENTRY_SPACE,
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    pub position_ledger: Account<'info, PositionLedger>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExportPositionHistory<'info> {
+    pub position_ledger: Account<'info, PositionLedger>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCeilingController<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + (8 * 6) + 8 + 8 + 8 + 1,
+        seeds = [b"ceiling_controller", collateral_type.key().as_ref()],
+        bump
+    )]
+    pub ceiling_controller: Account<'info, CeilingController>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCeilingController<'info> {
+    #[account(mut, seeds = [b"ceiling_controller", ceiling_controller.collateral_type.as_ref()], bump = ceiling_controller.bump)]
+    pub ceiling_controller: Account<'info, CeilingController>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdjustDebtCeiling<'info> {
+    #[account(
+        mut,
+        seeds = [b"ceiling_controller", collateral_type.key().as_ref()],
+        bump = ceiling_controller.bump,
+        has_one = collateral_type @ ErrorCode::InvalidCollateralType
+    )]
+    pub ceiling_controller: Account<'info, CeilingController>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"epoch_state"], bump = epoch_state.bump)]
+    pub epoch_state: Account<'info, EpochState>,
+    #[account(seeds = [b"peg_state"], bump = peg_state.bump)]
+    pub peg_state: Account<'info, PegState>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump, constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen)]
+    pub system_state: Account<'info, SystemState>,
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartNextAuctionLot<'info> {
+    #[account(
+        init,
+        payer = keeper,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 32 + 32,
+        seeds = [b"collateral_auction", auction_series.position.as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, CollateralAuction>,
+    #[account(
+        mut,
+        seeds = [b"auction_series", auction_series.position.as_ref()],
+        bump = auction_series.bump,
+        has_one = collateral_type @ ErrorCode::InvalidCollateralType
+    )]
+    pub auction_series: Account<'info, AuctionSeries>,
+    #[account(seeds = [b"auction_params", collateral_type.key().as_ref()], bump = auction_params.bump)]
+    pub auction_params: Account<'info, AuctionParams>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// CHECK: Keeper that started the series, refunded its rent with the last lot
+    #[account(mut, address = auction_series.keeper @ ErrorCode::Unauthorized)]
+    pub series_keeper: UncheckedAccount<'info>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_maker: Pubkey)]
+pub struct OpenMmFacility<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + (8 * 5) + 8 + 32 + 8 + 8 + 1 + 1,
+        seeds = [b"mm_facility", market_maker.as_ref()],
+        bump
+    )]
+    pub mm_facility: Account<'info, MmFacility>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageMmFacility<'info> {
+    #[account(mut, seeds = [b"mm_facility", mm_facility.market_maker.as_ref()], bump = mm_facility.bump)]
+    pub mm_facility: Account<'info, MmFacility>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MmFacilityMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"mm_facility", market_maker.key().as_ref()],
+        bump = mm_facility.bump,
+        has_one = market_maker @ ErrorCode::Unauthorized
+    )]
+    pub mm_facility: Account<'info, MmFacility>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = market_maker)]
+    pub market_maker_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = stablecoin_mint, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump, constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen)]
+    pub system_state: Account<'info, SystemState>,
+    pub market_maker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MmFacilityRedeem<'info> {
+    #[account(
+        mut,
+        seeds = [b"mm_facility", market_maker.key().as_ref()],
+        bump = mm_facility.bump,
+        has_one = market_maker @ ErrorCode::Unauthorized
+    )]
+    pub mm_facility: Account<'info, MmFacility>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = market_maker)]
+    pub market_maker_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = stablecoin_mint, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    pub market_maker: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(attestor: Pubkey)]
+pub struct RegisterTravelRuleAttestor<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 1,
+        seeds = [b"travel_rule_attestor", attestor.as_ref()],
+        bump
+    )]
+    pub travel_rule_attestor: Account<'info, TravelRuleAttestor>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveTravelRuleAttestor<'info> {
+    #[account(
+        mut,
+        close = governance_authority,
+        seeds = [b"travel_rule_attestor", travel_rule_attestor.attestor.as_ref()],
+        bump = travel_rule_attestor.bump
+    )]
+    pub travel_rule_attestor: Account<'info, TravelRuleAttestor>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(memo_hash: [u8; 32])]
+pub struct AttestTravelRule<'info> {
+    #[account(
+        init,
+        payer = attestor,
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 1 + 1,
+        seeds = [b"travel_rule", memo_hash.as_ref()],
+        bump
+    )]
+    pub travel_rule_attestation: Account<'info, TravelRuleAttestation>,
+    #[account(
+        seeds = [b"travel_rule_attestor", attestor.key().as_ref()],
+        bump = travel_rule_attestor.bump,
+        has_one = attestor @ ErrorCode::Unauthorized
+    )]
+    pub travel_rule_attestor: Account<'info, TravelRuleAttestor>,
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, memo_hash: [u8; 32])]
+pub struct TransferWithMemo<'info> {
+    #[account(address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = sender)]
+    pub sender_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = stablecoin_mint)]
+    pub recipient_stablecoin_account: Account<'info, TokenAccount>,
+    /// Attestation the transfer is verified against, if the integrator requires one
+    #[account(mut, seeds = [b"travel_rule", memo_hash.as_ref()], bump = travel_rule_attestation.bump)]
+    pub travel_rule_attestation: Option<Account<'info, TravelRuleAttestation>>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    pub sender: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, amount: u64, destination: Pubkey)]
+pub struct RescueTokens<'info> {
+    #[account(mut, token::mint = mint)]
+    pub source_account: Account<'info, TokenAccount>,
+    /// CHECK: Program PDA owning the source account, derived from the rescue authority in the handler
+    pub source_authority: UncheckedAccount<'info>,
+    #[account(mut, address = destination @ ErrorCode::InvalidAccountData, token::mint = mint)]
+    pub destination_account: Account<'info, TokenAccount>,
+    /// Treasury reserves whose reserve mint and vault stay protected from rescues
+    #[account(seeds = [b"treasury_reserves"], bump = treasury_reserves.bump)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRedemptionQueue<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 32 + 8 * REDEMPTION_LANE_COUNT + 8 * REDEMPTION_LANE_COUNT + 8 + 8 + 1,
+        seeds = [b"redemption_queue", treasury_reserves.key().as_ref()],
+        bump
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the reserve and escrow vaults
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(token::mint = system_state.stablecoin_mint, token::authority = treasury_authority)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRedemptionPriorityFee<'info> {
+    #[account(mut, seeds = [b"redemption_queue", redemption_queue.treasury_reserves.as_ref()], bump = redemption_queue.bump)]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, lane: RedemptionLane, request_id: u64, partner_id: Option<u64>)]
+pub struct RequestRedemption<'info> {
+    #[account(mut, seeds = [b"redemption_queue", treasury_reserves.key().as_ref()], bump = redemption_queue.bump)]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 1 + 8 + 32 + 8 + 8 + 1 + 8 + 1,
+        seeds = [b"redemption_request", redemption_queue.key().as_ref(), &[lane as u8], &request_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    #[account(mut, address = redemption_queue.escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = system_state.stablecoin_mint, token::authority = owner)]
+    pub owner_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(address = treasury_reserves.reserve_mint @ ErrorCode::InvalidMint)]
+    pub reserve_mint: Account<'info, Mint>,
+    /// Owner's reserve asset ATA receiving the fills, created on a first request
+    #[account(init_if_needed, payer = owner, associated_token::mint = reserve_mint, associated_token::authority = owner)]
+    pub owner_reserve_account: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    /// Partner originating the redemption; required when a partner id is passed
+    #[account(mut)]
+    pub partner: Option<Account<'info, Partner>>,
+    /// Owner's position, whose fee credits are spent on the redemption fee when passed
+    #[account(mut, constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Option<Account<'info, UserAccount>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillRedemption<'info> {
+    #[account(mut, seeds = [b"redemption_queue", treasury_reserves.key().as_ref()], bump = redemption_queue.bump)]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        mut,
+        seeds = [b"redemption_request", redemption_queue.key().as_ref(), &[redemption_request.lane as u8], &redemption_request.id.to_le_bytes()],
+        bump = redemption_request.bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    #[account(mut, has_one = reserve_vault)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the reserve and escrow vaults
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reserve_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = redemption_queue.escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, address = redemption_request.destination @ ErrorCode::InvalidAccountData)]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    /// Trailing redemption volume the fill is recorded in, once a monitor exists for the reserves
+    #[account(mut, seeds = [b"liquidity_monitor", treasury_reserves.key().as_ref()], bump = liquidity_monitor.bump)]
+    pub liquidity_monitor: Option<Account<'info, LiquidityMonitor>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRedemption<'info> {
+    #[account(mut, seeds = [b"redemption_queue", treasury_reserves.key().as_ref()], bump = redemption_queue.bump)]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        constraint = redemption_request.queue == redemption_queue.key() @ ErrorCode::InvalidAccountData
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the escrow vault
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut, address = redemption_queue.escrow_vault @ ErrorCode::InvalidAccountData)]
+    pub escrow_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = escrow_vault.mint, token::authority = owner)]
+    pub owner_stablecoin_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRedemptionRequest<'info> {
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::Unauthorized,
+        constraint = redemption_request.queue == redemption_queue.key() @ ErrorCode::InvalidAccountData
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WhitelistReceiptCollateral<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 1 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"receipt_config", collateral_type.key().as_ref()],
+        bump
+    )]
+    pub receipt_config: Account<'info, ReceiptCollateralConfig>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    /// CHECK: Owned by the lending market program; parsed by the receipt module
+    #[account(owner = market_program.key() @ ErrorCode::InvalidReceiptAccount)]
+    pub reserve: UncheckedAccount<'info>,
+    /// CHECK: Lending market program the reserve belongs to
+    #[account(executable)]
+    pub market_program: UncheckedAccount<'info>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReceiptCollateralParams<'info> {
+    #[account(mut, seeds = [b"receipt_config", receipt_config.collateral_type.as_ref()], bump = receipt_config.bump)]
+    pub receipt_config: Account<'info, ReceiptCollateralConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshReceiptPrice<'info> {
+    #[account(
+        seeds = [b"receipt_config", collateral_type.key().as_ref()],
+        bump = receipt_config.bump,
+        has_one = collateral_type,
+        has_one = reserve
+    )]
+    pub receipt_config: Account<'info, ReceiptCollateralConfig>,
+    #[account(mut)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(mut, seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// CHECK: Whitelisted reserve or bank, parsed by the receipt module
+    #[account(owner = receipt_config.market_program @ ErrorCode::InvalidReceiptAccount)]
+    pub reserve: UncheckedAccount<'info>,
+    /// CHECK: Underlying asset price feed, validated against the collateral type and parsed by the oracle module
+    pub price_feed: UncheckedAccount<'info>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+}
+
+#[derive(Accounts)]
+pub struct EnableAutoDeleverage<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + (8 + 8 + 8 + 8) + 8 + 8 + 1,
+        seeds = [b"deleverage_config", user_account.key().as_ref()],
+        bump
+    )]
+    pub deleverage_config: Account<'info, DeleverageConfig>,
+    #[account(constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageAutoDeleverage<'info> {
+    #[account(mut, has_one = user_account @ ErrorCode::InvalidAccountData, seeds = [b"deleverage_config", user_account.key().as_ref()], bump = deleverage_config.bump)]
+    pub deleverage_config: Account<'info, DeleverageConfig>,
+    #[account(constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoDeleverage<'info> {
+    #[account(mut, has_one = user_account @ ErrorCode::InvalidAccountData, seeds = [b"deleverage_config", user_account.key().as_ref()], bump = deleverage_config.bump)]
+    pub deleverage_config: Account<'info, DeleverageConfig>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+    /// Opt-in position ledger appended to by this instruction
+    #[account(mut, seeds = [b"position_ledger", user_account.key().as_ref()], bump = position_ledger.bump)]
+    pub position_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(mut, address = user_account.collateral_type @ ErrorCode::InvalidCollateralType)]
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    /// Keeper's position credited with the sold collateral and the tip
+    #[account(
+        mut,
+        constraint = keeper_position.owner == keeper.key() @ ErrorCode::Unauthorized,
+        constraint = keeper_position.collateral_type == user_account.collateral_type @ ErrorCode::InvalidCollateralType,
+        constraint = keeper_position.key() != user_account.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub keeper_position: Account<'info, UserAccount>,
+    /// Opt-in ledger of the keeper's position
+    #[account(mut, seeds = [b"position_ledger", keeper_position.key().as_ref()], bump = keeper_ledger.bump)]
+    pub keeper_ledger: Option<Account<'info, PositionLedger>>,
+    #[account(mut, address = system_state.stablecoin_mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = stablecoin_mint, token::authority = keeper)]
+    pub keeper_stablecoin_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [b"protocol_stats"], bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    pub system_state: Account<'info, SystemState>,
+    #[account(seeds = [b"pause_state"], bump = pause_state.bump)]
+    pub pause_state: Account<'info, PauseState>,
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BeginPoolSunset<'info> {
+    #[account(mut, constraint = reward_pool.sunset_deadline == 0 @ ErrorCode::PoolSunsetting)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepSunsetRewards<'info> {
+    #[account(
+        has_one = reward_vault @ ErrorCode::InvalidAccountData,
+        constraint = reward_pool.reward_source == RewardSource::Revenue @ ErrorCode::RewardSourceMismatch
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: PDA owning the reward vault
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReturnSunsetStake<'info> {
+    #[account(mut, has_one = reward_pool @ ErrorCode::InvalidAccountData)]
+    pub staker_account: Account<'info, StakerAccount>,
+    #[account(mut)]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: Owner of the stake, whose ATA is the only place it can be returned to
+    #[account(address = staker_account.owner @ ErrorCode::InvalidAccountOwner)]
+    pub staker_owner: UncheckedAccount<'info>,
+    #[account(address = staking_pool.mint @ ErrorCode::InvalidMint)]
+    pub staking_mint: Account<'info, Mint>,
+    /// Staker's own ATA, created by the caller if the staker has none
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = staking_mint,
+        associated_token::authority = staker_owner,
+        constraint = owner_token_account.key() != staking_pool.key() @ ErrorCode::DuplicateAccounts
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    /// CHECK: PDA owning the staking vault, signs the return
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    /// Required while the stake holds rewards staked by `claim_to_stake`, which are minted into the vault
+    #[account(mut, address = reward_pool.reward_mint @ ErrorCode::InvalidMint)]
+    pub reward_token_mint: Option<Account<'info, Mint>>,
+    #[account(seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    /// CHECK: PDA holding mint authority over the reward mint
+    #[account(seeds = [b"reward_mint_authority", reward_pool.key().as_ref()], bump)]
+    pub reward_mint_authority: Option<UncheckedAccount<'info>>,
+    /// Governance or any keeper may return a lingering stake
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSunsetPool<'info> {
+    #[account(mut, close = governance_authority, constraint = reward_pool.total_staked == 0 @ ErrorCode::RewardPoolNotEmpty)]
+    pub reward_pool: Account<'info, RewardPool>,
+    /// CHECK: PDA owning the pool's vaults
+    #[account(seeds = [b"staking_authority", reward_pool.key().as_ref()], bump = reward_pool.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut, address = reward_pool.staking_vault @ ErrorCode::InvalidAccountData)]
+    pub staking_pool: Account<'info, TokenAccount>,
+    /// Receives early withdrawal penalties left in the staking vault
+    #[account(mut, token::mint = staking_pool.mint, constraint = residual_destination.key() != staking_pool.key() @ ErrorCode::DuplicateAccounts)]
+    pub residual_destination: Account<'info, TokenAccount>,
+    /// Revenue reward vault, swept to the treasury and closed; required for revenue pools
+    #[account(mut, address = reward_pool.reward_vault @ ErrorCode::InvalidAccountData)]
+    pub reward_vault: Option<Account<'info, TokenAccount>>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Option<Account<'info, TokenAccount>>,
+    /// Emission budget of an emissions pool, closed with it
+    #[account(mut, close = governance_authority, seeds = [b"emission_schedule", reward_pool.key().as_ref()], bump = emission_schedule.bump)]
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLiquidityMonitor<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 8 * LCR_WINDOW_BUCKETS + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"liquidity_monitor", treasury_reserves.key().as_ref()],
+        bump
+    )]
+    pub liquidity_monitor: Account<'info, LiquidityMonitor>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLiquidityThresholds<'info> {
+    #[account(mut, seeds = [b"liquidity_monitor", liquidity_monitor.treasury_reserves.as_ref()], bump = liquidity_monitor.bump)]
+    pub liquidity_monitor: Account<'info, LiquidityMonitor>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CheckLiquidityCoverage<'info> {
+    #[account(mut, seeds = [b"liquidity_monitor", treasury_reserves.key().as_ref()], bump = liquidity_monitor.bump)]
+    pub liquidity_monitor: Account<'info, LiquidityMonitor>,
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+}
+
+#[derive(Accounts)]
+pub struct RecallForLiquidityCoverage<'info> {
+    #[account(mut, seeds = [b"liquidity_monitor", treasury_reserves.key().as_ref()], bump = liquidity_monitor.bump)]
+    pub liquidity_monitor: Account<'info, LiquidityMonitor>,
+    #[account(mut, seeds = [b"treasury_reserves"], bump = treasury_reserves.bump, has_one = reserve_vault)]
+    pub treasury_reserves: Account<'info, TreasuryReserves>,
+    /// CHECK: PDA that owns the reserve vault and signs strategy CPIs
+    #[account(seeds = [b"treasury_authority"], bump = treasury_reserves.authority_bump)]
+    pub treasury_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub reserve_vault: Account<'info, TokenAccount>,
+    #[account(mut, has_one = adapter_program, has_one = strategy_account, has_one = treasury_reserves)]
+    pub strategy: Account<'info, YieldStrategy>,
+    /// CHECK: Whitelisted adapter program, pinned by the strategy account
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+    /// CHECK: Position account owned by the strategy program, pinned by the strategy account
+    #[account(mut)]
+    pub strategy_account: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(partner_id: u64)]
+pub struct RegisterPartner<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 8 + 32 + 8 + 1 + 8 + 8 + 8 + 1,
+        seeds = [b"partner", partner_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub partner: Account<'info, Partner>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPartnerTerms<'info> {
+    #[account(mut, seeds = [b"partner", partner.partner_id.to_le_bytes().as_ref()], bump = partner.bump)]
+    pub partner: Account<'info, Partner>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPartnerRevenue<'info> {
+    #[account(mut, seeds = [b"partner", partner.partner_id.to_le_bytes().as_ref()], bump = partner.bump, has_one = wallet @ ErrorCode::Unauthorized)]
+    pub partner: Account<'info, Partner>,
+    #[account(mut, address = system_state.treasury_account @ ErrorCode::InvalidTreasuryAccount)]
+    pub treasury_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA the treasury delegates its distributable revenue to
+    #[account(seeds = [b"revenue_authority"], bump)]
+    pub revenue_authority: UncheckedAccount<'info>,
+    #[account(mut, token::mint = system_state.stablecoin_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    #[account(seeds = [b"system_state"], bump = system_state.bump)]
+    pub system_state: Account<'info, SystemState>,
+    pub wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetEpochRevenuePool<'info> {
+    #[account(mut, seeds = [b"epoch_state"], bump = epoch_state.bump)]
+    pub epoch_state: Account<'info, EpochState>,
+    /// Revenue reward pool to fund at each close; omitted to stop funding
+    #[account(constraint = revenue_pool.reward_source == RewardSource::Revenue @ ErrorCode::RewardSourceMismatch)]
+    pub revenue_pool: Option<Account<'info, RewardPool>>,
+    #[account(
+        seeds = [b"system_state"],
+        bump = system_state.bump,
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
 }
@@ -0,0 +1,197 @@
+// testing.rs
+
+//! Instructions that let a fuzzer drive the protocol into arbitrary states.
+//!
+//! The module only exists when the program is built with the `testing`
+//! feature. Its instructions are reached through the program's fallback
+//! rather than `#[program]` entries, so they never reach a release build or
+//! its IDL; clients build them from the usual `sha256("global:<name>")[..8]`
+//! discriminator followed by the Borsh-encoded arguments. Property-based
+//! tests can then set prices, age positions and force state transitions, and
+//! run mint and liquidation sequences against `assert_invariants`. Never
+//! deploy a `testing` build.
+
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::{AccountsExit, Bumps};
+
+use crate::errors::ErrorCode;
+use crate::state::{CollateralType, HealthBand, PriceCache, SystemState, UserAccount};
+
+// -------------------------------------
+// Contexts for Testing Instructions
+// -------------------------------------
+
+#[derive(Accounts)]
+pub struct TestSetPrice<'info> {
+    #[account(mut, seeds = [b"price_cache", collateral_type.key().as_ref()], bump = price_cache.bump)]
+    pub price_cache: Account<'info, PriceCache>,
+    pub collateral_type: Account<'info, CollateralType>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TestForcePosition<'info> {
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TestForceSystemState<'info> {
+    #[account(mut, has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+// -------------------------------------
+// Testing Instruction Dispatch
+// -------------------------------------
+
+#[derive(AnchorDeserialize)]
+struct TestSetPriceArgs {
+    price: u64,
+    confidence: u64,
+    publish_time: Option<i64>,
+}
+
+#[derive(AnchorDeserialize)]
+struct TestWarpPositionArgs {
+    seconds: u64,
+}
+
+#[derive(AnchorDeserialize)]
+struct TestForcePositionArgs {
+    collateral_balance: u64,
+    stablecoin_balance: u64,
+    health_band: HealthBand,
+}
+
+#[derive(AnchorDeserialize)]
+struct TestForceSystemStateArgs {
+    config_frozen: bool,
+    operation_locked: bool,
+    launch_mode: bool,
+}
+
+/// Dispatch a testing instruction by its Anchor discriminator.
+pub fn dispatch<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+    require!(data.len() >= 8, anchor_lang::error::ErrorCode::InstructionMissing);
+    let (discriminator, ix_data) = data.split_at(8);
+
+    if discriminator == sighash("test_set_price") {
+        run::<TestSetPrice, TestSetPriceArgs>(program_id, accounts, ix_data, |ctx, args| {
+            test_set_price(ctx, args.price, args.confidence, args.publish_time)
+        })
+    } else if discriminator == sighash("test_warp_position") {
+        run::<TestForcePosition, TestWarpPositionArgs>(program_id, accounts, ix_data, |ctx, args| {
+            test_warp_position(ctx, args.seconds)
+        })
+    } else if discriminator == sighash("test_force_position") {
+        run::<TestForcePosition, TestForcePositionArgs>(program_id, accounts, ix_data, |ctx, args| {
+            test_force_position(ctx, args.collateral_balance, args.stablecoin_balance, args.health_band)
+        })
+    } else if discriminator == sighash("test_force_system_state") {
+        run::<TestForceSystemState, TestForceSystemStateArgs>(program_id, accounts, ix_data, |ctx, args| {
+            test_force_system_state(ctx, args.config_frozen, args.operation_locked, args.launch_mode)
+        })
+    } else {
+        Err(anchor_lang::error::ErrorCode::InstructionFallbackNotFound.into())
+    }
+}
+
+/// Anchor discriminator of a global instruction.
+fn sighash(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Validate the accounts, run the handler and persist the accounts, as Anchor's generated entries do.
+fn run<'info, T, A>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    ix_data: &[u8],
+    handler: impl FnOnce(Context<'_, '_, 'info, 'info, T>, A) -> Result<()>,
+) -> Result<()>
+where
+    T: Accounts<'info, <T as Bumps>::Bumps> + Bumps + AccountsExit<'info>,
+    <T as Bumps>::Bumps: Default,
+    A: AnchorDeserialize,
+{
+    let args = A::deserialize(&mut &ix_data[..]).map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+
+    let mut bumps = <T as Bumps>::Bumps::default();
+    let mut reallocs = BTreeSet::new();
+    let mut remaining_accounts: &'info [AccountInfo<'info>] = accounts;
+    let mut validated = T::try_accounts(program_id, &mut remaining_accounts, ix_data, &mut bumps, &mut reallocs)?;
+
+    handler(Context::new(program_id, &mut validated, remaining_accounts, bumps), args)?;
+
+    validated.exit(program_id)
+}
+
+// -------------------------------------
+// Testing Instructions
+// -------------------------------------
+
+/// Overwrite a collateral's cached price, bypassing the oracle.
+///
+/// `publish_time` defaults to now, so the price is fresh for the next read.
+pub fn test_set_price(ctx: Context<TestSetPrice>, price: u64, confidence: u64, publish_time: Option<i64>) -> Result<()> {
+    let price_cache = &mut ctx.accounts.price_cache;
+    price_cache.price = price;
+    price_cache.reference_price = price;
+    price_cache.confidence = confidence;
+    price_cache.publish_time = match publish_time {
+        Some(publish_time) => publish_time,
+        None => Clock::get()?.unix_timestamp,
+    };
+
+    Ok(())
+}
+
+/// Move a position `seconds` into the future by backdating its recorded timestamps.
+///
+/// The runtime clock cannot be warped from a program, but every time-based
+/// rule on a position compares the clock with these timestamps, so shifting
+/// them back has the same effect for that position.
+pub fn test_warp_position(ctx: Context<TestForcePosition>, seconds: u64) -> Result<()> {
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.last_mint_time = user_account.last_mint_time.saturating_sub(seconds);
+    user_account.last_liquidation_time = user_account.last_liquidation_time.saturating_sub(seconds);
+    user_account.opened_at = user_account.opened_at.saturating_sub(seconds as i64);
+
+    Ok(())
+}
+
+/// Force a position's balances and health band, skipping every collateral check.
+pub fn test_force_position(
+    ctx: Context<TestForcePosition>,
+    collateral_balance: u64,
+    stablecoin_balance: u64,
+    health_band: HealthBand,
+) -> Result<()> {
+    let user_account = &mut ctx.accounts.user_account;
+    user_account.collateral_balance = collateral_balance;
+    user_account.stablecoin_balance = stablecoin_balance;
+    user_account.health_band = health_band;
+
+    Ok(())
+}
+
+/// Force the protocol-wide flags, e.g. to leave the operation lock held after a simulated failed CPI.
+pub fn test_force_system_state(ctx: Context<TestForceSystemState>, config_frozen: bool, operation_locked: bool, launch_mode: bool) -> Result<()> {
+    let system_state = &mut ctx.accounts.system_state;
+    system_state.config_frozen = config_frozen;
+    system_state.operation_locked = operation_locked;
+    system_state.launch_mode = launch_mode;
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+// transfer_fee.rs
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as SplMint;
+use anchor_spl::token_2022::{self, InitializeMint2, Token2022};
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    self, HarvestWithheldTokensToMint, TransferFeeInitialize, TransferFeeSetTransferFee, WithdrawWithheldTokensFromMint,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::state::SystemState;
+
+/// Seed of the fee mint config PDA.
+pub const FEE_MINT_CONFIG_SEED: &[u8] = b"fee_mint_config";
+/// Seed of the PDA holding the mint's transfer fee and withheld-withdraw authorities.
+pub const TRANSFER_FEE_AUTHORITY_SEED: &[u8] = b"transfer_fee_authority";
+
+// -------------------------------------
+// Fee Mint Config Structure
+// -------------------------------------
+#[account]
+pub struct FeeMintConfig {
+    pub mint: Pubkey,                   // Token-2022 stablecoin mint charging the transfer fee
+    pub transfer_fee_bps: u16,          // Transfer fee most recently set (bps)
+    pub maximum_fee: u64,               // Cap on the fee of a single transfer
+    pub fee_treasury: Pubkey,           // Token account harvested fees are withdrawn to (default until set)
+    pub harvested_amount: u64,          // Fees withdrawn to the treasury so far
+    pub authority_bump: u8,             // Bump of the transfer fee authority PDA
+    pub bump: u8,                       // Bump of the config PDA
+}
+
+// -------------------------------------
+// Contexts for Transfer Fee Instructions
+// -------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeFeeMint<'info> {
+    /// New mint account, created and initialized with the transfer fee extension below
+    #[account(mut)]
+    pub stablecoin_mint: Signer<'info>,
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 2 + 8 + 32 + 8 + 1 + 1,
+        seeds = [FEE_MINT_CONFIG_SEED, stablecoin_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_config: Account<'info, FeeMintConfig>,
+    /// CHECK: PDA set as the mint's transfer fee config and withdraw-withheld authority
+    #[account(seeds = [TRANSFER_FEE_AUTHORITY_SEED], bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTransferFee<'info> {
+    #[account(mut, seeds = [FEE_MINT_CONFIG_SEED, fee_config.mint.as_ref()], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeMintConfig>,
+    /// CHECK: Mint pinned by the fee config
+    #[account(mut, address = fee_config.mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: UncheckedAccount<'info>,
+    /// CHECK: Transfer fee config authority PDA
+    #[account(seeds = [TRANSFER_FEE_AUTHORITY_SEED], bump = fee_config.authority_bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTreasury<'info> {
+    #[account(mut, seeds = [FEE_MINT_CONFIG_SEED, fee_config.mint.as_ref()], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeMintConfig>,
+    #[account(token::mint = fee_config.mint, token::token_program = Token2022::id())]
+    pub fee_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestTransferFees<'info> {
+    #[account(mut, seeds = [FEE_MINT_CONFIG_SEED, fee_config.mint.as_ref()], bump = fee_config.bump)]
+    pub fee_config: Account<'info, FeeMintConfig>,
+    #[account(mut, address = fee_config.mint @ ErrorCode::InvalidMint)]
+    pub stablecoin_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, address = fee_config.fee_treasury @ ErrorCode::InvalidTreasuryAccount)]
+    pub fee_treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Withdraw-withheld authority PDA
+    #[account(seeds = [TRANSFER_FEE_AUTHORITY_SEED], bump = fee_config.authority_bump)]
+    pub fee_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+// -------------------------------------
+// Transfer Fee Instructions
+// -------------------------------------
+
+/// Create a Token-2022 stablecoin mint with the transfer fee extension, controlled by the fee authority PDA.
+pub fn initialize_fee_mint(ctx: Context<InitializeFeeMint>, decimals: u8, transfer_fee_bps: u16, maximum_fee: u64) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(&[ExtensionType::TransferFeeConfig])?;
+    let cpi_accounts = CreateAccount {
+        from: ctx.accounts.governance_authority.to_account_info(),
+        to: ctx.accounts.stablecoin_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    system_program::create_account(cpi_ctx, Rent::get()?.minimum_balance(space), space as u64, &token_2022::ID)?;
+
+    // Extensions must be initialized before the mint itself
+    let fee_authority = ctx.accounts.fee_authority.key();
+    let cpi_accounts = TransferFeeInitialize {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    transfer_fee::transfer_fee_initialize(cpi_ctx, Some(&fee_authority), Some(&fee_authority), transfer_fee_bps, maximum_fee)?;
+
+    let cpi_accounts = InitializeMint2 {
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_2022::initialize_mint2(cpi_ctx, decimals, &ctx.accounts.governance_authority.key(), None)?;
+
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.mint = ctx.accounts.stablecoin_mint.key();
+    fee_config.transfer_fee_bps = transfer_fee_bps;
+    fee_config.maximum_fee = maximum_fee;
+    fee_config.fee_treasury = Pubkey::default();
+    fee_config.harvested_amount = 0;
+    fee_config.authority_bump = ctx.bumps.fee_authority;
+    fee_config.bump = ctx.bumps.fee_config;
+
+    emit!(TransferFeeUpdatedEvent {
+        mint: fee_config.mint,
+        transfer_fee_bps,
+        maximum_fee,
+    });
+
+    Ok(())
+}
+
+/// Set the transfer fee. Zero disables it; Token-2022 applies changes two epochs later.
+pub fn set_transfer_fee(ctx: Context<SetTransferFee>, transfer_fee_bps: u16, maximum_fee: u64) -> Result<()> {
+    let bump = [ctx.accounts.fee_config.authority_bump];
+    let seeds: &[&[u8]] = &[TRANSFER_FEE_AUTHORITY_SEED, &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = TransferFeeSetTransferFee {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        authority: ctx.accounts.fee_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    transfer_fee::transfer_fee_set(cpi_ctx, transfer_fee_bps, maximum_fee)?;
+
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.transfer_fee_bps = transfer_fee_bps;
+    fee_config.maximum_fee = maximum_fee;
+
+    emit!(TransferFeeUpdatedEvent {
+        mint: fee_config.mint,
+        transfer_fee_bps,
+        maximum_fee,
+    });
+
+    Ok(())
+}
+
+/// Point fee harvesting at a treasury token account of the fee mint.
+pub fn set_fee_treasury(ctx: Context<SetFeeTreasury>) -> Result<()> {
+    ctx.accounts.fee_config.fee_treasury = ctx.accounts.fee_treasury.key();
+
+    Ok(())
+}
+
+/// Sweep fees withheld in the token accounts passed as remaining accounts into the mint,
+/// then withdraw everything withheld in the mint to the treasury. Callable by anyone.
+pub fn harvest_transfer_fees<'info>(ctx: Context<'_, '_, '_, 'info, HarvestTransferFees<'info>>) -> Result<()> {
+    if !ctx.remaining_accounts.is_empty() {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer_fee::harvest_withheld_tokens_to_mint(cpi_ctx, ctx.remaining_accounts.to_vec())?;
+    }
+
+    let balance_before = ctx.accounts.fee_treasury.amount;
+    let bump = [ctx.accounts.fee_config.authority_bump];
+    let seeds: &[&[u8]] = &[TRANSFER_FEE_AUTHORITY_SEED, &bump];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = WithdrawWithheldTokensFromMint {
+        token_program_id: ctx.accounts.token_program.to_account_info(),
+        mint: ctx.accounts.stablecoin_mint.to_account_info(),
+        destination: ctx.accounts.fee_treasury.to_account_info(),
+        authority: ctx.accounts.fee_authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    transfer_fee::withdraw_withheld_tokens_from_mint(cpi_ctx)?;
+
+    ctx.accounts.fee_treasury.reload()?;
+    let harvested = ctx.accounts.fee_treasury.amount.checked_sub(balance_before).ok_or(ErrorCode::Overflow)?;
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.harvested_amount = fee_config.harvested_amount.checked_add(harvested).ok_or(ErrorCode::Overflow)?;
+
+    emit!(TransferFeesHarvestedEvent {
+        mint: fee_config.mint,
+        fee_treasury: fee_config.fee_treasury,
+        amount: harvested,
+        harvested_amount: fee_config.harvested_amount,
+    });
+
+    Ok(())
+}
+
+// -------------------------------------
+// Event Definitions
+// -------------------------------------
+
+#[event]
+pub struct TransferFeeUpdatedEvent {
+    pub mint: Pubkey,
+    pub transfer_fee_bps: u16,
+    pub maximum_fee: u64,
+}
+
+#[event]
+pub struct TransferFeesHarvestedEvent {
+    pub mint: Pubkey,
+    pub fee_treasury: Pubkey,
+    pub amount: u64,
+    pub harvested_amount: u64,
+}
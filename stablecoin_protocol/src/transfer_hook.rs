@@ -0,0 +1,234 @@
+// transfer_hook.rs
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHookAccount;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Account as SplTokenAccount;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::errors::ErrorCode;
+use crate::state::SystemState;
+
+/// Seed of the transfer hook config PDA.
+pub const TRANSFER_HOOK_CONFIG_SEED: &[u8] = b"transfer_hook_config";
+/// Seed of the extra account metas PDA defined by the transfer hook interface.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+// -------------------------------------
+// Transfer Hook Config Structure
+// -------------------------------------
+#[account]
+pub struct TransferHookConfig {
+    pub mint: Pubkey,                   // Permissioned stablecoin mint screened by the hook
+    pub screening_enabled: bool,        // Whether transfers are checked against the blacklist
+    pub bump: u8,                       // Bump of the config PDA
+}
+
+// -------------------------------------
+// Contexts for Transfer Hook Instructions
+// -------------------------------------
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct InitializePermissionedMint<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        mint::decimals = decimals,
+        mint::authority = governance_authority,
+        mint::token_program = token_program,
+        extensions::transfer_hook::authority = governance_authority,
+        extensions::transfer_hook::program_id = crate::ID,
+    )]
+    pub stablecoin_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 32 + 1 + 1,
+        seeds = [TRANSFER_HOOK_CONFIG_SEED, stablecoin_mint.key().as_ref()],
+        bump
+    )]
+    pub hook_config: Account<'info, TransferHookConfig>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    /// CHECK: Validation account defined by the transfer hook interface, written below
+    #[account(
+        init,
+        payer = governance_authority,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, stablecoin_mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub stablecoin_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(has_one = governance_authority @ ErrorCode::RestrictedToGovernance)]
+    pub system_state: Account<'info, SystemState>,
+    #[account(mut)]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTransferHookConfig<'info> {
+    #[account(mut, seeds = [TRANSFER_HOOK_CONFIG_SEED, hook_config.mint.as_ref()], bump = hook_config.bump)]
+    pub hook_config: Account<'info, TransferHookConfig>,
+    #[account(
+        has_one = governance_authority @ ErrorCode::RestrictedToGovernance,
+        constraint = !system_state.config_frozen @ ErrorCode::ConfigFrozen
+    )]
+    pub system_state: Account<'info, SystemState>,
+    pub governance_authority: Signer<'info>,
+}
+
+/// Accounts passed by Token-2022 to the execute instruction, in interface order.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = stablecoin_mint)]
+    pub source_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub stablecoin_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(token::mint = stablecoin_mint)]
+    pub destination_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Owner or delegate of the source account, validated by Token-2022
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: Validation account defined by the transfer hook interface
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, stablecoin_mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    #[account(seeds = [TRANSFER_HOOK_CONFIG_SEED, stablecoin_mint.key().as_ref()], bump = hook_config.bump)]
+    pub hook_config: Account<'info, TransferHookConfig>,
+    /// CHECK: Blacklist PDA of the source owner, only exists if the owner is blacklisted
+    #[account(seeds = [b"blacklist", source_token.owner.as_ref()], bump)]
+    pub source_blacklist: UncheckedAccount<'info>,
+    /// CHECK: Blacklist PDA of the destination owner, only exists if the owner is blacklisted
+    #[account(seeds = [b"blacklist", destination_token.owner.as_ref()], bump)]
+    pub destination_blacklist: UncheckedAccount<'info>,
+    /// CHECK: Blacklist PDA of the signing owner or delegate, only exists if it is blacklisted
+    #[account(seeds = [b"blacklist", owner.key().as_ref()], bump)]
+    pub authority_blacklist: UncheckedAccount<'info>,
+}
+
+// -------------------------------------
+// Transfer Hook Instructions
+// -------------------------------------
+
+/// Extra accounts Token-2022 resolves and appends to every execute call.
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        // Index 5: hook config, derived from the mint
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: TRANSFER_HOOK_CONFIG_SEED.to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // Index 6: blacklist PDA of the source token account owner
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"blacklist".to_vec() },
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Index 7: blacklist PDA of the destination token account owner
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"blacklist".to_vec() },
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 },
+            ],
+            false,
+            false,
+        )?,
+        // Index 8: blacklist PDA of the transfer authority, which is a delegate when it is not the owner
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"blacklist".to_vec() },
+                Seed::AccountKey { index: 3 },
+            ],
+            false,
+            false,
+        )?,
+    ])
+}
+
+/// Create the permissioned Token-2022 stablecoin mint pointing its transfer hook at this program.
+pub fn initialize_permissioned_mint(ctx: Context<InitializePermissionedMint>, _decimals: u8) -> Result<()> {
+    let hook_config = &mut ctx.accounts.hook_config;
+    hook_config.mint = ctx.accounts.stablecoin_mint.key();
+    hook_config.screening_enabled = true;
+    hook_config.bump = ctx.bumps.hook_config;
+
+    emit!(TransferHookConfigUpdatedEvent {
+        mint: hook_config.mint,
+        screening_enabled: true,
+    });
+
+    Ok(())
+}
+
+/// Write the extra account metas Token-2022 needs to call the hook.
+pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+    Ok(())
+}
+
+/// Toggle blacklist screening for the permissioned mint.
+pub fn set_transfer_hook_config(ctx: Context<SetTransferHookConfig>, screening_enabled: bool) -> Result<()> {
+    let hook_config = &mut ctx.accounts.hook_config;
+    hook_config.screening_enabled = screening_enabled;
+
+    emit!(TransferHookConfigUpdatedEvent {
+        mint: hook_config.mint,
+        screening_enabled,
+    });
+
+    Ok(())
+}
+
+/// Reject transfers where either side, or the delegate moving the tokens, is on the blacklist.
+pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+    // Only Token-2022 may invoke the hook, in the middle of a transfer
+    let source_info = ctx.accounts.source_token.to_account_info();
+    let source_data = source_info.try_borrow_data()?;
+    let source_account = StateWithExtensions::<SplTokenAccount>::unpack(&source_data)?;
+    let transferring = source_account.get_extension::<TransferHookAccount>()?.transferring;
+    require!(bool::from(transferring), ErrorCode::Unauthorized);
+
+    if !ctx.accounts.hook_config.screening_enabled {
+        return Ok(());
+    }
+
+    require!(!is_blacklisted(&ctx.accounts.source_blacklist), ErrorCode::AddressBlacklisted);
+    require!(!is_blacklisted(&ctx.accounts.destination_blacklist), ErrorCode::AddressBlacklisted);
+    require!(!is_blacklisted(&ctx.accounts.authority_blacklist), ErrorCode::AddressBlacklisted);
+
+    Ok(())
+}
+
+/// A wallet is blacklisted when its blacklist PDA exists and is owned by this program.
+fn is_blacklisted(blacklist_entry: &AccountInfo) -> bool {
+    blacklist_entry.owner == &crate::ID && !blacklist_entry.data_is_empty()
+}
+
+// -------------------------------------
+// Event Definitions
+// -------------------------------------
+
+#[event]
+pub struct TransferHookConfigUpdatedEvent {
+    pub mint: Pubkey,
+    pub screening_enabled: bool,
+}
@@ -3,17 +3,28 @@
 describe("Stablecoin Protocol Tests", () => {
   let userAccountKp;
   let userStablecoinAccount;
+  let treasuryAccount;
   let stablecoinMint;
-  let governanceAccountKp;
+  let governancePda;
+  let systemStatePda;
+
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new web3.PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 
   before(async () => {
     // Set up keypairs and initial accounts before running tests
     userAccountKp = new web3.Keypair();
-    governanceAccountKp = new web3.Keypair();
+    [governancePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("governance")], pg.PROGRAM_ID);
+    [systemStatePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("system_state")], pg.PROGRAM_ID);
     stablecoinMint = new web3.Keypair();
 
-    // Create a token account for the user
-    userStablecoinAccount = await pg.createTokenAccount(stablecoinMint.publicKey, pg.wallet.publicKey);
+    // The user's stablecoin ATA is created by the first mint
+    [userStablecoinAccount] = web3.PublicKey.findProgramAddressSync(
+      [pg.wallet.publicKey.toBuffer(), web3.TokenProgram.programId.toBuffer(), stablecoinMint.publicKey.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+
+    // Create the treasury token account receiving minting fees
+    treasuryAccount = await pg.createTokenAccount(stablecoinMint.publicKey, pg.wallet.publicKey);
   });
 
   it("initialize", async () => {
@@ -24,11 +35,10 @@ describe("Stablecoin Protocol Tests", () => {
     const txHash = await pg.program.methods
       .initialize(collateralRatio)
       .accounts({
-        governance: governanceAccountKp.publicKey,
+        governance: governancePda,
         payer: pg.wallet.publicKey,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([governanceAccountKp])
       .rpc();
 
     console.log(`Initialize TX Hash: ${txHash}`);
@@ -37,13 +47,183 @@ describe("Stablecoin Protocol Tests", () => {
     await pg.connection.confirmTransaction(txHash);
 
     // Fetch the governance account data
-    const governanceAccount = await pg.program.account.governance.fetch(governanceAccountKp.publicKey);
+    const governanceAccount = await pg.program.account.governance.fetch(governancePda);
     console.log("On-chain governance data:", governanceAccount.collateralRatio.toString());
 
     // Check if the collateral ratio matches the expected value
     assert(collateralRatio.eq(new BN(governanceAccount.collateralRatio)));
   });
 
+  it("initialize_system_state", async () => {
+    // Send transaction to initialize the system state with the wallet as governance
+    const txHash = await pg.program.methods
+      .initializeSystemState(pg.wallet.publicKey)
+      .accounts({
+        systemState: systemStatePda,
+        payer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log(`Initialize System State TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the system state and check the default health bands
+    const systemState = await pg.program.account.systemState.fetch(systemStatePda);
+    assert(systemState.governanceAuthority.equals(pg.wallet.publicKey));
+    assert(new BN(13000).eq(new BN(systemState.healthWarningBps)));
+    assert(new BN(11500).eq(new BN(systemState.healthCriticalBps)));
+  });
+
+  it("set_canonical_accounts", async () => {
+    // Pin the stablecoin mint and treasury account checked by the minting contexts
+    const txHash = await pg.program.methods
+      .setCanonicalAccounts()
+      .accounts({
+        systemState: systemStatePda,
+        stablecoinMint: stablecoinMint.publicKey,
+        treasuryAccount: treasuryAccount,
+        governanceAuthority: pg.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log(`Set Canonical Accounts TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the system state and check the pinned accounts
+    const systemState = await pg.program.account.systemState.fetch(systemStatePda);
+    assert(systemState.stablecoinMint.equals(stablecoinMint.publicKey));
+    assert(systemState.treasuryAccount.equals(treasuryAccount));
+  });
+
+  it("set_oracle_programs", async () => {
+    // Stand-in oracle program; price feeds owned by anything else are rejected
+    const oracleProgram = web3.Keypair.generate().publicKey;
+
+    const txHash = await pg.program.methods
+      .setOraclePrograms([oracleProgram])
+      .accounts({
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log(`Set Oracle Programs TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the system state and check the whitelist fills the first slot only
+    const systemState = await pg.program.account.systemState.fetch(systemStatePda);
+    assert(systemState.oraclePrograms[0].equals(oracleProgram));
+    assert(systemState.oraclePrograms[1].equals(web3.PublicKey.default));
+  });
+
+  it("open_position", async () => {
+    // Tag the position with a back-office sub-account identifier
+    const tagBuffer = Buffer.alloc(32);
+    tagBuffer.write("desk-1");
+    const tag = Array.from(tagBuffer);
+
+    const txHash = await pg.program.methods
+      .openPosition(tag)
+      .accounts({
+        userAccount: userAccountKp.publicKey,
+        governance: governancePda,
+        payer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([userAccountKp])
+      .rpc();
+
+    console.log(`Open Position TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the position and check the tag and governance collateral ratio
+    const userAccount = await pg.program.account.userAccount.fetch(userAccountKp.publicKey);
+    assert(Buffer.from(userAccount.tag).equals(tagBuffer));
+    assert(new BN(150).eq(new BN(userAccount.collateralRatio)));
+  });
+
+  it("initialize_peg_state", async () => {
+    // Stand-in market price feed; the band is not enforced until governance enables it
+    const priceFeed = web3.Keypair.generate().publicKey;
+
+    const txHash = await pg.program.methods
+      .initializePegState()
+      .accounts({
+        priceFeed: priceFeed,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log(`Initialize Peg State TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the peg state and check the TWAP starts at $1 with the band disabled
+    const [pegStatePda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("peg_state")],
+      pg.PROGRAM_ID
+    );
+    const pegState = await pg.program.account.pegState.fetch(pegStatePda);
+    assert(new BN(1_000_000).eq(new BN(pegState.twapPrice)));
+    assert(!pegState.enforced);
+  });
+
+  it("initialize_pause_state", async () => {
+    const txHash = await pg.program.methods
+      .initializePauseState()
+      .accounts({
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log(`Initialize Pause State TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the pause state and check nothing starts paused
+    const [pauseStatePda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("pause_state")],
+      pg.PROGRAM_ID
+    );
+    const pauseState = await pg.program.account.pauseState.fetch(pauseStatePda);
+    assert(!pauseState.mintingPaused);
+    assert(!pauseState.liquidationsPaused);
+    assert(!pauseState.governancePaused);
+    assert(!pauseState.emergencyShutdown);
+  });
+
+  it("initialize_protocol_stats", async () => {
+    // 50% concentration cap, warning at 40%, not enforced below 1M of debt
+    const txHash = await pg.program.methods
+      .initializeProtocolStats(new BN(5000), new BN(4000), new BN(1_000_000))
+      .accounts({
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log(`Initialize Protocol Stats TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+  });
+
   it("mint_stablecoin", async () => {
     // Amount to mint
     const mintAmount = new BN(1000);
@@ -51,13 +231,19 @@ describe("Stablecoin Protocol Tests", () => {
 
     // Send transaction to mint stablecoin
     const txHash = await pg.program.methods
-      .mintStablecoin(mintAmount, currentPrice)
+      .mintStablecoin(mintAmount, currentPrice, null, null, null)
       .accounts({
         userAccount: userAccountKp.publicKey,
+        positionLedger: null,
         userStablecoinAccount: userStablecoinAccount,
         stablecoinMint: stablecoinMint.publicKey,
-        treasuryAccount: pg.wallet.publicKey, // Assuming the treasury is controlled by the payer
+        treasuryAccount: treasuryAccount,
+        systemState: systemStatePda,
+        collateralType: null,
+        partner: null,
         tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
         payer: pg.wallet.publicKey,
       })
       .signers([userAccountKp])
@@ -74,18 +260,75 @@ describe("Stablecoin Protocol Tests", () => {
 
     // Ensure the minted amount is reflected in the user's account
     assert(mintAmount.eq(new BN(userAccount.stablecoinBalance)));
+
+    // Untyped debt is tracked globally so the supply invariant can account for it
+    const [protocolStatsPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("protocol_stats")],
+      pg.PROGRAM_ID
+    );
+    const protocolStats = await pg.program.account.protocolStats.fetch(protocolStatsPda);
+    assert(mintAmount.eq(new BN(protocolStats.legacyIssuance)));
+  });
+
+  it("initialize_liquidation_revenue", async () => {
+    // Liquidator keeps 70% of the penalty, 20% to insurance, 10% to the surplus buffer
+    const liquidatorShareBps = new BN(7000);
+    const insuranceShareBps = new BN(2000);
+
+    const txHash = await pg.program.methods
+      .initializeLiquidationRevenue(liquidatorShareBps, insuranceShareBps)
+      .accounts({
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log(`Initialize Liquidation Revenue TX Hash: ${txHash}`);
+
+    // Confirm transaction
+    await pg.connection.confirmTransaction(txHash);
+
+    // Fetch the revenue split and check the surplus share is the remainder
+    const [liquidationRevenue] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidation_revenue")],
+      pg.PROGRAM_ID
+    );
+    const revenue = await pg.program.account.liquidationRevenue.fetch(liquidationRevenue);
+    assert(new BN(1000).eq(new BN(revenue.surplusShareBps)));
   });
 
   it("partial_liquidate", async () => {
     // Amount to liquidate
     const liquidationAmount = new BN(500);
 
+    // The liquidator's share of the penalty is credited to its own position
+    const liquidatorAccountKp = new web3.Keypair();
+    await pg.program.methods
+      .openPosition(Array(32).fill(0))
+      .accounts({
+        userAccount: liquidatorAccountKp.publicKey,
+        governance: governancePda,
+        payer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([liquidatorAccountKp])
+      .rpc();
+
     // Send transaction to partially liquidate user's position
     const txHash = await pg.program.methods
       .partialLiquidate(liquidationAmount)
       .accounts({
         userAccount: userAccountKp.publicKey,
-        liquidatorCollateralAccount: userStablecoinAccount,
+        positionLedger: null,
+        liquidatorAccount: liquidatorAccountKp.publicKey,
+        collateralType: null,
+        priceCache: null,
+        marginAccount: null,
+        systemState: systemStatePda,
+        liquidatorStablecoinAccount: null,
+        treasuryAccount: null,
+        revenueAuthority: null,
         tokenProgram: web3.TokenProgram.programId,
         payer: pg.wallet.publicKey,
       })
@@ -104,5 +347,846 @@ describe("Stablecoin Protocol Tests", () => {
     // Ensure the stablecoin balance reflects the liquidation
     const expectedBalance = new BN(1000).sub(liquidationAmount); // Original balance minus liquidation
     assert(expectedBalance.eq(new BN(userAccount.stablecoinBalance)));
+
+    // The liquidator's position holds 70% of the 10% legacy penalty on the liquidated amount
+    const liquidatorAccount = await pg.program.account.userAccount.fetch(liquidatorAccountKp.publicKey);
+    assert(new BN(35).eq(new BN(liquidatorAccount.collateralBalance)));
+  });
+
+  // Resolve if `promise` fails, with the given Anchor error code when one is passed
+  async function expectError(promise, code) {
+    try {
+      await promise;
+    } catch (err) {
+      if (code) assert.equal(err.error?.errorCode?.code, code);
+      return;
+    }
+    assert.fail(`Expected ${code ?? "the transaction"} to fail`);
+  }
+
+  it("rejects a second system state", async () => {
+    // The system state is a singleton PDA, so nobody can create one naming themselves as governance
+    const attacker = web3.Keypair.generate();
+    await expectError(
+      pg.program.methods
+        .initializeSystemState(attacker.publicKey)
+        .accounts({
+          systemState: systemStatePda,
+          payer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc()
+    );
+  });
+
+  it("rejects an empty oracle program whitelist", async () => {
+    await expectError(
+      pg.program.methods
+        .setOraclePrograms([])
+        .accounts({
+          systemState: systemStatePda,
+          governanceAuthority: pg.wallet.publicKey,
+        })
+        .rpc(),
+      "InvalidOraclePrograms"
+    );
+  });
+
+  it("rejects governance calls from another signer", async () => {
+    const attacker = web3.Keypair.generate();
+    await expectError(
+      pg.program.methods
+        .setCanonicalAccounts()
+        .accounts({
+          systemState: systemStatePda,
+          stablecoinMint: stablecoinMint.publicKey,
+          treasuryAccount: treasuryAccount,
+          governanceAuthority: attacker.publicKey,
+        })
+        .signers([attacker])
+        .rpc(),
+      "RestrictedToGovernance"
+    );
+  });
+
+  it("rejects resuming collateral minting from a non-governance signer", async () => {
+    const [governanceConfigPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("governance_config"), governancePda.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    await pg.program.methods
+      .initializeGovernanceConfig()
+      .accounts({
+        governanceConfig: governanceConfigPda,
+        governance: governancePda,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const collateralTypeKp = new web3.Keypair();
+    await pg.program.methods
+      .addCollateralType(new BN(150))
+      .accounts({
+        collateralType: collateralTypeKp.publicKey,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([collateralTypeKp])
+      .rpc();
+
+    const attacker = web3.Keypair.generate();
+    await expectError(
+      pg.program.methods
+        .setCollateralMintingPaused(false)
+        .accounts({
+          collateralType: collateralTypeKp.publicKey,
+          systemState: systemStatePda,
+          guardianSet: null,
+          followUpProposal: null,
+          governance: governancePda,
+          governanceConfig: governanceConfigPda,
+          authority: attacker.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([attacker])
+        .rpc(),
+      "RestrictedToGovernance"
+    );
+  });
+
+  it("rejects a veto through an account other than the canonical system state", async () => {
+    const [governanceConfigPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("governance_config"), governancePda.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    const [pauseStatePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("pause_state")], pg.PROGRAM_ID);
+    const proposalKp = new web3.Keypair();
+    await pg.program.methods
+      .proposeCollateralRatioChange(new BN(200))
+      .accounts({
+        proposal: proposalKp.publicKey,
+        governance: governancePda,
+        governanceConfig: governanceConfigPda,
+        systemState: null,
+        pauseState: pauseStatePda,
+        proposer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([proposalKp])
+      .rpc();
+
+    await expectError(
+      pg.program.methods
+        .vetoProposal()
+        .accounts({
+          proposal: proposalKp.publicKey,
+          systemState: governancePda,
+          governanceAuthority: pg.wallet.publicKey,
+        })
+        .rpc()
+    );
+    const proposal = await pg.program.account.proposal.fetch(proposalKp.publicKey);
+    assert(proposal.status.pending !== undefined);
+  });
+
+  it("rejects an oracle refresh for receipt-priced collateral", async () => {
+    const collateralTypeKp = new web3.Keypair();
+    await pg.program.methods
+      .addCollateralType(new BN(150))
+      .accounts({
+        collateralType: collateralTypeKp.publicKey,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([collateralTypeKp])
+      .rpc();
+
+    const [receiptConfigPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_config"), collateralTypeKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    // marginfi banks mint no receipt token, so any account of an executable program stands in for the bank
+    const whitelist = (haircutBps, maxRateAgeSecs) =>
+      pg.program.methods
+        .whitelistReceiptCollateral({ marginfi: {} }, new BN(haircutBps), new BN(maxRateAgeSecs))
+        .accounts({
+          receiptConfig: receiptConfigPda,
+          collateralType: collateralTypeKp.publicKey,
+          reserve: treasuryAccount,
+          marketProgram: web3.TokenProgram.programId,
+          systemState: systemStatePda,
+          governanceAuthority: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+    await expectError(whitelist(10000, 60), "InvalidAmount");
+    await expectError(whitelist(500, 30 * 86400), "InvalidAmount");
+    await whitelist(500, 60);
+
+    const collateralType = await pg.program.account.collateralType.fetch(collateralTypeKp.publicKey);
+    assert(collateralType.receiptConfig.equals(receiptConfigPda));
+
+    const [priceCachePda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("price_cache"), collateralTypeKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    await pg.program.methods
+      .initializePriceCache()
+      .accounts({
+        priceCache: priceCachePda,
+        collateralType: collateralTypeKp.publicKey,
+        payer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await expectError(
+      pg.program.methods
+        .refreshPrice()
+        .accounts({
+          priceCache: priceCachePda,
+          collateralType: collateralTypeKp.publicKey,
+          priceFeed: collateralType.priceFeed,
+          systemState: systemStatePda,
+        })
+        .rpc(),
+      "ReceiptPricedCollateral"
+    );
+  });
+
+  it("rejects minting against another wallet's position", async () => {
+    const attacker = web3.Keypair.generate();
+    await expectError(
+      pg.program.methods
+        .mintStablecoin(new BN(1000), new BN(110), null, null, null)
+        .accounts({
+          userAccount: userAccountKp.publicKey,
+          positionLedger: null,
+          userStablecoinAccount: userStablecoinAccount,
+          stablecoinMint: stablecoinMint.publicKey,
+          treasuryAccount: treasuryAccount,
+          systemState: systemStatePda,
+          collateralType: null,
+          partner: null,
+          tokenProgram: web3.TokenProgram.programId,
+          payer: attacker.publicKey,
+        })
+        .signers([userAccountKp, attacker])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("rejects a treasury account other than the canonical one", async () => {
+    const otherTreasury = await pg.createTokenAccount(stablecoinMint.publicKey, pg.wallet.publicKey);
+    await expectError(
+      pg.program.methods
+        .mintStablecoin(new BN(1000), new BN(110), null, null, null)
+        .accounts({
+          userAccount: userAccountKp.publicKey,
+          positionLedger: null,
+          userStablecoinAccount: userStablecoinAccount,
+          stablecoinMint: stablecoinMint.publicKey,
+          treasuryAccount: otherTreasury,
+          systemState: systemStatePda,
+          collateralType: null,
+          partner: null,
+          tokenProgram: web3.TokenProgram.programId,
+          payer: pg.wallet.publicKey,
+        })
+        .signers([userAccountKp])
+        .rpc(),
+      "InvalidTreasuryAccount"
+    );
+  });
+
+  it("rejects a mint that lands after its deadline", async () => {
+    const deadline = new BN(Math.floor(Date.now() / 1000) - 60);
+    await expectError(
+      pg.program.methods
+        .mintStablecoin(new BN(1000), new BN(110), deadline, null, null)
+        .accounts({
+          userAccount: userAccountKp.publicKey,
+          positionLedger: null,
+          userStablecoinAccount: userStablecoinAccount,
+          stablecoinMint: stablecoinMint.publicKey,
+          treasuryAccount: treasuryAccount,
+          systemState: systemStatePda,
+          collateralType: null,
+          partner: null,
+          tokenProgram: web3.TokenProgram.programId,
+          payer: pg.wallet.publicKey,
+        })
+        .signers([userAccountKp])
+        .rpc(),
+      "DeadlineExceeded"
+    );
+  });
+});
+
+describe("Staking", () => {
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new web3.PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+  const rewardPoolKp = new web3.Keypair();
+  const stakerAccountKp = new web3.Keypair();
+  let systemStatePda;
+  let stakingMint;
+  let rewardMint;
+  let stakingAuthority;
+  let stakingPool;
+  let userTokenAccount;
+
+  // Resolve if `promise` fails with the given Anchor error code
+  async function expectError(promise, code) {
+    try {
+      await promise;
+    } catch (err) {
+      assert.equal(err.error?.errorCode?.code, code);
+      return;
+    }
+    assert.fail(`Expected ${code} to fail`);
+  }
+
+  // The withdrawing wallet's staking token ATA, created by the withdrawal if missing
+  function stakingAta(owner) {
+    return web3.PublicKey.findProgramAddressSync(
+      [owner.toBuffer(), web3.TokenProgram.programId.toBuffer(), stakingMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    )[0];
+  }
+
+  function withdrawAccounts(payer, rewardPool = rewardPoolKp.publicKey) {
+    return {
+      stakerAccount: stakerAccountKp.publicKey,
+      rewardPool,
+      stakingMint,
+      userTokenAccount: stakingAta(payer),
+      stakingPool,
+      stakingAuthority,
+      tokenProgram: web3.TokenProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      clock: web3.SYSVAR_CLOCK_PUBKEY,
+      systemState: systemStatePda,
+      payer,
+      rewardTokenMint: null,
+      emissionSchedule: null,
+      rewardMintAuthority: null,
+      veConfig: null,
+      veLock: null,
+    };
+  }
+
+  before(async () => {
+    [systemStatePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("system_state")], pg.PROGRAM_ID);
+    stakingMint = await pg.createMint(pg.wallet.publicKey, 6);
+    rewardMint = await pg.createMint(pg.wallet.publicKey, 6);
+    [stakingAuthority] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("staking_authority"), rewardPoolKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    [stakingPool] = web3.PublicKey.findProgramAddressSync(
+      [stakingAuthority.toBuffer(), web3.TokenProgram.programId.toBuffer(), stakingMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    userTokenAccount = await pg.createTokenAccount(stakingMint, pg.wallet.publicKey);
+    await pg.mintTo(stakingMint, userTokenAccount, 1_000_000);
+
+    await pg.program.methods
+      .initializeRewardPool(new BN(1000), new BN(1))
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        rewardMint,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([rewardPoolKp])
+      .rpc();
+
+    await pg.program.methods
+      .initializeStakingPool()
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        stakingMint,
+        stakingAuthority,
+        stakingPool,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("open_staker_account and stake_tokens", async () => {
+    await pg.program.methods
+      .openStakerAccount()
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        owner: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([stakerAccountKp])
+      .rpc();
+
+    const txHash = await pg.program.methods
+      .stakeTokens(new BN(500_000), new BN(60))
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        userTokenAccount,
+        stakingPool,
+        tokenProgram: web3.TokenProgram.programId,
+        payer: pg.wallet.publicKey,
+        veConfig: null,
+        veLock: null,
+      })
+      .rpc();
+    await pg.connection.confirmTransaction(txHash);
+
+    const stakerAccount = await pg.program.account.stakerAccount.fetch(stakerAccountKp.publicKey);
+    assert(stakerAccount.owner.equals(pg.wallet.publicKey));
+    assert(new BN(500_000).eq(new BN(stakerAccount.stakedBalance)));
+  });
+
+  it("rejects withdrawing another wallet's stake", async () => {
+    const attacker = web3.Keypair.generate();
+    // Fund the attacker so its ATA can be created and the ownership check is what rejects
+    await pg.program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: pg.wallet.publicKey,
+          toPubkey: attacker.publicKey,
+          lamports: web3.LAMPORTS_PER_SOL / 10,
+        })
+      )
+    );
+    await expectError(
+      pg.program.methods
+        .withdrawStake(new BN(500_000))
+        .accounts(withdrawAccounts(attacker.publicKey))
+        .signers([attacker])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("rejects withdrawing through another reward pool", async () => {
+    const otherPoolKp = new web3.Keypair();
+    await pg.program.methods
+      .initializeRewardPool(new BN(1000), new BN(1))
+      .accounts({
+        rewardPool: otherPoolKp.publicKey,
+        rewardMint,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([otherPoolKp])
+      .rpc();
+
+    await expectError(
+      pg.program.methods
+        .withdrawStake(new BN(500_000))
+        .accounts(withdrawAccounts(pg.wallet.publicKey, otherPoolKp.publicKey))
+        .rpc(),
+      "InvalidAccountData"
+    );
+  });
+});
+
+describe("Reward accumulator precision", () => {
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new web3.PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+  const REWARD_RATE = 1_000;
+  const rewardPoolKp = new web3.Keypair();
+  const stakerAccountKp = new web3.Keypair();
+  let systemStatePda;
+  let rewardMint;
+  let stakingAuthority;
+  let stakingPool;
+  let emissionSchedule;
+  let rewardMintAuthority;
+  let userTokenAccount;
+  let rewardTokenAccount;
+
+  before(async () => {
+    [systemStatePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("system_state")], pg.PROGRAM_ID);
+    const stakingMint = await pg.createMint(pg.wallet.publicKey, 6);
+    rewardMint = await pg.createMint(pg.wallet.publicKey, 6);
+    [stakingAuthority] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("staking_authority"), rewardPoolKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    [stakingPool] = web3.PublicKey.findProgramAddressSync(
+      [stakingAuthority.toBuffer(), web3.TokenProgram.programId.toBuffer(), stakingMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    [emissionSchedule] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("emission_schedule"), rewardPoolKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    [rewardMintAuthority] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_mint_authority"), rewardPoolKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    userTokenAccount = await pg.createTokenAccount(stakingMint, pg.wallet.publicKey);
+    // The wallet's reward ATA is created by the first claim
+    [rewardTokenAccount] = web3.PublicKey.findProgramAddressSync(
+      [pg.wallet.publicKey.toBuffer(), web3.TokenProgram.programId.toBuffer(), rewardMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    await pg.mintTo(stakingMint, userTokenAccount, 1_000_000);
+
+    await pg.program.methods
+      .initializeRewardPool(new BN(REWARD_RATE), new BN(1))
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        rewardMint,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([rewardPoolKp])
+      .rpc();
+
+    await pg.program.methods
+      .initializeStakingPool()
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        stakingMint,
+        stakingAuthority,
+        stakingPool,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    await pg.program.methods
+      .initializeEmissionSchedule(new BN(86_400), new BN(1_000_000_000))
+      .accounts({
+        emissionSchedule,
+        rewardPool: rewardPoolKp.publicKey,
+        rewardMintAuthority,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // Hand the reward mint's authority to the pool's mint authority PDA (SPL Token SetAuthority, MintTokens)
+    const setAuthorityData = Buffer.concat([Buffer.from([6, 0, 1]), rewardMintAuthority.toBuffer()]);
+    await pg.program.provider.sendAndConfirm(
+      new web3.Transaction().add(
+        new web3.TransactionInstruction({
+          programId: web3.TokenProgram.programId,
+          keys: [
+            { pubkey: rewardMint, isSigner: false, isWritable: true },
+            { pubkey: pg.wallet.publicKey, isSigner: true, isWritable: false },
+          ],
+          data: setAuthorityData,
+        })
+      )
+    );
+
+    await pg.program.methods
+      .openStakerAccount()
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        owner: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([stakerAccountKp])
+      .rpc();
+  });
+
+  it("pays a stake its full emission, less at most 1 unit of rounding", async () => {
+    // An odd stake, so each second's emission does not divide evenly into the accumulator
+    await pg.program.methods
+      .stakeTokens(new BN(333_333), new BN(60))
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        userTokenAccount,
+        stakingPool,
+        tokenProgram: web3.TokenProgram.programId,
+        payer: pg.wallet.publicKey,
+        veConfig: null,
+        veLock: null,
+      })
+      .rpc({ commitment: "confirmed" });
+    const stakedAt = (await pg.program.account.rewardPool.fetch(rewardPoolKp.publicKey, "confirmed")).lastUpdateTime;
+
+    await new Promise((resolve) => setTimeout(resolve, 3_000));
+
+    await pg.program.methods
+      .claimRewards()
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        rewardRecipient: pg.wallet.publicKey,
+        destinationRewardAccount: rewardTokenAccount,
+        rewardTokenMint: rewardMint,
+        emissionSchedule,
+        rewardMintAuthority,
+        tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        veConfig: null,
+        veLock: null,
+        payer: pg.wallet.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const rewardPool = await pg.program.account.rewardPool.fetch(rewardPoolKp.publicKey, "confirmed");
+    const claimed = new BN((await pg.connection.getTokenAccountBalance(rewardTokenAccount, "confirmed")).value.amount);
+    const elapsed = new BN(rewardPool.lastUpdateTime).sub(new BN(stakedAt));
+    assert(elapsed.gtn(0));
+
+    // The only staker is entitled to everything emitted since it staked
+    const entitled = elapsed.muln(REWARD_RATE);
+    assert(claimed.lte(entitled));
+    assert(entitled.sub(claimed).lten(1));
+
+    // What the accumulator could not credit stays below one unit of working balance
+    assert(new BN(rewardPool.rewardResidue).lt(new BN(rewardPool.totalWorking)));
+  });
+});
+
+
+// Needs a program built with the `testing` feature, which forces the liquidated position insolvent
+describe("Bad debt slashing", () => {
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new web3.PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+  const rewardPoolKp = new web3.Keypair();
+  const stakerAccountKp = new web3.Keypair();
+  const debtorKp = new web3.Keypair();
+  const liquidatorKp = new web3.Keypair();
+  let systemStatePda;
+  let protocolStatsPda;
+  let badDebtQueuePda;
+  let stablecoinMint;
+  let treasuryAccount;
+  let walletStablecoinAccount;
+  let stakingAuthority;
+  let stakingPool;
+
+  // Send a `testing` feature instruction, reached through the program's fallback
+  async function sendTestingIx(name, data, keys) {
+    const discriminator = Buffer.from(anchor.utils.sha256.hash(`global:${name}`), "hex").subarray(0, 8);
+    const ix = new web3.TransactionInstruction({ programId: pg.PROGRAM_ID, keys, data: Buffer.concat([discriminator, data]) });
+    await pg.program.provider.sendAndConfirm(new web3.Transaction().add(ix));
+  }
+
+  async function forcePosition(userAccount, collateralBalance, stablecoinBalance) {
+    const data = Buffer.concat([
+      new BN(collateralBalance).toArrayLike(Buffer, "le", 8),
+      new BN(stablecoinBalance).toArrayLike(Buffer, "le", 8),
+      Buffer.from([0]), // HealthBand::Healthy
+    ]);
+    await sendTestingIx("test_force_position", data, [
+      { pubkey: userAccount, isSigner: false, isWritable: true },
+      { pubkey: systemStatePda, isSigner: false, isWritable: false },
+      { pubkey: pg.wallet.publicKey, isSigner: true, isWritable: false },
+    ]);
+  }
+
+  async function openPosition(kp) {
+    await pg.program.methods
+      .openPosition(Array(32).fill(0))
+      .accounts({
+        userAccount: kp.publicKey,
+        governance: web3.PublicKey.findProgramAddressSync([Buffer.from("governance")], pg.PROGRAM_ID)[0],
+        payer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([kp])
+      .rpc();
+  }
+
+  before(async () => {
+    [systemStatePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("system_state")], pg.PROGRAM_ID);
+    [protocolStatsPda] = web3.PublicKey.findProgramAddressSync([Buffer.from("protocol_stats")], pg.PROGRAM_ID);
+    [badDebtQueuePda] = web3.PublicKey.findProgramAddressSync([Buffer.from("bad_debt_queue")], pg.PROGRAM_ID);
+    const systemState = await pg.program.account.systemState.fetch(systemStatePda);
+    stablecoinMint = systemState.stablecoinMint;
+    treasuryAccount = systemState.treasuryAccount;
+    [walletStablecoinAccount] = web3.PublicKey.findProgramAddressSync(
+      [pg.wallet.publicKey.toBuffer(), web3.TokenProgram.programId.toBuffer(), stablecoinMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+    [stakingAuthority] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("staking_authority"), rewardPoolKp.publicKey.toBuffer()],
+      pg.PROGRAM_ID
+    );
+    [stakingPool] = web3.PublicKey.findProgramAddressSync(
+      [stakingAuthority.toBuffer(), web3.TokenProgram.programId.toBuffer(), stablecoinMint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    );
+
+    // A stablecoin staking pool slashed for bad debt, with 1000 staked
+    await pg.program.methods
+      .initializeRewardPool(new BN(0), new BN(1))
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        rewardMint: await pg.createMint(pg.wallet.publicKey, 6),
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([rewardPoolKp])
+      .rpc();
+    await pg.program.methods
+      .initializeStakingPool()
+      .accounts({
+        rewardPool: rewardPoolKp.publicKey,
+        stakingMint: stablecoinMint,
+        stakingAuthority,
+        stakingPool,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    await pg.program.methods
+      .initializeBadDebtQueue(new BN(1_000_000))
+      .accounts({
+        badDebtQueue: badDebtQueuePda,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    await pg.program.methods
+      .setBadDebtSlashingPool()
+      .accounts({
+        badDebtQueue: badDebtQueuePda,
+        rewardPool: rewardPoolKp.publicKey,
+        systemState: systemStatePda,
+        governanceAuthority: pg.wallet.publicKey,
+      })
+      .rpc();
+
+    // A legacy position that mints 2000 against forced collateral, then is forced insolvent at 1000 of debt
+    await openPosition(debtorKp);
+    await openPosition(liquidatorKp);
+    await forcePosition(debtorKp.publicKey, 400_000, 0);
+    await pg.program.methods
+      .mintStablecoin(new BN(2000), new BN(110), null, null, null)
+      .accounts({
+        userAccount: debtorKp.publicKey,
+        positionLedger: null,
+        userStablecoinAccount: walletStablecoinAccount,
+        stablecoinMint,
+        treasuryAccount,
+        systemState: systemStatePda,
+        protocolStats: protocolStatsPda,
+        collateralType: null,
+        partner: null,
+        tokenProgram: web3.TokenProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        payer: pg.wallet.publicKey,
+        optionalAuthority: null,
+      })
+      .rpc();
+    await forcePosition(debtorKp.publicKey, 100, 1000);
+
+    await pg.program.methods
+      .openStakerAccount()
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        owner: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([stakerAccountKp])
+      .rpc();
+    await pg.program.methods
+      .stakeTokens(new BN(1000), new BN(60))
+      .accounts({
+        stakerAccount: stakerAccountKp.publicKey,
+        rewardPool: rewardPoolKp.publicKey,
+        userTokenAccount: walletStablecoinAccount,
+        stakingPool,
+        tokenProgram: web3.TokenProgram.programId,
+        payer: pg.wallet.publicKey,
+        veConfig: null,
+        veLock: null,
+      })
+      .rpc();
+  });
+
+  it("keeps the accounting invariants after stakers are slashed for bad debt", async () => {
+    // Insurance absorbs what it holds of the shortfall, the rest is queued as bad debt
+    await pg.program.methods
+      .fullLiquidate()
+      .accounts({
+        userAccount: debtorKp.publicKey,
+        liquidatorAccount: liquidatorKp.publicKey,
+        positionLedger: null,
+        liquidatorLedger: null,
+        stablecoinMint,
+        liquidatorStablecoinAccount: walletStablecoinAccount,
+        collateralType: null,
+        priceCache: null,
+        marginAccount: null,
+        protocolStats: protocolStatsPda,
+        badDebtQueue: badDebtQueuePda,
+        systemState: systemStatePda,
+        tokenProgram: web3.TokenProgram.programId,
+        payer: pg.wallet.publicKey,
+      })
+      .rpc();
+
+    const statsBefore = await pg.program.account.protocolStats.fetch(protocolStatsPda);
+    await pg.program.methods
+      .slashStakersForBadDebt()
+      .accounts({
+        badDebtQueue: badDebtQueuePda,
+        protocolStats: protocolStatsPda,
+        rewardPool: rewardPoolKp.publicKey,
+        stakingPool,
+        stakingAuthority,
+        stablecoinMint,
+        systemState: systemStatePda,
+        tokenProgram: web3.TokenProgram.programId,
+      })
+      .rpc();
+
+    // At most 30% of the vault is slashed, retiring that much written-off debt
+    const rewardPool = await pg.program.account.rewardPool.fetch(rewardPoolKp.publicKey);
+    assert(new BN(300).eq(new BN(rewardPool.slashedStake)));
+    assert(new BN(1000).eq(new BN(rewardPool.totalStaked)));
+    const statsAfter = await pg.program.account.protocolStats.fetch(protocolStatsPda);
+    assert(new BN(statsBefore.writtenOffDebt).subn(300).eq(new BN(statsAfter.writtenOffDebt)));
+
+    const report = await pg.program.methods
+      .assertInvariants()
+      .accounts({
+        stablecoinMint,
+        protocolStats: protocolStatsPda,
+        systemState: systemStatePda,
+        rewardPool: rewardPoolKp.publicKey,
+        stakingVault: stakingPool,
+        wrapperVault: null,
+        wrapperTokenVault: null,
+        wrappedMint: null,
+        stakePoolConfig: null,
+        lstVault: null,
+      })
+      .view();
+    assert(report.holds);
+    assert(new BN(700).eq(new BN(report.stakingVaultBalance)));
   });
 });